@@ -1,13 +1,20 @@
-extern crate signals;
-
 pub mod poly_ui {
     pub mod app;
+    pub mod backends;
 
     pub mod components;
     pub mod controls;
     pub mod events;
+    #[cfg(feature = "framebuffer")]
+    pub mod framebuffer;
+    pub mod headless;
     pub mod layouts;
     pub mod widgets;
 
+    #[cfg(feature = "sdl2")]
     pub mod sdl2;
+    pub mod software;
+    pub mod svg;
+    #[cfg(feature = "tui")]
+    pub mod tui;
 }
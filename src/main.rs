@@ -1,18 +1,25 @@
-extern crate signals;
-
 pub mod poly_ui {
     pub mod app;
+    pub mod backends;
 
     pub mod components;
     pub mod controls;
     pub mod events;
+    #[cfg(feature = "framebuffer")]
+    pub mod framebuffer;
+    pub mod headless;
     pub mod layouts;
     pub mod widgets;
 
+    #[cfg(feature = "sdl2")]
     pub mod sdl2;
+    pub mod software;
+    pub mod svg;
+    #[cfg(feature = "tui")]
+    pub mod tui;
 }
 
-use crate::poly_ui::app::AppTrait;
+use crate::poly_ui::backends::Registry;
 use crate::poly_ui::components::Transform;
 use crate::poly_ui::controls::PushButton;
 use nalgebra::Point2;
@@ -30,11 +37,12 @@ fn main() -> Result<(), String> {
     //     run(path)?;
     // }
 
-    let mut app = poly_ui::sdl2::App::default();
+    let mut app = Registry::default().create_from_env()?;
     let window = app
         .get_windows_manager()
-        .create_window("Test window", 800, 600);
+        .create_window("Test window", 800, 600)?;
     let button = PushButton::new();
+    button.borrow_mut().set_text("Button");
     let transform = Transform::new(&Point2::<i32>::new(10, 10), &Vector2::<u32>::new(100, 100));
     window
         .borrow_mut()
@@ -0,0 +1,129 @@
+// deps
+use serde::Deserialize;
+use serde::Serialize;
+// super
+use super::Color;
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// Describes how colors of a layer are combined with colors already painted below it.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BlendMode {
+    /// Layer is painted over the background.
+    Normal,
+    /// Layer colors are added to the background colors.
+    Add,
+    /// Layer colors are multiplied with the background colors, result is never lighter.
+    Multiply,
+    /// Inverted colors are multiplied and the result is inverted, result is never darker.
+    Screen,
+}
+
+//************************************************************************************************
+impl BlendMode {
+    /// Composites source color over the destination color. Both colors (and the result) have
+    /// straight, not premultiplied, alpha.
+    /// # Arguments
+    /// * `src` - color of the layer
+    /// * `dst` - color already painted below the layer
+    /// * `opacity` - opacity of the layer in range [0, 1], it multiplies source alpha
+    /// # Returns
+    /// Resulting color.
+    pub fn blend(self, src: &Color, dst: &Color, opacity: f32) -> Color {
+        let src_alpha = src.a as f32 / 255.0 * opacity.clamp(0.0, 1.0);
+        let dst_alpha = dst.a as f32 / 255.0;
+
+        let out_alpha = match self {
+            BlendMode::Add => (src_alpha + dst_alpha).min(1.0),
+            _ => src_alpha + dst_alpha * (1.0 - src_alpha),
+        };
+        if out_alpha <= 0.0 {
+            return Color {
+                r: 0,
+                g: 0,
+                b: 0,
+                a: 0,
+            };
+        }
+
+        let channel = |s: u8, d: u8| {
+            let cs = s as f32 / 255.0;
+            let cb = d as f32 / 255.0;
+            let premultiplied = match self {
+                BlendMode::Add => (cs * src_alpha + cb * dst_alpha).min(1.0),
+                _ => {
+                    let mixed = match self {
+                        BlendMode::Multiply => cs * cb,
+                        BlendMode::Screen => cs + cb - cs * cb,
+                        _ => cs,
+                    };
+                    src_alpha * (1.0 - dst_alpha) * cs
+                        + src_alpha * dst_alpha * mixed
+                        + (1.0 - src_alpha) * dst_alpha * cb
+                }
+            };
+            (premultiplied / out_alpha * 255.0).round().min(255.0) as u8
+        };
+
+        Color {
+            r: channel(src.r, dst.r),
+            g: channel(src.g, dst.g),
+            b: channel(src.b, dst.b),
+            a: (out_alpha * 255.0).round() as u8,
+        }
+    }
+}
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+#[cfg(test)]
+mod tests {
+    // super
+    use super::*;
+
+    //********************************************************************************************
+    fn gray(value: u8, a: u8) -> Color {
+        Color {
+            r: value,
+            g: value,
+            b: value,
+            a,
+        }
+    }
+
+    //********************************************************************************************
+    #[test]
+    fn normal() {
+        assert_eq!(
+            BlendMode::Normal.blend(&gray(255, 255), &gray(0, 255), 1.0),
+            gray(255, 255)
+        );
+        assert_eq!(
+            BlendMode::Normal.blend(&gray(255, 255), &gray(0, 255), 0.5),
+            gray(128, 255)
+        );
+        assert_eq!(
+            BlendMode::Normal.blend(&gray(200, 255), &gray(0, 0), 0.5),
+            gray(200, 128)
+        );
+    }
+
+    //********************************************************************************************
+    #[test]
+    fn add_multiply_screen() {
+        assert_eq!(
+            BlendMode::Add.blend(&gray(100, 255), &gray(100, 255), 1.0),
+            gray(200, 255)
+        );
+        assert_eq!(
+            BlendMode::Multiply.blend(&gray(128, 255), &gray(255, 255), 1.0),
+            gray(128, 255)
+        );
+        assert_eq!(
+            BlendMode::Screen.blend(&gray(0, 255), &gray(100, 255), 1.0),
+            gray(100, 255)
+        );
+    }
+}
@@ -0,0 +1,409 @@
+// std
+use std::rc::Rc;
+// deps
+use nalgebra::Point2;
+use serde::Deserialize;
+use serde::Serialize;
+// super
+use super::Color;
+use super::Image;
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// Describes what happens with a gradient (or an image pattern) outside of its defined area.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SpreadMode {
+    /// Colors of the edges are extended indefinitely.
+    Pad,
+    /// Gradient is repeated from the start.
+    Repeat,
+    /// Gradient is repeated but every second repetition is mirrored.
+    Reflect,
+}
+
+//************************************************************************************************
+impl SpreadMode {
+    /// Maps gradient parameter from any range to the [0, 1] range according to this mode.
+    /// # Arguments
+    /// * `t` - gradient parameter, 0 at the gradient start and 1 at its end
+    /// # Returns
+    /// Parameter in range [0, 1].
+    pub fn apply(self, t: f32) -> f32 {
+        match self {
+            SpreadMode::Pad => t.clamp(0.0, 1.0),
+            SpreadMode::Repeat => t - t.floor(),
+            SpreadMode::Reflect => {
+                let period = t.rem_euclid(2.0);
+                if period > 1.0 {
+                    2.0 - period
+                } else {
+                    period
+                }
+            }
+        }
+    }
+}
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// Single color stop of a gradient.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GradientStop {
+    /// Position of this stop along the gradient in range [0, 1].
+    pub offset: f32,
+    pub color: Color,
+}
+
+//************************************************************************************************
+impl GradientStop {
+    pub fn new(offset: f32, color: &Color) -> Self {
+        Self {
+            offset,
+            color: *color,
+        }
+    }
+}
+
+//************************************************************************************************
+/// Evaluates color of the gradient described by its stops at the given parameter. Stops are
+/// expected to be sorted by their offsets.
+fn stops_color_at(stops: &[GradientStop], t: f32) -> Color {
+    match stops.len() {
+        0 => Color::TRANSPARENT,
+        1 => stops[0].color,
+        _ => {
+            if t <= stops[0].offset {
+                return stops[0].color;
+            }
+
+            for pair in stops.windows(2) {
+                if t <= pair[1].offset {
+                    let range = pair[1].offset - pair[0].offset;
+                    let factor = if range > 0.0 {
+                        (t - pair[0].offset) / range
+                    } else {
+                        1.0
+                    };
+                    return pair[0].color.mix(&pair[1].color, factor);
+                }
+            }
+
+            stops[stops.len() - 1].color
+        }
+    }
+}
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// Gradient changing color along the line from `start` to `end`. Colors are constant along lines
+/// perpendicular to it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LinearGradient {
+    pub start: Point2<f32>,
+    pub end: Point2<f32>,
+    pub stops: Vec<GradientStop>,
+    pub spread: SpreadMode,
+}
+
+//************************************************************************************************
+impl LinearGradient {
+    /// # Arguments
+    /// * `start` - point where gradient parameter is 0
+    /// * `end` - point where gradient parameter is 1
+    /// * `stops` - color stops; they will be sorted by their offsets
+    /// * `spread` - what happens before start and after end of the gradient
+    pub fn new(
+        start: &Point2<f32>,
+        end: &Point2<f32>,
+        stops: Vec<GradientStop>,
+        spread: SpreadMode,
+    ) -> Self {
+        Self {
+            start: *start,
+            end: *end,
+            stops: sorted_stops(stops),
+            spread,
+        }
+    }
+
+    /// # Arguments
+    /// * `point` - point in the coordinates of the painter using this gradient
+    /// # Returns
+    /// Color of this gradient at the given point.
+    pub fn color_at(&self, point: &Point2<f32>) -> Color {
+        let dir = self.end - self.start;
+        let length_squared = dir.norm_squared();
+        let t = if length_squared > 0.0 {
+            (point - self.start).dot(&dir) / length_squared
+        } else {
+            0.0
+        };
+
+        stops_color_at(&self.stops, self.spread.apply(t))
+    }
+}
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// Gradient changing color with the distance from `center`. Gradient parameter is 1 on the
+/// circle with the given `radius`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RadialGradient {
+    pub center: Point2<f32>,
+    pub radius: f32,
+    pub stops: Vec<GradientStop>,
+    pub spread: SpreadMode,
+}
+
+//************************************************************************************************
+impl RadialGradient {
+    /// # Arguments
+    /// * `center` - point where gradient parameter is 0
+    /// * `radius` - distance from center at which gradient parameter is 1
+    /// * `stops` - color stops; they will be sorted by their offsets
+    /// * `spread` - what happens outside of the gradient circle
+    pub fn new(
+        center: &Point2<f32>,
+        radius: f32,
+        stops: Vec<GradientStop>,
+        spread: SpreadMode,
+    ) -> Self {
+        Self {
+            center: *center,
+            radius,
+            stops: sorted_stops(stops),
+            spread,
+        }
+    }
+
+    /// # Arguments
+    /// * `point` - point in the coordinates of the painter using this gradient
+    /// # Returns
+    /// Color of this gradient at the given point.
+    pub fn color_at(&self, point: &Point2<f32>) -> Color {
+        let t = if self.radius > 0.0 {
+            (point - self.center).norm() / self.radius
+        } else {
+            1.0
+        };
+
+        stops_color_at(&self.stops, self.spread.apply(t))
+    }
+}
+
+//************************************************************************************************
+fn sorted_stops(mut stops: Vec<GradientStop>) -> Vec<GradientStop> {
+    stops.sort_by(|a, b| a.offset.total_cmp(&b.offset));
+    stops
+}
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// Image repeated over the painted area. Image top left corner is placed at `offset` and the
+/// spread mode is applied in both directions.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImagePattern {
+    pub image: Rc<Image>,
+    pub offset: Point2<i32>,
+    pub spread: SpreadMode,
+}
+
+//************************************************************************************************
+impl ImagePattern {
+    pub fn new(image: Rc<Image>, offset: &Point2<i32>, spread: SpreadMode) -> Self {
+        Self {
+            image,
+            offset: *offset,
+            spread,
+        }
+    }
+
+    /// # Arguments
+    /// * `point` - point in the coordinates of the painter using this pattern
+    /// # Returns
+    /// Color of the image pixel that covers the given point.
+    pub fn color_at(&self, point: &Point2<f32>) -> Color {
+        let size = self.image.size();
+        if size.x == 0 || size.y == 0 {
+            return Color {
+                r: 0,
+                g: 0,
+                b: 0,
+                a: 0,
+            };
+        }
+
+        let map = |value: f32, offset: i32, size: u32| {
+            let t = self.spread.apply((value - offset as f32) / size as f32);
+            ((t * size as f32) as u32).min(size - 1)
+        };
+
+        self.image.pixel(
+            map(point.x, self.offset.x, size.x),
+            map(point.y, self.offset.y, size.y),
+        )
+    }
+}
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// Brush describes how Painters fill shapes and stroke lines. All coordinates used by brushes
+/// are local coordinates of the Painter the brush is set on.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Brush {
+    Solid(Color),
+    LinearGradient(LinearGradient),
+    RadialGradient(RadialGradient),
+    Pattern(ImagePattern),
+}
+
+//************************************************************************************************
+impl Brush {
+    /// # Returns
+    /// True if this brush has the same color everywhere.
+    pub fn is_solid(&self) -> bool {
+        matches!(self, Brush::Solid(_))
+    }
+
+    /// # Arguments
+    /// * `point` - point in the coordinates of the painter using this brush
+    /// # Returns
+    /// Color of this brush at the given point.
+    pub fn color_at(&self, point: &Point2<f32>) -> Color {
+        match self {
+            Brush::Solid(color) => *color,
+            Brush::LinearGradient(gradient) => gradient.color_at(point),
+            Brush::RadialGradient(gradient) => gradient.color_at(point),
+            Brush::Pattern(pattern) => pattern.color_at(point),
+        }
+    }
+}
+
+//************************************************************************************************
+impl Default for Brush {
+    fn default() -> Self {
+        Brush::Solid(Color {
+            r: 0,
+            g: 0,
+            b: 0,
+            a: 255,
+        })
+    }
+}
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+#[cfg(test)]
+mod tests {
+    // super
+    use super::*;
+
+    //********************************************************************************************
+    fn black_to_white() -> Vec<GradientStop> {
+        vec![
+            GradientStop::new(
+                1.0,
+                &Color {
+                    r: 255,
+                    g: 255,
+                    b: 255,
+                    a: 255,
+                },
+            ),
+            GradientStop::new(
+                0.0,
+                &Color {
+                    r: 0,
+                    g: 0,
+                    b: 0,
+                    a: 255,
+                },
+            ),
+        ]
+    }
+
+    //********************************************************************************************
+    #[test]
+    fn spread_modes() {
+        assert_eq!(SpreadMode::Pad.apply(-0.5), 0.0);
+        assert_eq!(SpreadMode::Pad.apply(1.5), 1.0);
+        assert_eq!(SpreadMode::Repeat.apply(1.25), 0.25);
+        assert_eq!(SpreadMode::Repeat.apply(-0.25), 0.75);
+        assert_eq!(SpreadMode::Reflect.apply(1.25), 0.75);
+        assert_eq!(SpreadMode::Reflect.apply(-0.25), 0.25);
+    }
+
+    //********************************************************************************************
+    #[test]
+    fn linear_gradient() {
+        let gradient = LinearGradient::new(
+            &Point2::<f32>::new(0.0, 0.0),
+            &Point2::<f32>::new(100.0, 0.0),
+            black_to_white(),
+            SpreadMode::Pad,
+        );
+
+        assert_eq!(gradient.color_at(&Point2::<f32>::new(-10.0, 5.0)).r, 0);
+        assert_eq!(gradient.color_at(&Point2::<f32>::new(50.0, 30.0)).r, 128);
+        assert_eq!(gradient.color_at(&Point2::<f32>::new(150.0, 0.0)).r, 255);
+    }
+
+    //********************************************************************************************
+    #[test]
+    fn nan_stop_offset() {
+        let mut stops = black_to_white();
+        stops.push(GradientStop::new(f32::NAN, &Color::TRANSPARENT));
+        let gradient = LinearGradient::new(
+            &Point2::<f32>::new(0.0, 0.0),
+            &Point2::<f32>::new(100.0, 0.0),
+            stops,
+            SpreadMode::Pad,
+        );
+
+        assert_eq!(gradient.color_at(&Point2::<f32>::new(0.0, 0.0)).r, 0);
+    }
+
+    //********************************************************************************************
+    #[test]
+    fn radial_gradient() {
+        let gradient = RadialGradient::new(
+            &Point2::<f32>::new(10.0, 10.0),
+            10.0,
+            black_to_white(),
+            SpreadMode::Repeat,
+        );
+
+        assert_eq!(gradient.color_at(&Point2::<f32>::new(10.0, 10.0)).r, 0);
+        assert_eq!(gradient.color_at(&Point2::<f32>::new(10.0, 15.0)).r, 128);
+        assert_eq!(gradient.color_at(&Point2::<f32>::new(25.0, 10.0)).r, 128);
+    }
+
+    //********************************************************************************************
+    #[test]
+    fn image_pattern() {
+        let mut image = Image::new(&nalgebra::Vector2::<u32>::new(2, 1));
+        let red = Color {
+            r: 255,
+            g: 0,
+            b: 0,
+            a: 255,
+        };
+        image.set_pixel(1, 0, &red);
+        let pattern = ImagePattern::new(
+            Rc::new(image),
+            &Point2::<i32>::new(0, 0),
+            SpreadMode::Repeat,
+        );
+
+        assert_eq!(pattern.color_at(&Point2::<f32>::new(0.5, 0.5)).a, 0);
+        assert_eq!(pattern.color_at(&Point2::<f32>::new(1.5, 0.5)), red);
+        assert_eq!(pattern.color_at(&Point2::<f32>::new(3.5, 7.5)), red);
+    }
+}
@@ -0,0 +1,79 @@
+// std
+use std::cell::RefCell;
+use std::fmt::Debug;
+use std::rc::Rc;
+
+thread_local! {
+    /// Clipboard of the application running on this thread.
+    static CLIPBOARD: RefCell<Rc<RefCell<dyn ClipboardTrait>>> =
+        RefCell::new(Rc::new(RefCell::new(MemoryClipboard::default())));
+}
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// System clipboard holding text; backends implement it with the clipboard of their platform.
+pub trait ClipboardTrait: Debug {
+    /// # Returns
+    /// Text in the clipboard, None if it's empty or it doesn't contain text.
+    fn text(&self) -> Option<String>;
+
+    /// # Arguments
+    /// * `text` - text replacing the content of the clipboard
+    fn set_text(&mut self, text: &str);
+}
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// Clipboard shared only inside of the application; it's used by backends without access to a
+/// system clipboard and in tests.
+#[derive(Debug, Default)]
+pub struct MemoryClipboard {
+    text: Option<String>,
+}
+
+//************************************************************************************************
+impl ClipboardTrait for MemoryClipboard {
+    fn text(&self) -> Option<String> {
+        self.text.clone()
+    }
+
+    fn set_text(&mut self, text: &str) {
+        self.text = Some(text.to_string());
+    }
+}
+
+//************************************************************************************************
+/// # Returns
+/// Clipboard Widgets of this thread copy to and paste from.
+pub fn clipboard() -> Rc<RefCell<dyn ClipboardTrait>> {
+    CLIPBOARD.with(|clipboard| clipboard.borrow().clone())
+}
+
+//************************************************************************************************
+/// Replaces the clipboard of this thread; backends call it when they are created.
+/// # Arguments
+/// * `clipboard` - new clipboard
+pub fn set_clipboard(clipboard: Rc<RefCell<dyn ClipboardTrait>>) {
+    CLIPBOARD.with(|current| *current.borrow_mut() = clipboard);
+}
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+#[cfg(test)]
+mod tests {
+    // super
+    use super::*;
+
+    //********************************************************************************************
+    #[test]
+    fn replace_clipboard() {
+        clipboard().borrow_mut().set_text("first");
+        assert_eq!(clipboard().borrow().text().as_deref(), Some("first"));
+
+        set_clipboard(Rc::new(RefCell::new(MemoryClipboard::default())));
+        assert_eq!(clipboard().borrow().text(), None);
+    }
+}
@@ -0,0 +1,502 @@
+// std
+use std::fmt;
+use std::str::FromStr;
+// deps
+use serde::Deserialize;
+use serde::Serialize;
+// super
+use super::BlendMode;
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// Color struct used by Painters. Channels are sRGB encoded and alpha is straight (not
+/// premultiplied) unless stated otherwise.
+///
+/// Colors can be parsed from hex (`#rgb`, `#rgba`, `#rrggbb`, `#rrggbbaa`) and CSS-style strings
+/// (`rgb(255, 0, 0)`, `rgba(255 0 0 / 50%)`, `hsl(120deg, 100%, 50%)`, `hsla(...)` and named
+/// colors like `red` or `transparent`) with str::parse().
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub a: u8,
+}
+
+/// Named colors recognized when parsing.
+const NAMED_COLORS: &[(&str, Color)] = &[
+    ("transparent", Color::TRANSPARENT),
+    ("black", Color::BLACK),
+    ("white", Color::WHITE),
+    ("gray", Color::GRAY),
+    ("grey", Color::GRAY),
+    ("silver", Color::SILVER),
+    ("red", Color::RED),
+    ("maroon", Color::MAROON),
+    ("lime", Color::LIME),
+    ("green", Color::GREEN),
+    ("blue", Color::BLUE),
+    ("navy", Color::NAVY),
+    ("yellow", Color::YELLOW),
+    ("olive", Color::OLIVE),
+    ("cyan", Color::CYAN),
+    ("aqua", Color::CYAN),
+    ("teal", Color::TEAL),
+    ("magenta", Color::MAGENTA),
+    ("fuchsia", Color::MAGENTA),
+    ("purple", Color::PURPLE),
+    ("orange", Color::ORANGE),
+];
+
+//************************************************************************************************
+impl Color {
+    pub const TRANSPARENT: Color = Color::rgba(0, 0, 0, 0);
+    pub const BLACK: Color = Color::rgb(0, 0, 0);
+    pub const WHITE: Color = Color::rgb(255, 255, 255);
+    pub const GRAY: Color = Color::rgb(128, 128, 128);
+    pub const SILVER: Color = Color::rgb(192, 192, 192);
+    pub const RED: Color = Color::rgb(255, 0, 0);
+    pub const MAROON: Color = Color::rgb(128, 0, 0);
+    pub const LIME: Color = Color::rgb(0, 255, 0);
+    pub const GREEN: Color = Color::rgb(0, 128, 0);
+    pub const BLUE: Color = Color::rgb(0, 0, 255);
+    pub const NAVY: Color = Color::rgb(0, 0, 128);
+    pub const YELLOW: Color = Color::rgb(255, 255, 0);
+    pub const OLIVE: Color = Color::rgb(128, 128, 0);
+    pub const CYAN: Color = Color::rgb(0, 255, 255);
+    pub const TEAL: Color = Color::rgb(0, 128, 128);
+    pub const MAGENTA: Color = Color::rgb(255, 0, 255);
+    pub const PURPLE: Color = Color::rgb(128, 0, 128);
+    pub const ORANGE: Color = Color::rgb(255, 165, 0);
+
+    /// # Returns
+    /// Opaque color with the given channels.
+    pub const fn rgb(r: u8, g: u8, b: u8) -> Self {
+        Self { r, g, b, a: 255 }
+    }
+
+    pub const fn rgba(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Self { r, g, b, a }
+    }
+
+    /// # Returns
+    /// This color with the alpha replaced.
+    pub fn with_alpha(&self, a: u8) -> Self {
+        Self { a, ..*self }
+    }
+
+    /// # Arguments
+    /// * `name` - case insensitive name of the color, e.g. "red"
+    /// # Returns
+    /// Named color or None if the name is unknown.
+    pub fn named(name: &str) -> Option<Self> {
+        NAMED_COLORS
+            .iter()
+            .find(|(known, _)| known.eq_ignore_ascii_case(name))
+            .map(|(_, color)| *color)
+    }
+
+    /// Parses hex color in one of the forms `#rgb`, `#rgba`, `#rrggbb` or `#rrggbbaa`; the
+    /// leading `#` is optional.
+    /// # Returns
+    /// Parsed color or error message.
+    pub fn from_hex(hex: &str) -> Result<Self, String> {
+        let digits = hex.trim().trim_start_matches('#');
+        let error = || format!("invalid hex color '{}'", hex);
+        if !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(error());
+        }
+
+        let channel = |i: usize, len: usize| {
+            let value = u8::from_str_radix(&digits[i * len..(i + 1) * len], 16).unwrap();
+            if len == 1 {
+                value * 17
+            } else {
+                value
+            }
+        };
+        match digits.len() {
+            3 => Ok(Self::rgb(channel(0, 1), channel(1, 1), channel(2, 1))),
+            4 => Ok(Self::rgba(
+                channel(0, 1),
+                channel(1, 1),
+                channel(2, 1),
+                channel(3, 1),
+            )),
+            6 => Ok(Self::rgb(channel(0, 2), channel(1, 2), channel(2, 2))),
+            8 => Ok(Self::rgba(
+                channel(0, 2),
+                channel(1, 2),
+                channel(2, 2),
+                channel(3, 2),
+            )),
+            _ => Err(error()),
+        }
+    }
+
+    /// # Returns
+    /// Hex representation `#rrggbb`, or `#rrggbbaa` if the color is not opaque.
+    pub fn to_hex(&self) -> String {
+        if self.a == 255 {
+            format!("#{:02x}{:02x}{:02x}", self.r, self.g, self.b)
+        } else {
+            format!("#{:02x}{:02x}{:02x}{:02x}", self.r, self.g, self.b, self.a)
+        }
+    }
+
+    /// # Arguments
+    /// * `h` - hue in degrees, any value is wrapped to [0, 360)
+    /// * `s` - saturation in range [0, 1]
+    /// * `l` - lightness in range [0, 1]
+    /// # Returns
+    /// Opaque color.
+    pub fn from_hsl(h: f32, s: f32, l: f32) -> Self {
+        let s = s.clamp(0.0, 1.0);
+        let l = l.clamp(0.0, 1.0);
+        let chroma = (1.0 - (2.0 * l - 1.0).abs()) * s;
+        from_hue(h, chroma, l - chroma / 2.0)
+    }
+
+    /// # Returns
+    /// Hue in degrees [0, 360), saturation and lightness in [0, 1]. Alpha is ignored.
+    pub fn to_hsl(&self) -> (f32, f32, f32) {
+        let (max, min, hue) = self.hue();
+        let l = (max + min) / 2.0;
+        let s = if max == min {
+            0.0
+        } else {
+            (max - min) / (1.0 - (2.0 * l - 1.0).abs())
+        };
+        (hue, s, l)
+    }
+
+    /// # Arguments
+    /// * `h` - hue in degrees, any value is wrapped to [0, 360)
+    /// * `s` - saturation in range [0, 1]
+    /// * `v` - value in range [0, 1]
+    /// # Returns
+    /// Opaque color.
+    pub fn from_hsv(h: f32, s: f32, v: f32) -> Self {
+        let v = v.clamp(0.0, 1.0);
+        let chroma = v * s.clamp(0.0, 1.0);
+        from_hue(h, chroma, v - chroma)
+    }
+
+    /// # Returns
+    /// Hue in degrees [0, 360), saturation and value in [0, 1]. Alpha is ignored.
+    pub fn to_hsv(&self) -> (f32, f32, f32) {
+        let (max, min, hue) = self.hue();
+        let s = if max == 0.0 { 0.0 } else { (max - min) / max };
+        (hue, s, max)
+    }
+
+    /// # Returns
+    /// Channels converted from sRGB to linear light in range [0, 1]; alpha is only scaled.
+    pub fn to_linear(&self) -> [f32; 4] {
+        let linear = |channel: u8| {
+            let c = channel as f32 / 255.0;
+            if c <= 0.04045 {
+                c / 12.92
+            } else {
+                ((c + 0.055) / 1.055).powf(2.4)
+            }
+        };
+        [
+            linear(self.r),
+            linear(self.g),
+            linear(self.b),
+            self.a as f32 / 255.0,
+        ]
+    }
+
+    /// # Arguments
+    /// * `linear` - linear light channels and alpha in range [0, 1]
+    /// # Returns
+    /// sRGB encoded color.
+    pub fn from_linear(linear: [f32; 4]) -> Self {
+        let encode = |c: f32| {
+            let c = c.clamp(0.0, 1.0);
+            if c <= 0.003_130_8 {
+                c * 12.92
+            } else {
+                1.055 * c.powf(1.0 / 2.4) - 0.055
+            }
+        };
+        Self::rgba(
+            to_channel(encode(linear[0])),
+            to_channel(encode(linear[1])),
+            to_channel(encode(linear[2])),
+            to_channel(linear[3]),
+        )
+    }
+
+    /// # Returns
+    /// This color with channels multiplied by alpha.
+    pub fn premultiply(&self) -> Self {
+        let multiply = |channel: u8| ((channel as u32 * self.a as u32 + 127) / 255) as u8;
+        Self::rgba(multiply(self.r), multiply(self.g), multiply(self.b), self.a)
+    }
+
+    /// Inverse of premultiply(). Fully transparent colors become transparent black.
+    /// # Returns
+    /// This premultiplied color with straight alpha.
+    pub fn unpremultiply(&self) -> Self {
+        if self.a == 0 {
+            return Self::TRANSPARENT;
+        }
+        let divide = |channel: u8| {
+            ((channel as u32 * 255 + self.a as u32 / 2) / self.a as u32).min(255) as u8
+        };
+        Self::rgba(divide(self.r), divide(self.g), divide(self.b), self.a)
+    }
+
+    /// # Arguments
+    /// * `amount` - how much lightness in range [0, 1] is added
+    /// # Returns
+    /// Lighter color with the same hue, saturation and alpha.
+    pub fn lighten(&self, amount: f32) -> Self {
+        let (h, s, l) = self.to_hsl();
+        Self::from_hsl(h, s, l + amount).with_alpha(self.a)
+    }
+
+    /// # Arguments
+    /// * `amount` - how much lightness in range [0, 1] is removed
+    /// # Returns
+    /// Darker color with the same hue, saturation and alpha.
+    pub fn darken(&self, amount: f32) -> Self {
+        self.lighten(-amount)
+    }
+
+    /// Linearly interpolates all channels including alpha.
+    /// # Arguments
+    /// * `other` - color returned for factor 1
+    /// * `factor` - interpolation factor, 0 returns this color
+    /// # Returns
+    /// Mixed color.
+    pub fn mix(&self, other: &Color, factor: f32) -> Self {
+        let lerp = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * factor).round() as u8;
+        Self::rgba(
+            lerp(self.r, other.r),
+            lerp(self.g, other.g),
+            lerp(self.b, other.b),
+            lerp(self.a, other.a),
+        )
+    }
+
+    /// Composites this color over the given one (see BlendMode::Normal).
+    /// # Arguments
+    /// * `dst` - color below this one
+    /// # Returns
+    /// Resulting color with straight alpha.
+    pub fn over(&self, dst: &Color) -> Self {
+        BlendMode::Normal.blend(self, dst, 1.0)
+    }
+
+    /// # Returns
+    /// Maximal and minimal channel in range [0, 1] and hue in degrees.
+    fn hue(&self) -> (f32, f32, f32) {
+        let r = self.r as f32 / 255.0;
+        let g = self.g as f32 / 255.0;
+        let b = self.b as f32 / 255.0;
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let delta = max - min;
+
+        let hue = if delta == 0.0 {
+            0.0
+        } else if max == r {
+            60.0 * ((g - b) / delta).rem_euclid(6.0)
+        } else if max == g {
+            60.0 * ((b - r) / delta + 2.0)
+        } else {
+            60.0 * ((r - g) / delta + 4.0)
+        };
+        (max, min, hue)
+    }
+}
+
+//************************************************************************************************
+impl Default for Color {
+    fn default() -> Self {
+        Self::TRANSPARENT
+    }
+}
+
+//************************************************************************************************
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_hex())
+    }
+}
+
+//************************************************************************************************
+impl FromStr for Color {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let text = text.trim();
+        if text.starts_with('#') {
+            return Self::from_hex(text);
+        }
+        if let Some(color) = Self::named(text) {
+            return Ok(color);
+        }
+
+        let error = || format!("invalid color '{}'", text);
+        let lower = text.to_ascii_lowercase();
+        let open = lower.find('(').ok_or_else(error)?;
+        if !lower.ends_with(')') {
+            return Err(error());
+        }
+        let function = &lower[..open];
+        let args: Vec<&str> = lower[open + 1..lower.len() - 1]
+            .split(|c: char| c == ',' || c == '/' || c.is_whitespace())
+            .filter(|arg| !arg.is_empty())
+            .collect();
+        if args.len() != 3 && args.len() != 4 {
+            return Err(error());
+        }
+
+        let alpha = match args.get(3) {
+            Some(arg) => to_channel(parse_number(arg, 1.0).ok_or_else(error)?),
+            None => 255,
+        };
+        match function {
+            "rgb" | "rgba" => {
+                let channel = |arg: &str| parse_number(arg, 255.0).map(|c| to_channel(c / 255.0));
+                Ok(Self::rgba(
+                    channel(args[0]).ok_or_else(error)?,
+                    channel(args[1]).ok_or_else(error)?,
+                    channel(args[2]).ok_or_else(error)?,
+                    alpha,
+                ))
+            }
+            "hsl" | "hsla" => {
+                let hue = args[0]
+                    .trim_end_matches("deg")
+                    .parse::<f32>()
+                    .map_err(|_| error())?;
+                let percent = |arg: &str| {
+                    arg.strip_suffix('%')
+                        .and_then(|value| value.parse::<f32>().ok())
+                        .map(|value| value / 100.0)
+                };
+                Ok(Self::from_hsl(
+                    hue,
+                    percent(args[1]).ok_or_else(error)?,
+                    percent(args[2]).ok_or_else(error)?,
+                )
+                .with_alpha(alpha))
+            }
+            _ => Err(error()),
+        }
+    }
+}
+
+//************************************************************************************************
+/// # Arguments
+/// * `h` - hue in degrees
+/// * `chroma` - chroma in range [0, 1]
+/// * `offset` - value added to all channels
+/// # Returns
+/// Opaque color with the given hue and chroma.
+fn from_hue(h: f32, chroma: f32, offset: f32) -> Color {
+    let sector = h.rem_euclid(360.0) / 60.0;
+    let x = chroma * (1.0 - (sector % 2.0 - 1.0).abs());
+    let (r, g, b) = match sector as u32 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+    Color::rgb(
+        to_channel(r + offset),
+        to_channel(g + offset),
+        to_channel(b + offset),
+    )
+}
+
+//************************************************************************************************
+fn to_channel(value: f32) -> u8 {
+    (value.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+//************************************************************************************************
+/// Parses number or percentage of a CSS color function.
+/// # Arguments
+/// * `arg` - text of the argument
+/// * `max` - value corresponding to 100%
+fn parse_number(arg: &str, max: f32) -> Option<f32> {
+    match arg.strip_suffix('%') {
+        Some(percent) => percent.parse::<f32>().ok().map(|value| value / 100.0 * max),
+        None => arg.parse::<f32>().ok(),
+    }
+}
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+#[cfg(test)]
+mod tests {
+    // super
+    use super::*;
+
+    //********************************************************************************************
+    #[test]
+    fn parse() {
+        assert_eq!(Color::from_hex("#f00"), Ok(Color::RED));
+        assert_eq!("#00ff0080".parse(), Ok(Color::rgba(0, 255, 0, 128)));
+        assert_eq!("Navy".parse(), Ok(Color::NAVY));
+        assert_eq!("rgb(255, 128, 0)".parse(), Ok(Color::rgb(255, 128, 0)));
+        assert_eq!(
+            "rgba(0 0 255 / 50%)".parse(),
+            Ok(Color::rgba(0, 0, 255, 128))
+        );
+        assert_eq!("hsl(120deg, 100%, 25%)".parse(), Ok(Color::rgb(0, 128, 0)));
+        assert_eq!(
+            "hsla(0, 100%, 50%, 0)".parse(),
+            Ok(Color::RED.with_alpha(0))
+        );
+        assert!("#12345".parse::<Color>().is_err());
+        assert!("rgb(1, 2)".parse::<Color>().is_err());
+        assert!("nocolor".parse::<Color>().is_err());
+        assert_eq!(Color::rgba(1, 2, 3, 4).to_string(), "#01020304");
+    }
+
+    //********************************************************************************************
+    #[test]
+    fn conversions() {
+        let color = Color::rgb(51, 153, 204);
+        let (h, s, l) = color.to_hsl();
+        assert_eq!(
+            (h.round(), (s * 100.0).round(), (l * 100.0).round()),
+            (200.0, 60.0, 50.0)
+        );
+        assert_eq!(Color::from_hsl(h, s, l), color);
+        let (h, s, v) = color.to_hsv();
+        assert_eq!(Color::from_hsv(h, s, v), color);
+
+        let linear = Color::GRAY.to_linear();
+        assert!((linear[0] - 0.2158).abs() < 0.001);
+        assert_eq!(Color::from_linear(linear), Color::GRAY);
+
+        let premultiplied = Color::rgba(200, 100, 0, 128).premultiply();
+        assert_eq!(premultiplied, Color::rgba(100, 50, 0, 128));
+        assert_eq!(premultiplied.unpremultiply(), Color::rgba(199, 100, 0, 128));
+    }
+
+    //********************************************************************************************
+    #[test]
+    fn helpers() {
+        assert_eq!(Color::RED.lighten(0.25), Color::rgb(255, 128, 128));
+        assert_eq!(Color::RED.darken(0.25), Color::MAROON);
+        assert_eq!(Color::BLACK.mix(&Color::WHITE, 0.5), Color::GRAY);
+        assert_eq!(
+            Color::RED.with_alpha(128).over(&Color::BLUE),
+            Color::rgb(128, 0, 127)
+        );
+    }
+}
@@ -0,0 +1,329 @@
+// deps
+use nalgebra::Point2;
+use nalgebra::Vector2;
+use serde::Deserialize;
+use serde::Serialize;
+// super
+use super::Color;
+use super::Image;
+use super::Rect;
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// Shadow cast by a rectangular box, following CSS box-shadow. The shadow shape is the box moved
+/// by `offset` and grown by `spread` (shrunk if negative) which is then blurred with a Gaussian
+/// blur whose standard deviation is half of `blur_radius`. Outer shadows are painted only
+/// outside of the box, inset shadows only inside of it.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BoxShadow {
+    pub offset: Vector2<i32>,
+    pub blur_radius: u32,
+    pub spread: i32,
+    pub color: Color,
+    pub inset: bool,
+}
+
+//************************************************************************************************
+impl BoxShadow {
+    /// # Arguments
+    /// * `offset` - shift of the shadow relative to the box
+    /// * `blur_radius` - blur radius in pixels, 0 means sharp edges
+    /// * `spread` - how much the shadow shape is larger than the box
+    /// * `color` - color of the shadow
+    /// # Returns
+    /// Outer shadow.
+    pub fn new(offset: &Vector2<i32>, blur_radius: u32, spread: i32, color: &Color) -> Self {
+        Self {
+            offset: *offset,
+            blur_radius,
+            spread,
+            color: *color,
+            inset: false,
+        }
+    }
+
+    /// # Returns
+    /// Inset version of this shadow.
+    pub fn inset(&self) -> Self {
+        Self {
+            inset: true,
+            ..*self
+        }
+    }
+
+    /// # Arguments
+    /// * `size` - size of the box casting the shadow
+    /// # Returns
+    /// Image of the shadow and position of its top left corner relative to the box. Pixels not
+    /// covered by the shadow are transparent.
+    pub fn render(&self, size: &Vector2<u32>) -> (Image, Point2<i32>) {
+        let extent = kernel_half_size(self.blur_radius) as i32;
+        let bounds = Rect {
+            pos: Point2::<i32>::new(0, 0),
+            size: *size,
+        };
+
+        if self.inset {
+            // the area around the box is covered by the shadow as well so it bleeds into the box
+            let hole = inflate(&translate(&bounds, &self.offset), -self.spread);
+            let work = inflate(&bounds, extent);
+            let mut image = filled(&work.size, &self.color);
+            if let Some(hole) = hole.intersection(&work) {
+                fill_rect(
+                    &mut image,
+                    &translate(&hole, &-work.pos.coords),
+                    &Color::TRANSPARENT,
+                );
+            }
+            blur_image(&mut image, self.blur_radius);
+            let cropped = image.crop(&translate(&bounds, &-work.pos.coords));
+            (cropped, bounds.pos)
+        } else {
+            let shape = inflate(&translate(&bounds, &self.offset), self.spread);
+            let work = inflate(&shape, extent);
+            let mut image = Image::new(&work.size);
+            fill_rect(
+                &mut image,
+                &translate(&shape, &-work.pos.coords),
+                &self.color,
+            );
+            blur_image(&mut image, self.blur_radius);
+            fill_rect(
+                &mut image,
+                &translate(&bounds, &-work.pos.coords),
+                &Color::TRANSPARENT,
+            );
+            (image, work.pos)
+        }
+    }
+}
+
+//************************************************************************************************
+/// Blurs the given rect of the image with a Gaussian blur. Standard deviation of the blur is half
+/// of the radius, like in CSS. Colors are blurred with premultiplied alpha, so transparent
+/// pixels don't darken their surroundings, and pixels outside of the rect are not sampled.
+/// # Arguments
+/// * `image` - blurred image
+/// * `rect` - blurred rect, it is clipped to the image
+/// * `radius` - blur radius in pixels
+pub fn gaussian_blur(image: &mut Image, rect: &Rect, radius: u32) {
+    let image_rect = Rect {
+        pos: Point2::<i32>::new(0, 0),
+        size: image.size(),
+    };
+    let rect = match image_rect.intersection(rect) {
+        Some(rect) => rect,
+        None => return,
+    };
+    let kernel = kernel(radius);
+    if kernel.len() < 2 {
+        return;
+    }
+
+    let (width, height) = (rect.size.x as usize, rect.size.y as usize);
+    let mut pixels: Vec<[f32; 4]> = Vec::with_capacity(width * height);
+    for y in 0..rect.size.y {
+        for x in 0..rect.size.x {
+            let color = image.pixel(rect.pos.x as u32 + x, rect.pos.y as u32 + y);
+            let alpha = color.a as f32 / 255.0;
+            pixels.push([
+                color.r as f32 * alpha,
+                color.g as f32 * alpha,
+                color.b as f32 * alpha,
+                color.a as f32,
+            ]);
+        }
+    }
+
+    let horizontal = convolve(&pixels, width, height, &kernel, 1, width);
+    let blurred = convolve(&horizontal, height, width, &kernel, width, 1);
+
+    for (index, pixel) in blurred.iter().enumerate() {
+        let channel = |value: f32| value.round().clamp(0.0, 255.0) as u8;
+        let alpha = pixel[3] / 255.0;
+        let color = if alpha > 0.0 {
+            Color::rgba(
+                channel(pixel[0] / alpha),
+                channel(pixel[1] / alpha),
+                channel(pixel[2] / alpha),
+                channel(pixel[3]),
+            )
+        } else {
+            Color::TRANSPARENT
+        };
+        image.set_pixel(
+            rect.pos.x as u32 + (index % width) as u32,
+            rect.pos.y as u32 + (index / width) as u32,
+            &color,
+        );
+    }
+}
+
+//************************************************************************************************
+/// # Returns
+/// Number of pixels the blur with the given radius reaches on each side (three standard
+/// deviations).
+fn kernel_half_size(radius: u32) -> u32 {
+    (radius as f32 * 1.5).ceil() as u32
+}
+
+//************************************************************************************************
+/// # Returns
+/// Normalized weights of the Gaussian kernel for the given radius.
+fn kernel(radius: u32) -> Vec<f32> {
+    let half = kernel_half_size(radius) as i32;
+    let sigma = radius as f32 / 2.0;
+    let weights: Vec<f32> = (-half..=half)
+        .map(|x| (-(x * x) as f32 / (2.0 * sigma * sigma)).exp())
+        .collect();
+    let sum: f32 = weights.iter().sum();
+    weights.iter().map(|weight| weight / sum).collect()
+}
+
+//************************************************************************************************
+/// Convolves all lines of the buffer with the kernel. Samples outside of a line are clamped to
+/// its ends.
+/// # Arguments
+/// * `pixels` - convolved buffer
+/// * `length` - number of pixels in a line
+/// * `lines` - number of lines
+/// * `step` - distance between neighbouring pixels of a line in the buffer
+/// * `line_step` - distance between first pixels of neighbouring lines in the buffer
+fn convolve(
+    pixels: &[[f32; 4]],
+    length: usize,
+    lines: usize,
+    kernel: &[f32],
+    step: usize,
+    line_step: usize,
+) -> Vec<[f32; 4]> {
+    let half = (kernel.len() / 2) as i64;
+    let mut result = vec![[0.0; 4]; pixels.len()];
+    for line in 0..lines {
+        for i in 0..length {
+            let mut sum = [0.0; 4];
+            for (k, weight) in kernel.iter().enumerate() {
+                let sample = (i as i64 + k as i64 - half).clamp(0, length as i64 - 1) as usize;
+                let pixel = &pixels[line * line_step + sample * step];
+                for (total, value) in sum.iter_mut().zip(pixel.iter()) {
+                    *total += value * weight;
+                }
+            }
+            result[line * line_step + i * step] = sum;
+        }
+    }
+    result
+}
+
+//************************************************************************************************
+fn blur_image(image: &mut Image, radius: u32) {
+    let rect = Rect {
+        pos: Point2::<i32>::new(0, 0),
+        size: image.size(),
+    };
+    gaussian_blur(image, &rect, radius);
+}
+
+//************************************************************************************************
+fn filled(size: &Vector2<u32>, color: &Color) -> Image {
+    let mut image = Image::new(size);
+    for y in 0..size.y {
+        for x in 0..size.x {
+            image.set_pixel(x, y, color);
+        }
+    }
+    image
+}
+
+//************************************************************************************************
+fn fill_rect(image: &mut Image, rect: &Rect, color: &Color) {
+    let image_rect = Rect {
+        pos: Point2::<i32>::new(0, 0),
+        size: image.size(),
+    };
+    if let Some(rect) = image_rect.intersection(rect) {
+        for y in rect.pos.y..rect.pos.y + rect.size.y as i32 {
+            for x in rect.pos.x..rect.pos.x + rect.size.x as i32 {
+                image.set_pixel(x as u32, y as u32, color);
+            }
+        }
+    }
+}
+
+//************************************************************************************************
+fn translate(rect: &Rect, offset: &Vector2<i32>) -> Rect {
+    Rect {
+        pos: rect.pos + offset,
+        size: rect.size,
+    }
+}
+
+//************************************************************************************************
+/// # Returns
+/// Rect grown by the amount on every side, or shrunk if it's negative (never below zero size).
+fn inflate(rect: &Rect, amount: i32) -> Rect {
+    let grow = |size: u32| (size as i64 + 2 * amount as i64).max(0) as u32;
+    Rect {
+        pos: Point2::<i32>::new(rect.pos.x - amount, rect.pos.y - amount),
+        size: Vector2::<u32>::new(grow(rect.size.x), grow(rect.size.y)),
+    }
+}
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+#[cfg(test)]
+mod tests {
+    // super
+    use super::*;
+
+    //********************************************************************************************
+    #[test]
+    fn blur() {
+        let mut image = Image::new(&Vector2::<u32>::new(9, 1));
+        image.set_pixel(4, 0, &Color::RED);
+        let rect = Rect {
+            pos: Point2::<i32>::new(0, 0),
+            size: image.size(),
+        };
+        gaussian_blur(&mut image, &rect, 2);
+
+        // color is kept, only alpha spreads
+        assert_eq!(image.pixel(3, 0).r, 255);
+        assert_eq!(image.pixel(3, 0).g, 0);
+        assert!(image.pixel(4, 0).a > image.pixel(3, 0).a);
+        assert_eq!(image.pixel(3, 0).a, image.pixel(5, 0).a);
+        assert_eq!(image.pixel(0, 0).a, 0);
+        let total: u32 = (0..9).map(|x| image.pixel(x, 0).a as u32).sum();
+        assert!((253..=257).contains(&total));
+    }
+
+    //********************************************************************************************
+    #[test]
+    fn outer_and_inset_shadow() {
+        let size = Vector2::<u32>::new(4, 4);
+        let shadow = BoxShadow::new(&Vector2::<i32>::new(2, 1), 0, 1, &Color::BLACK);
+        let (image, pos) = shadow.render(&size);
+        assert_eq!(pos, Point2::<i32>::new(1, 0));
+        assert_eq!(image.size(), Vector2::<u32>::new(6, 6));
+        // covered by the box
+        assert_eq!(image.pixel(0, 0).a, 0);
+        // right of the box
+        assert_eq!(image.pixel(5, 2), Color::BLACK);
+
+        let (image, pos) = shadow.inset().render(&size);
+        assert_eq!(pos, Point2::<i32>::new(0, 0));
+        assert_eq!(image.size(), size);
+        // hole is the box moved by (2, 1) and shrunk by 1
+        assert_eq!(image.pixel(3, 2).a, 0);
+        assert_eq!(image.pixel(2, 2), Color::BLACK);
+        assert_eq!(image.pixel(3, 1), Color::BLACK);
+
+        let blurred = BoxShadow::new(&Vector2::<i32>::new(0, 0), 4, 0, &Color::BLACK);
+        let (image, pos) = blurred.render(&size);
+        assert_eq!(pos, Point2::<i32>::new(-6, -6));
+        assert!(image.pixel(5, 8).a > image.pixel(2, 8).a);
+        assert_eq!(image.pixel(0, 0).a, 0);
+    }
+}
@@ -0,0 +1,214 @@
+// deps
+use nalgebra::Point2;
+use nalgebra::Vector2;
+use serde::Deserialize;
+use serde::Serialize;
+// super
+use super::Rect;
+
+/// Width of a glyph cell in font units; 5 columns of the glyph and 1 of spacing.
+const CELL_WIDTH: u32 = 6;
+/// Height of a line in font units; 7 rows of the glyph, 1 above and 1 below.
+const CELL_HEIGHT: u32 = 9;
+
+/// Columns of glyphs of printable ASCII characters (0x20 - 0x7e), the least significant bit is
+/// the top row.
+#[rustfmt::skip]
+const GLYPHS: [[u8; 5]; 95] = [
+    [0x00, 0x00, 0x00, 0x00, 0x00], [0x00, 0x00, 0x5f, 0x00, 0x00], [0x00, 0x07, 0x00, 0x07, 0x00],
+    [0x14, 0x7f, 0x14, 0x7f, 0x14], [0x24, 0x2a, 0x7f, 0x2a, 0x12], [0x23, 0x13, 0x08, 0x64, 0x62],
+    [0x36, 0x49, 0x55, 0x22, 0x50], [0x00, 0x05, 0x03, 0x00, 0x00], [0x00, 0x1c, 0x22, 0x41, 0x00],
+    [0x00, 0x41, 0x22, 0x1c, 0x00], [0x08, 0x2a, 0x1c, 0x2a, 0x08], [0x08, 0x08, 0x3e, 0x08, 0x08],
+    [0x00, 0x50, 0x30, 0x00, 0x00], [0x08, 0x08, 0x08, 0x08, 0x08], [0x00, 0x60, 0x60, 0x00, 0x00],
+    [0x20, 0x10, 0x08, 0x04, 0x02], [0x3e, 0x51, 0x49, 0x45, 0x3e], [0x00, 0x42, 0x7f, 0x40, 0x00],
+    [0x42, 0x61, 0x51, 0x49, 0x46], [0x21, 0x41, 0x45, 0x4b, 0x31], [0x18, 0x14, 0x12, 0x7f, 0x10],
+    [0x27, 0x45, 0x45, 0x45, 0x39], [0x3c, 0x4a, 0x49, 0x49, 0x30], [0x01, 0x71, 0x09, 0x05, 0x03],
+    [0x36, 0x49, 0x49, 0x49, 0x36], [0x06, 0x49, 0x49, 0x29, 0x1e], [0x00, 0x36, 0x36, 0x00, 0x00],
+    [0x00, 0x56, 0x36, 0x00, 0x00], [0x08, 0x14, 0x22, 0x41, 0x00], [0x14, 0x14, 0x14, 0x14, 0x14],
+    [0x00, 0x41, 0x22, 0x14, 0x08], [0x02, 0x01, 0x51, 0x09, 0x06], [0x32, 0x49, 0x79, 0x41, 0x3e],
+    [0x7e, 0x11, 0x11, 0x11, 0x7e], [0x7f, 0x49, 0x49, 0x49, 0x36], [0x3e, 0x41, 0x41, 0x41, 0x22],
+    [0x7f, 0x41, 0x41, 0x22, 0x1c], [0x7f, 0x49, 0x49, 0x49, 0x41], [0x7f, 0x09, 0x09, 0x09, 0x01],
+    [0x3e, 0x41, 0x49, 0x49, 0x7a], [0x7f, 0x08, 0x08, 0x08, 0x7f], [0x00, 0x41, 0x7f, 0x41, 0x00],
+    [0x20, 0x40, 0x41, 0x3f, 0x01], [0x7f, 0x08, 0x14, 0x22, 0x41], [0x7f, 0x40, 0x40, 0x40, 0x40],
+    [0x7f, 0x02, 0x0c, 0x02, 0x7f], [0x7f, 0x04, 0x08, 0x10, 0x7f], [0x3e, 0x41, 0x41, 0x41, 0x3e],
+    [0x7f, 0x09, 0x09, 0x09, 0x06], [0x3e, 0x41, 0x51, 0x21, 0x5e], [0x7f, 0x09, 0x19, 0x29, 0x46],
+    [0x46, 0x49, 0x49, 0x49, 0x31], [0x01, 0x01, 0x7f, 0x01, 0x01], [0x3f, 0x40, 0x40, 0x40, 0x3f],
+    [0x1f, 0x20, 0x40, 0x20, 0x1f], [0x3f, 0x40, 0x38, 0x40, 0x3f], [0x63, 0x14, 0x08, 0x14, 0x63],
+    [0x07, 0x08, 0x70, 0x08, 0x07], [0x61, 0x51, 0x49, 0x45, 0x43], [0x00, 0x7f, 0x41, 0x41, 0x00],
+    [0x02, 0x04, 0x08, 0x10, 0x20], [0x00, 0x41, 0x41, 0x7f, 0x00], [0x04, 0x02, 0x01, 0x02, 0x04],
+    [0x40, 0x40, 0x40, 0x40, 0x40], [0x00, 0x01, 0x02, 0x04, 0x00], [0x20, 0x54, 0x54, 0x54, 0x78],
+    [0x7f, 0x48, 0x44, 0x44, 0x38], [0x38, 0x44, 0x44, 0x44, 0x20], [0x38, 0x44, 0x44, 0x48, 0x7f],
+    [0x38, 0x54, 0x54, 0x54, 0x18], [0x08, 0x7e, 0x09, 0x01, 0x02], [0x0c, 0x52, 0x52, 0x52, 0x3e],
+    [0x7f, 0x08, 0x04, 0x04, 0x78], [0x00, 0x44, 0x7d, 0x40, 0x00], [0x20, 0x40, 0x44, 0x3d, 0x00],
+    [0x7f, 0x10, 0x28, 0x44, 0x00], [0x00, 0x41, 0x7f, 0x40, 0x00], [0x7c, 0x04, 0x18, 0x04, 0x78],
+    [0x7c, 0x08, 0x04, 0x04, 0x78], [0x38, 0x44, 0x44, 0x44, 0x38], [0x7c, 0x14, 0x14, 0x14, 0x08],
+    [0x08, 0x14, 0x14, 0x18, 0x7c], [0x7c, 0x08, 0x04, 0x04, 0x08], [0x48, 0x54, 0x54, 0x54, 0x20],
+    [0x04, 0x3f, 0x44, 0x40, 0x20], [0x3c, 0x40, 0x40, 0x20, 0x7c], [0x1c, 0x20, 0x40, 0x20, 0x1c],
+    [0x3c, 0x40, 0x30, 0x40, 0x3c], [0x44, 0x28, 0x10, 0x28, 0x44], [0x0c, 0x50, 0x50, 0x50, 0x3c],
+    [0x44, 0x64, 0x54, 0x4c, 0x44], [0x00, 0x08, 0x36, 0x41, 0x00], [0x00, 0x00, 0x7f, 0x00, 0x00],
+    [0x00, 0x41, 0x36, 0x08, 0x00], [0x08, 0x04, 0x08, 0x10, 0x08],
+];
+
+/// Glyph of characters outside of the font, an empty box.
+const MISSING_GLYPH: [u8; 5] = [0x7f, 0x41, 0x41, 0x41, 0x7f];
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// Monospace bitmap font built into the crate so text can be painted by every Painter without
+/// font files. Glyphs of printable ASCII characters are 5x7 pixels in 6x9 cells scaled by an
+/// integer factor; other characters are painted as an empty box and control characters are not
+/// painted at all.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Font {
+    scale: u32,
+    bold: bool,
+}
+
+//************************************************************************************************
+impl Font {
+    /// # Arguments
+    /// * `scale` - size of one font pixel in logical pixels, at least 1
+    pub fn new(scale: u32) -> Self {
+        Self {
+            scale: scale.max(1),
+            bold: false,
+        }
+    }
+
+    /// # Returns
+    /// Copy of this font painted with thicker strokes.
+    pub fn bold(&self) -> Self {
+        Self {
+            bold: true,
+            ..*self
+        }
+    }
+
+    pub fn scale(&self) -> u32 {
+        self.scale
+    }
+
+    pub fn is_bold(&self) -> bool {
+        self.bold
+    }
+
+    /// # Returns
+    /// Advance of every character in logical pixels.
+    pub fn char_width(&self) -> u32 {
+        CELL_WIDTH * self.scale
+    }
+
+    /// # Returns
+    /// Distance between two lines of text in logical pixels.
+    pub fn line_height(&self) -> u32 {
+        CELL_HEIGHT * self.scale
+    }
+
+    /// # Arguments
+    /// * `text` - single line of text
+    /// # Returns
+    /// Width of the text in logical pixels.
+    pub fn text_width(&self, text: &str) -> u32 {
+        text.chars().count() as u32 * self.char_width()
+    }
+
+    /// # Arguments
+    /// * `text` - single line of text
+    /// # Returns
+    /// Size of the text in logical pixels.
+    pub fn text_size(&self, text: &str) -> Vector2<u32> {
+        Vector2::<u32>::new(self.text_width(text), self.line_height())
+    }
+
+    /// Computes rects covering all pixels of one line of text. Vertical runs of glyph pixels are
+    /// merged so there are only a few rects per character.
+    /// # Arguments
+    /// * `text` - single line of text
+    /// * `pos` - top left corner of the line
+    /// # Returns
+    /// Rects to fill.
+    pub fn text_rects(&self, text: &str, pos: &Point2<i32>) -> Vec<Rect> {
+        let scale = self.scale as i32;
+        let width = if self.bold {
+            2 * self.scale
+        } else {
+            self.scale
+        };
+        let mut rects = vec![];
+        for (index, ch) in text.chars().enumerate() {
+            let columns = match ch as u32 {
+                0x20..=0x7e => &GLYPHS[ch as usize - 0x20],
+                0..=0x1f | 0x7f => continue,
+                _ => &MISSING_GLYPH,
+            };
+            let left = pos.x + index as i32 * CELL_WIDTH as i32 * scale;
+            for (x, column) in columns.iter().enumerate() {
+                let mut y = 0;
+                while y < 8 {
+                    if column >> y & 1 == 0 {
+                        y += 1;
+                        continue;
+                    }
+                    let start = y;
+                    while y < 8 && column >> y & 1 == 1 {
+                        y += 1;
+                    }
+                    rects.push(Rect {
+                        pos: Point2::<i32>::new(
+                            left + x as i32 * scale,
+                            pos.y + (start + 1) * scale,
+                        ),
+                        size: Vector2::<u32>::new(width, ((y - start) * scale) as u32),
+                    });
+                }
+            }
+        }
+        rects
+    }
+}
+
+//************************************************************************************************
+impl Default for Font {
+    fn default() -> Self {
+        Self::new(1)
+    }
+}
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+#[cfg(test)]
+mod tests {
+    // super
+    use super::*;
+
+    //********************************************************************************************
+    #[test]
+    fn metrics_and_rects() {
+        let font = Font::new(2);
+        assert_eq!(Font::new(0).scale(), 1);
+        assert_eq!(font.text_size("abc"), Vector2::<u32>::new(36, 18));
+        assert!(font.text_rects(" \n", &Point2::<i32>::new(0, 0)).is_empty());
+
+        // 'I' is a vertical bar with a serif at both ends
+        let rects = font.text_rects("xI", &Point2::<i32>::new(1, 0));
+        let rects = &rects[rects.len() - 5..];
+        assert_eq!(
+            rects[2],
+            Rect {
+                pos: Point2::<i32>::new(17, 2),
+                size: Vector2::<u32>::new(2, 14),
+            }
+        );
+        assert_eq!(rects[0].size, Vector2::<u32>::new(2, 2));
+        assert_eq!(
+            font.bold().text_rects("I", &Point2::<i32>::new(0, 0))[2].size,
+            Vector2::<u32>::new(4, 14)
+        );
+        assert_eq!(
+            font.text_rects("\u{263a}", &Point2::<i32>::new(0, 0)).len(),
+            8
+        );
+    }
+}
@@ -0,0 +1,419 @@
+// std
+use std::fs::File;
+use std::io;
+use std::io::BufReader;
+use std::io::BufWriter;
+use std::io::Read;
+use std::io::Write;
+use std::path::Path;
+// deps
+use nalgebra::Point2;
+use nalgebra::Vector2;
+use serde::Deserialize;
+use serde::Serialize;
+// super
+use super::Color;
+use super::Rect;
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// In-memory RGBA image with 8 bits per channel. Pixels are stored row by row without any
+/// padding, so pixel (x, y) starts at byte (y * width + x) * 4 and channels are in R, G, B, A
+/// order.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Image {
+    size: Vector2<u32>,
+    pixels: Vec<u8>,
+}
+
+//************************************************************************************************
+impl Image {
+    /// # Arguments
+    /// * `size` - size of the new image in pixels
+    /// # Returns
+    /// Image of the given size with all pixels set to transparent black.
+    pub fn new(size: &Vector2<u32>) -> Self {
+        Self {
+            size: *size,
+            pixels: vec![0; size.x as usize * size.y as usize * 4],
+        }
+    }
+
+    /// Creates image from already existing RGBA data. If the length of `pixels` doesn't match
+    /// the given size the function will panic.
+    /// # Arguments
+    /// * `size` - size of the image in pixels
+    /// * `pixels` - RGBA bytes, row by row
+    pub fn from_pixels(size: &Vector2<u32>, pixels: Vec<u8>) -> Self {
+        assert_eq!(pixels.len(), size.x as usize * size.y as usize * 4);
+
+        Self {
+            size: *size,
+            pixels,
+        }
+    }
+
+    /// # Returns
+    /// Size of this image in pixels.
+    pub fn size(&self) -> Vector2<u32> {
+        self.size
+    }
+
+    /// # Returns
+    /// Raw RGBA bytes of this image, row by row.
+    pub fn pixels(&self) -> &[u8] {
+        &self.pixels
+    }
+
+    /// # Returns
+    /// Mutable raw RGBA bytes of this image, row by row.
+    pub fn pixels_mut(&mut self) -> &mut [u8] {
+        &mut self.pixels
+    }
+
+    /// # Arguments
+    /// * `x` - column of the pixel, must be lower than image width
+    /// * `y` - row of the pixel, must be lower than image height
+    /// # Returns
+    /// Color of the pixel at the given position.
+    pub fn pixel(&self, x: u32, y: u32) -> Color {
+        let idx = self.index(x, y);
+        Color {
+            r: self.pixels[idx],
+            g: self.pixels[idx + 1],
+            b: self.pixels[idx + 2],
+            a: self.pixels[idx + 3],
+        }
+    }
+
+    /// Overwrites pixel at the given position with the new color. No blending is performed.
+    /// # Arguments
+    /// * `x` - column of the pixel, must be lower than image width
+    /// * `y` - row of the pixel, must be lower than image height
+    /// * `color` - new color of the pixel
+    pub fn set_pixel(&mut self, x: u32, y: u32, color: &Color) {
+        let idx = self.index(x, y);
+        self.pixels[idx] = color.r;
+        self.pixels[idx + 1] = color.g;
+        self.pixels[idx + 2] = color.b;
+        self.pixels[idx + 3] = color.a;
+    }
+
+    /// Compares this image with the other one channel by channel.
+    /// # Arguments
+    /// * `other` - image to compare with, usually the expected one
+    /// * `tolerance` - maximal difference of a single channel for pixels to be still equal
+    /// # Returns
+    /// Result of the comparison. Images of different sizes never match.
+    pub fn compare(&self, other: &Image, tolerance: u8) -> ImageDiff {
+        let size =
+            Vector2::<u32>::new(self.size.x.max(other.size.x), self.size.y.max(other.size.y));
+        let mut diff = ImageDiff {
+            size_matches: self.size == other.size,
+            mismatched_pixels: 0,
+            max_difference: 0,
+            image: Image::new(&size),
+        };
+
+        for y in 0..size.y {
+            for x in 0..size.x {
+                let inside = |image: &Image| x < image.size.x && y < image.size.y;
+                let difference = if inside(self) && inside(other) {
+                    let lhs = &self.pixels[self.index(x, y)..self.index(x, y) + 4];
+                    let rhs = &other.pixels[other.index(x, y)..other.index(x, y) + 4];
+                    lhs.iter()
+                        .zip(rhs)
+                        .map(|(l, r)| l.abs_diff(*r))
+                        .max()
+                        .unwrap_or(0)
+                } else {
+                    255
+                };
+                diff.max_difference = diff.max_difference.max(difference);
+
+                let color = if difference > tolerance || !inside(self) || !inside(other) {
+                    diff.mismatched_pixels += 1;
+                    Color {
+                        r: 255,
+                        g: 0,
+                        b: 0,
+                        a: 255,
+                    }
+                } else {
+                    // matching pixels are shown as faded grayscale of the original
+                    let pixel = self.pixel(x, y);
+                    let gray = ((pixel.r as u32 + pixel.g as u32 + pixel.b as u32) / 3) as u8;
+                    Color {
+                        r: gray,
+                        g: gray,
+                        b: gray,
+                        a: 64,
+                    }
+                };
+                diff.image.set_pixel(x, y, &color);
+            }
+        }
+
+        diff
+    }
+
+    /// Writes this image to a PNG file with 8 bits per channel RGBA pixels.
+    /// # Arguments
+    /// * `path` - path of the file, existing file will be overwritten
+    pub fn save_png<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        self.write_png(BufWriter::new(File::create(path)?))
+    }
+
+    /// Encodes this image as PNG with 8 bits per channel RGBA pixels.
+    /// # Arguments
+    /// * `writer` - destination of the encoded data
+    pub fn write_png<W: Write>(&self, writer: W) -> io::Result<()> {
+        let mut encoder = png::Encoder::new(writer, self.size.x, self.size.y);
+        encoder.set_color(png::ColorType::RGBA);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.write_header()?.write_image_data(&self.pixels)?;
+        Ok(())
+    }
+
+    /// Reads image from a PNG file. Grayscale, palette and RGB images are converted to RGBA.
+    /// # Arguments
+    /// * `path` - path of the file
+    pub fn load_png<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let mut decoder = png::Decoder::new(BufReader::new(File::open(path)?));
+        decoder.set_transformations(png::Transformations::EXPAND | png::Transformations::STRIP_16);
+        let (info, mut reader) = decoder.read_info()?;
+        let mut data = vec![0; info.buffer_size()];
+        reader.next_frame(&mut data)?;
+
+        let pixels = match info.color_type {
+            png::ColorType::RGBA => data,
+            png::ColorType::RGB => data
+                .chunks(3)
+                .flat_map(|c| vec![c[0], c[1], c[2], 255])
+                .collect(),
+            png::ColorType::GrayscaleAlpha => data
+                .chunks(2)
+                .flat_map(|c| vec![c[0], c[0], c[0], c[1]])
+                .collect(),
+            png::ColorType::Grayscale => data.iter().flat_map(|c| vec![*c, *c, *c, 255]).collect(),
+            png::ColorType::Indexed => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "unexpanded palette image",
+                ))
+            }
+        };
+
+        Ok(Self::from_pixels(
+            &Vector2::<u32>::new(info.width, info.height),
+            pixels,
+        ))
+    }
+
+    /// Writes this image to an uncompressed 32 bits per pixel BMP file. Alpha is stored in the
+    /// fourth byte of each pixel which is ignored by most viewers.
+    /// # Arguments
+    /// * `path` - path of the file, existing file will be overwritten
+    pub fn save_bmp<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        const HEADERS_SIZE: u32 = 14 + 40;
+        let data_size = self.size.x * self.size.y * 4;
+
+        let mut file = BufWriter::new(File::create(path)?);
+        // file header
+        file.write_all(b"BM")?;
+        file.write_all(&(HEADERS_SIZE + data_size).to_le_bytes())?;
+        file.write_all(&0u32.to_le_bytes())?;
+        file.write_all(&HEADERS_SIZE.to_le_bytes())?;
+        // info header, negative height means rows are stored top to bottom
+        file.write_all(&40u32.to_le_bytes())?;
+        file.write_all(&(self.size.x as i32).to_le_bytes())?;
+        file.write_all(&(-(self.size.y as i32)).to_le_bytes())?;
+        file.write_all(&1u16.to_le_bytes())?;
+        file.write_all(&32u16.to_le_bytes())?;
+        file.write_all(&0u32.to_le_bytes())?;
+        file.write_all(&data_size.to_le_bytes())?;
+        file.write_all(&2835i32.to_le_bytes())?;
+        file.write_all(&2835i32.to_le_bytes())?;
+        file.write_all(&0u32.to_le_bytes())?;
+        file.write_all(&0u32.to_le_bytes())?;
+        // pixels in BGRA order
+        for pixel in self.pixels.chunks(4) {
+            file.write_all(&[pixel[2], pixel[1], pixel[0], pixel[3]])?;
+        }
+        file.flush()
+    }
+
+    /// Reads image written by save_bmp(). Only uncompressed 24 and 32 bits per pixel files are
+    /// supported. 24 bits per pixel images are opaque.
+    /// # Arguments
+    /// * `path` - path of the file
+    pub fn load_bmp<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let mut data = vec![];
+        File::open(path)?.read_to_end(&mut data)?;
+        let invalid = |msg: &str| io::Error::new(io::ErrorKind::InvalidData, msg.to_string());
+        if data.len() < 54 || &data[0..2] != b"BM" {
+            return Err(invalid("not a BMP file"));
+        }
+
+        let u32_at =
+            |i: usize| u32::from_le_bytes([data[i], data[i + 1], data[i + 2], data[i + 3]]);
+        let offset = u32_at(10) as usize;
+        let width = u32_at(18) as i32;
+        let height = u32_at(22) as i32;
+        let bits = u16::from_le_bytes([data[28], data[29]]);
+        let compression = u32_at(30);
+        if width < 0 || (bits != 24 && bits != 32) || compression != 0 {
+            return Err(invalid("unsupported BMP format"));
+        }
+
+        let size = Vector2::<u32>::new(width as u32, height.unsigned_abs());
+        let bytes_per_pixel = bits as usize / 8;
+        let stride = (size.x as usize * bytes_per_pixel).div_ceil(4) * 4;
+        if data.len() < offset + stride * size.y as usize {
+            return Err(invalid("truncated BMP file"));
+        }
+
+        let mut image = Image::new(&size);
+        for y in 0..size.y {
+            let row = if height < 0 { y } else { size.y - 1 - y };
+            for x in 0..size.x {
+                let i = offset + row as usize * stride + x as usize * bytes_per_pixel;
+                let a = if bytes_per_pixel == 4 {
+                    data[i + 3]
+                } else {
+                    255
+                };
+                image.set_pixel(
+                    x,
+                    y,
+                    &Color {
+                        r: data[i + 2],
+                        g: data[i + 1],
+                        b: data[i],
+                        a,
+                    },
+                );
+            }
+        }
+        Ok(image)
+    }
+
+    /// # Arguments
+    /// * `rect` - copied rect, it is clipped to this image
+    /// # Returns
+    /// New image with pixels of the given rect. It's empty if the rect is outside of this image.
+    pub fn crop(&self, rect: &Rect) -> Image {
+        let bounds = Rect {
+            pos: Point2::<i32>::new(0, 0),
+            size: self.size,
+        };
+        let clipped = match bounds.intersection(rect) {
+            Some(clipped) => clipped,
+            None => return Image::new(&Vector2::<u32>::new(0, 0)),
+        };
+
+        let mut pixels = Vec::with_capacity(clipped.size.x as usize * clipped.size.y as usize * 4);
+        for y in 0..clipped.size.y {
+            let start = self.index(clipped.pos.x as u32, clipped.pos.y as u32 + y);
+            pixels.extend_from_slice(&self.pixels[start..start + clipped.size.x as usize * 4]);
+        }
+        Image::from_pixels(&clipped.size, pixels)
+    }
+
+    fn index(&self, x: u32, y: u32) -> usize {
+        assert!(x < self.size.x && y < self.size.y);
+        (y as usize * self.size.x as usize + x as usize) * 4
+    }
+}
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// Result of Image::compare().
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImageDiff {
+    /// Whether both images have the same size.
+    pub size_matches: bool,
+    /// Number of pixels with at least one channel differing more than the tolerance.
+    pub mismatched_pixels: usize,
+    /// Largest difference of a single channel found in the images.
+    pub max_difference: u8,
+    /// Visualization of the differences. Mismatched pixels are red, the others are faded
+    /// grayscale of the compared image.
+    pub image: Image,
+}
+
+//************************************************************************************************
+impl ImageDiff {
+    /// # Returns
+    /// True if the images have the same size and all pixels are within the tolerance.
+    pub fn is_match(&self) -> bool {
+        self.size_matches && self.mismatched_pixels == 0
+    }
+}
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+#[cfg(test)]
+mod tests {
+    // std
+    use std::env;
+    // super
+    use super::*;
+
+    //********************************************************************************************
+    fn test_image() -> Image {
+        let mut image = Image::new(&Vector2::<u32>::new(3, 2));
+        for (i, byte) in image.pixels_mut().iter_mut().enumerate() {
+            *byte = (i * 10) as u8;
+        }
+        image
+    }
+
+    //********************************************************************************************
+    #[test]
+    fn compare() {
+        let image = test_image();
+        let mut other = image.clone();
+        other.set_pixel(
+            1,
+            1,
+            &Color {
+                r: 0,
+                g: 0,
+                b: 0,
+                a: 0,
+            },
+        );
+
+        assert!(image.compare(&image, 0).is_match());
+        let diff = image.compare(&other, 5);
+        assert!(!diff.is_match());
+        assert_eq!(diff.mismatched_pixels, 1);
+        assert_eq!(diff.max_difference, 190);
+        assert_eq!(diff.image.pixel(1, 1).r, 255);
+        assert!(image.compare(&other, 190).is_match());
+        assert!(!image
+            .compare(&Image::new(&Vector2::<u32>::new(3, 3)), 255)
+            .is_match());
+    }
+
+    //********************************************************************************************
+    #[test]
+    fn save_and_load() {
+        let image = test_image();
+        let dir = env::temp_dir();
+        let png_path = dir.join(format!("poly_ui_image_{}.png", std::process::id()));
+        let bmp_path = dir.join(format!("poly_ui_image_{}.bmp", std::process::id()));
+
+        image.save_png(&png_path).unwrap();
+        image.save_bmp(&bmp_path).unwrap();
+        assert_eq!(Image::load_png(&png_path).unwrap(), image);
+        assert_eq!(Image::load_bmp(&bmp_path).unwrap(), image);
+
+        std::fs::remove_file(png_path).unwrap();
+        std::fs::remove_file(bmp_path).unwrap();
+    }
+}
@@ -1,11 +1,58 @@
 mod app_trait;
+mod blend_mode;
+mod brush;
+mod clipboard;
+mod color;
+mod effects;
+mod font;
+mod image;
+mod nine_patch;
+mod painter_state;
 mod painter_trait;
+mod recording_painter;
+mod text_layout;
 mod windows_manager_trait;
 
 pub use app_trait::AppTrait;
-pub use painter_trait::Color;
+pub use blend_mode::BlendMode;
+pub use brush::Brush;
+pub use brush::GradientStop;
+pub use brush::ImagePattern;
+pub use brush::LinearGradient;
+pub use brush::RadialGradient;
+pub use brush::SpreadMode;
+pub use clipboard::clipboard;
+pub use clipboard::set_clipboard;
+pub use clipboard::ClipboardTrait;
+pub use clipboard::MemoryClipboard;
+pub use color::Color;
+pub use effects::gaussian_blur;
+pub use effects::BoxShadow;
+pub use font::Font;
+pub use image::Image;
+pub use image::ImageDiff;
+pub use nine_patch::ImageFill;
+pub use nine_patch::Insets;
+pub use nine_patch::NinePatch;
+pub use painter_state::device_bounds;
+pub use painter_state::integer_translation;
+pub use painter_state::is_axis_aligned;
+pub use painter_state::local_visible_rect;
+pub use painter_state::PainterState;
+pub use painter_state::PainterStateStack;
 pub use painter_trait::Line;
 pub use painter_trait::MockPainter;
 pub use painter_trait::PainterTrait;
 pub use painter_trait::Rect;
+pub use recording_painter::DisplayCommand;
+pub use recording_painter::DisplayList;
+pub use recording_painter::DisplayListDiff;
+pub use recording_painter::DrawCommand;
+pub use recording_painter::RecordingPainter;
+pub use text_layout::ElideMode;
+pub use text_layout::Glyph;
+pub use text_layout::HorizontalAlignment;
+pub use text_layout::TextLayout;
+pub use text_layout::TextLine;
+pub use text_layout::VerticalAlignment;
 pub use windows_manager_trait::WindowsManagerTrait;
@@ -0,0 +1,289 @@
+// std
+use std::rc::Rc;
+// deps
+use nalgebra::Point2;
+use nalgebra::Vector2;
+use serde::Deserialize;
+use serde::Serialize;
+// super
+use super::Image;
+use super::Rect;
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// Describes how an image covers a rect larger or smaller than the image.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ImageFill {
+    /// Image is scaled to the size of the rect.
+    Stretch,
+    /// Image is repeated from the top left corner of the rect and clipped to it.
+    Tile,
+}
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// Widths of the image borders of a NinePatch in pixels.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Insets {
+    pub left: u32,
+    pub top: u32,
+    pub right: u32,
+    pub bottom: u32,
+}
+
+//************************************************************************************************
+impl Insets {
+    pub fn new(left: u32, top: u32, right: u32, bottom: u32) -> Self {
+        Self {
+            left,
+            top,
+            right,
+            bottom,
+        }
+    }
+
+    /// # Returns
+    /// Insets with the same width on all sides.
+    pub fn uniform(width: u32) -> Self {
+        Self::new(width, width, width, width)
+    }
+}
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// Image split by insets into nine patches (also known as border image). When it's drawn into a
+/// rect the corners keep their size, the edges are stretched or tiled along the rect sides and
+/// the center fills the rest. If the rect is smaller than the corners, all borders are scaled
+/// down proportionally. Patches are cut out of the image once, when the NinePatch is created.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NinePatch {
+    image: Rc<Image>,
+    insets: Insets,
+    fill: ImageFill,
+    patches: Vec<Rc<Image>>,
+}
+
+//************************************************************************************************
+impl NinePatch {
+    /// # Arguments
+    /// * `image` - source image
+    /// * `insets` - widths of the borders, they are reduced to fit into the image
+    /// * `fill` - how the edges and the center are fitted to the painted rect
+    pub fn new(image: Rc<Image>, insets: &Insets, fill: ImageFill) -> Self {
+        let size = image.size();
+        let left = insets.left.min(size.x);
+        let top = insets.top.min(size.y);
+        let insets = Insets::new(
+            left,
+            top,
+            insets.right.min(size.x - left),
+            insets.bottom.min(size.y - top),
+        );
+
+        let xs = split(0, size.x, insets.left, insets.right);
+        let ys = split(0, size.y, insets.top, insets.bottom);
+        let mut patches = vec![];
+        for row in ys.windows(2) {
+            for column in xs.windows(2) {
+                patches.push(Rc::new(image.crop(&span_rect(column, row))));
+            }
+        }
+
+        Self {
+            image,
+            insets,
+            fill,
+            patches,
+        }
+    }
+
+    /// # Returns
+    /// Source image.
+    pub fn image(&self) -> &Rc<Image> {
+        &self.image
+    }
+
+    /// # Returns
+    /// Insets fitted into the image.
+    pub fn insets(&self) -> &Insets {
+        &self.insets
+    }
+
+    /// # Returns
+    /// How the edges and the center are fitted to the painted rect.
+    pub fn fill(&self) -> ImageFill {
+        self.fill
+    }
+
+    /// Computes where the patches go when this NinePatch is drawn into the given rect.
+    /// # Arguments
+    /// * `rect` - rect covered by the NinePatch
+    /// # Returns
+    /// Non empty patches with their destination rects and fill modes. Corners are always
+    /// stretched (to their own size unless the rect is too small).
+    pub fn pieces(&self, rect: &Rect) -> Vec<(&Rc<Image>, Rect, ImageFill)> {
+        let horizontal = self.insets.left + self.insets.right;
+        let vertical = self.insets.top + self.insets.bottom;
+        let mut factor: f32 = 1.0;
+        if horizontal > rect.size.x {
+            factor = factor.min(rect.size.x as f32 / horizontal as f32);
+        }
+        if vertical > rect.size.y {
+            factor = factor.min(rect.size.y as f32 / vertical as f32);
+        }
+        let scaled = |width: u32| (width as f32 * factor).round() as u32;
+
+        let xs = split(
+            rect.pos.x,
+            rect.size.x,
+            scaled(self.insets.left),
+            scaled(self.insets.right),
+        );
+        let ys = split(
+            rect.pos.y,
+            rect.size.y,
+            scaled(self.insets.top),
+            scaled(self.insets.bottom),
+        );
+
+        let mut pieces = vec![];
+        for (row_index, row) in ys.windows(2).enumerate() {
+            for (column_index, column) in xs.windows(2).enumerate() {
+                let patch = &self.patches[row_index * 3 + column_index];
+                let target = span_rect(column, row);
+                if patch.size().x == 0
+                    || patch.size().y == 0
+                    || target.size.x == 0
+                    || target.size.y == 0
+                {
+                    continue;
+                }
+                let corner = row_index != 1 && column_index != 1;
+                let fill = if corner {
+                    ImageFill::Stretch
+                } else {
+                    self.fill
+                };
+                pieces.push((patch, target, fill));
+            }
+        }
+        pieces
+    }
+}
+
+//************************************************************************************************
+/// # Returns
+/// Four coordinates splitting the span [start, start + length) into the start border, the middle
+/// and the end border.
+fn split(start: i32, length: u32, first: u32, last: u32) -> [i32; 4] {
+    let first = first.min(length);
+    let last = last.min(length - first);
+    [
+        start,
+        start + first as i32,
+        start + (length - last) as i32,
+        start + length as i32,
+    ]
+}
+
+//************************************************************************************************
+fn span_rect(columns: &[i32], rows: &[i32]) -> Rect {
+    Rect {
+        pos: Point2::<i32>::new(columns[0], rows[0]),
+        size: Vector2::<u32>::new((columns[1] - columns[0]) as u32, (rows[1] - rows[0]) as u32),
+    }
+}
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+#[cfg(test)]
+mod tests {
+    // crate
+    use crate::poly_ui::app::Color;
+    use crate::poly_ui::app::PainterTrait;
+    use crate::poly_ui::software::Painter;
+    // super
+    use super::*;
+
+    //********************************************************************************************
+    fn rect(x: i32, y: i32, w: u32, h: u32) -> Rect {
+        Rect {
+            pos: Point2::<i32>::new(x, y),
+            size: Vector2::<u32>::new(w, h),
+        }
+    }
+
+    //********************************************************************************************
+    /// 4x4 image with red 1px border, blue and green checkered center.
+    fn skin() -> Rc<Image> {
+        let mut image = Image::new(&Vector2::<u32>::new(4, 4));
+        for y in 0..4 {
+            for x in 0..4 {
+                let color = if x == 0 || y == 0 || x == 3 || y == 3 {
+                    Color::RED
+                } else if (x + y) % 2 == 0 {
+                    Color::BLUE
+                } else {
+                    Color::LIME
+                };
+                image.set_pixel(x, y, &color);
+            }
+        }
+        Rc::new(image)
+    }
+
+    //********************************************************************************************
+    #[test]
+    fn pieces() {
+        let patch = NinePatch::new(skin(), &Insets::uniform(1), ImageFill::Stretch);
+        let pieces = patch.pieces(&rect(10, 10, 6, 5));
+        assert_eq!(pieces.len(), 9);
+        assert_eq!(pieces[0].1, rect(10, 10, 1, 1));
+        assert_eq!(pieces[4].1, rect(11, 11, 4, 3));
+        assert_eq!(pieces[4].0.size(), Vector2::<u32>::new(2, 2));
+        assert_eq!(pieces[8].1, rect(15, 14, 1, 1));
+
+        // too narrow rect scales all borders down
+        let patch = NinePatch::new(skin(), &Insets::new(2, 1, 2, 1), ImageFill::Tile);
+        let pieces = patch.pieces(&rect(0, 0, 2, 8));
+        let rects: Vec<Rect> = pieces.iter().map(|piece| piece.1).collect();
+        assert_eq!(
+            rects,
+            vec![
+                rect(0, 0, 1, 1),
+                rect(1, 0, 1, 1),
+                rect(0, 1, 1, 6),
+                rect(1, 1, 1, 6),
+                rect(0, 7, 1, 1),
+                rect(1, 7, 1, 1)
+            ]
+        );
+        assert_eq!(pieces[2].2, ImageFill::Tile);
+        assert_eq!(pieces[0].2, ImageFill::Stretch);
+    }
+
+    //********************************************************************************************
+    #[test]
+    fn draw() {
+        let mut painter = Painter::new(&Vector2::<u32>::new(8, 6));
+        let stretched = NinePatch::new(skin(), &Insets::uniform(1), ImageFill::Stretch);
+        painter.draw_nine_patch(&stretched, &rect(0, 0, 8, 6));
+        assert_eq!(painter.image().pixel(0, 0), Color::RED);
+        assert_eq!(painter.image().pixel(7, 3), Color::RED);
+        // center 2x2 is stretched to 6x4
+        assert_eq!(painter.image().pixel(1, 1), Color::BLUE);
+        assert_eq!(painter.image().pixel(3, 2), Color::BLUE);
+        assert_eq!(painter.image().pixel(4, 2), Color::LIME);
+
+        let tiled = NinePatch::new(skin(), &Insets::uniform(1), ImageFill::Tile);
+        painter.draw_nine_patch(&tiled, &rect(0, 0, 8, 6));
+        assert_eq!(painter.image().pixel(1, 1), Color::BLUE);
+        assert_eq!(painter.image().pixel(2, 1), Color::LIME);
+        assert_eq!(painter.image().pixel(3, 1), Color::BLUE);
+        assert_eq!(painter.image().pixel(7, 5), Color::RED);
+    }
+}
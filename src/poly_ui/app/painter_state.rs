@@ -0,0 +1,218 @@
+// deps
+use nalgebra::Matrix3;
+use nalgebra::Point2;
+use nalgebra::Vector2;
+// super
+use super::Brush;
+use super::Rect;
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// State of the Painter that can be saved and restored with PainterTrait::save() and
+/// PainterTrait::restore().
+#[derive(Debug, Clone, PartialEq)]
+pub struct PainterState {
+    pub brush: Brush,
+    /// Affine transform applied to all coordinates passed to the Painter. It's relative to the
+    /// Painter origin so identity means no transformation.
+    pub matrix: Matrix3<f32>,
+}
+
+//************************************************************************************************
+impl Default for PainterState {
+    fn default() -> Self {
+        Self {
+            brush: Brush::default(),
+            matrix: Matrix3::<f32>::identity(),
+        }
+    }
+}
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// Helper for Painter implementations storing current state and all saved states.
+#[derive(Debug, Clone, Default)]
+pub struct PainterStateStack {
+    current: PainterState,
+    saved: Vec<PainterState>,
+}
+
+//************************************************************************************************
+impl PainterStateStack {
+    /// # Arguments
+    /// * `brush` - initial brush
+    /// # Returns
+    /// Stack with no saved states, given brush and identity matrix.
+    pub fn new(brush: &Brush) -> Self {
+        Self {
+            current: PainterState {
+                brush: brush.clone(),
+                matrix: Matrix3::<f32>::identity(),
+            },
+            saved: vec![],
+        }
+    }
+
+    /// # Returns
+    /// Current state.
+    pub fn current(&self) -> &PainterState {
+        &self.current
+    }
+
+    /// # Returns
+    /// Mutable current state.
+    pub fn current_mut(&mut self) -> &mut PainterState {
+        &mut self.current
+    }
+
+    /// Pushes copy of the current state on the stack.
+    pub fn save(&mut self) {
+        self.saved.push(self.current.clone());
+    }
+
+    /// Replaces current state with the last saved one. If there is no saved state nothing
+    /// happens.
+    pub fn restore(&mut self) {
+        if let Some(state) = self.saved.pop() {
+            self.current = state;
+        }
+    }
+}
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// # Arguments
+/// * `matrix` - affine transform
+/// # Returns
+/// True if the matrix only translates (and possibly scales) so rects stay axis aligned rects.
+pub fn is_axis_aligned(matrix: &Matrix3<f32>) -> bool {
+    matrix[(0, 1)] == 0.0 && matrix[(1, 0)] == 0.0
+}
+
+//************************************************************************************************
+/// # Arguments
+/// * `matrix` - affine transform
+/// # Returns
+/// Translation of the matrix if it doesn't scale nor rotate and the translation is integral.
+pub fn integer_translation(matrix: &Matrix3<f32>) -> Option<Point2<i32>> {
+    let x = matrix[(0, 2)];
+    let y = matrix[(1, 2)];
+    if is_axis_aligned(matrix)
+        && matrix[(0, 0)] == 1.0
+        && matrix[(1, 1)] == 1.0
+        && x.fract() == 0.0
+        && y.fract() == 0.0
+    {
+        Some(Point2::<i32>::new(x as i32, y as i32))
+    } else {
+        None
+    }
+}
+
+//************************************************************************************************
+/// # Arguments
+/// * `matrix` - affine transform
+/// * `rect` - rect to transform
+/// # Returns
+/// Pixel aligned bounding box of the given rect transformed with the given matrix or None if the
+/// result is empty.
+pub fn device_bounds(matrix: &Matrix3<f32>, rect: &Rect) -> Option<Rect> {
+    if rect.size.x == 0 || rect.size.y == 0 {
+        return None;
+    }
+
+    let left = rect.pos.x as f32;
+    let top = rect.pos.y as f32;
+    let right = left + rect.size.x as f32;
+    let bottom = top + rect.size.y as f32;
+    let corners = [
+        matrix.transform_point(&Point2::<f32>::new(left, top)),
+        matrix.transform_point(&Point2::<f32>::new(right, top)),
+        matrix.transform_point(&Point2::<f32>::new(right, bottom)),
+        matrix.transform_point(&Point2::<f32>::new(left, bottom)),
+    ];
+
+    let min_x = corners.iter().map(|p| p.x).fold(f32::MAX, f32::min).round() as i32;
+    let min_y = corners.iter().map(|p| p.y).fold(f32::MAX, f32::min).round() as i32;
+    let max_x = corners.iter().map(|p| p.x).fold(f32::MIN, f32::max).round() as i32;
+    let max_y = corners.iter().map(|p| p.y).fold(f32::MIN, f32::max).round() as i32;
+    if max_x <= min_x || max_y <= min_y {
+        return None;
+    }
+
+    Some(Rect {
+        pos: Point2::<i32>::new(min_x, min_y),
+        size: Vector2::<u32>::new((max_x - min_x) as u32, (max_y - min_y) as u32),
+    })
+}
+
+//************************************************************************************************
+/// # Arguments
+/// * `matrix` - matrix from local coordinates to device coordinates
+/// * `clip` - clip rect in device coordinates, None if everything is clipped
+/// # Returns
+/// Bounding rect of the clip rect in local coordinates, enlarged by one pixel in every
+/// direction to account for rounding. Empty rect if everything is clipped.
+pub fn local_visible_rect(matrix: &Matrix3<f32>, clip: &Option<Rect>) -> Rect {
+    let empty = Rect {
+        pos: Point2::<i32>::new(0, 0),
+        size: Vector2::<u32>::new(0, 0),
+    };
+    let bounds = clip.and_then(|clip| {
+        matrix
+            .try_inverse()
+            .and_then(|inverse| device_bounds(&inverse, &clip))
+    });
+
+    match bounds {
+        Some(bounds) => Rect {
+            pos: Point2::<i32>::new(bounds.pos.x - 1, bounds.pos.y - 1),
+            size: Vector2::<u32>::new(bounds.size.x + 2, bounds.size.y + 2),
+        },
+        None => empty,
+    }
+}
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+#[cfg(test)]
+mod tests {
+    // super
+    use super::*;
+
+    //********************************************************************************************
+    #[test]
+    fn save_restore() {
+        let mut stack = PainterStateStack::default();
+        stack.save();
+        stack.current_mut().matrix = Matrix3::<f32>::new_scaling(2.0);
+        stack.save();
+        stack.current_mut().matrix = Matrix3::<f32>::new_scaling(3.0);
+
+        stack.restore();
+        assert_eq!(stack.current().matrix, Matrix3::<f32>::new_scaling(2.0));
+        stack.restore();
+        assert_eq!(stack.current().matrix, Matrix3::<f32>::identity());
+        stack.restore();
+        assert_eq!(stack.current().matrix, Matrix3::<f32>::identity());
+    }
+
+    //********************************************************************************************
+    #[test]
+    fn matrix_helpers() {
+        let translation = Matrix3::<f32>::new_translation(&nalgebra::Vector2::<f32>::new(3.0, 4.0));
+        let rotation = Matrix3::<f32>::new_rotation(std::f32::consts::FRAC_PI_2);
+
+        assert_eq!(
+            integer_translation(&translation),
+            Some(Point2::<i32>::new(3, 4))
+        );
+        assert_eq!(integer_translation(&rotation), None);
+        assert!(is_axis_aligned(&Matrix3::<f32>::new_scaling(2.0)));
+        assert!(!is_axis_aligned(&rotation));
+    }
+}
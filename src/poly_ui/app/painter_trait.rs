@@ -1,41 +1,139 @@
+use nalgebra::Matrix3;
 use nalgebra::Point2;
 use nalgebra::Vector2;
+use serde::Deserialize;
+use serde::Serialize;
 use std::boxed::Box;
+use std::rc::Rc;
 
 use crate::poly_ui::components::Transform;
-
-//************************************************************************************************
-//************************************************************************************************
-//************************************************************************************************
-/// Color struct used by Painters
-#[derive(Debug)]
-pub struct Color {
-    pub r: u8,
-    pub g: u8,
-    pub b: u8,
-    pub a: u8,
-}
+// super
+use super::BlendMode;
+use super::BoxShadow;
+use super::Brush;
+use super::Color;
+use super::Font;
+use super::Image;
+use super::ImageFill;
+use super::ImagePattern;
+use super::NinePatch;
+use super::SpreadMode;
 
 //************************************************************************************************
 //************************************************************************************************
 //************************************************************************************************
 /// Line struct used by Painters
-#[derive(Debug)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Line {
     pub start: Point2<i32>,
     pub end: Point2<i32>,
 }
 
+//************************************************************************************************
+impl Line {
+    /// # Returns
+    /// All points covered by this line (including both ends) generated with the Bresenham
+    /// algorithm. Useful for painters that have to color lines pixel by pixel.
+    pub fn rasterize(&self) -> Vec<Point2<i32>> {
+        let dx = (self.end.x - self.start.x).abs();
+        let dy = -(self.end.y - self.start.y).abs();
+        let step_x = if self.start.x < self.end.x { 1 } else { -1 };
+        let step_y = if self.start.y < self.end.y { 1 } else { -1 };
+        let mut error = dx + dy;
+        let mut current = self.start;
+        let mut result = Vec::with_capacity((dx.max(-dy) + 1) as usize);
+
+        loop {
+            result.push(current);
+            if current == self.end {
+                break;
+            }
+            let doubled_error = 2 * error;
+            if doubled_error >= dy {
+                error += dy;
+                current.x += step_x;
+            }
+            if doubled_error <= dx {
+                error += dx;
+                current.y += step_y;
+            }
+        }
+
+        result
+    }
+}
+
 //************************************************************************************************
 //************************************************************************************************
 //************************************************************************************************
 /// Rect struct used by Painters
-#[derive(Debug)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Rect {
     pub pos: Point2<i32>,
     pub size: Vector2<u32>,
 }
 
+//************************************************************************************************
+impl Rect {
+    /// # Returns
+    /// True if the point lies inside this rect.
+    pub fn contains(&self, point: &Point2<i32>) -> bool {
+        point.x >= self.pos.x
+            && point.y >= self.pos.y
+            && point.x < self.pos.x + self.size.x as i32
+            && point.y < self.pos.y + self.size.y as i32
+    }
+
+    /// # Returns
+    /// Common part of both rects or None if they don't overlap.
+    pub fn intersection(&self, other: &Rect) -> Option<Rect> {
+        let left = self.pos.x.max(other.pos.x);
+        let top = self.pos.y.max(other.pos.y);
+        let right = (self.pos.x + self.size.x as i32).min(other.pos.x + other.size.x as i32);
+        let bottom = (self.pos.y + self.size.y as i32).min(other.pos.y + other.size.y as i32);
+
+        if right <= left || bottom <= top {
+            None
+        } else {
+            Some(Rect {
+                pos: Point2::<i32>::new(left, top),
+                size: Vector2::<u32>::new((right - left) as u32, (bottom - top) as u32),
+            })
+        }
+    }
+
+    /// # Returns
+    /// Lines forming the outline of this rect. Empty rects have no outline.
+    pub fn outline(&self) -> Vec<Line> {
+        if self.size.x == 0 || self.size.y == 0 {
+            return vec![];
+        }
+
+        let left = self.pos.x;
+        let top = self.pos.y;
+        let right = self.pos.x + self.size.x as i32 - 1;
+        let bottom = self.pos.y + self.size.y as i32 - 1;
+        vec![
+            Line {
+                start: Point2::<i32>::new(left, top),
+                end: Point2::<i32>::new(right, top),
+            },
+            Line {
+                start: Point2::<i32>::new(right, top),
+                end: Point2::<i32>::new(right, bottom),
+            },
+            Line {
+                start: Point2::<i32>::new(right, bottom),
+                end: Point2::<i32>::new(left, bottom),
+            },
+            Line {
+                start: Point2::<i32>::new(left, bottom),
+                end: Point2::<i32>::new(left, top),
+            },
+        ]
+    }
+}
+
 //************************************************************************************************
 //************************************************************************************************
 //************************************************************************************************
@@ -44,7 +142,11 @@ pub struct Rect {
 pub trait PainterTrait {
     /// SubPainters are used when widget has a child that should get its own part of the screen
     /// to paint. We don't want to give it the whole window to paint so we provide transform for
-    /// the new subpainter and the returned painter has new position and new size.
+    /// the new subpainter and the returned painter has new position and new size. The position
+    /// is transformed with the current matrix of this Painter and the SubPainter inherits it, so
+    /// children of a scaled or rotated widget are scaled and rotated as well. SubPainter is
+    /// clipped to its rect intersected with the clip of this Painter; under rotation the
+    /// bounding box of the rotated rect is used for clipping.
     /// # Arguments
     /// * `transform` - transform for the SubPainter relative to this Painter
     /// # returns
@@ -55,18 +157,110 @@ pub trait PainterTrait {
     /// Size of this subpainter.
     fn size(&self) -> Vector2<u32>;
 
-    /// Clears the whole Painter rect with currently set draw Color.
+    /// # Returns
+    /// Bounding rect of the area this Painter can paint on, in coordinates of the current
+    /// matrix, or None if the Painter doesn't clip. Empty rect means that everything is clipped.
+    /// Widgets may skip painting of anything outside of it. The default implementation returns
+    /// None.
+    fn visible_rect(&self) -> Option<Rect> {
+        None
+    }
+
+    /// Clears the whole Painter rect with currently set Brush.
     fn clear(&mut self);
 
     /// # Returns
-    /// Currently set draw color.
-    fn draw_color(&self) -> Color;
+    /// Currently set brush.
+    fn brush(&self) -> Brush;
 
-    /// Sets new draw color for this Painter. All points lines and shapes will be drawed with this
-    /// color from now on.
+    /// Sets new brush for this Painter. All points, lines and shapes will be filled or stroked
+    /// with this brush from now on. SubPainters created after this call inherit the brush.
+    /// # Arguments
+    /// * `new` - new brush for this painter
+    fn set_brush(&mut self, new: &Brush);
+
+    /// # Returns
+    /// Currently set draw color. If the current brush is not solid its color at the origin of
+    /// this Painter is returned.
+    fn draw_color(&self) -> Color {
+        self.brush().color_at(&Point2::<f32>::new(0.0, 0.0))
+    }
+
+    /// Convenience function for setting solid brush with the given color.
     /// # Arguments
     /// * `new` - new draw color for this painter
-    fn set_draw_color(&mut self, new: &Color);
+    fn set_draw_color(&mut self, new: &Color) {
+        self.set_brush(&Brush::Solid(*new));
+    }
+
+    /// Pushes the current state of this Painter (brush and matrix) on its state stack.
+    fn save(&mut self);
+
+    /// Pops the last saved state from the state stack and makes it current. If there is no
+    /// saved state nothing happens.
+    fn restore(&mut self);
+
+    /// # Returns
+    /// Current transformation matrix. It is applied to all coordinates passed to drawing
+    /// functions; identity means that coordinates are relative to the Painter origin.
+    fn matrix(&self) -> Matrix3<f32>;
+
+    /// # Returns
+    /// Current matrix combined with the transforms of the parent painters; it maps coordinates
+    /// to pixels of the device. Painters without pixels return the current matrix.
+    fn device_matrix(&self) -> Matrix3<f32> {
+        self.matrix()
+    }
+
+    /// Replaces the current transformation matrix.
+    /// # Arguments
+    /// * `new` - new affine transformation matrix
+    fn set_matrix(&mut self, new: &Matrix3<f32>);
+
+    /// Multiplies the current matrix by the given one, so the given transformation is applied
+    /// to coordinates before all previously applied transformations.
+    /// # Arguments
+    /// * `matrix` - affine transformation matrix
+    fn transform(&mut self, matrix: &Matrix3<f32>) {
+        let new = self.matrix() * matrix;
+        self.set_matrix(&new);
+    }
+
+    /// # Arguments
+    /// * `x` - horizontal translation
+    /// * `y` - vertical translation
+    fn translate(&mut self, x: f32, y: f32) {
+        self.transform(&Matrix3::<f32>::new_translation(&Vector2::<f32>::new(x, y)));
+    }
+
+    /// # Arguments
+    /// * `x` - horizontal scale factor
+    /// * `y` - vertical scale factor
+    fn scale(&mut self, x: f32, y: f32) {
+        self.transform(&Matrix3::<f32>::new_nonuniform_scaling(
+            &Vector2::<f32>::new(x, y),
+        ));
+    }
+
+    /// # Arguments
+    /// * `angle` - clockwise rotation angle in radians (y axis points down)
+    fn rotate(&mut self, angle: f32) {
+        self.transform(&Matrix3::<f32>::new_rotation(angle));
+    }
+
+    /// Starts an offscreen layer. Everything painted with this Painter and all SubPainters
+    /// (created before or after this call) goes to the layer until the matching pop_layer() call.
+    /// Then the whole layer is composited at once with the given opacity and blend mode, so
+    /// overlapping children don't show through each other. Layers can be nested. The layer is
+    /// clipped to the clip of this Painter.
+    /// # Arguments
+    /// * `opacity` - opacity of the whole layer in range [0, 1]
+    /// * `blend_mode` - how the layer is combined with what was painted below it
+    fn push_layer(&mut self, opacity: f32, blend_mode: BlendMode);
+
+    /// Ends the last started layer and composites it onto the previous layer (or the window).
+    /// Calling this function without matching push_layer() call does nothing.
+    fn pop_layer(&mut self);
 
     fn draw_point(&mut self, point: &Point2<i32>);
     fn draw_points(&mut self, points: &[Point2<i32>]);
@@ -76,6 +270,114 @@ pub trait PainterTrait {
     fn draw_rects(&mut self, rect: &[Rect]);
     fn fill_rect(&mut self, rect: Rect);
     fn fill_rects(&mut self, rect: &[Rect]);
+
+    /// Draws the whole image with its top left corner at the given position. Image pixels are
+    /// blended with what was painted before. The default implementation fills the image rect
+    /// with an ImagePattern brush; backends may override it with a faster blit.
+    /// # Arguments
+    /// * `image` - drawn image
+    /// * `pos` - position of the image in coordinates of the current matrix
+    fn draw_image(&mut self, image: &Rc<Image>, pos: &Point2<i32>) {
+        self.save();
+        self.set_brush(&Brush::Pattern(ImagePattern::new(
+            image.clone(),
+            pos,
+            SpreadMode::Pad,
+        )));
+        self.fill_rect(Rect {
+            pos: *pos,
+            size: image.size(),
+        });
+        self.restore();
+    }
+
+    /// Draws the whole image into the given rect, either scaled to it or repeated over it. The
+    /// default implementation fills the rect with an ImagePattern brush.
+    /// # Arguments
+    /// * `image` - drawn image
+    /// * `rect` - covered rect in coordinates of the current matrix
+    /// * `fill` - how the image is fitted to the rect
+    fn draw_image_rect(&mut self, image: &Rc<Image>, rect: &Rect, fill: ImageFill) {
+        let size = image.size();
+        if size.x == 0 || size.y == 0 || rect.size.x == 0 || rect.size.y == 0 {
+            return;
+        }
+
+        self.save();
+        match fill {
+            ImageFill::Stretch => {
+                self.translate(rect.pos.x as f32, rect.pos.y as f32);
+                self.scale(
+                    rect.size.x as f32 / size.x as f32,
+                    rect.size.y as f32 / size.y as f32,
+                );
+                self.set_brush(&Brush::Pattern(ImagePattern::new(
+                    image.clone(),
+                    &Point2::<i32>::new(0, 0),
+                    SpreadMode::Pad,
+                )));
+                self.fill_rect(Rect {
+                    pos: Point2::<i32>::new(0, 0),
+                    size,
+                });
+            }
+            ImageFill::Tile => {
+                self.set_brush(&Brush::Pattern(ImagePattern::new(
+                    image.clone(),
+                    &rect.pos,
+                    SpreadMode::Repeat,
+                )));
+                self.fill_rect(*rect);
+            }
+        }
+        self.restore();
+    }
+
+    /// Draws the NinePatch into the given rect: corners keep their size while the edges and the
+    /// center are stretched or tiled (see NinePatch).
+    /// # Arguments
+    /// * `patch` - drawn NinePatch
+    /// * `rect` - covered rect in coordinates of the current matrix
+    fn draw_nine_patch(&mut self, patch: &NinePatch, rect: &Rect) {
+        for (image, target, fill) in patch.pieces(rect) {
+            self.draw_image_rect(image, &target, fill);
+        }
+    }
+
+    /// Paints shadow of the given box (see BoxShadow). Outer shadows are usually painted before
+    /// the box and inset shadows after its background. The default implementation renders the
+    /// shadow in software and draws it with draw_image().
+    /// # Arguments
+    /// * `rect` - box casting the shadow in coordinates of the current matrix
+    /// * `shadow` - parameters of the shadow
+    fn draw_box_shadow(&mut self, rect: &Rect, shadow: &BoxShadow) {
+        let (image, pos) = shadow.render(&rect.size);
+        if image.size().x > 0 && image.size().y > 0 {
+            self.draw_image(&Rc::new(image), &(rect.pos + pos.coords));
+        }
+    }
+
+    /// Paints one line of text with the draw color. The default implementation fills rects of
+    /// the glyph pixels (see Font::text_rects()).
+    /// # Arguments
+    /// * `text` - painted line, new line characters are not painted
+    /// * `pos` - top left corner of the line in coordinates of the current matrix
+    /// * `font` - font of the text
+    fn draw_text(&mut self, text: &str, pos: &Point2<i32>, font: &Font) {
+        let rects = font.text_rects(text, pos);
+        if !rects.is_empty() {
+            self.fill_rects(&rects);
+        }
+    }
+
+    /// Blurs what was already painted in the given rect with a Gaussian blur, e.g. a backdrop
+    /// behind a modal overlay. The blur radius is scaled together with the current matrix.
+    /// Painters that can't read painted pixels back ignore it, which is also what the default
+    /// implementation does.
+    /// # Arguments
+    /// * `rect` - blurred rect in coordinates of the current matrix, it is clipped to the Painter
+    /// * `radius` - blur radius, see gaussian_blur()
+    fn blur_rect(&mut self, _rect: &Rect, _radius: u32) {}
 }
 
 //************************************************************************************************
@@ -107,16 +409,30 @@ impl PainterTrait for MockPainter {
 
     fn clear(&mut self) {}
 
-    fn draw_color(&self) -> Color {
-        Color {
+    fn brush(&self) -> Brush {
+        Brush::Solid(Color {
             r: 0,
             g: 0,
             b: 0,
             a: 0,
-        }
+        })
     }
 
-    fn set_draw_color(&mut self, _new: &Color) {}
+    fn set_brush(&mut self, _new: &Brush) {}
+
+    fn save(&mut self) {}
+
+    fn restore(&mut self) {}
+
+    fn matrix(&self) -> Matrix3<f32> {
+        Matrix3::<f32>::identity()
+    }
+
+    fn set_matrix(&mut self, _new: &Matrix3<f32>) {}
+
+    fn push_layer(&mut self, _opacity: f32, _blend_mode: BlendMode) {}
+
+    fn pop_layer(&mut self) {}
 
     fn draw_point(&mut self, _point: &Point2<i32>) {}
 
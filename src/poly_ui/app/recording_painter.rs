@@ -0,0 +1,486 @@
+// std
+use std::{cell::RefCell, rc::Rc};
+// deps
+use nalgebra::Matrix3;
+use nalgebra::Point2;
+use nalgebra::Vector2;
+use serde::Deserialize;
+use serde::Serialize;
+// crate
+use crate::poly_ui::components::Transform;
+// super
+use super::BlendMode;
+use super::BoxShadow;
+use super::Brush;
+use super::Font;
+use super::Line;
+use super::PainterStateStack;
+use super::PainterTrait;
+use super::Rect;
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// Single call made on a Painter. Provided trait functions (set_draw_color(), translate(),
+/// scale(), ...) are recorded as the calls they are implemented with (set_brush(),
+/// set_matrix()).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum DrawCommand {
+    /// SubPainter with index `child` was created. `offset` is the resolved position of its
+    /// origin relative to the root Painter.
+    SubPainter {
+        child: usize,
+        transform: Transform,
+        offset: Point2<f32>,
+    },
+    Clear,
+    SetBrush(Brush),
+    Save,
+    Restore,
+    SetMatrix(Matrix3<f32>),
+    PushLayer {
+        opacity: f32,
+        blend_mode: BlendMode,
+    },
+    PopLayer,
+    DrawPoint(Point2<i32>),
+    DrawPoints(Vec<Point2<i32>>),
+    DrawLine(Line),
+    DrawLines(Vec<Line>),
+    DrawRect(Rect),
+    DrawRects(Vec<Rect>),
+    FillRect(Rect),
+    FillRects(Vec<Rect>),
+    DrawBoxShadow(Rect, BoxShadow),
+    DrawText(String, Point2<i32>, Font),
+    BlurRect(Rect, u32),
+}
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// DrawCommand together with the index of the Painter it was issued on. The root Painter has
+/// index 0 and SubPainters get consecutive indices in order of creation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DisplayCommand {
+    pub painter: usize,
+    pub command: DrawCommand,
+}
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// Single difference between two DisplayLists.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DisplayListDiff {
+    /// Command at the given index of the first list is not present in the second one.
+    Removed(usize, DisplayCommand),
+    /// Command at the given index of the second list is not present in the first one.
+    Added(usize, DisplayCommand),
+}
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// Ordered list of all commands recorded by RecordingPainter and its SubPainters.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DisplayList {
+    pub commands: Vec<DisplayCommand>,
+}
+
+//************************************************************************************************
+impl DisplayList {
+    /// Issues all recorded commands on the given painter. SubPainters are recreated from the
+    /// given painter with the recorded transforms, so the result is the same as if the recorded
+    /// widgets were painted with it directly.
+    /// # Arguments
+    /// * `painter` - painter that plays the role of the recorded root Painter
+    /// # Returns
+    /// Error if a command refers to a SubPainter that is not created before it, e.g. in a list
+    /// deserialized from a corrupted file. Commands preceding it are already issued.
+    pub fn replay(&self, painter: &mut dyn PainterTrait) -> Result<(), String> {
+        let mut sub_painters: Vec<Box<dyn PainterTrait>> = vec![];
+
+        for (position, entry) in self.commands.iter().enumerate() {
+            let target: &mut dyn PainterTrait = match entry.painter {
+                0 => &mut *painter,
+                idx => match sub_painters.get_mut(idx - 1) {
+                    Some(sub_painter) => &mut **sub_painter,
+                    None => {
+                        return Err(format!(
+                            "command {} refers to unknown painter {}",
+                            position, idx
+                        ))
+                    }
+                },
+            };
+
+            match &entry.command {
+                DrawCommand::SubPainter { transform, .. } => {
+                    let sub_painter = target.sub_painter(transform);
+                    sub_painters.push(sub_painter);
+                }
+                DrawCommand::Clear => target.clear(),
+                DrawCommand::SetBrush(brush) => target.set_brush(brush),
+                DrawCommand::Save => target.save(),
+                DrawCommand::Restore => target.restore(),
+                DrawCommand::SetMatrix(matrix) => target.set_matrix(matrix),
+                DrawCommand::PushLayer {
+                    opacity,
+                    blend_mode,
+                } => target.push_layer(*opacity, *blend_mode),
+                DrawCommand::PopLayer => target.pop_layer(),
+                DrawCommand::DrawPoint(point) => target.draw_point(point),
+                DrawCommand::DrawPoints(points) => target.draw_points(points),
+                DrawCommand::DrawLine(line) => target.draw_line(line),
+                DrawCommand::DrawLines(lines) => target.draw_lines(lines),
+                DrawCommand::DrawRect(rect) => target.draw_rect(*rect),
+                DrawCommand::DrawRects(rects) => target.draw_rects(rects),
+                DrawCommand::FillRect(rect) => target.fill_rect(*rect),
+                DrawCommand::FillRects(rects) => target.fill_rects(rects),
+                DrawCommand::DrawBoxShadow(rect, shadow) => target.draw_box_shadow(rect, shadow),
+                DrawCommand::DrawText(text, pos, font) => target.draw_text(text, pos, font),
+                DrawCommand::BlurRect(rect, radius) => target.blur_rect(rect, *radius),
+            }
+        }
+        Ok(())
+    }
+
+    /// Computes the shortest list of commands that have to be removed from this list and added
+    /// to it to get the other list.
+    /// # Arguments
+    /// * `other` - list to compare with
+    /// # Returns
+    /// Differences ordered by their position in the lists. Empty if both lists are equal.
+    pub fn diff(&self, other: &DisplayList) -> Vec<DisplayListDiff> {
+        let old = &self.commands;
+        let new = &other.commands;
+
+        // lengths of the longest common subsequences of all suffixes
+        let mut lengths = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+        for i in (0..old.len()).rev() {
+            for j in (0..new.len()).rev() {
+                lengths[i][j] = if old[i] == new[j] {
+                    lengths[i + 1][j + 1] + 1
+                } else {
+                    lengths[i + 1][j].max(lengths[i][j + 1])
+                };
+            }
+        }
+
+        let mut result = vec![];
+        let (mut i, mut j) = (0, 0);
+        while i < old.len() || j < new.len() {
+            if i < old.len() && j < new.len() && old[i] == new[j] {
+                i += 1;
+                j += 1;
+            } else if j < new.len() && (i == old.len() || lengths[i][j + 1] >= lengths[i + 1][j]) {
+                result.push(DisplayListDiff::Added(j, new[j].clone()));
+                j += 1;
+            } else {
+                result.push(DisplayListDiff::Removed(i, old[i].clone()));
+                i += 1;
+            }
+        }
+
+        result
+    }
+}
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// Recording shared by the root RecordingPainter and all its SubPainters.
+#[derive(Debug, Default)]
+struct Recording {
+    list: DisplayList,
+    painter_count: usize,
+}
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// Painter that doesn't paint anything but records every call made on it and on its
+/// SubPainters into a DisplayList. It keeps track of its state so brush() and matrix() return
+/// what was set. Useful for asserting what widgets paint and for caching painted output.
+#[derive(Debug)]
+pub struct RecordingPainter {
+    recording: Rc<RefCell<Recording>>,
+    index: usize,
+    origin: Matrix3<f32>,
+    size: Vector2<u32>,
+    states: PainterStateStack,
+}
+
+//************************************************************************************************
+impl RecordingPainter {
+    /// # Arguments
+    /// * `size` - size reported by the root Painter
+    /// # Returns
+    /// Root RecordingPainter with an empty DisplayList.
+    pub fn new(size: &Vector2<u32>) -> Self {
+        Self {
+            recording: Rc::new(RefCell::new(Recording {
+                list: DisplayList::default(),
+                painter_count: 1,
+            })),
+            index: 0,
+            origin: Matrix3::<f32>::identity(),
+            size: *size,
+            states: PainterStateStack::default(),
+        }
+    }
+
+    /// # Returns
+    /// Copy of everything recorded so far by this Painter and all Painters sharing its
+    /// recording.
+    pub fn display_list(&self) -> DisplayList {
+        self.recording.borrow().list.clone()
+    }
+
+    fn record(&self, command: DrawCommand) {
+        self.recording
+            .borrow_mut()
+            .list
+            .commands
+            .push(DisplayCommand {
+                painter: self.index,
+                command,
+            });
+    }
+}
+
+//************************************************************************************************
+impl PainterTrait for RecordingPainter {
+    fn sub_painter(&self, transform: &Transform) -> Box<dyn PainterTrait> {
+        let origin = self.origin
+            * self.states.current().matrix
+            * Matrix3::<f32>::new_translation(&Vector2::<f32>::new(
+                transform.pos.x as f32,
+                transform.pos.y as f32,
+            ));
+        let child = {
+            let mut recording = self.recording.borrow_mut();
+            recording.painter_count += 1;
+            recording.painter_count - 1
+        };
+        self.record(DrawCommand::SubPainter {
+            child,
+            transform: *transform,
+            offset: origin.transform_point(&Point2::<f32>::new(0.0, 0.0)),
+        });
+
+        Box::new(RecordingPainter {
+            recording: self.recording.clone(),
+            index: child,
+            origin,
+            size: transform.size,
+            states: PainterStateStack::new(&self.states.current().brush),
+        })
+    }
+
+    fn size(&self) -> Vector2<u32> {
+        self.size
+    }
+
+    fn clear(&mut self) {
+        self.record(DrawCommand::Clear);
+    }
+
+    fn brush(&self) -> Brush {
+        self.states.current().brush.clone()
+    }
+
+    fn set_brush(&mut self, new: &Brush) {
+        self.states.current_mut().brush = new.clone();
+        self.record(DrawCommand::SetBrush(new.clone()));
+    }
+
+    fn save(&mut self) {
+        self.states.save();
+        self.record(DrawCommand::Save);
+    }
+
+    fn restore(&mut self) {
+        self.states.restore();
+        self.record(DrawCommand::Restore);
+    }
+
+    fn matrix(&self) -> Matrix3<f32> {
+        self.states.current().matrix
+    }
+
+    fn device_matrix(&self) -> Matrix3<f32> {
+        self.origin * self.states.current().matrix
+    }
+
+    fn set_matrix(&mut self, new: &Matrix3<f32>) {
+        self.states.current_mut().matrix = *new;
+        self.record(DrawCommand::SetMatrix(*new));
+    }
+
+    fn push_layer(&mut self, opacity: f32, blend_mode: BlendMode) {
+        self.record(DrawCommand::PushLayer {
+            opacity,
+            blend_mode,
+        });
+    }
+
+    fn pop_layer(&mut self) {
+        self.record(DrawCommand::PopLayer);
+    }
+
+    fn draw_point(&mut self, point: &Point2<i32>) {
+        self.record(DrawCommand::DrawPoint(*point));
+    }
+
+    fn draw_points(&mut self, points: &[Point2<i32>]) {
+        self.record(DrawCommand::DrawPoints(points.to_vec()));
+    }
+
+    fn draw_line(&mut self, line: &Line) {
+        self.record(DrawCommand::DrawLine(*line));
+    }
+
+    fn draw_lines(&mut self, lines: &[Line]) {
+        self.record(DrawCommand::DrawLines(lines.to_vec()));
+    }
+
+    fn draw_rect(&mut self, rect: Rect) {
+        self.record(DrawCommand::DrawRect(rect));
+    }
+
+    fn draw_rects(&mut self, rects: &[Rect]) {
+        self.record(DrawCommand::DrawRects(rects.to_vec()));
+    }
+
+    fn fill_rect(&mut self, rect: Rect) {
+        self.record(DrawCommand::FillRect(rect));
+    }
+
+    fn fill_rects(&mut self, rects: &[Rect]) {
+        self.record(DrawCommand::FillRects(rects.to_vec()));
+    }
+
+    fn draw_box_shadow(&mut self, rect: &Rect, shadow: &BoxShadow) {
+        self.record(DrawCommand::DrawBoxShadow(*rect, *shadow));
+    }
+
+    fn draw_text(&mut self, text: &str, pos: &Point2<i32>, font: &Font) {
+        self.record(DrawCommand::DrawText(text.to_string(), *pos, *font));
+    }
+
+    fn blur_rect(&mut self, rect: &Rect, radius: u32) {
+        self.record(DrawCommand::BlurRect(*rect, radius));
+    }
+}
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+#[cfg(test)]
+mod tests {
+    // crate
+    use crate::poly_ui::app::Color;
+    use crate::poly_ui::controls::PushButton;
+    use crate::poly_ui::layouts::CanvasLayout;
+    use crate::poly_ui::widgets::WidgetTrait;
+    // super
+    use super::*;
+
+    //********************************************************************************************
+    fn record_button_in_layout() -> DisplayList {
+        let layout = CanvasLayout::new();
+        let button = PushButton::new();
+        layout.borrow_mut().add_child_with_transform(
+            button.make_owned(),
+            &Transform::new(&Point2::<i32>::new(10, 20), &Vector2::<u32>::new(30, 40)),
+        );
+
+        let mut painter = RecordingPainter::new(&Vector2::<u32>::new(100, 100));
+        layout.borrow_mut().paint(&mut painter);
+        painter.display_list()
+    }
+
+    //********************************************************************************************
+    #[test]
+    fn record_sub_painters() {
+        let list = record_button_in_layout();
+
+        assert_eq!(
+            list.commands[0],
+            DisplayCommand {
+                painter: 0,
+                command: DrawCommand::SubPainter {
+                    child: 1,
+                    transform: Transform::new(
+                        &Point2::<i32>::new(10, 20),
+                        &Vector2::<u32>::new(30, 40)
+                    ),
+                    offset: Point2::<f32>::new(10.0, 20.0),
+                },
+            }
+        );
+        assert!(list.commands[1..].iter().all(|entry| entry.painter == 1));
+        assert!(list.commands.contains(&DisplayCommand {
+            painter: 1,
+            command: DrawCommand::DrawRect(Rect {
+                pos: Point2::<i32>::new(0, 0),
+                size: Vector2::<u32>::new(30, 40),
+            }),
+        }));
+    }
+
+    //********************************************************************************************
+    #[test]
+    fn replay() {
+        let list = record_button_in_layout();
+        let mut painter = RecordingPainter::new(&Vector2::<u32>::new(100, 100));
+        list.replay(&mut painter).unwrap();
+
+        assert_eq!(painter.display_list(), list);
+        assert!(list.diff(&painter.display_list()).is_empty());
+
+        let mut corrupted = list.clone();
+        corrupted.commands[0].painter = corrupted.commands.len();
+        let mut painter = RecordingPainter::new(&Vector2::<u32>::new(100, 100));
+        assert!(corrupted.replay(&mut painter).is_err());
+        assert!(painter.display_list().commands.is_empty());
+    }
+
+    //********************************************************************************************
+    #[test]
+    fn diff() {
+        let mut first = RecordingPainter::new(&Vector2::<u32>::new(10, 10));
+        first.clear();
+        first.draw_point(&Point2::<i32>::new(1, 1));
+        let mut second = RecordingPainter::new(&Vector2::<u32>::new(10, 10));
+        second.clear();
+        second.set_draw_color(&Color {
+            r: 255,
+            g: 0,
+            b: 0,
+            a: 255,
+        });
+
+        let diff = first.display_list().diff(&second.display_list());
+        assert_eq!(diff.len(), 2);
+        assert_eq!(
+            diff[0],
+            DisplayListDiff::Added(1, second.display_list().commands[1].clone())
+        );
+        assert_eq!(
+            diff[1],
+            DisplayListDiff::Removed(1, first.display_list().commands[1].clone())
+        );
+    }
+
+    //********************************************************************************************
+    #[test]
+    fn serialize() {
+        let list = record_button_in_layout();
+        let json = serde_json::to_string(&list).unwrap();
+
+        assert_eq!(serde_json::from_str::<DisplayList>(&json).unwrap(), list);
+    }
+}
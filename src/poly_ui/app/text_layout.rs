@@ -0,0 +1,352 @@
+// deps
+use nalgebra::Point2;
+use nalgebra::Vector2;
+use serde::Deserialize;
+use serde::Serialize;
+// super
+use super::Font;
+
+/// Text replacing the elided part of a line.
+const ELLIPSIS: &str = "...";
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum HorizontalAlignment {
+    Left,
+    Center,
+    Right,
+}
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum VerticalAlignment {
+    Top,
+    Center,
+    Bottom,
+}
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// Which part of a line too long to fit is replaced with an ellipsis.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ElideMode {
+    /// Line is clipped.
+    None,
+    Left,
+    Middle,
+    Right,
+}
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// Laid out character.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Glyph {
+    pub ch: char,
+    /// Index of the character in the text, None for characters of an ellipsis.
+    pub index: Option<usize>,
+}
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// Laid out line of text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextLine {
+    /// Index of the character in the text the line starts at.
+    pub start: usize,
+    pub glyphs: Vec<Glyph>,
+}
+
+//************************************************************************************************
+impl TextLine {
+    /// # Returns
+    /// Painted text of the line.
+    pub fn text(&self) -> String {
+        self.glyphs.iter().map(|glyph| glyph.ch).collect()
+    }
+
+    /// # Returns
+    /// Index of the character in the text after the last character of the line.
+    pub fn end(&self) -> usize {
+        self.glyphs
+            .iter()
+            .rev()
+            .find_map(|glyph| glyph.index)
+            .map_or(self.start, |index| index + 1)
+    }
+}
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// Text split into lines painted with a Font. Lines are split at new line characters and, when
+/// wrapping, at spaces (words longer than the width are split anywhere). Without wrapping lines
+/// wider than the width are elided. Characters are indexed in chars, not bytes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextLayout {
+    font: Font,
+    lines: Vec<TextLine>,
+}
+
+//************************************************************************************************
+impl TextLayout {
+    /// # Arguments
+    /// * `text` - laid out text
+    /// * `font` - font of the text
+    /// * `width` - available width in logical pixels, None if lines are never wrapped or elided
+    /// * `wrap` - whether lines wider than the width are wrapped
+    /// * `elide` - how lines wider than the width are elided when they are not wrapped
+    pub fn new(text: &str, font: &Font, width: Option<u32>, wrap: bool, elide: ElideMode) -> Self {
+        let columns = width.map(|width| (width / font.char_width()) as usize);
+        let chars = text.chars().collect::<Vec<char>>();
+        let mut lines = vec![];
+        let mut start = 0;
+        for paragraph in chars.split(|ch| *ch == '\n') {
+            match columns {
+                Some(columns) if wrap => {
+                    wrap_paragraph(paragraph, start, columns.max(1), &mut lines)
+                }
+                Some(columns) => lines.push(elide_line(paragraph, start, columns, elide)),
+                None => lines.push(elide_line(paragraph, start, paragraph.len(), elide)),
+            }
+            start += paragraph.len() + 1;
+        }
+
+        Self { font: *font, lines }
+    }
+
+    pub fn font(&self) -> &Font {
+        &self.font
+    }
+
+    pub fn lines(&self) -> &Vec<TextLine> {
+        &self.lines
+    }
+
+    /// # Returns
+    /// Size of the bounding box of all lines in logical pixels.
+    pub fn size(&self) -> Vector2<u32> {
+        let columns = self
+            .lines
+            .iter()
+            .map(|line| line.glyphs.len())
+            .max()
+            .unwrap_or(0);
+        Vector2::<u32>::new(
+            columns as u32 * self.font.char_width(),
+            self.lines.len() as u32 * self.font.line_height(),
+        )
+    }
+
+    /// Aligns lines in a box.
+    /// # Arguments
+    /// * `size` - size of the box the text is painted in
+    /// * `horizontal` - alignment of every line
+    /// * `vertical` - alignment of all lines together
+    /// # Returns
+    /// Top left corners of all lines.
+    pub fn line_positions(
+        &self,
+        size: &Vector2<u32>,
+        horizontal: HorizontalAlignment,
+        vertical: VerticalAlignment,
+    ) -> Vec<Point2<i32>> {
+        let free_height = size.y as i32 - self.size().y as i32;
+        let top = match vertical {
+            VerticalAlignment::Top => 0,
+            VerticalAlignment::Center => free_height / 2,
+            VerticalAlignment::Bottom => free_height,
+        };
+        self.lines
+            .iter()
+            .enumerate()
+            .map(|(index, line)| {
+                let free_width =
+                    size.x as i32 - (line.glyphs.len() as u32 * self.font.char_width()) as i32;
+                let left = match horizontal {
+                    HorizontalAlignment::Left => 0,
+                    HorizontalAlignment::Center => free_width / 2,
+                    HorizontalAlignment::Right => free_width,
+                };
+                Point2::<i32>::new(left, top + (index as u32 * self.font.line_height()) as i32)
+            })
+            .collect()
+    }
+
+    /// Finds the boundary between characters nearest to the given point.
+    /// # Arguments
+    /// * `positions` - line positions, see line_positions()
+    /// * `point` - point in the box of the text
+    /// # Returns
+    /// Index of the character in the text the boundary is in front of.
+    pub fn hit_test(&self, positions: &[Point2<i32>], point: &Point2<i32>) -> usize {
+        let line_height = self.font.line_height() as i32;
+        let line = positions
+            .iter()
+            .rposition(|pos| point.y >= pos.y)
+            .unwrap_or(0)
+            .min(self.lines.len() - 1);
+        let line_pos = positions[line];
+        let line = &self.lines[line];
+        if point.y >= line_pos.y + line_height && line.glyphs.is_empty() {
+            return line.end();
+        }
+
+        let char_width = self.font.char_width() as i32;
+        let column = (point.x - line_pos.x + char_width / 2).div_euclid(char_width);
+        let column = column.max(0) as usize;
+        if column >= line.glyphs.len() {
+            return line.end();
+        }
+        match line.glyphs[column].index {
+            Some(index) => index,
+            // boundary inside of an ellipsis is after the last character in front of it
+            None => line.glyphs[..column]
+                .iter()
+                .rev()
+                .find_map(|glyph| glyph.index)
+                .map_or(line.start, |index| index + 1),
+        }
+    }
+}
+
+//************************************************************************************************
+fn glyphs(chars: &[char], start: usize) -> Vec<Glyph> {
+    chars
+        .iter()
+        .enumerate()
+        .map(|(offset, ch)| Glyph {
+            ch: *ch,
+            index: Some(start + offset),
+        })
+        .collect()
+}
+
+//************************************************************************************************
+fn ellipsis(columns: usize) -> Vec<Glyph> {
+    ELLIPSIS
+        .chars()
+        .take(columns)
+        .map(|ch| Glyph { ch, index: None })
+        .collect()
+}
+
+//************************************************************************************************
+fn wrap_paragraph(paragraph: &[char], start: usize, columns: usize, lines: &mut Vec<TextLine>) {
+    let mut begin = 0;
+    loop {
+        let rest = &paragraph[begin..];
+        if rest.len() <= columns {
+            lines.push(TextLine {
+                start: start + begin,
+                glyphs: glyphs(rest, start + begin),
+            });
+            return;
+        }
+
+        // break at the last space that fits, the space itself is not painted
+        let (end, next) = match rest[..=columns].iter().rposition(|ch| *ch == ' ') {
+            Some(space) if space > 0 => (space, space + 1),
+            _ => (columns, columns),
+        };
+        lines.push(TextLine {
+            start: start + begin,
+            glyphs: glyphs(&rest[..end], start + begin),
+        });
+        begin += next;
+    }
+}
+
+//************************************************************************************************
+fn elide_line(line: &[char], start: usize, columns: usize, mode: ElideMode) -> TextLine {
+    let ellipsis_len = ELLIPSIS.chars().count();
+    let glyphs = if line.len() <= columns || mode == ElideMode::None {
+        glyphs(line, start)
+    } else if columns <= ellipsis_len {
+        ellipsis(columns)
+    } else {
+        let kept = columns - ellipsis_len;
+        let (head, tail) = match mode {
+            ElideMode::Left => (0, kept),
+            ElideMode::Middle => (kept - kept / 2, kept / 2),
+            _ => (kept, 0),
+        };
+        let tail_start = line.len() - tail;
+        let mut result = glyphs(&line[..head], start);
+        result.extend(ellipsis(ellipsis_len));
+        result.extend(glyphs(&line[tail_start..], start + tail_start));
+        result
+    };
+    TextLine { start, glyphs }
+}
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+#[cfg(test)]
+mod tests {
+    // super
+    use super::*;
+
+    //********************************************************************************************
+    fn texts(layout: &TextLayout) -> Vec<String> {
+        layout.lines().iter().map(|line| line.text()).collect()
+    }
+
+    //********************************************************************************************
+    #[test]
+    fn wrap_and_elide() {
+        let font = Font::default();
+        let text = "one two three\nfour";
+        let layout = TextLayout::new(text, &font, None, true, ElideMode::Right);
+        assert_eq!(texts(&layout), vec!["one two three", "four"]);
+        assert_eq!(layout.size(), Vector2::<u32>::new(78, 18));
+
+        let layout = TextLayout::new(text, &font, Some(48), true, ElideMode::None);
+        assert_eq!(texts(&layout), vec!["one two", "three", "four"]);
+        assert_eq!(layout.lines()[1].start, 8);
+        let layout = TextLayout::new("abcdefghij", &font, Some(24), true, ElideMode::None);
+        assert_eq!(texts(&layout), vec!["abcd", "efgh", "ij"]);
+
+        let elided = |mode: ElideMode| texts(&TextLayout::new(text, &font, Some(42), false, mode));
+        assert_eq!(elided(ElideMode::None), vec!["one two three", "four"]);
+        assert_eq!(elided(ElideMode::Right), vec!["one ...", "four"]);
+        assert_eq!(elided(ElideMode::Left), vec!["...hree", "four"]);
+        assert_eq!(elided(ElideMode::Middle), vec!["on...ee", "four"]);
+    }
+
+    //********************************************************************************************
+    #[test]
+    fn align_and_hit_test() {
+        let font = Font::default();
+        let layout = TextLayout::new("ab\n\nabcd", &font, Some(12), false, ElideMode::Right);
+        assert_eq!(texts(&layout), vec!["ab", "", ".."]);
+
+        let size = Vector2::<u32>::new(20, 37);
+        let positions =
+            layout.line_positions(&size, HorizontalAlignment::Right, VerticalAlignment::Center);
+        assert_eq!(
+            positions,
+            vec![
+                Point2::<i32>::new(8, 5),
+                Point2::<i32>::new(20, 14),
+                Point2::<i32>::new(8, 23)
+            ]
+        );
+
+        assert_eq!(layout.hit_test(&positions, &Point2::<i32>::new(0, 0)), 0);
+        assert_eq!(layout.hit_test(&positions, &Point2::<i32>::new(16, 6)), 1);
+        assert_eq!(layout.hit_test(&positions, &Point2::<i32>::new(19, 6)), 2);
+        assert_eq!(layout.hit_test(&positions, &Point2::<i32>::new(0, 15)), 3);
+        // the whole elided line is hidden by the ellipsis
+        assert_eq!(layout.hit_test(&positions, &Point2::<i32>::new(12, 30)), 4);
+        assert_eq!(layout.hit_test(&positions, &Point2::<i32>::new(30, 50)), 4);
+    }
+}
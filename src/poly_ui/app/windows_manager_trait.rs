@@ -14,19 +14,21 @@ pub trait WindowsManagerTrait {
     /// * `width` - width for the new window
     /// * `height` - height for the new window
     /// # Returns
-    /// Newly created window.
+    /// Newly created window or error description if the backend can't create it.
     fn create_window(
         &mut self,
         title: &str,
         width: u32,
         height: u32,
-    ) -> Rc<RefCell<dyn WindowTrait>>;
+    ) -> Result<Rc<RefCell<dyn WindowTrait>>, String>;
 
     /// Updates all opened windows with provided delta time.
     /// # Arguments
     /// * `dt` - delta time in milliseconds from the last update
     fn update_windows(&mut self, dt: f32);
 
-    /// Paints all opened windows.
-    fn paint_windows(&mut self);
+    /// Paints damaged regions of all opened windows.
+    /// # Returns
+    /// True if any window was painted.
+    fn paint_windows(&mut self) -> bool;
 }
@@ -0,0 +1,5 @@
+mod registry;
+
+pub use registry::AppFactory;
+pub use registry::Registry;
+pub use registry::BACKEND_VAR;
@@ -0,0 +1,152 @@
+// crate
+use crate::poly_ui::app::AppTrait;
+
+/// Environment variable with the name of the backend Registry::create_from_env() uses.
+pub const BACKEND_VAR: &str = "POLY_UI_BACKEND";
+
+/// Creates the App of one backend.
+pub type AppFactory = Box<dyn Fn() -> Result<Box<dyn AppTrait>, String>>;
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// Backends available at runtime by name. The default Registry contains all backends compiled in
+/// with cargo features ("sdl2", "tui") and "headless" which is always available; the framebuffer
+/// backend needs its configuration so it has to be registered by the application.
+pub struct Registry {
+    backends: Vec<(String, AppFactory)>,
+}
+
+//************************************************************************************************
+impl Registry {
+    /// # Returns
+    /// Registry with no backends.
+    pub fn new() -> Self {
+        Self { backends: vec![] }
+    }
+
+    /// Adds the backend after already registered ones or replaces the one with the same name.
+    /// # Arguments
+    /// * `name` - name the backend is selected by
+    /// * `factory` - creates the App of the backend
+    pub fn register(&mut self, name: &str, factory: AppFactory) {
+        match self.backends.iter_mut().find(|(other, _)| other == name) {
+            Some(backend) => backend.1 = factory,
+            None => self.backends.push((name.to_string(), factory)),
+        }
+    }
+
+    /// # Returns
+    /// Names of registered backends in order of preference.
+    pub fn names(&self) -> Vec<&str> {
+        self.backends
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect()
+    }
+
+    /// Creates the App of the backend with the given name or, if there is none, of the first
+    /// backend that can be initialized (e.g. SDL fails without a display).
+    /// # Arguments
+    /// * `name` - name of the backend, None to pick the first one that works
+    /// # Returns
+    /// App or error description if the backend is unknown or no backend could be initialized.
+    pub fn create(&self, name: Option<&str>) -> Result<Box<dyn AppTrait>, String> {
+        if let Some(name) = name {
+            return match self.backends.iter().find(|(other, _)| other == name) {
+                Some((_, factory)) => factory().map_err(|error| format!("{}: {}", name, error)),
+                None => Err(format!(
+                    "unknown backend {}, available: {}",
+                    name,
+                    self.names().join(", ")
+                )),
+            };
+        }
+
+        let mut errors = vec![];
+        for (name, factory) in &self.backends {
+            match factory() {
+                Ok(app) => return Ok(app),
+                Err(error) => errors.push(format!("{}: {}", name, error)),
+            }
+        }
+        if errors.is_empty() {
+            errors.push("no backends registered".to_string());
+        }
+        Err(errors.join("; "))
+    }
+
+    /// Creates the App of the backend named by the BACKEND_VAR environment variable or of the
+    /// first one that works if it's not set. See create().
+    pub fn create_from_env(&self) -> Result<Box<dyn AppTrait>, String> {
+        self.create(std::env::var(BACKEND_VAR).ok().as_deref())
+    }
+}
+
+//************************************************************************************************
+impl Default for Registry {
+    fn default() -> Self {
+        let mut registry = Self::new();
+        #[cfg(feature = "sdl2")]
+        registry.register(
+            "sdl2",
+            Box::new(|| {
+                let app =
+                    crate::poly_ui::sdl2::App::new(crate::poly_ui::sdl2::AppConfig::default())?;
+                Ok(Box::new(app) as Box<dyn AppTrait>)
+            }),
+        );
+        #[cfg(feature = "tui")]
+        registry.register(
+            "tui",
+            Box::new(|| Ok(Box::new(crate::poly_ui::tui::App::new()?) as Box<dyn AppTrait>)),
+        );
+        registry.register(
+            "headless",
+            Box::new(|| Ok(Box::new(crate::poly_ui::headless::App::new()) as Box<dyn AppTrait>)),
+        );
+        registry
+    }
+}
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+#[cfg(test)]
+mod tests {
+    // super
+    use super::*;
+
+    //********************************************************************************************
+    #[test]
+    fn select_backend() {
+        let mut registry = Registry::new();
+        assert!(registry.create(None).is_err());
+
+        registry.register("broken", Box::new(|| Err("no display".to_string())));
+        registry.register(
+            "headless",
+            Box::new(|| Ok(Box::new(crate::poly_ui::headless::App::new()) as Box<dyn AppTrait>)),
+        );
+        assert_eq!(registry.names(), vec!["broken", "headless"]);
+
+        // the first backend that works is picked
+        let mut app = registry.create(None).unwrap();
+        app.get_windows_manager()
+            .create_window("test", 4, 4)
+            .unwrap();
+        assert_eq!(app.exec(), Ok(()));
+
+        assert_eq!(
+            registry.create(Some("broken")).err(),
+            Some("broken: no display".to_string())
+        );
+        assert_eq!(
+            registry.create(Some("none")).err(),
+            Some("unknown backend none, available: broken, headless".to_string())
+        );
+        registry.register("broken", Box::new(|| Err("still broken".to_string())));
+        assert_eq!(registry.names().len(), 2);
+        assert!(Registry::default().names().contains(&"headless"));
+    }
+}
@@ -0,0 +1,339 @@
+// std
+use std::{cell::RefCell, rc::Rc, rc::Weak};
+// deps
+use nalgebra::Point2;
+use nalgebra::Vector2;
+// crate
+use crate::poly_ui::app::Color;
+use crate::poly_ui::app::PainterTrait;
+use crate::poly_ui::app::Rect;
+use crate::poly_ui::widgets::WidgetTrait;
+// super
+use super::Transform;
+
+/// If more damaged rects than this are accumulated they are replaced with their bounding box.
+const MAX_DAMAGE_RECTS: usize = 16;
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// DamageTracker collects regions of a Widget that have to be repainted. Every Hierarchy owns one
+/// and when a child is added to the Hierarchy, the tracker of the child Hierarchy is attached to
+/// the tracker of the parent. Damage added to an attached tracker is clipped to the child rect,
+/// translated to the parent coordinates and passed to the parent. Only trackers without parent
+/// (the one of the Window Layout Widget) accumulate damage. Every tracker also counts the damage
+/// it received, so caches of the Widget can tell whether it changed since they were painted.
+#[derive(Debug, Default)]
+pub struct DamageTracker {
+    parent: Option<Weak<RefCell<DamageTracker>>>,
+    transform: Transform,
+    rects: Vec<Rect>,
+    version: u64,
+}
+
+//************************************************************************************************
+impl DamageTracker {
+    /// Attaches this tracker to the parent one and damages the whole child rect in the parent.
+    /// Damage accumulated so far is dropped because the parent will repaint the whole child.
+    /// # Arguments
+    /// * `parent` - tracker of the parent Hierarchy
+    /// * `transform` - Transform of the child in the parent
+    pub fn attach(&mut self, parent: &Rc<RefCell<DamageTracker>>, transform: &Transform) {
+        self.parent = Some(Rc::downgrade(parent));
+        self.transform = *transform;
+        self.rects.clear();
+        parent.borrow_mut().add(&transform_rect(transform));
+    }
+
+    /// Detaches this tracker from its parent and damages the area the child covered in it.
+    pub fn detach(&mut self) {
+        if let Some(parent) = self.parent.take().and_then(|parent| parent.upgrade()) {
+            parent.borrow_mut().add(&transform_rect(&self.transform));
+        }
+    }
+
+    /// Changes Transform of the child in the parent. If it differs from the previous one both
+    /// the old and the new area are damaged in the parent.
+    /// # Arguments
+    /// * `transform` - new Transform of the child in the parent
+    pub fn set_transform(&mut self, transform: &Transform) {
+        if self.transform == *transform {
+            return;
+        }
+
+        let old = self.transform;
+        self.transform = *transform;
+        if let Some(parent) = self.parent.as_ref().and_then(|parent| parent.upgrade()) {
+            let mut parent = parent.borrow_mut();
+            parent.add(&transform_rect(&old));
+            parent.add(&transform_rect(transform));
+        }
+    }
+
+    /// Damages the whole Widget.
+    pub fn add_all(&mut self) {
+        if self.parent.is_some() {
+            self.add(&Rect {
+                pos: Point2::<i32>::new(0, 0),
+                size: self.transform.size,
+            });
+        } else {
+            // the root doesn't know its size; its damage is clipped by the Window
+            self.add(&Rect {
+                pos: Point2::<i32>::new(0, 0),
+                size: Vector2::<u32>::new(u32::MAX / 4, u32::MAX / 4),
+            });
+        }
+    }
+
+    /// Damages given rect of the Widget.
+    /// # Arguments
+    /// * `rect` - damaged rect in the Widget coordinates
+    pub fn add(&mut self, rect: &Rect) {
+        if rect.size.x == 0 || rect.size.y == 0 {
+            return;
+        }
+        self.version += 1;
+
+        match self.parent.as_ref().and_then(|parent| parent.upgrade()) {
+            Some(parent) => {
+                let local = Rect {
+                    pos: Point2::<i32>::new(0, 0),
+                    size: self.transform.size,
+                };
+                if let Some(clipped) = local.intersection(rect) {
+                    parent.borrow_mut().add(&Rect {
+                        pos: clipped.pos + self.transform.pos.coords,
+                        size: clipped.size,
+                    });
+                }
+            }
+            None => {
+                if self
+                    .rects
+                    .iter()
+                    .any(|damaged| contains_rect(damaged, rect))
+                {
+                    return;
+                }
+                self.rects.retain(|damaged| !contains_rect(rect, damaged));
+                self.rects.push(*rect);
+                if self.rects.len() > MAX_DAMAGE_RECTS {
+                    let bounds = self
+                        .rects
+                        .iter()
+                        .skip(1)
+                        .fold(self.rects[0], |acc, rect| bounding_rect(&acc, rect));
+                    self.rects = vec![bounds];
+                }
+            }
+        }
+    }
+
+    /// # Returns
+    /// Number of damage additions to this tracker. It changes whenever the Widget or any of its
+    /// descendants is damaged.
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    /// # Returns
+    /// True if there is any accumulated damage.
+    pub fn is_damaged(&self) -> bool {
+        !self.rects.is_empty()
+    }
+
+    /// Takes accumulated damage. Overlapping rects are merged into their bounding boxes.
+    /// # Returns
+    /// Damaged rects, empty if nothing has to be repainted.
+    pub fn take(&mut self) -> Vec<Rect> {
+        let mut rects: Vec<Rect> = vec![];
+        for rect in self.rects.drain(..) {
+            let mut merged = rect;
+            // merging may make the rect overlap with rects that were merged before
+            loop {
+                let (overlapping, rest): (Vec<Rect>, Vec<Rect>) = rects
+                    .into_iter()
+                    .partition(|other| merged.intersection(other).is_some());
+                rects = rest;
+                if overlapping.is_empty() {
+                    break;
+                }
+                merged = overlapping
+                    .iter()
+                    .fold(merged, |acc, rect| bounding_rect(&acc, rect));
+            }
+            rects.push(merged);
+        }
+        rects
+    }
+}
+
+//************************************************************************************************
+/// Paints the widget only in the given regions. Every region is cleared with the background
+/// color and the widget is painted with a SubPainter clipped to the region, so only children
+/// intersecting the region are painted.
+/// # Arguments
+/// * `widget` - widget painted at position (0, 0) of the painter
+/// * `painter` - painter of the whole widget
+/// * `regions` - rects in the widget coordinates that have to be repainted
+/// * `background` - color the regions are cleared with
+pub fn paint_damage(
+    widget: &mut dyn WidgetTrait,
+    painter: &mut dyn PainterTrait,
+    regions: &[Rect],
+    background: &Color,
+) {
+    for region in regions {
+        let mut sub_painter = painter.sub_painter(&Transform::new(&region.pos, &region.size));
+        sub_painter.set_draw_color(background);
+        sub_painter.clear();
+        // keep widget coordinates, the sub painter only clips
+        sub_painter.translate(-region.pos.x as f32, -region.pos.y as f32);
+        let mut widget_painter =
+            sub_painter.sub_painter(&Transform::new(&Point2::<i32>::new(0, 0), &painter.size()));
+        widget.paint(&mut *widget_painter);
+    }
+}
+
+//************************************************************************************************
+fn transform_rect(transform: &Transform) -> Rect {
+    Rect {
+        pos: transform.pos,
+        size: transform.size,
+    }
+}
+
+//************************************************************************************************
+fn contains_rect(outer: &Rect, inner: &Rect) -> bool {
+    inner.pos.x >= outer.pos.x
+        && inner.pos.y >= outer.pos.y
+        && inner.pos.x as i64 + inner.size.x as i64 <= outer.pos.x as i64 + outer.size.x as i64
+        && inner.pos.y as i64 + inner.size.y as i64 <= outer.pos.y as i64 + outer.size.y as i64
+}
+
+//************************************************************************************************
+fn bounding_rect(first: &Rect, second: &Rect) -> Rect {
+    let left = first.pos.x.min(second.pos.x);
+    let top = first.pos.y.min(second.pos.y);
+    let right = (first.pos.x + first.size.x as i32).max(second.pos.x + second.size.x as i32);
+    let bottom = (first.pos.y + first.size.y as i32).max(second.pos.y + second.size.y as i32);
+    Rect {
+        pos: Point2::<i32>::new(left, top),
+        size: Vector2::<u32>::new((right - left) as u32, (bottom - top) as u32),
+    }
+}
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+#[cfg(test)]
+mod tests {
+    // crate
+    use crate::poly_ui::layouts::CanvasLayout;
+    use crate::poly_ui::software::Painter;
+    use crate::poly_ui::widgets::MockWidget;
+    // super
+    use super::*;
+
+    //********************************************************************************************
+    fn rect(x: i32, y: i32, w: u32, h: u32) -> Rect {
+        Rect {
+            pos: Point2::<i32>::new(x, y),
+            size: Vector2::<u32>::new(w, h),
+        }
+    }
+
+    //********************************************************************************************
+    #[test]
+    fn propagate_to_root() {
+        let root = Rc::new(RefCell::new(DamageTracker::default()));
+        let child = Rc::new(RefCell::new(DamageTracker::default()));
+        let grandchild = Rc::new(RefCell::new(DamageTracker::default()));
+        child.borrow_mut().attach(
+            &root,
+            &Transform::new(&Point2::<i32>::new(10, 10), &Vector2::<u32>::new(50, 50)),
+        );
+        grandchild.borrow_mut().attach(
+            &child,
+            &Transform::new(&Point2::<i32>::new(5, 5), &Vector2::<u32>::new(10, 10)),
+        );
+        assert_eq!(root.borrow_mut().take(), vec![rect(10, 10, 50, 50)]);
+        assert!(!root.borrow().is_damaged());
+
+        grandchild.borrow_mut().add(&rect(8, 8, 10, 10));
+        assert_eq!(root.borrow_mut().take(), vec![rect(23, 23, 2, 2)]);
+
+        grandchild.borrow_mut().set_transform(&Transform::new(
+            &Point2::<i32>::new(5, 5),
+            &Vector2::<u32>::new(10, 10),
+        ));
+        assert!(!root.borrow().is_damaged());
+        grandchild.borrow_mut().set_transform(&Transform::new(
+            &Point2::<i32>::new(0, 0),
+            &Vector2::<u32>::new(10, 10),
+        ));
+        assert_eq!(root.borrow_mut().take(), vec![rect(10, 10, 15, 15)]);
+
+        child.borrow_mut().detach();
+        grandchild.borrow_mut().add_all();
+        assert_eq!(root.borrow_mut().take(), vec![rect(10, 10, 50, 50)]);
+    }
+
+    //********************************************************************************************
+    #[test]
+    fn merge_rects() {
+        let mut root = DamageTracker::default();
+        root.add(&rect(0, 0, 10, 10));
+        root.add(&rect(2, 2, 2, 2));
+        root.add(&rect(20, 0, 10, 10));
+        root.add(&rect(5, 5, 10, 10));
+        assert_eq!(root.take(), vec![rect(20, 0, 10, 10), rect(0, 0, 15, 15)]);
+
+        for i in 0..MAX_DAMAGE_RECTS as i32 + 1 {
+            root.add(&rect(i * 10, 0, 5, 5));
+        }
+        assert_eq!(root.take(), vec![rect(0, 0, 165, 5)]);
+    }
+
+    //********************************************************************************************
+    #[test]
+    fn paint_only_damaged_children() {
+        let layout = CanvasLayout::new();
+        let first = MockWidget::new();
+        let second = MockWidget::new();
+        let first_ptr = first.get().clone();
+        let second_ptr = second.get().clone();
+        layout.borrow_mut().add_child_with_transform(
+            first.make_owned(),
+            &Transform::new(&Point2::<i32>::new(0, 0), &Vector2::<u32>::new(10, 10)),
+        );
+        layout.borrow_mut().add_child_with_transform(
+            second.make_owned(),
+            &Transform::new(&Point2::<i32>::new(20, 0), &Vector2::<u32>::new(10, 10)),
+        );
+        layout.borrow().get_hierarchy().damage().borrow_mut().take();
+
+        second_ptr.borrow().request_repaint_rect(&rect(2, 2, 3, 3));
+        let regions = layout.borrow().get_hierarchy().damage().borrow_mut().take();
+        assert_eq!(regions, vec![rect(22, 2, 3, 3)]);
+
+        let mut painter = Painter::new(&Vector2::<u32>::new(30, 10));
+        let background = Color {
+            r: 0,
+            g: 0,
+            b: 255,
+            a: 255,
+        };
+        paint_damage(
+            &mut *layout.borrow_mut(),
+            &mut painter,
+            &regions,
+            &background,
+        );
+        assert_eq!(first_ptr.borrow().paint_call_count, 0);
+        assert_eq!(second_ptr.borrow().paint_call_count, 1);
+        assert_eq!(painter.image().pixel(22, 2), background);
+        assert_eq!(painter.image().pixel(21, 2).a, 0);
+    }
+}
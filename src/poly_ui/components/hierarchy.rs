@@ -1,13 +1,17 @@
 // std
-use std::fmt::Debug;
+use std::{cell::Cell, cell::RefCell, fmt::Debug, rc::Rc};
 // deps
 use nalgebra::Point2;
 use nalgebra::Vector2;
 use uuid::Uuid;
 // crate
 use crate::poly_ui::app::PainterTrait;
+use crate::poly_ui::app::Rect;
+use crate::poly_ui::events::Event;
 use crate::poly_ui::widgets::OwnedWidget;
 // super
+use super::DamageTracker;
+use super::PaintCache;
 use super::Transform;
 
 //************************************************************************************************
@@ -31,9 +35,24 @@ pub struct HierarchyChild {
 /// counterintuitive to handle it this way fot the Widget developer. Child widgets are painted
 /// from first index to the last so the last widget is drawn on top of every other widget in this
 /// hierarhcy (which might be the case in CanvasLayout).
+///
+/// Hierarchy also owns the DamageTracker of its Widget. Trackers of children are attached to it
+/// so repaint requests of any Widget get to the Window. The PaintCache of the Widget is stored
+/// here as well and it's used by the parent Hierarchy when painting the Widget.
+///
+/// For event dispatching Hierarchy remembers the child under the mouse, the child that grabbed
+/// the mouse by handling a press and the child on the path to the focused Widget. Widgets may
+/// request focus while handling a key event; the focus is moved to them when the event returns
+/// to the Hierarchies of their ancestors.
 #[derive(Debug, Default)]
 pub struct Hierarchy {
     children: Vec<HierarchyChild>,
+    damage: Rc<RefCell<DamageTracker>>,
+    cache: Rc<RefCell<PaintCache>>,
+    hovered: Cell<Option<Uuid>>,
+    grabbed: Cell<Option<Uuid>>,
+    focused: Cell<Option<Uuid>>,
+    focus_requested: Cell<bool>,
 }
 
 //************************************************************************************************
@@ -44,12 +63,7 @@ impl Hierarchy {
     /// # Arguments
     /// * `child` - Widget to add
     pub fn add(&mut self, child: OwnedWidget) {
-        assert_eq!(self.index(child.borrow().id()).is_none(), true);
-
-        self.children.push(HierarchyChild {
-            widget: child,
-            transform: Transform::default(),
-        });
+        self.add_with_transform(child, &Transform::default());
     }
 
     /// Adds provided OwnedWidget Widget to the hierarchy and sets its transform to provided one.
@@ -61,6 +75,12 @@ impl Hierarchy {
     pub fn add_with_transform(&mut self, child: OwnedWidget, transform: &Transform) {
         assert_eq!(self.index(child.borrow().id()).is_none(), true);
 
+        child
+            .borrow()
+            .get_hierarchy()
+            .damage()
+            .borrow_mut()
+            .attach(&self.damage, transform);
         self.children.push(HierarchyChild {
             widget: child,
             transform: *transform,
@@ -74,7 +94,19 @@ impl Hierarchy {
     /// # Returns
     /// OwnedWidget Widget that was removed from the Hierarchy.
     pub fn remove(&mut self, id: &Uuid) -> OwnedWidget {
-        self.children.remove(self.index(id).unwrap()).widget
+        let child = self.children.remove(self.index(id).unwrap()).widget;
+        for state in &[&self.hovered, &self.grabbed, &self.focused] {
+            if state.get() == Some(*id) {
+                state.set(None);
+            }
+        }
+        child
+            .borrow()
+            .get_hierarchy()
+            .damage()
+            .borrow_mut()
+            .detach();
+        child
     }
 
     /// Sets position of the Widget with the provided id.
@@ -84,6 +116,7 @@ impl Hierarchy {
     pub fn set_pos(&mut self, id: &Uuid, pos: &Point2<i32>) {
         let idx = self.index(id).unwrap();
         self.children[idx].transform.pos = *pos;
+        self.update_child_damage(idx);
     }
 
     /// Sets size of the Widget with the provided id.
@@ -93,6 +126,7 @@ impl Hierarchy {
     pub fn set_size(&mut self, id: &Uuid, size: &Vector2<u32>) {
         let idx = self.index(id).unwrap();
         self.children[idx].transform.size = *size;
+        self.update_child_damage(idx);
     }
 
     /// Sets the whole Transform of the Widget with the provided id.
@@ -102,6 +136,7 @@ impl Hierarchy {
     pub fn set_transform(&mut self, id: &Uuid, transform: &Transform) {
         let idx = self.index(id).unwrap();
         self.children[idx].transform = *transform;
+        self.update_child_damage(idx);
     }
 
     /// # Arguments
@@ -130,6 +165,38 @@ impl Hierarchy {
         &self.children[self.index(id).unwrap()].transform
     }
 
+    /// # Returns
+    /// DamageTracker of the Widget owning this Hierarchy.
+    pub fn damage(&self) -> &Rc<RefCell<DamageTracker>> {
+        &self.damage
+    }
+
+    /// Requests repaint of the whole Widget owning this Hierarchy.
+    pub fn request_repaint(&self) {
+        self.damage.borrow_mut().add_all();
+    }
+
+    /// Requests repaint of the given rect of the Widget owning this Hierarchy.
+    /// # Arguments
+    /// * `rect` - rect in the Widget coordinates
+    pub fn request_repaint_rect(&self, rect: &Rect) {
+        self.damage.borrow_mut().add(rect);
+    }
+
+    /// # Returns
+    /// PaintCache of the Widget owning this Hierarchy.
+    pub fn cache(&self) -> &Rc<RefCell<PaintCache>> {
+        &self.cache
+    }
+
+    /// Marks the Widget owning this Hierarchy as cacheable. Painted output of cacheable Widgets
+    /// (including their children) is kept and reused until they are damaged or resized.
+    /// # Arguments
+    /// * `cacheable` - true if the Widget should be cached
+    pub fn set_cacheable(&self, cacheable: bool) {
+        self.cache.borrow_mut().set_enabled(cacheable);
+    }
+
     /// Helper function for updating all children in the hierarchy.
     /// # Arguments
     /// * `dt` - delta time from the last update in milliseconds
@@ -139,14 +206,229 @@ impl Hierarchy {
         }
     }
 
-    /// Helper function for painting all children in the hierarchy.
+    /// Helper function for painting all children in the hierarchy. Cacheable children are painted
+    /// through their PaintCache.
     /// # Arguments
     /// * `parent_canvas` - Canvas which is used to paint parent widget
     pub fn paint_children(&self, parent_canvas: &mut dyn PainterTrait) {
+        let visible = parent_canvas.visible_rect();
         for child in self.children() {
+            if let Some(visible) = &visible {
+                let rect = Rect {
+                    pos: child.transform.pos,
+                    size: child.transform.size,
+                };
+                if visible.intersection(&rect).is_none() {
+                    continue;
+                }
+            }
+
             let mut borrowed_child = child.widget.get().borrow_mut();
             let mut sub_canvas = parent_canvas.sub_painter(&child.transform);
-            borrowed_child.paint(&mut *sub_canvas);
+            let cache = borrowed_child.get_hierarchy().cache().clone();
+            if cache.borrow().is_enabled() {
+                cache
+                    .borrow_mut()
+                    .paint(&mut *borrowed_child, &mut *sub_canvas);
+            } else {
+                borrowed_child.paint(&mut *sub_canvas);
+            }
+        }
+    }
+
+    /// Helper function for passing an event to children, the last (topmost) child gets it first.
+    /// Mouse events are passed only to children under the mouse with the position translated to
+    /// their coordinates, except for the child that handled a mouse press; it gets all mouse
+    /// events but the wheel until the release. Key and text input events are passed only to the child with
+    /// focus. Focus is moved to the child that handled a mouse press if it accepts focus or
+    /// contains the focused Widget. MouseLeave, FocusIn and FocusOut events are generated for
+    /// children as needed.
+    /// # Arguments
+    /// * `event` - event in coordinates of the Widget owning this Hierarchy
+    /// # Returns
+    /// True if any child handled the event.
+    pub fn dispatch_event(&self, event: &Event) -> bool {
+        match event {
+            Event::MouseLeave => match self.hovered.take() {
+                Some(id) => self.send_event(&id, event),
+                None => false,
+            },
+            Event::FocusIn => false,
+            Event::FocusOut => match self.focused.take() {
+                Some(id) => self.send_event(&id, event),
+                None => false,
+            },
+            Event::KeyPress(_) | Event::KeyReleaseEvent(_) | Event::TextInput(_) => {
+                let handled = match self.focused.get() {
+                    Some(id) => self.send_event(&id, event),
+                    None => false,
+                };
+                // the parent moves its focus to the Widget owning this Hierarchy as well
+                if self.apply_focus_requests() {
+                    self.focus_requested.set(true);
+                }
+                handled
+            }
+            Event::MouseMoveEvent(movement) => {
+                let hovered = self.child_at(&movement.pos());
+                if let Some(id) = self.hovered.replace(hovered) {
+                    if Some(id) != hovered {
+                        self.send_event(&id, &Event::MouseLeave);
+                    }
+                }
+                match self.grabbed.get() {
+                    Some(id) => self.send_event(&id, event),
+                    None => self.dispatch_at_pos(event).is_some(),
+                }
+            }
+            Event::MousePressEvent(_) => {
+                if let Some(id) = self.grabbed.get() {
+                    return self.send_event(&id, event);
+                }
+                let id = match self.dispatch_at_pos(event) {
+                    Some(id) => id,
+                    None => return false,
+                };
+                self.grabbed.set(Some(id));
+                let takes_focus = match self.index(&id) {
+                    Some(index) => {
+                        let child = self.children[index].widget.borrow();
+                        child.accepts_focus() || child.get_hierarchy().focused().is_some()
+                    }
+                    None => false,
+                };
+                if takes_focus {
+                    self.set_focus(Some(id));
+                }
+                true
+            }
+            Event::MouseReleaseEvent(_) => match self.grabbed.take() {
+                Some(id) => self.send_event(&id, event),
+                None => self.dispatch_at_pos(event).is_some(),
+            },
+            Event::MouseWheel(_) => self.dispatch_at_pos(event).is_some(),
+        }
+    }
+
+    /// # Returns
+    /// Id of the child that has focus or contains the focused Widget.
+    pub fn focused(&self) -> Option<Uuid> {
+        self.focused.get()
+    }
+
+    /// Moves focus to the child. The child that had it before gets FocusOut and the new one
+    /// gets FocusIn if it accepts focus and doesn't contain the focused Widget.
+    /// # Arguments
+    /// * `id` - id of the child getting focus, None to clear it
+    pub fn set_focus(&self, id: Option<Uuid>) {
+        let old = self.focused.replace(id);
+        if old == id {
+            return;
+        }
+        if let Some(old) = &old {
+            self.send_event(old, &Event::FocusOut);
+        }
+        let index = match id.and_then(|id| self.index(&id)) {
+            Some(index) => index,
+            None => return,
+        };
+        let mut child = self.children[index].widget.get().borrow_mut();
+        if child.accepts_focus() && child.get_hierarchy().focused().is_none() {
+            child.handle_event(&Event::FocusIn);
+        }
+    }
+
+    /// Requests focus for the Widget owning this Hierarchy. Hierarchies of its ancestors move
+    /// the focus to it after they dispatch the current key event.
+    pub fn request_focus(&self) {
+        self.focus_requested.set(true);
+    }
+
+    /// Moves focus to the child that requested it or contains a Widget that requested it.
+    /// # Returns
+    /// True if any child requested focus.
+    fn apply_focus_requests(&self) -> bool {
+        let mut requested = None;
+        for child in self.children() {
+            let hierarchy_requested = {
+                let widget = child.widget.borrow();
+                let hierarchy = widget.get_hierarchy();
+                let descendant = hierarchy.apply_focus_requests();
+                hierarchy.focus_requested.replace(false) || descendant
+            };
+            if hierarchy_requested {
+                requested = Some(*child.widget.borrow().id());
+            }
         }
+        if requested.is_some() {
+            self.set_focus(requested);
+        }
+        requested.is_some()
+    }
+
+    /// # Returns
+    /// Id of the topmost child under the given position.
+    fn child_at(&self, pos: &Point2<i32>) -> Option<Uuid> {
+        self.children()
+            .iter()
+            .rev()
+            .find(|child| {
+                Rect {
+                    pos: child.transform.pos,
+                    size: child.transform.size,
+                }
+                .contains(pos)
+            })
+            .map(|child| *child.widget.borrow().id())
+    }
+
+    /// Passes the mouse event to children under the mouse until one handles it.
+    /// # Returns
+    /// Id of the child that handled the event.
+    fn dispatch_at_pos(&self, event: &Event) -> Option<Uuid> {
+        let pos = event.pos()?;
+        for child in self.children().iter().rev() {
+            let rect = Rect {
+                pos: child.transform.pos,
+                size: child.transform.size,
+            };
+            if !rect.contains(&pos) {
+                continue;
+            }
+            let event = event.with_pos(&(pos - child.transform.pos.coords));
+            let mut widget = child.widget.get().borrow_mut();
+            if widget.handle_event(&event) {
+                return Some(*widget.id());
+            }
+        }
+        None
+    }
+
+    /// Passes the event to the child with the given id, mouse position is translated to its
+    /// coordinates.
+    /// # Returns
+    /// True if the child exists and handled the event.
+    fn send_event(&self, id: &Uuid, event: &Event) -> bool {
+        let child = match self.index(id) {
+            Some(index) => &self.children[index],
+            None => return false,
+        };
+        let event = match event.pos() {
+            Some(pos) => event.with_pos(&(pos - child.transform.pos.coords)),
+            None => *event,
+        };
+        child.widget.get().borrow_mut().handle_event(&event)
+    }
+
+    /// Passes new Transform of the child with the given index to its DamageTracker.
+    fn update_child_damage(&self, idx: usize) {
+        let child = &self.children[idx];
+        child
+            .widget
+            .borrow()
+            .get_hierarchy()
+            .damage()
+            .borrow_mut()
+            .set_transform(&child.transform);
     }
 }
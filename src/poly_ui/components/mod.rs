@@ -1,5 +1,15 @@
+mod damage;
 mod hierarchy;
+mod paint_cache;
+mod scale;
+mod signal;
 mod transform;
 
+pub use damage::paint_damage;
+pub use damage::DamageTracker;
 pub use hierarchy::Hierarchy;
+pub use paint_cache::PaintCache;
+pub use scale::{logical_painter, logical_size};
+pub use signal::Signal;
+pub use signal::SlotId;
 pub use transform::Transform;
@@ -0,0 +1,236 @@
+// std
+use std::rc::Rc;
+// deps
+use nalgebra::Matrix3;
+use nalgebra::Point2;
+use nalgebra::Vector2;
+// crate
+use crate::poly_ui::app::Image;
+use crate::poly_ui::app::ImageFill;
+use crate::poly_ui::app::PainterTrait;
+use crate::poly_ui::app::Rect;
+use crate::poly_ui::software::Painter;
+use crate::poly_ui::widgets::WidgetTrait;
+// super
+use super::Transform;
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// PaintCache stores painted output of a Widget including all its children. Every Hierarchy owns
+/// one; when it's enabled the parent Hierarchy paints the Widget with a single image draw as long
+/// as neither the Widget nor any of its descendants was damaged and its size didn't change.
+/// Output is painted offscreen with the software Painter in pixels of the device (e.g. twice the
+/// logical size on a HiDPI screen), so it's drawn back 1:1 and looks the same as the Widget
+/// painted directly.
+#[derive(Debug, Default)]
+pub struct PaintCache {
+    enabled: bool,
+    image: Option<Rc<Image>>,
+    version: u64,
+}
+
+//************************************************************************************************
+impl PaintCache {
+    /// # Returns
+    /// True if the Widget should be painted through this cache.
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Enables or disables caching. Disabling it drops the cached image.
+    /// # Arguments
+    /// * `enabled` - true if the Widget should be painted through this cache
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        if !enabled {
+            self.invalidate();
+        }
+    }
+
+    /// Drops the cached image so the Widget is painted again the next time.
+    pub fn invalidate(&mut self) {
+        self.image = None;
+    }
+
+    /// # Returns
+    /// Currently cached image, None if there is none.
+    pub fn image(&self) -> Option<&Rc<Image>> {
+        self.image.as_ref()
+    }
+
+    /// Paints the Widget from the cached image. The image is painted again first if it's missing,
+    /// if the Widget was damaged since it was painted or if the size of the painter or its scale
+    /// to the device changed.
+    /// # Arguments
+    /// * `widget` - cached Widget
+    /// * `painter` - painter of the Widget
+    pub fn paint(&mut self, widget: &mut dyn WidgetTrait, painter: &mut dyn PainterTrait) {
+        let size = painter.size();
+        let scale = device_scale(&painter.device_matrix());
+        let physical = Vector2::<u32>::new(
+            (size.x as f32 * scale).ceil() as u32,
+            (size.y as f32 * scale).ceil() as u32,
+        );
+        let version = widget.get_hierarchy().damage().borrow().version();
+        let valid = match &self.image {
+            Some(image) => image.size() == physical && self.version == version,
+            None => false,
+        };
+
+        if !valid {
+            let mut offscreen = Painter::new(&physical);
+            offscreen.scale(scale, scale);
+            let mut logical =
+                offscreen.sub_painter(&Transform::new(&Point2::<i32>::new(0, 0), &size));
+            widget.paint(&mut *logical);
+            self.image = Some(Rc::new(offscreen.image().clone()));
+            // painting may damage the Widget (e.g. layouts placing children for the first time)
+            self.version = widget.get_hierarchy().damage().borrow().version();
+        }
+
+        if let Some(image) = &self.image {
+            if image.size() == size {
+                painter.draw_image(image, &Point2::<i32>::new(0, 0));
+            } else {
+                let rect = Rect {
+                    pos: Point2::<i32>::new(0, 0),
+                    size,
+                };
+                painter.draw_image_rect(image, &rect, ImageFill::Stretch);
+            }
+        }
+    }
+}
+
+//************************************************************************************************
+/// # Arguments
+/// * `matrix` - matrix from painter coordinates to device pixels
+/// # Returns
+/// Number of device pixels per painter unit, 1 for degenerate matrices.
+fn device_scale(matrix: &Matrix3<f32>) -> f32 {
+    let determinant = matrix[(0, 0)] * matrix[(1, 1)] - matrix[(0, 1)] * matrix[(1, 0)];
+    let scale = determinant.abs().sqrt();
+    if scale.is_finite() && scale > 0.0 {
+        scale
+    } else {
+        1.0
+    }
+}
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+#[cfg(test)]
+mod tests {
+    // deps
+    use nalgebra::Vector2;
+    // crate
+    use crate::poly_ui::app::Color;
+    use crate::poly_ui::app::Rect;
+    use crate::poly_ui::components::logical_painter;
+    use crate::poly_ui::controls::PushButton;
+    use crate::poly_ui::layouts::CanvasLayout;
+    use crate::poly_ui::widgets::MockWidget;
+    // super
+    use super::*;
+
+    //********************************************************************************************
+    #[test]
+    fn reuse_until_damaged() {
+        let layout = CanvasLayout::new();
+        let panel = CanvasLayout::new();
+        let child = MockWidget::new();
+        let panel_id = *panel.borrow().id();
+        let child_ptr = child.get().clone();
+        panel.borrow().set_cacheable(true);
+        panel.borrow_mut().add_child_with_transform(
+            child.make_owned(),
+            &Transform::new(&Point2::<i32>::new(1, 1), &Vector2::<u32>::new(2, 2)),
+        );
+        layout.borrow_mut().add_child_with_transform(
+            panel.make_owned(),
+            &Transform::new(&Point2::<i32>::new(2, 2), &Vector2::<u32>::new(4, 4)),
+        );
+
+        let mut painter = Painter::new(&Vector2::<u32>::new(8, 8));
+        for _ in 0..3 {
+            layout.borrow_mut().paint(&mut painter);
+        }
+        assert_eq!(child_ptr.borrow().paint_call_count, 1);
+
+        child_ptr.borrow().request_repaint_rect(&Rect {
+            pos: Point2::<i32>::new(0, 0),
+            size: Vector2::<u32>::new(1, 1),
+        });
+        layout.borrow_mut().paint(&mut painter);
+        layout.borrow_mut().paint(&mut painter);
+        assert_eq!(child_ptr.borrow().paint_call_count, 2);
+
+        layout
+            .borrow_mut()
+            .set_child_size(&panel_id, &Vector2::<u32>::new(5, 5));
+        layout.borrow_mut().paint(&mut painter);
+        assert_eq!(child_ptr.borrow().paint_call_count, 3);
+    }
+
+    //********************************************************************************************
+    #[test]
+    fn draw_cached_image() {
+        let mut cache = PaintCache::default();
+        cache.set_enabled(true);
+        let mut widget = MockWidget::new_raw();
+        let mut painter = Painter::new(&Vector2::<u32>::new(3, 3));
+        let red = Color {
+            r: 255,
+            g: 0,
+            b: 0,
+            a: 255,
+        };
+        painter.set_draw_color(&red);
+        painter.clear();
+
+        cache.paint(&mut widget, &mut painter);
+        let image = cache.image().unwrap().clone();
+        assert_eq!(image.size(), Vector2::<u32>::new(3, 3));
+        // transparent cached output keeps what was painted below it
+        assert_eq!(painter.image().pixel(1, 1), red);
+
+        cache.paint(&mut widget, &mut painter);
+        assert!(Rc::ptr_eq(cache.image().unwrap(), &image));
+        cache.set_enabled(false);
+        assert!(cache.image().is_none());
+    }
+
+    //********************************************************************************************
+    #[test]
+    fn cached_equals_uncached_on_hidpi() {
+        let paint = |cacheable: bool| {
+            let layout = CanvasLayout::new();
+            let panel = CanvasLayout::new();
+            let button = PushButton::new();
+            button.borrow_mut().set_text("Ab");
+            panel.borrow().set_cacheable(cacheable);
+            panel.borrow_mut().add_child_with_transform(
+                button.make_owned(),
+                &Transform::new(&Point2::<i32>::new(1, 1), &Vector2::<u32>::new(20, 10)),
+            );
+            layout.borrow_mut().add_child_with_transform(
+                panel.make_owned(),
+                &Transform::new(&Point2::<i32>::new(3, 2), &Vector2::<u32>::new(24, 14)),
+            );
+
+            let mut painter = Painter::new(&Vector2::<u32>::new(60, 40));
+            {
+                let mut logical = logical_painter(&mut painter, 2.0);
+                layout.borrow_mut().paint(&mut *logical);
+            }
+            let image = painter.image().clone();
+            image
+        };
+
+        let cached = paint(true);
+        assert_eq!(cached, paint(false));
+        assert!(cached.pixels().iter().any(|channel| *channel != 0));
+    }
+}
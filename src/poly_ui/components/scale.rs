@@ -0,0 +1,75 @@
+// deps
+use nalgebra::Point2;
+use nalgebra::Vector2;
+// crate
+use crate::poly_ui::app::PainterTrait;
+// super
+use super::Transform;
+
+//************************************************************************************************
+/// # Arguments
+/// * `physical` - size in physical pixels
+/// * `scale` - number of physical pixels per logical pixel
+/// # Returns
+/// Size in logical pixels, rounded to the nearest integer.
+pub fn logical_size(physical: &Vector2<u32>, scale: f32) -> Vector2<u32> {
+    Vector2::<u32>::new(
+        (physical.x as f32 / scale).round() as u32,
+        (physical.y as f32 / scale).round() as u32,
+    )
+}
+
+//************************************************************************************************
+/// Creates a SubPainter covering the whole given painter which works in logical pixels.
+/// Everything painted with it (and its SubPainters) is scaled by the given factor, so Transforms
+/// and sizes of widgets stay the same on screens with different pixel density.
+/// # Arguments
+/// * `painter` - painter working in physical pixels
+/// * `scale` - number of physical pixels per logical pixel
+/// # Returns
+/// SubPainter of the logical size of the painter.
+pub fn logical_painter(painter: &mut dyn PainterTrait, scale: f32) -> Box<dyn PainterTrait> {
+    let size = logical_size(&painter.size(), scale);
+    painter.save();
+    painter.scale(scale, scale);
+    let logical = painter.sub_painter(&Transform::new(&Point2::<i32>::new(0, 0), &size));
+    painter.restore();
+    logical
+}
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+#[cfg(test)]
+mod tests {
+    // crate
+    use crate::poly_ui::app::Color;
+    use crate::poly_ui::app::Rect;
+    use crate::poly_ui::software::Painter;
+    // super
+    use super::*;
+
+    //********************************************************************************************
+    #[test]
+    fn paint_in_logical_pixels() {
+        let mut painter = Painter::new(&Vector2::<u32>::new(9, 6));
+        let mut logical = logical_painter(&mut painter, 1.5);
+        assert_eq!(logical.size(), Vector2::<u32>::new(6, 4));
+
+        let red = Color {
+            r: 255,
+            g: 0,
+            b: 0,
+            a: 255,
+        };
+        logical.set_draw_color(&red);
+        logical.fill_rect(Rect {
+            pos: Point2::<i32>::new(2, 2),
+            size: Vector2::<u32>::new(2, 2),
+        });
+        assert_eq!(painter.image().pixel(3, 3), red);
+        assert_eq!(painter.image().pixel(5, 5), red);
+        assert_eq!(painter.image().pixel(2, 3).a, 0);
+        assert_eq!(painter.image().pixel(6, 3).a, 0);
+    }
+}
@@ -0,0 +1,210 @@
+// std
+use std::cell::Cell;
+use std::cell::RefCell;
+use std::fmt::Debug;
+use std::rc::Rc;
+
+/// Callback connected to a Signal. It's shared so the slots stay connected while they are
+/// called.
+type Slot<T> = Rc<RefCell<dyn FnMut(&T)>>;
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// Identifies a callback connected to a Signal so it can be disconnected.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct SlotId(usize);
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// Notification Widgets use to tell application code something happened (e.g. a button was
+/// clicked). Callbacks are called synchronously in order of connection. They are called while
+/// the Widget emitting the Signal is borrowed, so they must not borrow it again. Callbacks may
+/// connect, disconnect and emit the Signal again; a callback is not called again by an emit()
+/// made from inside itself.
+pub struct Signal<T> {
+    slots: RefCell<Vec<(SlotId, Slot<T>)>>,
+    next_id: Cell<usize>,
+}
+
+//************************************************************************************************
+impl<T> Signal<T> {
+    pub fn new() -> Self {
+        Self {
+            slots: RefCell::new(vec![]),
+            next_id: Cell::new(0),
+        }
+    }
+
+    /// Adds the callback called on every emit().
+    /// # Arguments
+    /// * `slot` - callback getting the emitted value
+    /// # Returns
+    /// Id to disconnect the callback with.
+    pub fn connect<F: FnMut(&T) + 'static>(&self, slot: F) -> SlotId {
+        let id = SlotId(self.next_id.get());
+        self.next_id.set(id.0 + 1);
+        self.slots
+            .borrow_mut()
+            .push((id, Rc::new(RefCell::new(slot))));
+        id
+    }
+
+    /// Removes the callback, it's not called anymore.
+    /// # Arguments
+    /// * `id` - id returned by connect()
+    pub fn disconnect(&self, id: SlotId) {
+        self.slots
+            .borrow_mut()
+            .retain(|(slot_id, _)| *slot_id != id);
+    }
+
+    /// # Returns
+    /// Number of connected callbacks.
+    pub fn slot_count(&self) -> usize {
+        self.slots.borrow().len()
+    }
+
+    /// Calls all connected callbacks. Callbacks connected during the emission are called from
+    /// the next emission, callbacks disconnected during it are not called anymore.
+    /// # Arguments
+    /// * `value` - value passed to the callbacks
+    pub fn emit(&self, value: &T) {
+        let slots = self.slots.borrow().clone();
+        for (id, slot) in slots {
+            let connected = self
+                .slots
+                .borrow()
+                .iter()
+                .any(|(slot_id, _)| *slot_id == id);
+            if !connected {
+                continue;
+            }
+            // the slot is already running when the emit comes from inside it
+            if let Ok(mut slot) = slot.try_borrow_mut() {
+                (*slot)(value);
+            }
+        }
+    }
+}
+
+//************************************************************************************************
+impl<T> Default for Signal<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+//************************************************************************************************
+impl<T> Debug for Signal<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Signal")
+            .field("slots", &self.slot_count())
+            .finish()
+    }
+}
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+#[cfg(test)]
+mod tests {
+    // std
+    use std::rc::Rc;
+    // super
+    use super::*;
+
+    //********************************************************************************************
+    #[test]
+    fn connect_emit_disconnect() {
+        let signal = Rc::new(Signal::<u32>::new());
+        let received = Rc::new(RefCell::new(vec![]));
+
+        let first = {
+            let received = received.clone();
+            signal.connect(move |value| received.borrow_mut().push(*value))
+        };
+        {
+            // connecting from a callback doesn't affect the current emission
+            let signal_ptr = Rc::downgrade(&signal);
+            let received = received.clone();
+            signal.connect(move |_| {
+                let received = received.clone();
+                signal_ptr
+                    .upgrade()
+                    .unwrap()
+                    .connect(move |value| received.borrow_mut().push(value * 100));
+            });
+        }
+
+        signal.emit(&1);
+        assert_eq!(*received.borrow(), vec![1]);
+        assert_eq!(signal.slot_count(), 3);
+        signal.disconnect(first);
+        signal.emit(&2);
+        assert_eq!(*received.borrow(), vec![1, 200]);
+    }
+
+    //********************************************************************************************
+    #[test]
+    fn disconnect_while_emitting() {
+        let signal = Rc::new(Signal::<u32>::new());
+        let received = Rc::new(RefCell::new(vec![]));
+        let second = Rc::new(Cell::new(None));
+
+        {
+            // the first slot disconnects itself and the second one
+            let signal_ptr = Rc::downgrade(&signal);
+            let received = received.clone();
+            let second = second.clone();
+            let first = Rc::new(Cell::new(None));
+            let first_clone = first.clone();
+            first.set(Some(signal.connect(move |value| {
+                received.borrow_mut().push(*value);
+                let signal = signal_ptr.upgrade().unwrap();
+                signal.disconnect(first_clone.get().unwrap());
+                signal.disconnect(second.get().unwrap());
+            })));
+        }
+        {
+            let received = received.clone();
+            second.set(Some(
+                signal.connect(move |value| received.borrow_mut().push(value * 10)),
+            ));
+        }
+
+        signal.emit(&1);
+        assert_eq!(*received.borrow(), vec![1]);
+        assert_eq!(signal.slot_count(), 0);
+        signal.emit(&2);
+        assert_eq!(*received.borrow(), vec![1]);
+    }
+
+    //********************************************************************************************
+    #[test]
+    fn reentrant_emit() {
+        let signal = Rc::new(Signal::<u32>::new());
+        let received = Rc::new(RefCell::new(vec![]));
+
+        {
+            // the first slot emits again for values below 3
+            let signal_ptr = Rc::downgrade(&signal);
+            let received = received.clone();
+            signal.connect(move |value| {
+                received.borrow_mut().push(*value);
+                if *value < 3 {
+                    signal_ptr.upgrade().unwrap().emit(&(value + 1));
+                }
+            });
+        }
+        {
+            let received = received.clone();
+            signal.connect(move |value| received.borrow_mut().push(value * 10));
+        }
+
+        signal.emit(&1);
+        // the nested emit reaches the other slot but not the running one
+        assert_eq!(*received.borrow(), vec![1, 20, 10]);
+    }
+}
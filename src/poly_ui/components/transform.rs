@@ -1,6 +1,8 @@
 // deps
 use nalgebra::Point2;
 use nalgebra::Vector2;
+use serde::Deserialize;
+use serde::Serialize;
 
 //************************************************************************************************
 //************************************************************************************************
@@ -10,7 +12,7 @@ use nalgebra::Vector2;
 ///
 /// When creating Transform with Transform::default() it will have position (0, 0) and transform
 /// (0, 0)
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Transform {
     pub pos: Point2<i32>,
     pub size: Vector2<u32>,
@@ -0,0 +1,508 @@
+// std
+use std::fmt::Debug;
+// deps
+use nalgebra::Point2;
+use nalgebra::Vector2;
+// crate
+use crate::poly_ui::app::Color;
+use crate::poly_ui::components::Signal;
+use crate::poly_ui::events::Event;
+use crate::poly_ui::events::Key;
+use crate::poly_ui::events::MouseButton;
+// super
+use super::ButtonGroup;
+
+/// Time in milliseconds a pressed auto-repeat button waits before the first repeated click.
+const AUTO_REPEAT_DELAY: f32 = 300.0;
+/// Time in milliseconds between repeated clicks of a held auto-repeat button.
+const AUTO_REPEAT_INTERVAL: f32 = 100.0;
+
+pub(super) const BACKGROUND_COLOR: Color = Color::rgb(60, 60, 60);
+pub(super) const HOVERED_COLOR: Color = Color::rgb(75, 75, 75);
+pub(super) const PRESSED_COLOR: Color = Color::rgb(40, 40, 40);
+pub(super) const DISABLED_COLOR: Color = Color::rgb(50, 50, 50);
+pub(super) const BORDER_COLOR: Color = Color::rgb(110, 110, 110);
+pub(super) const FOCUS_COLOR: Color = Color::rgb(51, 153, 255);
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// Visual state of a button. Focus and the checked state are independent of it.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ButtonState {
+    Normal,
+    /// Mouse is over the button.
+    Hovered,
+    /// Button is held down with the mouse or the keyboard.
+    Pressed,
+    Disabled,
+}
+
+//************************************************************************************************
+impl ButtonState {
+    /// # Returns
+    /// Color the default look of controls fills their background (or box) with in this state.
+    pub fn background_color(&self) -> Color {
+        match self {
+            ButtonState::Normal => BACKGROUND_COLOR,
+            ButtonState::Hovered => HOVERED_COLOR,
+            ButtonState::Pressed => PRESSED_COLOR,
+            ButtonState::Disabled => DISABLED_COLOR,
+        }
+    }
+}
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// Behaviour shared by clickable controls: tracks hover, press, focus and the checked state from
+/// events and emits clicked when the button is activated by a mouse click inside it or by
+/// Space/Enter while it has the focus. Controls own a ButtonBase, pass their events to it and
+/// paint themselves according to appearance().
+#[derive(Debug)]
+pub struct ButtonBase {
+    enabled: bool,
+    hovered: bool,
+    mouse_pressed: bool,
+    key_pressed: bool,
+    focused: bool,
+
+    checkable: bool,
+    checked: bool,
+    exclusive: bool,
+    group: Option<(ButtonGroup, usize)>,
+    auto_repeat: bool,
+    /// Milliseconds until the next repeated click while the button is held.
+    repeat_timer: Option<f32>,
+
+    clicked: Signal<bool>,
+    toggled: Signal<bool>,
+}
+
+//************************************************************************************************
+impl ButtonBase {
+    pub fn new() -> Self {
+        Self {
+            enabled: true,
+            hovered: false,
+            mouse_pressed: false,
+            key_pressed: false,
+            focused: false,
+            checkable: false,
+            checked: false,
+            exclusive: false,
+            group: None,
+            auto_repeat: false,
+            repeat_timer: None,
+            clicked: Signal::new(),
+            toggled: Signal::new(),
+        }
+    }
+
+    /// # Returns
+    /// Signal emitted with the checked state (false for not checkable buttons) whenever the
+    /// button is activated.
+    pub fn clicked(&self) -> &Signal<bool> {
+        &self.clicked
+    }
+
+    /// # Returns
+    /// Signal emitted with the new checked state whenever it changes.
+    pub fn toggled(&self) -> &Signal<bool> {
+        &self.toggled
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Disabled button ignores input and is painted with the Disabled state. Disabling the button
+    /// releases it without clicking.
+    /// # Arguments
+    /// * `enabled` - whether the button reacts on input
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        if !enabled {
+            self.mouse_pressed = false;
+            self.key_pressed = false;
+            self.repeat_timer = None;
+        }
+    }
+
+    pub fn is_checkable(&self) -> bool {
+        self.checkable
+    }
+
+    /// Checkable button toggles its checked state on every click.
+    /// # Arguments
+    /// * `checkable` - whether the button toggles, making it not checkable unchecks it
+    pub fn set_checkable(&mut self, checkable: bool) {
+        self.checkable = checkable;
+        if !checkable {
+            self.set_checked(false);
+        }
+    }
+
+    pub fn is_checked(&self) -> bool {
+        self.checked
+    }
+
+    /// Changes the checked state without clicking; toggled is emitted if it changes. It's
+    /// ignored for not checkable buttons.
+    /// # Arguments
+    /// * `checked` - new checked state
+    pub fn set_checked(&mut self, checked: bool) {
+        if (self.checkable || !checked) && self.checked != checked {
+            self.checked = checked;
+            self.toggled.emit(&checked);
+            if let Some((group, id)) = &self.group {
+                if checked {
+                    group.button_checked(*id);
+                } else {
+                    group.button_unchecked(*id);
+                }
+            }
+        }
+    }
+
+    /// # Returns
+    /// Whether clicking the checked button leaves it checked, either because the button is
+    /// exclusive itself or because it's in an exclusive ButtonGroup.
+    pub fn is_exclusive(&self) -> bool {
+        self.exclusive
+            || self
+                .group
+                .as_ref()
+                .is_some_and(|(group, _)| group.is_exclusive())
+    }
+
+    /// # Arguments
+    /// * `exclusive` - whether the button can be unchecked only from code or by its ButtonGroup
+    ///   (radio buttons)
+    pub fn set_exclusive(&mut self, exclusive: bool) {
+        self.exclusive = exclusive;
+    }
+
+    /// # Returns
+    /// ButtonGroup the button is in and its id there.
+    pub fn group(&self) -> Option<(ButtonGroup, usize)> {
+        self.group.clone()
+    }
+
+    /// Sets the group of the button, use ButtonGroup::add_button() instead.
+    /// # Arguments
+    /// * `group` - group and id of the button in it, None if the button isn't in a group
+    pub fn set_group(&mut self, group: Option<(ButtonGroup, usize)>) {
+        self.group = group;
+    }
+
+    pub fn auto_repeat(&self) -> bool {
+        self.auto_repeat
+    }
+
+    /// Auto-repeat button emits clicked periodically while it's held down.
+    /// # Arguments
+    /// * `auto_repeat` - whether clicks are repeated
+    pub fn set_auto_repeat(&mut self, auto_repeat: bool) {
+        self.auto_repeat = auto_repeat;
+        if !auto_repeat {
+            self.repeat_timer = None;
+        }
+    }
+
+    pub fn has_focus(&self) -> bool {
+        self.focused
+    }
+
+    /// # Returns
+    /// Whether the button is held down with the mouse or the keyboard.
+    pub fn is_down(&self) -> bool {
+        self.key_pressed || (self.mouse_pressed && self.hovered)
+    }
+
+    pub fn state(&self) -> ButtonState {
+        if !self.enabled {
+            ButtonState::Disabled
+        } else if self.is_down() {
+            ButtonState::Pressed
+        } else if self.hovered {
+            ButtonState::Hovered
+        } else {
+            ButtonState::Normal
+        }
+    }
+
+    /// # Returns
+    /// Everything the look of the button depends on: state, focus and checked state. The owner
+    /// repaints when it changes.
+    pub fn appearance(&self) -> (ButtonState, bool, bool) {
+        (self.state(), self.focused, self.checked)
+    }
+
+    /// Activates the button as if it was clicked: toggles a checkable button and emits clicked.
+    pub fn click(&mut self) {
+        if !self.enabled {
+            return;
+        }
+        if self.checkable && !(self.checked && self.is_exclusive()) {
+            self.set_checked(!self.checked);
+        }
+        self.clicked.emit(&self.checked);
+    }
+
+    /// Updates the state from the event and clicks the button when it's activated.
+    /// # Arguments
+    /// * `event` - event in coordinates of the button
+    /// * `size` - size of the button to check whether the mouse is inside
+    /// # Returns
+    /// True if the event was consumed.
+    pub fn handle_event(&mut self, event: &Event, size: &Vector2<u32>) -> bool {
+        match event {
+            Event::MouseLeave => {
+                self.hovered = false;
+                return false;
+            }
+            Event::FocusIn => self.focused = true,
+            Event::FocusOut => {
+                self.focused = false;
+                self.key_pressed = false;
+            }
+            _ if !self.enabled => return false,
+            Event::MousePressEvent(press) if press.button() == MouseButton::Left => {
+                self.hovered = Self::contains(size, &press.pos());
+                self.mouse_pressed = true;
+                self.start_repeat();
+            }
+            Event::MouseMoveEvent(movement) => {
+                self.hovered = Self::contains(size, &movement.pos());
+                return self.mouse_pressed;
+            }
+            Event::MouseReleaseEvent(release) if release.button() == MouseButton::Left => {
+                if !self.mouse_pressed {
+                    return false;
+                }
+                self.hovered = Self::contains(size, &release.pos());
+                self.mouse_pressed = false;
+                self.repeat_timer = None;
+                if self.hovered && !self.key_pressed {
+                    self.click();
+                }
+            }
+            Event::KeyPress(press) if self.focused && press.key() == Key::Space => {
+                if !self.key_pressed {
+                    self.key_pressed = true;
+                    self.start_repeat();
+                }
+            }
+            Event::KeyReleaseEvent(release) if self.focused && release.key() == Key::Space => {
+                if !self.key_pressed {
+                    return false;
+                }
+                self.key_pressed = false;
+                self.repeat_timer = None;
+                self.click();
+            }
+            Event::KeyPress(press)
+                if self.focused && matches!(press.key(), Key::Return | Key::KpEnter) =>
+            {
+                self.click()
+            }
+            Event::KeyPress(press) if self.focused && self.group.is_some() => match press.key() {
+                Key::Left | Key::Up => return self.move_in_group(-1),
+                Key::Right | Key::Down => return self.move_in_group(1),
+                _ => return false,
+            },
+            _ => return false,
+        }
+        true
+    }
+
+    /// Emits repeated clicks of a held auto-repeat button.
+    /// # Arguments
+    /// * `dt` - time from the previous update in milliseconds
+    pub fn update(&mut self, dt: f32) {
+        let Some(mut timer) = self.repeat_timer else {
+            return;
+        };
+        timer -= dt;
+        while timer <= 0.0 {
+            timer += AUTO_REPEAT_INTERVAL;
+            if self.is_down() {
+                self.click();
+            }
+        }
+        self.repeat_timer = Some(timer);
+    }
+
+    /// Moves the check of the exclusive ButtonGroup to the neighbouring button.
+    /// # Arguments
+    /// * `step` - 1 for the next button, -1 for the previous one
+    /// # Returns
+    /// Whether the button is in an exclusive group.
+    fn move_in_group(&mut self, step: isize) -> bool {
+        let Some((group, id)) = self.group.clone().filter(|(group, _)| group.is_exclusive()) else {
+            return false;
+        };
+        if group.move_check(id, step) == Some(id) {
+            self.click();
+        }
+        // the group can't uncheck this button while it's handling the event
+        if self.checked && group.checked_id() != Some(id) {
+            self.set_checked(false);
+        }
+        true
+    }
+
+    fn start_repeat(&mut self) {
+        if self.auto_repeat {
+            self.repeat_timer = Some(AUTO_REPEAT_DELAY);
+        }
+    }
+
+    fn contains(size: &Vector2<u32>, pos: &Point2<i32>) -> bool {
+        pos.x >= 0 && pos.y >= 0 && (pos.x as u32) < size.x && (pos.y as u32) < size.y
+    }
+}
+
+//************************************************************************************************
+impl Default for ButtonBase {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+#[cfg(test)]
+mod tests {
+    // std
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    // crate
+    use crate::poly_ui::events::KeyPressEvent;
+    use crate::poly_ui::events::KeyReleaseEvent;
+    use crate::poly_ui::events::MouseMoveEvent;
+    use crate::poly_ui::events::MousePressEvent;
+    use crate::poly_ui::events::MouseReleaseEvent;
+    // super
+    use super::*;
+
+    //********************************************************************************************
+    fn mouse(press: bool, x: i32, y: i32) -> Event {
+        let pos = Point2::<i32>::new(x, y);
+        if press {
+            Event::MousePressEvent(MousePressEvent::new(MouseButton::Left, &pos))
+        } else {
+            Event::MouseReleaseEvent(MouseReleaseEvent::new(MouseButton::Left, &pos))
+        }
+    }
+
+    //********************************************************************************************
+    fn record_clicks(base: &ButtonBase) -> Rc<RefCell<Vec<bool>>> {
+        let clicks = Rc::new(RefCell::new(vec![]));
+        let clicks_clone = clicks.clone();
+        base.clicked()
+            .connect(move |checked| clicks_clone.borrow_mut().push(*checked));
+        clicks
+    }
+
+    //********************************************************************************************
+    #[test]
+    fn mouse_click() {
+        let size = Vector2::<u32>::new(20, 10);
+        let mut base = ButtonBase::new();
+        let clicks = record_clicks(&base);
+
+        base.handle_event(
+            &Event::MouseMoveEvent(MouseMoveEvent::new(&Point2::<i32>::new(5, 5))),
+            &size,
+        );
+        assert_eq!(base.state(), ButtonState::Hovered);
+        assert!(base.handle_event(&mouse(true, 5, 5), &size));
+        assert_eq!(base.state(), ButtonState::Pressed);
+
+        // dragging out of the button and releasing there cancels the click
+        base.handle_event(
+            &Event::MouseMoveEvent(MouseMoveEvent::new(&Point2::<i32>::new(30, 5))),
+            &size,
+        );
+        assert_eq!(base.state(), ButtonState::Normal);
+        assert!(base.handle_event(&mouse(false, 30, 5), &size));
+        assert!(clicks.borrow().is_empty());
+
+        base.handle_event(&mouse(true, 5, 5), &size);
+        base.handle_event(&mouse(false, 6, 6), &size);
+        assert_eq!(*clicks.borrow(), vec![false]);
+        assert_eq!(base.state(), ButtonState::Hovered);
+
+        base.handle_event(&Event::MouseLeave, &size);
+        assert_eq!(base.state(), ButtonState::Normal);
+    }
+
+    //********************************************************************************************
+    #[test]
+    fn keyboard_and_checkable() {
+        let size = Vector2::<u32>::new(20, 10);
+        let mut base = ButtonBase::new();
+        base.set_checkable(true);
+        let clicks = record_clicks(&base);
+        let toggles = Rc::new(RefCell::new(vec![]));
+        let toggles_clone = toggles.clone();
+        base.toggled()
+            .connect(move |checked| toggles_clone.borrow_mut().push(*checked));
+
+        base.handle_event(&Event::FocusIn, &size);
+        assert_eq!(base.appearance(), (ButtonState::Normal, true, false));
+        base.handle_event(&Event::KeyPress(KeyPressEvent::new(Key::Space)), &size);
+        assert_eq!(base.state(), ButtonState::Pressed);
+        base.handle_event(
+            &Event::KeyReleaseEvent(KeyReleaseEvent::new(Key::Space)),
+            &size,
+        );
+        base.handle_event(&Event::KeyPress(KeyPressEvent::new(Key::Return)), &size);
+        assert_eq!(*clicks.borrow(), vec![true, false]);
+        assert_eq!(*toggles.borrow(), vec![true, false]);
+
+        base.set_checked(true);
+        assert!(base.is_checked());
+        base.set_checkable(false);
+        assert!(!base.is_checked());
+        assert_eq!(*toggles.borrow(), vec![true, false, true, false]);
+        assert!(!base.handle_event(&Event::KeyPress(KeyPressEvent::new(Key::A)), &size));
+
+        base.set_enabled(false);
+        assert_eq!(base.state(), ButtonState::Disabled);
+        assert!(!base.handle_event(&mouse(true, 5, 5), &size));
+        base.click();
+        assert_eq!(clicks.borrow().len(), 2);
+    }
+
+    //********************************************************************************************
+    #[test]
+    fn auto_repeat() {
+        let size = Vector2::<u32>::new(20, 10);
+        let mut base = ButtonBase::new();
+        base.set_auto_repeat(true);
+        let clicks = record_clicks(&base);
+
+        base.handle_event(&mouse(true, 5, 5), &size);
+        base.update(AUTO_REPEAT_DELAY - 1.0);
+        assert!(clicks.borrow().is_empty());
+        base.update(1.0 + AUTO_REPEAT_INTERVAL * 2.0);
+        assert_eq!(clicks.borrow().len(), 3);
+
+        // no repeats while the mouse is outside the held button
+        base.handle_event(
+            &Event::MouseMoveEvent(MouseMoveEvent::new(&Point2::<i32>::new(-1, 5))),
+            &size,
+        );
+        base.update(AUTO_REPEAT_INTERVAL);
+        assert_eq!(clicks.borrow().len(), 3);
+
+        base.handle_event(&mouse(false, 5, 5), &size);
+        assert_eq!(clicks.borrow().len(), 4);
+        base.update(AUTO_REPEAT_DELAY * 2.0);
+        assert_eq!(clicks.borrow().len(), 4);
+    }
+}
@@ -0,0 +1,272 @@
+// std
+use std::cell::Cell;
+use std::cell::RefCell;
+use std::fmt::Debug;
+use std::rc::Rc;
+use std::rc::Weak;
+// crate
+use crate::poly_ui::components::Signal;
+use crate::poly_ui::widgets::WidgetTrait;
+// super
+use super::ButtonBase;
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// Widget built on ButtonBase, it lets a ButtonGroup control buttons of different types.
+pub trait ButtonTrait: WidgetTrait {
+    fn button_base(&self) -> &ButtonBase;
+
+    /// Changing the base directly doesn't repaint the button, use the provided methods.
+    fn button_base_mut(&mut self) -> &mut ButtonBase;
+
+    /// Changes the checked state of a checkable button and repaints it.
+    /// # Arguments
+    /// * `checked` - new checked state
+    fn set_checked(&mut self, checked: bool) {
+        let appearance = self.button_base().appearance();
+        self.button_base_mut().set_checked(checked);
+        if self.button_base().appearance() != appearance {
+            self.request_repaint();
+        }
+    }
+
+    /// Clicks the button and repaints it.
+    fn click(&mut self) {
+        let appearance = self.button_base().appearance();
+        self.button_base_mut().click();
+        if self.button_base().appearance() != appearance {
+            self.request_repaint();
+        }
+    }
+}
+
+/// Button of a ButtonGroup with its id.
+type GroupMember = (usize, Weak<RefCell<dyn ButtonTrait>>);
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// Shared state of a ButtonGroup.
+#[derive(Debug)]
+struct GroupState {
+    buttons: RefCell<Vec<GroupMember>>,
+    checked: Cell<Option<usize>>,
+    exclusive: Cell<bool>,
+    checked_changed: Signal<Option<usize>>,
+}
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// Group of checkable buttons identified by ids. In an exclusive group (the default) at most one
+/// button is checked: checking a button unchecks the previous one, clicking the checked button
+/// doesn't uncheck it and arrow keys move the check and the focus to the previous/next enabled
+/// button. The buttons may live in different layouts; the group holds them weakly. ButtonGroup
+/// is a cheap handle, its clones refer to the same group.
+#[derive(Debug, Clone)]
+pub struct ButtonGroup {
+    state: Rc<GroupState>,
+}
+
+//************************************************************************************************
+impl ButtonGroup {
+    pub fn new() -> Self {
+        Self {
+            state: Rc::new(GroupState {
+                buttons: RefCell::new(vec![]),
+                checked: Cell::new(None),
+                exclusive: Cell::new(true),
+                checked_changed: Signal::new(),
+            }),
+        }
+    }
+
+    /// # Returns
+    /// Signal emitted with the id of the checked button whenever it changes, None when no
+    /// button is checked.
+    pub fn checked_changed(&self) -> &Signal<Option<usize>> {
+        &self.state.checked_changed
+    }
+
+    pub fn is_exclusive(&self) -> bool {
+        self.state.exclusive.get()
+    }
+
+    /// # Arguments
+    /// * `exclusive` - whether at most one button is checked, buttons checked in a non-exclusive
+    ///   group stay checked when the group becomes exclusive until one of them is toggled
+    pub fn set_exclusive(&self, exclusive: bool) {
+        self.state.exclusive.set(exclusive);
+    }
+
+    /// Makes the button checkable and adds it to the group. The button must not be borrowed.
+    /// # Arguments
+    /// * `button` - button to add, it's removed from its previous group
+    /// * `id` - id of the button in the group, e.g. index of an option
+    pub fn add_button<T: ButtonTrait + 'static>(&self, button: &Rc<RefCell<T>>, id: usize) {
+        let button: Rc<RefCell<dyn ButtonTrait>> = button.clone();
+        self.remove_button(id);
+        let checked = {
+            let mut button = button.borrow_mut();
+            let base = button.button_base_mut();
+            if let Some((group, previous_id)) = base.group() {
+                group.remove_button(previous_id);
+            }
+            base.set_checkable(true);
+            base.set_group(Some((self.clone(), id)));
+            base.is_checked()
+        };
+        self.state
+            .buttons
+            .borrow_mut()
+            .push((id, Rc::downgrade(&button)));
+        if checked {
+            self.button_checked(id);
+        }
+    }
+
+    /// Removes the button from the group, it keeps its checked state.
+    /// # Arguments
+    /// * `id` - id of the button
+    pub fn remove_button(&self, id: usize) {
+        let removed = {
+            let mut buttons = self.state.buttons.borrow_mut();
+            let index = buttons.iter().position(|(button_id, _)| *button_id == id);
+            index.map(|index| buttons.remove(index))
+        };
+        if let Some(button) = removed.and_then(|(_, button)| button.upgrade()) {
+            if let Ok(mut button) = button.try_borrow_mut() {
+                button.button_base_mut().set_group(None);
+            }
+        }
+        if self.state.checked.get() == Some(id) {
+            self.set_checked_id_value(None);
+        }
+    }
+
+    /// # Returns
+    /// Ids of the buttons in order of addition.
+    pub fn ids(&self) -> Vec<usize> {
+        self.state
+            .buttons
+            .borrow()
+            .iter()
+            .map(|(id, _)| *id)
+            .collect()
+    }
+
+    /// # Returns
+    /// Id of the checked button of an exclusive group.
+    pub fn checked_id(&self) -> Option<usize> {
+        self.state.checked.get()
+    }
+
+    /// Checks the button with the id (unchecking the previous one) or unchecks the checked one.
+    /// # Arguments
+    /// * `id` - id of the button to check, None to uncheck all
+    pub fn set_checked_id(&self, id: Option<usize>) {
+        match id {
+            Some(id) => {
+                if let Some(button) = self.button(id) {
+                    button.borrow_mut().set_checked(true);
+                }
+            }
+            None => {
+                if let Some(button) = self.checked_id().and_then(|id| self.button(id)) {
+                    button.borrow_mut().set_checked(false);
+                }
+            }
+        }
+    }
+
+    /// # Arguments
+    /// * `id` - id of the button
+    /// # Returns
+    /// Button with the id if it's still alive.
+    pub fn button(&self, id: usize) -> Option<Rc<RefCell<dyn ButtonTrait>>> {
+        self.state
+            .buttons
+            .borrow()
+            .iter()
+            .find(|(button_id, _)| *button_id == id)
+            .and_then(|(_, button)| button.upgrade())
+    }
+
+    /// Called by ButtonBase when the button with the id is checked; unchecks the others in an
+    /// exclusive group. The calling button is borrowed so it's skipped.
+    pub(super) fn button_checked(&self, id: usize) {
+        if !self.is_exclusive() {
+            return;
+        }
+        let previous = self.checked_id();
+        self.state.checked.set(Some(id));
+        let buttons = self.state.buttons.borrow().clone();
+        for (button_id, button) in buttons {
+            if button_id == id {
+                continue;
+            }
+            if let Some(button) = button.upgrade() {
+                if let Ok(mut button) = button.try_borrow_mut() {
+                    button.set_checked(false);
+                }
+            }
+        }
+        if previous != Some(id) {
+            self.state.checked_changed.emit(&Some(id));
+        }
+    }
+
+    /// Called by ButtonBase when the button with the id is unchecked.
+    pub(super) fn button_unchecked(&self, id: usize) {
+        if self.is_exclusive() && self.checked_id() == Some(id) {
+            self.set_checked_id_value(None);
+        }
+    }
+
+    /// Moves the check and the focus of an exclusive group to the neighbouring enabled button and
+    /// clicks it.
+    /// # Arguments
+    /// * `from` - id of the button the move is requested by; the move starts at the checked
+    ///   button or at this one if none is checked
+    /// * `step` - 1 for the next button, -1 for the previous one
+    /// # Returns
+    /// Id of the newly checked button if it's the requesting one; it's borrowed so the caller
+    /// has to click it.
+    pub(super) fn move_check(&self, from: usize, step: isize) -> Option<usize> {
+        let buttons = self.state.buttons.borrow().clone();
+        let start = self.checked_id().unwrap_or(from);
+        let start = buttons.iter().position(|(id, _)| *id == start)?;
+        let count = buttons.len() as isize;
+        let target = (1..count)
+            .map(|offset| &buttons[(start as isize + offset * step).rem_euclid(count) as usize])
+            .find(|(id, button)| {
+                *id == from
+                    || button
+                        .upgrade()
+                        .is_some_and(|button| button.borrow().button_base().is_enabled())
+            })?;
+        if target.0 == from {
+            return Some(from);
+        }
+        if let Some(button) = target.1.upgrade() {
+            let mut button = button.borrow_mut();
+            button.click();
+            button.request_focus();
+        }
+        None
+    }
+
+    fn set_checked_id_value(&self, id: Option<usize>) {
+        if self.state.checked.replace(id) != id {
+            self.state.checked_changed.emit(&id);
+        }
+    }
+}
+
+//************************************************************************************************
+impl Default for ButtonGroup {
+    fn default() -> Self {
+        Self::new()
+    }
+}
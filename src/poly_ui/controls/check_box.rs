@@ -0,0 +1,513 @@
+// std
+use std::cell::Cell;
+use std::cell::RefCell;
+use std::fmt::Debug;
+use std::rc::Rc;
+use std::rc::Weak;
+// deps
+use nalgebra::Point2;
+use nalgebra::Vector2;
+use uuid::Uuid;
+// crate
+use crate::poly_ui::app::Color;
+use crate::poly_ui::app::Font;
+use crate::poly_ui::app::Line;
+use crate::poly_ui::app::PainterTrait;
+use crate::poly_ui::app::Rect;
+use crate::poly_ui::components::Hierarchy;
+use crate::poly_ui::components::Signal;
+use crate::poly_ui::components::Transform;
+use crate::poly_ui::events::Event;
+use crate::poly_ui::widgets::NewWidget;
+use crate::poly_ui::widgets::OwnedWidget;
+use crate::poly_ui::widgets::WidgetTrait;
+// super
+use super::button_base::BORDER_COLOR;
+use super::button_base::FOCUS_COLOR;
+use super::ButtonBase;
+use super::ButtonState;
+
+/// Space between the box and the text.
+const TEXT_SPACING: u32 = 4;
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum CheckState {
+    Unchecked,
+    /// Neither checked nor unchecked, e.g. some children of a CheckBoxGroup are checked.
+    PartiallyChecked,
+    Checked,
+}
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// Box with a check mark and a text right of it. Clicking it or pressing Space while it's focused
+/// toggles it between Unchecked and Checked; PartiallyChecked can only be set from code (see
+/// CheckBoxGroup) and the next click checks the CheckBox.
+#[derive(Debug)]
+pub struct CheckBox {
+    id: Uuid,
+    hierarchy: Hierarchy,
+
+    base: ButtonBase,
+    partially_checked: bool,
+    text: String,
+    font: Font,
+    state_changed: Signal<CheckState>,
+
+    size: Vector2<u32>,
+}
+
+//************************************************************************************************
+impl CheckBox {
+    pub fn new_raw() -> Self {
+        let mut base = ButtonBase::new();
+        base.set_checkable(true);
+        Self {
+            id: Uuid::new_v4(),
+            hierarchy: Hierarchy::default(),
+            base,
+            partially_checked: false,
+            text: String::new(),
+            font: Font::default(),
+            state_changed: Signal::new(),
+            size: Vector2::<u32>::new(0, 0),
+        }
+    }
+
+    pub fn new() -> NewWidget<Self> {
+        NewWidget::new(Self::new_raw())
+    }
+
+    /// # Returns
+    /// Signal emitted with the new state whenever it changes, by the user or from code.
+    pub fn state_changed(&self) -> &Signal<CheckState> {
+        &self.state_changed
+    }
+
+    /// # Returns
+    /// Signal emitted with the checked state whenever the user toggles the CheckBox.
+    pub fn clicked(&self) -> &Signal<bool> {
+        self.base.clicked()
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn set_text(&mut self, text: &str) {
+        self.text = text.to_string();
+        self.request_repaint();
+    }
+
+    pub fn font(&self) -> &Font {
+        &self.font
+    }
+
+    pub fn set_font(&mut self, font: &Font) {
+        self.font = *font;
+        self.request_repaint();
+    }
+
+    pub fn check_state(&self) -> CheckState {
+        if self.partially_checked {
+            CheckState::PartiallyChecked
+        } else if self.base.is_checked() {
+            CheckState::Checked
+        } else {
+            CheckState::Unchecked
+        }
+    }
+
+    /// # Arguments
+    /// * `state` - new state, state_changed is emitted if it differs from the current one
+    pub fn set_check_state(&mut self, state: CheckState) {
+        if self.check_state() != state {
+            self.partially_checked = state == CheckState::PartiallyChecked;
+            self.base.set_checked(state == CheckState::Checked);
+            self.request_repaint();
+            self.state_changed.emit(&state);
+        }
+    }
+
+    pub fn is_checked(&self) -> bool {
+        self.check_state() == CheckState::Checked
+    }
+
+    /// # Arguments
+    /// * `checked` - whether the CheckBox is Checked or Unchecked
+    pub fn set_checked(&mut self, checked: bool) {
+        self.set_check_state(if checked {
+            CheckState::Checked
+        } else {
+            CheckState::Unchecked
+        });
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.base.is_enabled()
+    }
+
+    /// # Arguments
+    /// * `enabled` - whether the user can toggle the CheckBox, disabled one is grayed out
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.update_base(|base| base.set_enabled(enabled));
+    }
+
+    /// Toggles the CheckBox as if the user clicked it.
+    pub fn click(&mut self) {
+        self.update_base(|base| base.click());
+    }
+
+    /// Calls the function with the ButtonBase, leaves the PartiallyChecked state when the
+    /// function toggled the base, emits state_changed and repaints if needed.
+    fn update_base<F: FnOnce(&mut ButtonBase) -> R, R>(&mut self, function: F) -> R {
+        let appearance = self.base.appearance();
+        let result = function(&mut self.base);
+        if self.base.appearance() != appearance {
+            self.request_repaint();
+            if self.base.is_checked() != appearance.2 {
+                self.partially_checked = false;
+                self.state_changed.emit(&self.check_state());
+            }
+        }
+        result
+    }
+
+    /// # Returns
+    /// Size of the square box, it's a bit higher than the text.
+    fn box_size(&self) -> u32 {
+        self.font.line_height() + 4
+    }
+}
+
+//************************************************************************************************
+impl WidgetTrait for CheckBox {
+    fn id(&self) -> &Uuid {
+        &self.id
+    }
+
+    fn remove_child(&mut self, child: &Uuid) -> OwnedWidget {
+        self.hierarchy.remove(child)
+    }
+
+    fn get_hierarchy(&self) -> &Hierarchy {
+        &self.hierarchy
+    }
+
+    fn get_child_transform(&self, child: &Uuid) -> &Transform {
+        self.hierarchy.get_transform(child)
+    }
+
+    fn preferred_size(&self) -> Option<Vector2<u32>> {
+        let text = self.font.text_size(&self.text);
+        let box_size = self.box_size();
+        Some(match text.x {
+            0 => Vector2::<u32>::new(box_size, box_size),
+            _ => Vector2::<u32>::new(box_size + TEXT_SPACING + text.x, box_size.max(text.y)),
+        })
+    }
+
+    fn accepts_focus(&self) -> bool {
+        self.base.is_enabled()
+    }
+
+    fn handle_event(&mut self, event: &Event) -> bool {
+        if self.hierarchy.dispatch_event(event) {
+            return true;
+        }
+        let size = self.size;
+        self.update_base(|base| base.handle_event(event, &size))
+    }
+
+    fn update(&mut self, dt: f32) {
+        self.hierarchy.update_children(dt);
+    }
+
+    fn paint(&mut self, painter: &mut dyn PainterTrait) {
+        self.size = painter.size();
+        let (state, focused, _) = self.base.appearance();
+        let box_size = self.box_size();
+        let box_rect = Rect {
+            pos: Point2::<i32>::new(0, (self.size.y as i32 - box_size as i32) / 2),
+            size: Vector2::<u32>::new(box_size, box_size),
+        };
+        painter.set_draw_color(&state.background_color());
+        painter.fill_rect(box_rect);
+        painter.set_draw_color(if focused { &FOCUS_COLOR } else { &BORDER_COLOR });
+        painter.draw_rect(box_rect);
+
+        let mark_color = if state == ButtonState::Disabled {
+            Color::GRAY
+        } else {
+            Color::WHITE
+        };
+        painter.set_draw_color(&mark_color);
+        let (x, y, s) = (box_rect.pos.x, box_rect.pos.y, box_size as i32);
+        match self.check_state() {
+            CheckState::Checked => painter.draw_lines(&[
+                Line {
+                    start: Point2::<i32>::new(x + 3, y + s / 2),
+                    end: Point2::<i32>::new(x + s / 2 - 1, y + s - 4),
+                },
+                Line {
+                    start: Point2::<i32>::new(x + s / 2 - 1, y + s - 4),
+                    end: Point2::<i32>::new(x + s - 4, y + 3),
+                },
+            ]),
+            CheckState::PartiallyChecked => painter.fill_rect(Rect {
+                pos: Point2::<i32>::new(x + 3, y + s / 2 - 1),
+                size: Vector2::<u32>::new(box_size - 6, 2),
+            }),
+            CheckState::Unchecked => {}
+        }
+
+        if !self.text.is_empty() {
+            let text_pos = Point2::<i32>::new(
+                (box_size + TEXT_SPACING) as i32,
+                (self.size.y as i32 - self.font.line_height() as i32) / 2,
+            );
+            painter.draw_text(&self.text, &text_pos, &self.font);
+        }
+
+        self.hierarchy.paint_children(painter);
+    }
+}
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// Keeps a parent tri-state CheckBox in sync with a group of child CheckBoxes: the parent is
+/// Checked when all children are checked, Unchecked when none is and PartiallyChecked otherwise.
+/// Checking or unchecking the parent does the same with all children. The group holds the
+/// CheckBoxes weakly and works through their state_changed Signals, so it doesn't have to be
+/// kept alive after the children are added.
+#[derive(Debug, Clone)]
+pub struct CheckBoxGroup {
+    parent: Weak<RefCell<CheckBox>>,
+    children: Rc<RefCell<Vec<Weak<RefCell<CheckBox>>>>>,
+    /// Last known state of every child; children can't be borrowed while they emit a Signal.
+    states: Rc<RefCell<Vec<CheckState>>>,
+    /// Whether the group is changing the CheckBoxes itself so their Signals are ignored.
+    updating: Rc<Cell<bool>>,
+}
+
+//************************************************************************************************
+impl CheckBoxGroup {
+    /// # Arguments
+    /// * `parent` - CheckBox reflecting the state of the children
+    pub fn new(parent: &Rc<RefCell<CheckBox>>) -> Self {
+        let group = Self {
+            parent: Rc::downgrade(parent),
+            children: Rc::new(RefCell::new(vec![])),
+            states: Rc::new(RefCell::new(vec![])),
+            updating: Rc::new(Cell::new(false)),
+        };
+
+        let children = group.children.clone();
+        let states = group.states.clone();
+        let updating = group.updating.clone();
+        parent.borrow().state_changed().connect(move |state| {
+            if updating.get() || *state == CheckState::PartiallyChecked {
+                return;
+            }
+            updating.set(true);
+            for (child, child_state) in children.borrow().iter().zip(states.borrow_mut().iter_mut())
+            {
+                if let Some(child) = child.upgrade() {
+                    child.borrow_mut().set_check_state(*state);
+                }
+                *child_state = *state;
+            }
+            updating.set(false);
+        });
+        group
+    }
+
+    /// Adds the child and updates the state of the parent.
+    /// # Arguments
+    /// * `child` - CheckBox controlled by the parent
+    pub fn add(&self, child: &Rc<RefCell<CheckBox>>) {
+        let index = self.children.borrow().len();
+        self.children.borrow_mut().push(Rc::downgrade(child));
+        self.states.borrow_mut().push(child.borrow().check_state());
+
+        let group = self.clone();
+        child.borrow().state_changed().connect(move |state| {
+            if !group.updating.get() {
+                group.states.borrow_mut()[index] = *state;
+                group.update_parent();
+            }
+        });
+        self.update_parent();
+    }
+
+    /// # Returns
+    /// State of the parent derived from the states of the children.
+    pub fn combined_state(&self) -> CheckState {
+        let states = self.states.borrow();
+        if states.iter().all(|state| *state == CheckState::Checked) {
+            CheckState::Checked
+        } else if states.iter().all(|state| *state == CheckState::Unchecked) {
+            CheckState::Unchecked
+        } else {
+            CheckState::PartiallyChecked
+        }
+    }
+
+    fn update_parent(&self) {
+        if let Some(parent) = self.parent.upgrade() {
+            self.updating.set(true);
+            parent.borrow_mut().set_check_state(self.combined_state());
+            self.updating.set(false);
+        }
+    }
+}
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+#[cfg(test)]
+mod tests {
+    // crate
+    use crate::poly_ui::app::Brush;
+    use crate::poly_ui::app::DrawCommand;
+    use crate::poly_ui::app::RecordingPainter;
+    use crate::poly_ui::events::Key;
+    use crate::poly_ui::events::KeyPressEvent;
+    use crate::poly_ui::events::KeyReleaseEvent;
+    use crate::poly_ui::events::MouseButton;
+    use crate::poly_ui::events::MousePressEvent;
+    use crate::poly_ui::events::MouseReleaseEvent;
+    // super
+    use super::*;
+
+    //********************************************************************************************
+    fn click_at(check_box: &mut CheckBox, x: i32, y: i32) {
+        let pos = Point2::<i32>::new(x, y);
+        check_box.handle_event(&Event::MousePressEvent(MousePressEvent::new(
+            MouseButton::Left,
+            &pos,
+        )));
+        check_box.handle_event(&Event::MouseReleaseEvent(MouseReleaseEvent::new(
+            MouseButton::Left,
+            &pos,
+        )));
+    }
+
+    //********************************************************************************************
+    #[test]
+    fn unfocused_ignores_space() {
+        let mut check_box = CheckBox::new_raw();
+        let press = Event::KeyPress(KeyPressEvent::new(Key::Space));
+        let release = Event::KeyReleaseEvent(KeyReleaseEvent::new(Key::Space));
+        assert!(!check_box.handle_event(&press));
+        assert!(!check_box.handle_event(&release));
+        assert!(!check_box.is_checked());
+
+        check_box.handle_event(&Event::FocusIn);
+        check_box.handle_event(&Event::FocusOut);
+        assert!(!check_box.handle_event(&press));
+        assert!(!check_box.handle_event(&release));
+        assert!(!check_box.is_checked());
+    }
+
+    //********************************************************************************************
+    #[test]
+    fn toggle_and_paint() {
+        let mut check_box = CheckBox::new_raw();
+        check_box.set_text("Sound");
+        assert_eq!(
+            check_box.preferred_size(),
+            Some(Vector2::<u32>::new(47, 13))
+        );
+        let mut painter = RecordingPainter::new(&Vector2::<u32>::new(60, 13));
+        check_box.paint(&mut painter);
+
+        let states = Rc::new(RefCell::new(vec![]));
+        let states_clone = states.clone();
+        check_box
+            .state_changed()
+            .connect(move |state| states_clone.borrow_mut().push(*state));
+
+        click_at(&mut check_box, 30, 5);
+        assert!(check_box.is_checked());
+        check_box.handle_event(&Event::FocusIn);
+        check_box.handle_event(&Event::KeyPress(KeyPressEvent::new(Key::Space)));
+        check_box.handle_event(&Event::KeyReleaseEvent(KeyReleaseEvent::new(Key::Space)));
+        check_box.set_check_state(CheckState::PartiallyChecked);
+        click_at(&mut check_box, 5, 5);
+        assert_eq!(
+            *states.borrow(),
+            vec![
+                CheckState::Checked,
+                CheckState::Unchecked,
+                CheckState::PartiallyChecked,
+                CheckState::Checked,
+            ]
+        );
+
+        check_box.set_check_state(CheckState::PartiallyChecked);
+        assert_eq!(states.borrow().len(), 5);
+        check_box.set_enabled(false);
+        click_at(&mut check_box, 5, 5);
+        assert_eq!(check_box.check_state(), CheckState::PartiallyChecked);
+
+        let mut painter = RecordingPainter::new(&Vector2::<u32>::new(60, 13));
+        check_box.paint(&mut painter);
+        let commands = painter
+            .display_list()
+            .commands
+            .into_iter()
+            .map(|command| command.command)
+            .collect::<Vec<DrawCommand>>();
+        assert_eq!(
+            commands[4..],
+            [
+                DrawCommand::SetBrush(Brush::Solid(Color::GRAY)),
+                DrawCommand::FillRect(Rect {
+                    pos: Point2::<i32>::new(3, 5),
+                    size: Vector2::<u32>::new(7, 2),
+                }),
+                DrawCommand::DrawText(
+                    "Sound".to_string(),
+                    Point2::<i32>::new(17, 2),
+                    Font::default()
+                ),
+            ]
+        );
+    }
+
+    //********************************************************************************************
+    #[test]
+    fn group() {
+        let parent = CheckBox::new();
+        let children = (0..3).map(|_| CheckBox::new()).collect::<Vec<_>>();
+        children[0].borrow_mut().set_checked(true);
+        let group = CheckBoxGroup::new(parent.get());
+        for child in &children {
+            group.add(child.get());
+        }
+        assert_eq!(parent.borrow().check_state(), CheckState::PartiallyChecked);
+
+        children[1].borrow_mut().click();
+        children[2].borrow_mut().set_checked(true);
+        assert_eq!(parent.borrow().check_state(), CheckState::Checked);
+
+        // unchecking the parent unchecks all children
+        parent.borrow_mut().click();
+        assert!(children
+            .iter()
+            .all(|child| child.borrow().check_state() == CheckState::Unchecked));
+        assert_eq!(group.combined_state(), CheckState::Unchecked);
+
+        children[2].borrow_mut().click();
+        assert_eq!(parent.borrow().check_state(), CheckState::PartiallyChecked);
+        // clicking the partially checked parent checks everything
+        parent.borrow_mut().click();
+        assert!(children.iter().all(|child| child.borrow().is_checked()));
+    }
+}
@@ -0,0 +1,221 @@
+// std
+use std::fmt::Debug;
+
+//************************************************************************************************
+/// Characters accepted by an editable position of an InputMask.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+enum CharClass {
+    Digit,
+    Letter,
+    Alphanumeric,
+    Any,
+}
+
+//************************************************************************************************
+impl CharClass {
+    fn accepts(&self, ch: char) -> bool {
+        match self {
+            CharClass::Digit => ch.is_ascii_digit(),
+            CharClass::Letter => ch.is_alphabetic(),
+            CharClass::Alphanumeric => ch.is_alphanumeric(),
+            CharClass::Any => !ch.is_control(),
+        }
+    }
+}
+
+//************************************************************************************************
+/// Position of an InputMask.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+enum MaskChar {
+    /// Fixed character the user can't change.
+    Literal(char),
+    /// Position the user types a character of the class to; the text is not acceptable until
+    /// required positions are filled.
+    Slot { class: CharClass, required: bool },
+}
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// Template of text with a fixed layout, e.g. a date or a phone number. The mask is a string of
+/// position characters optionally followed by ';' and the blank character shown in empty
+/// positions ('_' by default):
+/// * `9` / `0` - required / optional digit
+/// * `A` / `a` - required / optional letter
+/// * `N` / `n` - required / optional letter or digit
+/// * `X` / `x` - required / optional any character
+/// * `\` - escapes the next character so it's a literal
+/// * any other character is a literal
+///
+/// Masked text always has one character per position, blanks in empty ones.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InputMask {
+    chars: Vec<MaskChar>,
+    blank: char,
+}
+
+//************************************************************************************************
+impl InputMask {
+    /// # Arguments
+    /// * `mask` - mask in the format described above, e.g. "99-99-9999;_"
+    pub fn new(mask: &str) -> Self {
+        let (positions, blank) = match mask.rsplit_once(';') {
+            Some((positions, blank)) if blank.chars().count() == 1 => {
+                (positions, blank.chars().next().unwrap_or('_'))
+            }
+            _ => (mask, '_'),
+        };
+        let mut chars = vec![];
+        let mut escaped = false;
+        for ch in positions.chars() {
+            let slot = |class, required| MaskChar::Slot { class, required };
+            chars.push(match ch {
+                _ if escaped => MaskChar::Literal(ch),
+                '\\' => {
+                    escaped = true;
+                    continue;
+                }
+                '9' => slot(CharClass::Digit, true),
+                '0' => slot(CharClass::Digit, false),
+                'A' => slot(CharClass::Letter, true),
+                'a' => slot(CharClass::Letter, false),
+                'N' => slot(CharClass::Alphanumeric, true),
+                'n' => slot(CharClass::Alphanumeric, false),
+                'X' => slot(CharClass::Any, true),
+                'x' => slot(CharClass::Any, false),
+                _ => MaskChar::Literal(ch),
+            });
+            escaped = false;
+        }
+        Self { chars, blank }
+    }
+
+    /// # Returns
+    /// Number of positions.
+    pub fn len(&self) -> usize {
+        self.chars.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.chars.is_empty()
+    }
+
+    /// # Returns
+    /// Character shown in empty positions.
+    pub fn blank(&self) -> char {
+        self.blank
+    }
+
+    /// # Arguments
+    /// * `index` - index of the position
+    /// # Returns
+    /// Whether the user can type to the position.
+    pub fn is_editable(&self, index: usize) -> bool {
+        matches!(self.chars.get(index), Some(MaskChar::Slot { .. }))
+    }
+
+    /// # Arguments
+    /// * `index` - index of the position
+    /// * `ch` - typed character
+    /// # Returns
+    /// Whether the character can be typed to the position.
+    pub fn accepts(&self, index: usize, ch: char) -> bool {
+        match self.chars.get(index) {
+            Some(MaskChar::Slot { class, .. }) => class.accepts(ch),
+            _ => false,
+        }
+    }
+
+    /// # Arguments
+    /// * `index` - index of the position
+    /// # Returns
+    /// Content of an empty position, the literal or the blank.
+    pub fn empty_char(&self, index: usize) -> char {
+        match self.chars.get(index) {
+            Some(MaskChar::Literal(ch)) => *ch,
+            _ => self.blank,
+        }
+    }
+
+    /// Fills the mask with the text as if it was typed: characters matching literals skip them
+    /// and characters not accepted by the next position are dropped.
+    /// # Arguments
+    /// * `text` - unmasked text
+    /// # Returns
+    /// Masked text.
+    pub fn apply(&self, text: &str) -> String {
+        let mut masked: Vec<char> = (0..self.len())
+            .map(|index| self.empty_char(index))
+            .collect();
+        let mut index = 0;
+        for ch in text.chars() {
+            while index < self.len() && !self.is_editable(index) && masked[index] != ch {
+                index += 1;
+            }
+            if index >= self.len() {
+                break;
+            }
+            if !self.is_editable(index) || self.accepts(index, ch) {
+                masked[index] = ch;
+                index += 1;
+            }
+        }
+        masked.into_iter().collect()
+    }
+
+    /// # Arguments
+    /// * `masked` - masked text
+    /// # Returns
+    /// Masked text without blanks in empty positions.
+    pub fn strip(&self, masked: &str) -> String {
+        masked
+            .chars()
+            .enumerate()
+            .filter(|(index, ch)| !(self.is_editable(*index) && *ch == self.blank))
+            .map(|(_, ch)| ch)
+            .collect()
+    }
+
+    /// # Arguments
+    /// * `masked` - masked text
+    /// # Returns
+    /// Whether all required positions are filled.
+    pub fn is_complete(&self, masked: &str) -> bool {
+        self.chars
+            .iter()
+            .zip(masked.chars())
+            .all(|(mask, ch)| match mask {
+                MaskChar::Slot { required: true, .. } => ch != self.blank,
+                _ => true,
+            })
+    }
+}
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+#[cfg(test)]
+mod tests {
+    // super
+    use super::*;
+
+    //********************************************************************************************
+    #[test]
+    fn apply_and_strip() {
+        let mask = InputMask::new("99-99-9999;#");
+        assert_eq!(mask.len(), 10);
+        assert_eq!(mask.blank(), '#');
+        assert_eq!(mask.apply(""), "##-##-####");
+        assert_eq!(mask.apply("31-1x22"), "31-12-2###");
+        assert_eq!(mask.strip("31-12-2###"), "31-12-2");
+        assert!(!mask.is_complete("31-12-2###"));
+        assert!(mask.is_complete(&mask.apply("31122024")));
+
+        let mask = InputMask::new("\\9Aa0");
+        assert!(!mask.is_editable(0));
+        assert!(mask.accepts(1, 'x'));
+        assert!(!mask.accepts(3, 'x'));
+        assert_eq!(mask.apply("9b"), "9b__");
+        assert!(mask.is_complete("9b__"));
+    }
+}
@@ -0,0 +1,502 @@
+// std
+use std::fmt::Debug;
+// deps
+use nalgebra::Point2;
+use nalgebra::Vector2;
+use uuid::Uuid;
+// crate
+use crate::poly_ui::app::clipboard;
+use crate::poly_ui::app::Color;
+use crate::poly_ui::app::ElideMode;
+use crate::poly_ui::app::Font;
+use crate::poly_ui::app::HorizontalAlignment;
+use crate::poly_ui::app::PainterTrait;
+use crate::poly_ui::app::Rect;
+use crate::poly_ui::app::TextLayout;
+use crate::poly_ui::app::VerticalAlignment;
+use crate::poly_ui::components::Hierarchy;
+use crate::poly_ui::components::Transform;
+use crate::poly_ui::events::Event;
+use crate::poly_ui::events::Key;
+use crate::poly_ui::events::MouseButton;
+use crate::poly_ui::widgets::NewWidget;
+use crate::poly_ui::widgets::OwnedWidget;
+use crate::poly_ui::widgets::WidgetTrait;
+
+/// Color of the background of selected text.
+pub(super) const SELECTION_COLOR: Color = Color::rgba(51, 153, 255, 160);
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// Widget displaying plain text. The text can be aligned in the Label, wrapped at spaces or
+/// elided when it doesn't fit and optionally selected with the mouse. A selectable Label takes
+/// focus and copies the selection to the clipboard on Ctrl+C. Its preferred size is the size of
+/// the text without wrapping.
+#[derive(Debug)]
+pub struct Label {
+    id: Uuid,
+    hierarchy: Hierarchy,
+
+    text: String,
+    font: Font,
+    color: Color,
+    horizontal_alignment: HorizontalAlignment,
+    vertical_alignment: VerticalAlignment,
+    word_wrap: bool,
+    elide_mode: ElideMode,
+
+    selectable: bool,
+    selection: (usize, usize),
+    selecting: bool,
+    size: Vector2<u32>,
+}
+
+//************************************************************************************************
+impl Label {
+    pub fn new_raw() -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            hierarchy: Hierarchy::default(),
+            text: String::new(),
+            font: Font::default(),
+            color: Color::WHITE,
+            horizontal_alignment: HorizontalAlignment::Left,
+            vertical_alignment: VerticalAlignment::Center,
+            word_wrap: false,
+            elide_mode: ElideMode::None,
+            selectable: false,
+            selection: (0, 0),
+            selecting: false,
+            size: Vector2::<u32>::new(0, 0),
+        }
+    }
+
+    pub fn new() -> NewWidget<Self> {
+        NewWidget::new(Self::new_raw())
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Sets displayed text and clears the selection.
+    /// # Arguments
+    /// * `text` - new text, lines are separated with '\n'
+    pub fn set_text(&mut self, text: &str) {
+        self.text = text.to_string();
+        self.selection = (0, 0);
+        self.request_repaint();
+    }
+
+    pub fn font(&self) -> &Font {
+        &self.font
+    }
+
+    pub fn set_font(&mut self, font: &Font) {
+        self.font = *font;
+        self.request_repaint();
+    }
+
+    pub fn color(&self) -> &Color {
+        &self.color
+    }
+
+    pub fn set_color(&mut self, color: &Color) {
+        self.color = *color;
+        self.request_repaint();
+    }
+
+    /// # Returns
+    /// Horizontal alignment of every line and vertical alignment of the whole text.
+    pub fn alignment(&self) -> (HorizontalAlignment, VerticalAlignment) {
+        (self.horizontal_alignment, self.vertical_alignment)
+    }
+
+    /// # Arguments
+    /// * `horizontal` - alignment of every line
+    /// * `vertical` - alignment of the whole text
+    pub fn set_alignment(&mut self, horizontal: HorizontalAlignment, vertical: VerticalAlignment) {
+        self.horizontal_alignment = horizontal;
+        self.vertical_alignment = vertical;
+        self.request_repaint();
+    }
+
+    pub fn word_wrap(&self) -> bool {
+        self.word_wrap
+    }
+
+    /// # Arguments
+    /// * `word_wrap` - whether lines wider than the Label are wrapped
+    pub fn set_word_wrap(&mut self, word_wrap: bool) {
+        self.word_wrap = word_wrap;
+        self.request_repaint();
+    }
+
+    pub fn elide_mode(&self) -> ElideMode {
+        self.elide_mode
+    }
+
+    /// # Arguments
+    /// * `elide_mode` - how lines wider than the Label are elided, it's ignored with word wrap
+    pub fn set_elide_mode(&mut self, elide_mode: ElideMode) {
+        self.elide_mode = elide_mode;
+        self.request_repaint();
+    }
+
+    pub fn is_selectable(&self) -> bool {
+        self.selectable
+    }
+
+    /// # Arguments
+    /// * `selectable` - whether the text can be selected with the mouse, disabling it clears the
+    ///   selection
+    pub fn set_selectable(&mut self, selectable: bool) {
+        self.selectable = selectable;
+        if !selectable {
+            self.selecting = false;
+            self.set_selection(0, 0);
+        }
+    }
+
+    /// # Returns
+    /// Range of selected characters (not bytes) of the text, None if nothing is selected.
+    pub fn selection(&self) -> Option<(usize, usize)> {
+        let (anchor, cursor) = self.selection;
+        if anchor == cursor {
+            None
+        } else {
+            Some((anchor.min(cursor), anchor.max(cursor)))
+        }
+    }
+
+    /// Selects characters of the text.
+    /// # Arguments
+    /// * `start` - index of the first selected character
+    /// * `end` - index after the last selected character, equal to start to clear the selection
+    pub fn set_selection(&mut self, start: usize, end: usize) {
+        let length = self.text.chars().count();
+        let selection = (start.min(length), end.min(length));
+        if selection != self.selection {
+            self.selection = selection;
+            self.request_repaint();
+        }
+    }
+
+    /// # Returns
+    /// Selected part of the text.
+    pub fn selected_text(&self) -> String {
+        match self.selection() {
+            Some((start, end)) => self.text.chars().skip(start).take(end - start).collect(),
+            None => String::new(),
+        }
+    }
+
+    /// # Arguments
+    /// * `width` - width of the Label
+    /// # Returns
+    /// Height of the text laid out in the given width.
+    pub fn height_for_width(&self, width: u32) -> u32 {
+        self.layout(width).size().y
+    }
+
+    fn layout(&self, width: u32) -> TextLayout {
+        TextLayout::new(
+            &self.text,
+            &self.font,
+            Some(width),
+            self.word_wrap,
+            self.elide_mode,
+        )
+    }
+
+    fn line_positions(&self, layout: &TextLayout) -> Vec<Point2<i32>> {
+        layout.line_positions(
+            &self.size,
+            self.horizontal_alignment,
+            self.vertical_alignment,
+        )
+    }
+
+    /// Moves the end of the selection to the character boundary nearest to the given point.
+    fn select_to(&mut self, pos: &Point2<i32>) {
+        let layout = self.layout(self.size.x);
+        let index = layout.hit_test(&self.line_positions(&layout), pos);
+        if index != self.selection.1 {
+            self.selection.1 = index;
+            self.request_repaint();
+        }
+    }
+}
+
+//************************************************************************************************
+impl WidgetTrait for Label {
+    fn id(&self) -> &Uuid {
+        &self.id
+    }
+
+    fn remove_child(&mut self, child: &Uuid) -> OwnedWidget {
+        self.hierarchy.remove(child)
+    }
+
+    fn get_hierarchy(&self) -> &Hierarchy {
+        &self.hierarchy
+    }
+
+    fn get_child_transform(&self, child: &Uuid) -> &Transform {
+        self.hierarchy.get_transform(child)
+    }
+
+    fn preferred_size(&self) -> Option<Vector2<u32>> {
+        Some(TextLayout::new(&self.text, &self.font, None, false, ElideMode::None).size())
+    }
+
+    fn accepts_focus(&self) -> bool {
+        self.selectable
+    }
+
+    fn handle_event(&mut self, event: &Event) -> bool {
+        if self.hierarchy.dispatch_event(event) {
+            return true;
+        }
+        if !self.selectable {
+            return false;
+        }
+
+        match event {
+            Event::MousePressEvent(press) if press.button() == MouseButton::Left => {
+                self.selecting = true;
+                self.select_to(&press.pos());
+                self.selection.0 = self.selection.1;
+                self.request_repaint();
+            }
+            Event::MouseMoveEvent(movement) if self.selecting => self.select_to(&movement.pos()),
+            Event::MouseReleaseEvent(release) if release.button() == MouseButton::Left => {
+                if self.selecting {
+                    self.select_to(&release.pos());
+                }
+                self.selecting = false;
+            }
+            Event::KeyPress(press) if press.key() == Key::C && press.modifiers().ctrl => {
+                if self.selection().is_none() {
+                    return false;
+                }
+                clipboard().borrow_mut().set_text(&self.selected_text());
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    fn update(&mut self, dt: f32) {
+        self.hierarchy.update_children(dt);
+    }
+
+    fn paint(&mut self, painter: &mut dyn PainterTrait) {
+        self.size = painter.size();
+        let layout = self.layout(self.size.x);
+        let positions = self.line_positions(&layout);
+
+        if let Some((start, end)) = self.selection() {
+            let cell = Vector2::<u32>::new(self.font.char_width(), self.font.line_height());
+            let rects = layout
+                .lines()
+                .iter()
+                .zip(&positions)
+                .flat_map(|(line, pos)| {
+                    line.glyphs
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, glyph)| {
+                            glyph
+                                .index
+                                .is_some_and(|index| index >= start && index < end)
+                        })
+                        .map(move |(column, _)| Rect {
+                            pos: Point2::<i32>::new(pos.x + (column as u32 * cell.x) as i32, pos.y),
+                            size: cell,
+                        })
+                })
+                .collect::<Vec<Rect>>();
+            painter.set_draw_color(&SELECTION_COLOR);
+            painter.fill_rects(&rects);
+        }
+
+        painter.set_draw_color(&self.color);
+        for (line, pos) in layout.lines().iter().zip(&positions) {
+            painter.draw_text(&line.text(), pos, &self.font);
+        }
+
+        self.hierarchy.paint_children(painter);
+    }
+}
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+#[cfg(test)]
+mod tests {
+    // crate
+    use crate::poly_ui::app::Brush;
+    use crate::poly_ui::app::DrawCommand;
+    use crate::poly_ui::app::RecordingPainter;
+    use crate::poly_ui::events::KeyModifiers;
+    use crate::poly_ui::events::KeyPressEvent;
+    use crate::poly_ui::events::MouseMoveEvent;
+    use crate::poly_ui::events::MousePressEvent;
+    use crate::poly_ui::events::MouseReleaseEvent;
+    use crate::poly_ui::layouts::GridLayout;
+    use crate::poly_ui::widgets::MockWidget;
+    // super
+    use super::*;
+
+    //********************************************************************************************
+    fn painted_commands(label: &mut Label, size: &Vector2<u32>) -> Vec<DrawCommand> {
+        let mut painter = RecordingPainter::new(size);
+        label.paint(&mut painter);
+        painter
+            .display_list()
+            .commands
+            .into_iter()
+            .map(|command| command.command)
+            .collect()
+    }
+
+    //********************************************************************************************
+    #[test]
+    fn paint_aligned_and_elided() {
+        let mut label = Label::new_raw();
+        label.set_text("hello world");
+        label.set_color(&Color::RED);
+        assert_eq!(label.preferred_size(), Some(Vector2::<u32>::new(66, 9)));
+
+        label.set_alignment(HorizontalAlignment::Right, VerticalAlignment::Bottom);
+        assert_eq!(
+            painted_commands(&mut label, &Vector2::<u32>::new(70, 20)),
+            vec![
+                DrawCommand::SetBrush(Brush::Solid(Color::RED)),
+                DrawCommand::DrawText(
+                    "hello world".to_string(),
+                    Point2::<i32>::new(4, 11),
+                    Font::default()
+                ),
+            ]
+        );
+
+        label.set_elide_mode(ElideMode::Right);
+        label.set_alignment(HorizontalAlignment::Center, VerticalAlignment::Top);
+        assert_eq!(
+            painted_commands(&mut label, &Vector2::<u32>::new(40, 20))[1],
+            DrawCommand::DrawText(
+                "hel...".to_string(),
+                Point2::<i32>::new(2, 0),
+                Font::default()
+            )
+        );
+
+        label.set_word_wrap(true);
+        assert_eq!(label.height_for_width(40), 18);
+        let commands = painted_commands(&mut label, &Vector2::<u32>::new(40, 20));
+        assert_eq!(
+            commands[2],
+            DrawCommand::DrawText(
+                "world".to_string(),
+                Point2::<i32>::new(5, 9),
+                Font::default()
+            )
+        );
+    }
+
+    //********************************************************************************************
+    #[test]
+    fn select_with_mouse() {
+        let mut label = Label::new_raw();
+        label.set_text("abc\ndef");
+        label.set_alignment(HorizontalAlignment::Left, VerticalAlignment::Top);
+        painted_commands(&mut label, &Vector2::<u32>::new(30, 18));
+
+        let press = Event::MousePressEvent(MousePressEvent::new(
+            MouseButton::Left,
+            &Point2::<i32>::new(5, 2),
+        ));
+        assert!(!label.handle_event(&press));
+        label.set_selectable(true);
+        assert!(label.handle_event(&press));
+        assert!(
+            label.handle_event(&Event::MouseMoveEvent(MouseMoveEvent::new(
+                &Point2::<i32>::new(8, 12)
+            )))
+        );
+        assert!(
+            label.handle_event(&Event::MouseReleaseEvent(MouseReleaseEvent::new(
+                MouseButton::Left,
+                &Point2::<i32>::new(8, 12)
+            )))
+        );
+        assert_eq!(label.selection(), Some((1, 5)));
+        assert_eq!(label.selected_text(), "bc\nd");
+        assert!(label.accepts_focus());
+        assert!(
+            label.handle_event(&Event::KeyPress(KeyPressEvent::with_modifiers(
+                Key::C,
+                KeyModifiers::CTRL
+            )))
+        );
+        assert_eq!(clipboard().borrow().text().as_deref(), Some("bc\nd"));
+
+        // moving after the release doesn't change the selection
+        label.handle_event(&Event::MouseMoveEvent(MouseMoveEvent::new(
+            &Point2::<i32>::new(0, 0),
+        )));
+        assert_eq!(label.selection(), Some((1, 5)));
+        let commands = painted_commands(&mut label, &Vector2::<u32>::new(30, 18));
+        assert_eq!(
+            commands[1],
+            DrawCommand::FillRects(vec![
+                Rect {
+                    pos: Point2::<i32>::new(6, 0),
+                    size: Vector2::<u32>::new(6, 9),
+                },
+                Rect {
+                    pos: Point2::<i32>::new(12, 0),
+                    size: Vector2::<u32>::new(6, 9),
+                },
+                Rect {
+                    pos: Point2::<i32>::new(0, 9),
+                    size: Vector2::<u32>::new(6, 9),
+                },
+            ])
+        );
+
+        label.set_text("x");
+        assert_eq!(label.selection(), None);
+    }
+
+    //********************************************************************************************
+    #[test]
+    fn preferred_size_in_grid() {
+        let grid = GridLayout::new();
+        let label = Label::new();
+        label.borrow_mut().set_text("a long label");
+        let label_ptr = label.get().clone();
+        grid.borrow_mut()
+            .insert_child_at(label.make_owned(), &Some(0), &Some(0));
+        let other = MockWidget::new();
+        let other_ptr = other.get().clone();
+        grid.borrow_mut()
+            .insert_child_at(other.make_owned(), &Some(1), &Some(0));
+
+        let mut painter = RecordingPainter::new(&Vector2::<u32>::new(100, 40));
+        grid.borrow_mut().paint(&mut painter);
+        let id = *label_ptr.borrow().id();
+        assert_eq!(
+            grid.borrow().get_child_transform(&id).size,
+            Vector2::<u32>::new(72, 40)
+        );
+        let id = *other_ptr.borrow().id();
+        assert_eq!(
+            grid.borrow().get_child_transform(&id),
+            &Transform::new(&Point2::<i32>::new(72, 0), &Vector2::<u32>::new(28, 40))
+        );
+    }
+}
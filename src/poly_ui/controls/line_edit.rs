@@ -0,0 +1,1110 @@
+// std
+use std::fmt::Debug;
+// deps
+use nalgebra::Point2;
+use nalgebra::Vector2;
+use uuid::Uuid;
+// crate
+use crate::poly_ui::app::clipboard;
+use crate::poly_ui::app::Color;
+use crate::poly_ui::app::Font;
+use crate::poly_ui::app::PainterTrait;
+use crate::poly_ui::app::Rect;
+use crate::poly_ui::components::Hierarchy;
+use crate::poly_ui::components::Signal;
+use crate::poly_ui::components::Transform;
+use crate::poly_ui::events::Event;
+use crate::poly_ui::events::Key;
+use crate::poly_ui::events::KeyPressEvent;
+use crate::poly_ui::events::MouseButton;
+use crate::poly_ui::widgets::NewWidget;
+use crate::poly_ui::widgets::OwnedWidget;
+use crate::poly_ui::widgets::WidgetTrait;
+// super
+use super::button_base::BORDER_COLOR;
+use super::button_base::FOCUS_COLOR;
+use super::label::SELECTION_COLOR;
+use super::InputMask;
+use super::ValidatorTrait;
+use super::Validity;
+
+/// Space between the border and the text.
+const PADDING_X: u32 = 4;
+const PADDING_Y: u32 = 3;
+/// Number of characters the preferred width has room for.
+const PREFERRED_CHARS: u32 = 12;
+/// Default maximal number of characters.
+const DEFAULT_MAX_LENGTH: usize = 32767;
+/// Time in milliseconds the cursor is shown or hidden while blinking.
+pub(super) const CURSOR_BLINK_INTERVAL: f32 = 500.0;
+/// Maximal number of edits kept on the undo stack.
+const UNDO_LIMIT: usize = 100;
+/// Character displayed instead of every character in the Password echo mode.
+const PASSWORD_CHAR: char = '*';
+/// Color of the background of editable text.
+pub(super) const BACKGROUND_COLOR: Color = Color::rgb(30, 30, 30);
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// How the text of a LineEdit is displayed.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum EchoMode {
+    Normal,
+    /// Every character is displayed as '*'.
+    Password,
+    /// Nothing is displayed.
+    NoEcho,
+}
+
+//************************************************************************************************
+/// Text with the cursor and the selection saved on the undo and redo stacks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct EditState {
+    chars: Vec<char>,
+    cursor: usize,
+    anchor: usize,
+}
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// Widget for editing a single line of text. The text is edited with the keyboard at the cursor
+/// or replaces the selection made with the mouse or with Shift and arrow keys; it scrolls
+/// horizontally to keep the cursor visible. Supported shortcuts are Ctrl+A (select all), Ctrl+C,
+/// Ctrl+X, Ctrl+V (clipboard), Ctrl+Z (undo) and Ctrl+Y or Ctrl+Shift+Z (redo); Ctrl with arrows
+/// moves by words.
+///
+/// Edits can be restricted with the maximal length, a validator rejecting invalid text and an
+/// input mask, which makes the text fixed length with typed characters overwriting its
+/// positions.
+///
+/// Positions are indices of characters, unlike TextEdit which uses byte offsets of its buffer.
+#[derive(Debug)]
+pub struct LineEdit {
+    id: Uuid,
+    hierarchy: Hierarchy,
+
+    /// Edited text, masked text when there is an input mask.
+    chars: Vec<char>,
+    cursor: usize,
+    anchor: usize,
+    /// Index of the first visible character.
+    scroll: usize,
+    font: Font,
+    placeholder: String,
+    max_length: usize,
+    echo_mode: EchoMode,
+    read_only: bool,
+    validator: Option<Box<dyn ValidatorTrait>>,
+    mask: Option<InputMask>,
+
+    undo_stack: Vec<EditState>,
+    redo_stack: Vec<EditState>,
+    /// Whether the last edit typed a character at the cursor; the next typed characters are
+    /// merged into its undo step.
+    typing: bool,
+    text_changed: Signal<String>,
+    return_pressed: Signal<()>,
+
+    focused: bool,
+    selecting: bool,
+    cursor_visible: bool,
+    blink_timer: f32,
+    size: Vector2<u32>,
+}
+
+//************************************************************************************************
+impl LineEdit {
+    pub fn new_raw() -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            hierarchy: Hierarchy::default(),
+            chars: vec![],
+            cursor: 0,
+            anchor: 0,
+            scroll: 0,
+            font: Font::default(),
+            placeholder: String::new(),
+            max_length: DEFAULT_MAX_LENGTH,
+            echo_mode: EchoMode::Normal,
+            read_only: false,
+            validator: None,
+            mask: None,
+            undo_stack: vec![],
+            redo_stack: vec![],
+            typing: false,
+            text_changed: Signal::new(),
+            return_pressed: Signal::new(),
+            focused: false,
+            selecting: false,
+            cursor_visible: true,
+            blink_timer: 0.0,
+            size: Vector2::<u32>::new(0, 0),
+        }
+    }
+
+    pub fn new() -> NewWidget<Self> {
+        NewWidget::new(Self::new_raw())
+    }
+
+    /// # Returns
+    /// Signal emitted with the new text whenever the text changes.
+    pub fn text_changed(&self) -> &Signal<String> {
+        &self.text_changed
+    }
+
+    /// # Returns
+    /// Signal emitted when Return or Enter is pressed and the text is acceptable.
+    pub fn return_pressed(&self) -> &Signal<()> {
+        &self.return_pressed
+    }
+
+    /// # Returns
+    /// Edited text; with an input mask it's without the blanks of empty positions.
+    pub fn text(&self) -> String {
+        let text: String = self.chars.iter().collect();
+        match &self.mask {
+            Some(mask) => mask.strip(&text),
+            None => text,
+        }
+    }
+
+    /// # Returns
+    /// Text as it's edited; with an input mask it contains literals and blanks.
+    pub fn displayed_text(&self) -> String {
+        self.chars.iter().collect()
+    }
+
+    /// Replaces the text, puts the cursor at its end and clears the undo history. The text is
+    /// not validated, but it's truncated to the maximal length and filled into the input mask.
+    /// # Arguments
+    /// * `text` - new text
+    pub fn set_text(&mut self, text: &str) {
+        self.chars = match &self.mask {
+            Some(mask) => mask.apply(text).chars().collect(),
+            None => text.chars().take(self.max_length).collect(),
+        };
+        self.cursor = self.chars.len();
+        self.anchor = self.cursor;
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+        self.typing = false;
+        self.text_edited();
+    }
+
+    pub fn font(&self) -> &Font {
+        &self.font
+    }
+
+    pub fn set_font(&mut self, font: &Font) {
+        self.font = *font;
+        self.request_repaint();
+    }
+
+    pub fn placeholder(&self) -> &str {
+        &self.placeholder
+    }
+
+    /// # Arguments
+    /// * `placeholder` - text displayed in gray while the LineEdit is empty
+    pub fn set_placeholder(&mut self, placeholder: &str) {
+        self.placeholder = placeholder.to_string();
+        self.request_repaint();
+    }
+
+    pub fn max_length(&self) -> usize {
+        self.max_length
+    }
+
+    /// # Arguments
+    /// * `max_length` - maximal number of characters, longer text is truncated; it's ignored
+    ///   with an input mask
+    pub fn set_max_length(&mut self, max_length: usize) {
+        self.max_length = max_length;
+        if self.mask.is_none() && self.chars.len() > max_length {
+            let text: String = self.chars.iter().take(max_length).collect();
+            self.set_text(&text);
+        }
+    }
+
+    pub fn echo_mode(&self) -> EchoMode {
+        self.echo_mode
+    }
+
+    /// # Arguments
+    /// * `echo_mode` - how the text is displayed, copying is disabled unless it's Normal
+    pub fn set_echo_mode(&mut self, echo_mode: EchoMode) {
+        self.echo_mode = echo_mode;
+        self.request_repaint();
+    }
+
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
+    /// # Arguments
+    /// * `read_only` - whether the user can only select and copy the text
+    pub fn set_read_only(&mut self, read_only: bool) {
+        self.read_only = read_only;
+        self.request_repaint();
+    }
+
+    pub fn validator(&self) -> Option<&dyn ValidatorTrait> {
+        self.validator.as_deref()
+    }
+
+    /// # Arguments
+    /// * `validator` - validator of the edited text, edits making the text invalid are rejected
+    pub fn set_validator(&mut self, validator: Option<Box<dyn ValidatorTrait>>) {
+        self.validator = validator;
+    }
+
+    pub fn input_mask(&self) -> Option<&InputMask> {
+        self.mask.as_ref()
+    }
+
+    /// Sets the input mask and fills the current text into it.
+    /// # Arguments
+    /// * `mask` - new input mask, None to edit free text
+    pub fn set_input_mask(&mut self, mask: Option<InputMask>) {
+        let text = self.text();
+        self.mask = mask;
+        self.set_text(&text);
+    }
+
+    /// # Returns
+    /// Whether the input mask is complete and the validator accepts the text.
+    pub fn has_acceptable_input(&self) -> bool {
+        let text = self.text();
+        self.mask
+            .as_ref()
+            .map_or(true, |mask| mask.is_complete(&self.displayed_text()))
+            && self.validator.as_ref().map_or(true, |validator| {
+                validator.validate(&text) == Validity::Acceptable
+            })
+    }
+
+    /// # Returns
+    /// Index of the character (not byte) the cursor is in front of.
+    pub fn cursor_position(&self) -> usize {
+        self.cursor
+    }
+
+    /// Moves the cursor and clears the selection.
+    /// # Arguments
+    /// * `position` - index of the character the cursor is put in front of
+    pub fn set_cursor_position(&mut self, position: usize) {
+        self.move_cursor(position, false);
+    }
+
+    /// # Returns
+    /// Range of selected characters (not bytes), None if nothing is selected.
+    pub fn selection(&self) -> Option<(usize, usize)> {
+        if self.anchor == self.cursor {
+            None
+        } else {
+            Some((self.anchor.min(self.cursor), self.anchor.max(self.cursor)))
+        }
+    }
+
+    /// Selects characters and puts the cursor at the end of the selection.
+    /// # Arguments
+    /// * `start` - index of the first selected character
+    /// * `end` - index after the last selected character, equal to start to clear the selection
+    pub fn set_selection(&mut self, start: usize, end: usize) {
+        self.move_cursor(start, false);
+        self.move_cursor(end, true);
+    }
+
+    pub fn select_all(&mut self) {
+        self.set_selection(0, self.chars.len());
+    }
+
+    /// # Returns
+    /// Selected part of the displayed text.
+    pub fn selected_text(&self) -> String {
+        match self.selection() {
+            Some((start, end)) => self.chars[start..end].iter().collect(),
+            None => String::new(),
+        }
+    }
+
+    /// Copies the selection to the clipboard unless the echo mode hides the text.
+    pub fn copy(&self) {
+        if self.selection().is_some() && self.echo_mode == EchoMode::Normal {
+            clipboard().borrow_mut().set_text(&self.selected_text());
+        }
+    }
+
+    /// Moves the selection to the clipboard unless the LineEdit is read only or the echo mode
+    /// hides the text.
+    pub fn cut(&mut self) {
+        if !self.read_only && self.echo_mode == EchoMode::Normal {
+            self.copy();
+            self.insert("");
+        }
+    }
+
+    /// Replaces the selection with the text in the clipboard.
+    pub fn paste(&mut self) {
+        let text = clipboard().borrow().text();
+        if let Some(text) = text {
+            self.insert(&text);
+        }
+    }
+
+    /// Replaces the selection with the text as if it was typed; it's rejected if the LineEdit is
+    /// read only or the text becomes invalid.
+    /// # Arguments
+    /// * `text` - inserted text, control characters are ignored
+    /// # Returns
+    /// Whether the text changed.
+    pub fn insert(&mut self, text: &str) -> bool {
+        let (start, end) = self.selection().unwrap_or((self.cursor, self.cursor));
+        self.replace(start, end, text)
+    }
+
+    pub fn is_undo_available(&self) -> bool {
+        !self.undo_stack.is_empty()
+    }
+
+    pub fn is_redo_available(&self) -> bool {
+        !self.redo_stack.is_empty()
+    }
+
+    /// Reverts the last edit.
+    pub fn undo(&mut self) {
+        if let Some(state) = self.undo_stack.pop() {
+            let current = self.restore(state);
+            self.redo_stack.push(current);
+            self.typing = false;
+        }
+    }
+
+    /// Repeats the last undone edit.
+    pub fn redo(&mut self) {
+        if let Some(state) = self.redo_stack.pop() {
+            let current = self.restore(state);
+            self.push_undo(current);
+            self.typing = false;
+        }
+    }
+
+    /// Replaces the text and the selection with a state from the undo or redo stack.
+    /// # Returns
+    /// Replaced state.
+    fn restore(&mut self, state: EditState) -> EditState {
+        let current = self.edit_state();
+        self.chars = state.chars;
+        self.cursor = state.cursor;
+        self.anchor = state.anchor;
+        self.text_edited();
+        current
+    }
+
+    fn edit_state(&self) -> EditState {
+        EditState {
+            chars: self.chars.clone(),
+            cursor: self.cursor,
+            anchor: self.anchor,
+        }
+    }
+
+    /// Replaces characters with the text; with an input mask the replaced positions are emptied
+    /// and the text overwrites positions from the start.
+    /// # Arguments
+    /// * `start` - index of the first replaced character
+    /// * `end` - index after the last replaced character
+    /// * `text` - new text, control characters are ignored
+    /// # Returns
+    /// Whether the edit was accepted and the text changed.
+    fn replace(&mut self, start: usize, end: usize, text: &str) -> bool {
+        if self.read_only {
+            return false;
+        }
+        let typed = start == end && start == self.cursor && text.chars().count() == 1;
+        let text = text.chars().filter(|ch| !ch.is_control());
+        let (chars, cursor) = match &self.mask {
+            Some(mask) => {
+                let mut chars = self.chars.clone();
+                for (index, ch) in chars.iter_mut().enumerate().take(end).skip(start) {
+                    *ch = mask.empty_char(index);
+                }
+                let mut cursor = start;
+                let mut typed = false;
+                for ch in text {
+                    while cursor < chars.len() && !mask.is_editable(cursor) && chars[cursor] != ch {
+                        cursor += 1;
+                    }
+                    if cursor >= chars.len() {
+                        break;
+                    }
+                    if !mask.is_editable(cursor) || mask.accepts(cursor, ch) {
+                        chars[cursor] = ch;
+                        cursor += 1;
+                        typed = true;
+                    }
+                }
+                while typed && cursor < chars.len() && !mask.is_editable(cursor) {
+                    cursor += 1;
+                }
+                (chars, cursor)
+            }
+            None => {
+                let room = self
+                    .max_length
+                    .saturating_sub(self.chars.len() - (end - start));
+                let inserted: Vec<char> = text.take(room).collect();
+                let cursor = start + inserted.len();
+                let chars = [&self.chars[..start], &inserted, &self.chars[end..]].concat();
+                (chars, cursor)
+            }
+        };
+        if chars == self.chars {
+            if cursor != self.cursor || self.anchor != self.cursor {
+                self.move_cursor(cursor, false);
+            }
+            return false;
+        }
+        if let Some(validator) = &self.validator {
+            let text: String = chars.iter().collect();
+            let text = match &self.mask {
+                Some(mask) => mask.strip(&text),
+                None => text,
+            };
+            if validator.validate(&text) == Validity::Invalid {
+                return false;
+            }
+        }
+        if !(typed && self.typing) {
+            self.push_undo(self.edit_state());
+        }
+        self.redo_stack.clear();
+        self.chars = chars;
+        self.cursor = cursor;
+        self.anchor = cursor;
+        self.text_edited();
+        self.typing = typed;
+        true
+    }
+
+    /// Pushes the state to the undo stack, the oldest state is dropped when it's full.
+    fn push_undo(&mut self, state: EditState) {
+        if self.undo_stack.len() >= UNDO_LIMIT {
+            self.undo_stack.remove(0);
+        }
+        self.undo_stack.push(state);
+    }
+
+    /// Scrolls to the cursor, repaints and emits the text_changed signal after a change of the
+    /// text.
+    fn text_edited(&mut self) {
+        self.cursor = self.cursor.min(self.chars.len());
+        self.anchor = self.anchor.min(self.chars.len());
+        self.cursor_moved();
+        self.text_changed.emit(&self.text());
+    }
+
+    /// # Arguments
+    /// * `position` - new index of the cursor
+    /// * `extend` - whether the selection is extended to the cursor instead of cleared
+    fn move_cursor(&mut self, position: usize, extend: bool) {
+        self.typing = false;
+        self.cursor = position.min(self.chars.len());
+        if !extend {
+            self.anchor = self.cursor;
+        }
+        self.cursor_moved();
+    }
+
+    /// Shows the cursor, scrolls to it and repaints.
+    fn cursor_moved(&mut self) {
+        self.cursor_visible = true;
+        self.blink_timer = 0.0;
+        self.scroll_to_cursor();
+        self.request_repaint();
+    }
+
+    /// # Returns
+    /// Number of whole characters fitting into the LineEdit.
+    fn visible_chars(&self) -> usize {
+        let width = self.size.x.saturating_sub(PADDING_X * 2);
+        (width / self.font.char_width().max(1)) as usize
+    }
+
+    /// Scrolls the least possible number of characters to make the cursor visible without
+    /// leaving empty space after the end of the text.
+    fn scroll_to_cursor(&mut self) {
+        let visible = self.visible_chars();
+        if self.cursor < self.scroll {
+            self.scroll = self.cursor;
+        } else if self.cursor > self.scroll + visible {
+            self.scroll = self.cursor - visible;
+        }
+        self.scroll = self.scroll.min(self.chars.len().saturating_sub(visible));
+    }
+
+    /// # Arguments
+    /// * `forward` - direction of the search
+    /// # Returns
+    /// Index of the start of the previous word or the end of the next word; the ends of the text
+    /// if the echo mode hides the words.
+    fn word_boundary(&self, forward: bool) -> usize {
+        if self.echo_mode != EchoMode::Normal {
+            return if forward { self.chars.len() } else { 0 };
+        }
+        let mut index = self.cursor;
+        if forward {
+            while index < self.chars.len() && self.chars[index].is_whitespace() {
+                index += 1;
+            }
+            while index < self.chars.len() && !self.chars[index].is_whitespace() {
+                index += 1;
+            }
+        } else {
+            while index > 0 && self.chars[index - 1].is_whitespace() {
+                index -= 1;
+            }
+            while index > 0 && !self.chars[index - 1].is_whitespace() {
+                index -= 1;
+            }
+        }
+        index
+    }
+
+    /// # Arguments
+    /// * `pos` - point in the LineEdit
+    /// # Returns
+    /// Index of the character boundary nearest to the point; points before the start scroll
+    /// back by one character.
+    fn hit_test(&self, pos: &Point2<i32>) -> usize {
+        if self.echo_mode == EchoMode::NoEcho {
+            return self.cursor;
+        }
+        let char_width = self.font.char_width().max(1) as i32;
+        let column = (pos.x - PADDING_X as i32 + char_width / 2).div_euclid(char_width);
+        (self.scroll as i32 + column).clamp(0, self.chars.len() as i32) as usize
+    }
+
+    /// # Returns
+    /// Whether the key was a handled shortcut or editing key.
+    fn handle_key(&mut self, press: &KeyPressEvent) -> bool {
+        let modifiers = press.modifiers();
+        let (start, end) = self.selection().unwrap_or((self.cursor, self.cursor));
+        match press.key() {
+            Key::Left if modifiers.ctrl => {
+                self.move_cursor(self.word_boundary(false), modifiers.shift)
+            }
+            Key::Right if modifiers.ctrl => {
+                self.move_cursor(self.word_boundary(true), modifiers.shift)
+            }
+            Key::Left if start != end && !modifiers.shift => self.move_cursor(start, false),
+            Key::Right if start != end && !modifiers.shift => self.move_cursor(end, false),
+            Key::Left => self.move_cursor(self.cursor.saturating_sub(1), modifiers.shift),
+            Key::Right => self.move_cursor(self.cursor + 1, modifiers.shift),
+            Key::Home => self.move_cursor(0, modifiers.shift),
+            Key::End => self.move_cursor(self.chars.len(), modifiers.shift),
+            Key::Backspace if start != end => {
+                self.replace(start, end, "");
+            }
+            Key::Backspace if self.cursor > 0 => {
+                let from = if modifiers.ctrl {
+                    self.word_boundary(false)
+                } else {
+                    self.cursor - 1
+                };
+                self.replace(from, self.cursor, "");
+            }
+            Key::Delete if start != end => {
+                self.replace(start, end, "");
+            }
+            Key::Delete if self.cursor < self.chars.len() => {
+                let to = if modifiers.ctrl {
+                    self.word_boundary(true)
+                } else {
+                    self.cursor + 1
+                };
+                self.replace(self.cursor, to, "");
+            }
+            Key::Backspace | Key::Delete => {}
+            Key::Return | Key::KpEnter => {
+                if self.has_acceptable_input() {
+                    self.return_pressed.emit(&());
+                }
+            }
+            Key::A if modifiers.ctrl => self.select_all(),
+            Key::C if modifiers.ctrl => self.copy(),
+            Key::X if modifiers.ctrl => self.cut(),
+            Key::V if modifiers.ctrl => self.paste(),
+            Key::Z if modifiers.ctrl && modifiers.shift => self.redo(),
+            Key::Z if modifiers.ctrl => self.undo(),
+            Key::Y if modifiers.ctrl => self.redo(),
+            _ => return false,
+        }
+        true
+    }
+}
+
+//************************************************************************************************
+impl WidgetTrait for LineEdit {
+    fn id(&self) -> &Uuid {
+        &self.id
+    }
+
+    fn remove_child(&mut self, child: &Uuid) -> OwnedWidget {
+        self.hierarchy.remove(child)
+    }
+
+    fn get_hierarchy(&self) -> &Hierarchy {
+        &self.hierarchy
+    }
+
+    fn get_child_transform(&self, child: &Uuid) -> &Transform {
+        self.hierarchy.get_transform(child)
+    }
+
+    fn preferred_size(&self) -> Option<Vector2<u32>> {
+        Some(Vector2::<u32>::new(
+            self.font.char_width() * PREFERRED_CHARS + PADDING_X * 2,
+            self.font.line_height() + PADDING_Y * 2,
+        ))
+    }
+
+    fn accepts_focus(&self) -> bool {
+        true
+    }
+
+    fn handle_event(&mut self, event: &Event) -> bool {
+        if self.hierarchy.dispatch_event(event) {
+            return true;
+        }
+
+        match event {
+            Event::FocusIn => {
+                self.focused = true;
+                self.cursor_moved();
+            }
+            Event::FocusOut => {
+                self.focused = false;
+                self.selecting = false;
+                self.request_repaint();
+            }
+            Event::MousePressEvent(press) if press.button() == MouseButton::Left => {
+                self.selecting = true;
+                self.move_cursor(self.hit_test(&press.pos()), false);
+            }
+            Event::MouseMoveEvent(movement) if self.selecting => {
+                self.move_cursor(self.hit_test(&movement.pos()), true);
+            }
+            Event::MouseReleaseEvent(release) if release.button() == MouseButton::Left => {
+                if self.selecting {
+                    self.move_cursor(self.hit_test(&release.pos()), true);
+                }
+                self.selecting = false;
+            }
+            Event::KeyPress(press) if self.focused => return self.handle_key(press),
+            Event::TextInput(input) if self.focused => {
+                let mut buffer = [0; 4];
+                self.insert(input.ch().encode_utf8(&mut buffer));
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    fn update(&mut self, dt: f32) {
+        if self.focused {
+            self.blink_timer += dt;
+            if self.blink_timer >= CURSOR_BLINK_INTERVAL {
+                self.blink_timer %= CURSOR_BLINK_INTERVAL;
+                self.cursor_visible = !self.cursor_visible;
+                self.request_repaint();
+            }
+        }
+        self.hierarchy.update_children(dt);
+    }
+
+    fn paint(&mut self, painter: &mut dyn PainterTrait) {
+        self.size = painter.size();
+        self.scroll_to_cursor();
+        let rect = Rect {
+            pos: Point2::<i32>::new(0, 0),
+            size: self.size,
+        };
+        painter.set_draw_color(&BACKGROUND_COLOR);
+        painter.fill_rect(rect);
+        painter.set_draw_color(if self.focused {
+            &FOCUS_COLOR
+        } else {
+            &BORDER_COLOR
+        });
+        painter.draw_rect(rect);
+
+        let char_width = self.font.char_width();
+        let line_height = self.font.line_height();
+        let text_y = (self.size.y as i32 - line_height as i32) / 2;
+        let column_x = |column: usize| PADDING_X as i32 + (column as u32 * char_width) as i32;
+        let visible = self.visible_chars();
+
+        if self.chars.is_empty() {
+            if !self.placeholder.is_empty() {
+                let placeholder: String = self.placeholder.chars().take(visible).collect();
+                painter.set_draw_color(&Color::GRAY);
+                painter.draw_text(
+                    &placeholder,
+                    &Point2::<i32>::new(column_x(0), text_y),
+                    &self.font,
+                );
+            }
+        } else if self.echo_mode != EchoMode::NoEcho {
+            let end = self.chars.len().min(self.scroll + visible);
+            if let Some((start, stop)) = self.selection() {
+                let (start, stop) = (start.max(self.scroll), stop.min(end));
+                if start < stop {
+                    painter.set_draw_color(&SELECTION_COLOR);
+                    painter.fill_rect(Rect {
+                        pos: Point2::<i32>::new(column_x(start - self.scroll), text_y),
+                        size: Vector2::<u32>::new((stop - start) as u32 * char_width, line_height),
+                    });
+                }
+            }
+            let text: String = match self.echo_mode {
+                EchoMode::Password => std::iter::repeat(PASSWORD_CHAR)
+                    .take(end - self.scroll)
+                    .collect(),
+                _ => self.chars[self.scroll..end].iter().collect(),
+            };
+            painter.set_draw_color(&Color::WHITE);
+            painter.draw_text(&text, &Point2::<i32>::new(column_x(0), text_y), &self.font);
+        }
+
+        if self.focused && self.cursor_visible && !self.read_only {
+            let column = match self.echo_mode {
+                EchoMode::NoEcho => 0,
+                _ => self.cursor - self.scroll,
+            };
+            painter.set_draw_color(&Color::WHITE);
+            painter.fill_rect(Rect {
+                pos: Point2::<i32>::new(column_x(column), text_y),
+                size: Vector2::<u32>::new(1, line_height),
+            });
+        }
+
+        self.hierarchy.paint_children(painter);
+    }
+}
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+#[cfg(test)]
+mod tests {
+    // std
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    // crate
+    use crate::poly_ui::app::AppTrait;
+    use crate::poly_ui::app::Brush;
+    use crate::poly_ui::app::DrawCommand;
+    use crate::poly_ui::app::RecordingPainter;
+    use crate::poly_ui::controls::IntValidator;
+    use crate::poly_ui::events::KeyModifiers;
+    use crate::poly_ui::events::MouseMoveEvent;
+    use crate::poly_ui::events::MousePressEvent;
+    use crate::poly_ui::events::MouseReleaseEvent;
+    use crate::poly_ui::events::TextInputEvent;
+    use crate::poly_ui::headless::App;
+    use crate::poly_ui::headless::FRAME_TIME;
+    // super
+    use super::*;
+
+    //********************************************************************************************
+    fn painted_commands(edit: &mut LineEdit, size: &Vector2<u32>) -> Vec<DrawCommand> {
+        let mut painter = RecordingPainter::new(size);
+        edit.paint(&mut painter);
+        painter
+            .display_list()
+            .commands
+            .into_iter()
+            .map(|command| command.command)
+            .collect()
+    }
+
+    //********************************************************************************************
+    fn type_text(edit: &mut LineEdit, text: &str) {
+        for ch in text.chars() {
+            edit.handle_event(&Event::TextInput(TextInputEvent::new(ch)));
+        }
+    }
+
+    //********************************************************************************************
+    fn press(edit: &mut LineEdit, key: Key, modifiers: KeyModifiers) -> bool {
+        edit.handle_event(&Event::KeyPress(KeyPressEvent::with_modifiers(
+            key, modifiers,
+        )))
+    }
+
+    //********************************************************************************************
+    #[test]
+    fn type_select_and_scroll() {
+        let mut edit = LineEdit::new_raw();
+        assert_eq!(edit.preferred_size(), Some(Vector2::<u32>::new(80, 15)));
+        let changes = Rc::new(RefCell::new(vec![]));
+        let changes_clone = changes.clone();
+        edit.text_changed()
+            .connect(move |text| changes_clone.borrow_mut().push(text.clone()));
+
+        // unfocused LineEdit ignores typing
+        type_text(&mut edit, "x");
+        assert_eq!(edit.text(), "");
+        edit.handle_event(&Event::FocusIn);
+        type_text(&mut edit, "hello world");
+        assert_eq!(edit.text(), "hello world");
+        assert_eq!(changes.borrow().len(), 11);
+        assert_eq!(changes.borrow()[10], "hello world");
+
+        assert!(press(&mut edit, Key::Left, KeyModifiers::CTRL));
+        assert!(press(&mut edit, Key::End, KeyModifiers::SHIFT));
+        assert_eq!(edit.selected_text(), "world");
+        type_text(&mut edit, "there");
+        assert_eq!(edit.text(), "hello there");
+        assert!(press(&mut edit, Key::Backspace, KeyModifiers::CTRL));
+        assert_eq!(edit.text(), "hello ");
+        assert!(!press(&mut edit, Key::Tab, KeyModifiers::NONE));
+
+        // 5 visible characters, the cursor at the end scrolls the text
+        edit.set_text("abcdefgh");
+        let size = Vector2::<u32>::new(38, 15);
+        let commands = painted_commands(&mut edit, &size);
+        assert_eq!(
+            commands[4..],
+            [
+                DrawCommand::SetBrush(Brush::Solid(Color::WHITE)),
+                DrawCommand::DrawText(
+                    "defgh".to_string(),
+                    Point2::<i32>::new(4, 3),
+                    Font::default()
+                ),
+                DrawCommand::SetBrush(Brush::Solid(Color::WHITE)),
+                DrawCommand::FillRect(Rect {
+                    pos: Point2::<i32>::new(34, 3),
+                    size: Vector2::<u32>::new(1, 9),
+                }),
+            ]
+        );
+
+        // dragging from 'e' to before the start selects and scrolls back
+        edit.handle_event(&Event::MousePressEvent(MousePressEvent::new(
+            MouseButton::Left,
+            &Point2::<i32>::new(11, 5),
+        )));
+        edit.handle_event(&Event::MouseMoveEvent(MouseMoveEvent::new(
+            &Point2::<i32>::new(-5, 5),
+        )));
+        edit.handle_event(&Event::MouseReleaseEvent(MouseReleaseEvent::new(
+            MouseButton::Left,
+            &Point2::<i32>::new(5, 5),
+        )));
+        assert_eq!(edit.selection(), Some((2, 4)));
+        let commands = painted_commands(&mut edit, &size);
+        assert_eq!(
+            commands[4..6],
+            [
+                DrawCommand::SetBrush(Brush::Solid(SELECTION_COLOR)),
+                DrawCommand::FillRect(Rect {
+                    pos: Point2::<i32>::new(4, 3),
+                    size: Vector2::<u32>::new(12, 9),
+                }),
+            ]
+        );
+        assert_eq!(
+            commands[7],
+            DrawCommand::DrawText(
+                "cdefg".to_string(),
+                Point2::<i32>::new(4, 3),
+                Font::default()
+            )
+        );
+
+        // the cursor blinks
+        edit.update(CURSOR_BLINK_INTERVAL);
+        assert_eq!(painted_commands(&mut edit, &size).len(), 8);
+        edit.handle_event(&Event::FocusOut);
+        edit.set_text("");
+        edit.set_placeholder("name");
+        assert_eq!(
+            painted_commands(&mut edit, &size)[2..],
+            [
+                DrawCommand::SetBrush(Brush::Solid(BORDER_COLOR)),
+                DrawCommand::DrawRect(Rect {
+                    pos: Point2::<i32>::new(0, 0),
+                    size,
+                }),
+                DrawCommand::SetBrush(Brush::Solid(Color::GRAY)),
+                DrawCommand::DrawText(
+                    "name".to_string(),
+                    Point2::<i32>::new(4, 3),
+                    Font::default()
+                ),
+            ]
+        );
+    }
+
+    //********************************************************************************************
+    #[test]
+    fn undo_steps() {
+        let mut edit = LineEdit::new_raw();
+        edit.handle_event(&Event::FocusIn);
+        // typed characters are one step until the cursor moves
+        type_text(&mut edit, "abc");
+        press(&mut edit, Key::Left, KeyModifiers::NONE);
+        type_text(&mut edit, "xy");
+        assert_eq!(edit.text(), "abxyc");
+        edit.undo();
+        assert_eq!(edit.text(), "abc");
+        edit.undo();
+        assert_eq!(edit.text(), "");
+        assert!(!edit.is_undo_available());
+
+        // only the last edits are kept
+        for _ in 0..UNDO_LIMIT + 10 {
+            type_text(&mut edit, "a");
+            press(&mut edit, Key::Home, KeyModifiers::NONE);
+        }
+        let mut steps = 0;
+        while edit.is_undo_available() {
+            edit.undo();
+            steps += 1;
+        }
+        assert_eq!(steps, UNDO_LIMIT);
+        assert_eq!(edit.text().len(), 10);
+    }
+
+    //********************************************************************************************
+    #[test]
+    fn clipboard_and_undo() {
+        let mut edit = LineEdit::new_raw();
+        edit.handle_event(&Event::FocusIn);
+        type_text(&mut edit, "copy me");
+        press(&mut edit, Key::A, KeyModifiers::CTRL);
+        press(&mut edit, Key::X, KeyModifiers::CTRL);
+        assert_eq!(edit.text(), "");
+        assert_eq!(clipboard().borrow().text().as_deref(), Some("copy me"));
+        press(&mut edit, Key::V, KeyModifiers::CTRL);
+        press(&mut edit, Key::V, KeyModifiers::CTRL);
+        assert_eq!(edit.text(), "copy mecopy me");
+
+        press(&mut edit, Key::Z, KeyModifiers::CTRL);
+        assert_eq!(edit.text(), "copy me");
+        press(&mut edit, Key::Z, KeyModifiers::CTRL);
+        assert_eq!(edit.text(), "");
+        let ctrl_shift = KeyModifiers {
+            shift: true,
+            ..KeyModifiers::CTRL
+        };
+        press(&mut edit, Key::Z, ctrl_shift);
+        assert_eq!(edit.text(), "copy me");
+        press(&mut edit, Key::Y, KeyModifiers::CTRL);
+        assert_eq!(edit.text(), "copy mecopy me");
+        assert!(!edit.is_redo_available());
+
+        // the password can't be copied and read only text can't be edited
+        edit.set_echo_mode(EchoMode::Password);
+        edit.select_all();
+        edit.cut();
+        assert_eq!(edit.text(), "copy mecopy me");
+        edit.set_read_only(true);
+        edit.select_all();
+        edit.copy();
+        type_text(&mut edit, "x");
+        assert_eq!(edit.text(), "copy mecopy me");
+        assert_eq!(clipboard().borrow().text().as_deref(), Some("copy me"));
+
+        edit.set_read_only(false);
+        edit.set_text("abc");
+        let commands = painted_commands(&mut edit, &Vector2::<u32>::new(50, 15));
+        assert_eq!(
+            commands[5],
+            DrawCommand::DrawText("***".to_string(), Point2::<i32>::new(4, 3), Font::default())
+        );
+    }
+
+    //********************************************************************************************
+    #[test]
+    fn validator_mask_and_max_length() {
+        let mut edit = LineEdit::new_raw();
+        edit.handle_event(&Event::FocusIn);
+        edit.set_max_length(3);
+        type_text(&mut edit, "abcd");
+        assert_eq!(edit.text(), "abc");
+
+        edit.set_max_length(10);
+        edit.set_text("");
+        edit.set_validator(Some(Box::new(IntValidator::new(0, 500))));
+        type_text(&mut edit, "4a-29");
+        assert_eq!(edit.text(), "429");
+        assert!(edit.has_acceptable_input());
+        type_text(&mut edit, "0");
+        assert_eq!(edit.text(), "429");
+
+        let returns = Rc::new(RefCell::new(0));
+        let returns_clone = returns.clone();
+        edit.return_pressed()
+            .connect(move |_| *returns_clone.borrow_mut() += 1);
+        edit.set_validator(None);
+        edit.set_input_mask(Some(InputMask::new("99-99")));
+        assert_eq!(edit.displayed_text(), "42-9_");
+        press(&mut edit, Key::Return, KeyModifiers::NONE);
+        assert_eq!(*returns.borrow(), 0);
+
+        edit.set_cursor_position(0);
+        type_text(&mut edit, "1x23");
+        assert_eq!(edit.displayed_text(), "12-3_");
+        assert_eq!(edit.cursor_position(), 4);
+        press(&mut edit, Key::Backspace, KeyModifiers::NONE);
+        press(&mut edit, Key::Backspace, KeyModifiers::NONE);
+        assert_eq!(edit.displayed_text(), "12-__");
+        assert_eq!(edit.text(), "12-");
+        type_text(&mut edit, "-45");
+        assert_eq!(edit.displayed_text(), "12-45");
+        press(&mut edit, Key::KpEnter, KeyModifiers::NONE);
+        assert_eq!(*returns.borrow(), 1);
+    }
+
+    //********************************************************************************************
+    #[test]
+    fn blink_in_window() {
+        let mut app = App::new();
+        let window = app
+            .get_windows_manager()
+            .create_window("test", 100, 20)
+            .unwrap();
+        let id = *window.borrow().id();
+        window
+            .borrow()
+            .widget()
+            .borrow_mut()
+            .add_child_with_transform(
+                LineEdit::new().make_owned(),
+                &Transform::new(&Point2::<i32>::new(0, 0), &Vector2::<u32>::new(100, 20)),
+            );
+        app.exec().unwrap();
+
+        // the click focuses the edit and the cursor blinks with the time of the frames
+        let pos = Point2::<i32>::new(5, 5);
+        app.windows_manager().send_event(
+            &id,
+            &Event::MousePressEvent(MousePressEvent::new(MouseButton::Left, &pos)),
+        );
+        app.windows_manager().send_event(
+            &id,
+            &Event::MouseReleaseEvent(MouseReleaseEvent::new(MouseButton::Left, &pos)),
+        );
+        let start = app.time();
+        assert!(app.step(FRAME_TIME));
+        while !app.step(FRAME_TIME) {}
+        assert!(app.time() - start >= CURSOR_BLINK_INTERVAL as f64);
+        assert!(app.time() - start < (CURSOR_BLINK_INTERVAL + FRAME_TIME) as f64);
+    }
+}
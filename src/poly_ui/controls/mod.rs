@@ -1,3 +1,23 @@
+mod button_base;
+mod button_group;
+mod check_box;
+mod input_mask;
+mod label;
+mod line_edit;
 mod push_button;
+mod radio_button;
+mod text_buffer;
+mod text_edit;
+mod validator;
 
+pub use button_base::{ButtonBase, ButtonState};
+pub use button_group::{ButtonGroup, ButtonTrait};
+pub use check_box::{CheckBox, CheckBoxGroup, CheckState};
+pub use input_mask::InputMask;
+pub use label::Label;
+pub use line_edit::{EchoMode, LineEdit};
 pub use push_button::PushButton;
+pub use radio_button::RadioButton;
+pub use text_buffer::{FindFlags, TextBuffer};
+pub use text_edit::{HighlighterTrait, StyledRange, TextEdit, TextStyle};
+pub use validator::{FloatValidator, IntValidator, RegexValidator, ValidatorTrait, Validity};
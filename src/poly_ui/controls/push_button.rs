@@ -1,27 +1,60 @@
 // std
+use std::collections::HashMap;
 use std::fmt::Debug;
+use std::rc::Rc;
 // deps
 use nalgebra::Point2;
+use nalgebra::Vector2;
 use uuid::Uuid;
 // crate
 use crate::poly_ui::app::Color;
+use crate::poly_ui::app::Font;
+use crate::poly_ui::app::Image;
+use crate::poly_ui::app::NinePatch;
 use crate::poly_ui::app::PainterTrait;
 use crate::poly_ui::app::Rect;
 use crate::poly_ui::components::Hierarchy;
+use crate::poly_ui::components::Signal;
 use crate::poly_ui::components::Transform;
+use crate::poly_ui::events::Event;
 use crate::poly_ui::widgets::NewWidget;
 use crate::poly_ui::widgets::OwnedWidget;
 use crate::poly_ui::widgets::WidgetTrait;
+// super
+use super::button_base::BORDER_COLOR;
+use super::button_base::FOCUS_COLOR;
+use super::ButtonBase;
+use super::ButtonState;
+use super::ButtonTrait;
+
+/// Horizontal space between the border and the content.
+const PADDING_X: u32 = 6;
+/// Vertical space between the border and the content.
+const PADDING_Y: u32 = 4;
+/// Space between the icon and the text.
+const ICON_SPACING: u32 = 4;
+
+const CHECKED_COLOR: Color = Color::rgb(45, 70, 100);
 
 //************************************************************************************************
 //************************************************************************************************
 //************************************************************************************************
+/// Button with a text and an optional icon left of it. It's clicked with the left mouse button
+/// or with Space/Enter when focused; application code is notified with the clicked() Signal.
+/// A checkable button toggles its checked state on every click.
 #[derive(Debug)]
 pub struct PushButton {
     id: Uuid,
     hierarchy: Hierarchy,
 
-    border_color: Color,
+    base: ButtonBase,
+    text: String,
+    icon: Option<Rc<Image>>,
+    font: Font,
+    skin: Option<NinePatch>,
+    state_skins: HashMap<ButtonState, NinePatch>,
+
+    size: Vector2<u32>,
 }
 
 //************************************************************************************************
@@ -30,18 +63,190 @@ impl PushButton {
         Self {
             id: Uuid::new_v4(),
             hierarchy: Hierarchy::default(),
-            border_color: Color {
-                r: 255,
-                g: 0,
-                b: 255,
-                a: 128,
-            },
+            base: ButtonBase::new(),
+            text: String::new(),
+            icon: None,
+            font: Font::default(),
+            skin: None,
+            state_skins: HashMap::new(),
+            size: Vector2::<u32>::new(0, 0),
         }
     }
 
     pub fn new() -> NewWidget<Self> {
         NewWidget::new(Self::new_raw())
     }
+
+    /// # Returns
+    /// Signal emitted with the checked state (always false for not checkable buttons) whenever
+    /// the button is clicked.
+    pub fn clicked(&self) -> &Signal<bool> {
+        self.base.clicked()
+    }
+
+    /// # Returns
+    /// Signal emitted with the new checked state whenever it changes.
+    pub fn toggled(&self) -> &Signal<bool> {
+        self.base.toggled()
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn set_text(&mut self, text: &str) {
+        self.text = text.to_string();
+        self.request_repaint();
+    }
+
+    pub fn icon(&self) -> Option<&Rc<Image>> {
+        self.icon.as_ref()
+    }
+
+    /// # Arguments
+    /// * `icon` - image painted left of the text, None removes it
+    pub fn set_icon(&mut self, icon: Option<Rc<Image>>) {
+        self.icon = icon;
+        self.request_repaint();
+    }
+
+    pub fn font(&self) -> &Font {
+        &self.font
+    }
+
+    pub fn set_font(&mut self, font: &Font) {
+        self.font = *font;
+        self.request_repaint();
+    }
+
+    pub fn state(&self) -> ButtonState {
+        self.base.state()
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.base.is_enabled()
+    }
+
+    /// # Arguments
+    /// * `enabled` - whether the button can be clicked, disabled button is grayed out
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.update_base(|base| base.set_enabled(enabled));
+    }
+
+    pub fn is_checkable(&self) -> bool {
+        self.base.is_checkable()
+    }
+
+    /// # Arguments
+    /// * `checkable` - whether clicks toggle the checked state
+    pub fn set_checkable(&mut self, checkable: bool) {
+        self.update_base(|base| base.set_checkable(checkable));
+    }
+
+    pub fn is_checked(&self) -> bool {
+        self.base.is_checked()
+    }
+
+    /// Changes the checked state of a checkable button without emitting clicked.
+    /// # Arguments
+    /// * `checked` - new checked state
+    pub fn set_checked(&mut self, checked: bool) {
+        self.update_base(|base| base.set_checked(checked));
+    }
+
+    pub fn auto_repeat(&self) -> bool {
+        self.base.auto_repeat()
+    }
+
+    /// # Arguments
+    /// * `auto_repeat` - whether clicked is emitted repeatedly while the button is held down
+    pub fn set_auto_repeat(&mut self, auto_repeat: bool) {
+        self.base.set_auto_repeat(auto_repeat);
+    }
+
+    /// Clicks the button programmatically.
+    pub fn click(&mut self) {
+        self.update_base(|base| base.click());
+    }
+
+    /// # Returns
+    /// NinePatch this button is painted with or None if it's painted with the default look.
+    pub fn skin(&self) -> Option<&NinePatch> {
+        self.skin.as_ref()
+    }
+
+    /// Sets NinePatch covering the whole button instead of the default look.
+    /// # Arguments
+    /// * `skin` - new skin, None restores the default look
+    pub fn set_skin(&mut self, skin: Option<NinePatch>) {
+        self.skin = skin;
+        self.request_repaint();
+    }
+
+    /// # Arguments
+    /// * `state` - state of the button
+    /// # Returns
+    /// NinePatch the button is painted with in the given state.
+    pub fn state_skin(&self, state: ButtonState) -> Option<&NinePatch> {
+        self.state_skins.get(&state).or(self.skin.as_ref())
+    }
+
+    /// Sets NinePatch used instead of skin() in the given state. Checked button uses the Pressed
+    /// skin unless it's disabled.
+    /// # Arguments
+    /// * `state` - state of the button
+    /// * `skin` - new skin, None falls back to skin()
+    pub fn set_state_skin(&mut self, state: ButtonState, skin: Option<NinePatch>) {
+        match skin {
+            Some(skin) => self.state_skins.insert(state, skin),
+            None => self.state_skins.remove(&state),
+        };
+        self.request_repaint();
+    }
+
+    /// Calls the function with the ButtonBase and repaints if the look of the button changed.
+    fn update_base<F: FnOnce(&mut ButtonBase) -> R, R>(&mut self, function: F) -> R {
+        let appearance = self.base.appearance();
+        let result = function(&mut self.base);
+        if self.base.appearance() != appearance {
+            self.request_repaint();
+        }
+        result
+    }
+
+    fn content_size(&self) -> Vector2<u32> {
+        let text = self.font.text_size(&self.text);
+        match &self.icon {
+            Some(icon) => {
+                let icon = icon.size();
+                let spacing = if self.text.is_empty() {
+                    0
+                } else {
+                    ICON_SPACING
+                };
+                Vector2::<u32>::new(icon.x + spacing + text.x, icon.y.max(text.y))
+            }
+            None => text,
+        }
+    }
+
+    fn background_color(state: ButtonState, checked: bool) -> Color {
+        match state {
+            ButtonState::Normal | ButtonState::Hovered if checked => CHECKED_COLOR,
+            state => state.background_color(),
+        }
+    }
+}
+
+//************************************************************************************************
+impl ButtonTrait for PushButton {
+    fn button_base(&self) -> &ButtonBase {
+        &self.base
+    }
+
+    fn button_base_mut(&mut self) -> &mut ButtonBase {
+        &mut self.base
+    }
 }
 
 //************************************************************************************************
@@ -62,18 +267,256 @@ impl WidgetTrait for PushButton {
         self.hierarchy.get_transform(child)
     }
 
+    fn preferred_size(&self) -> Option<Vector2<u32>> {
+        let content = self.content_size();
+        Some(Vector2::<u32>::new(
+            content.x + PADDING_X * 2,
+            content.y + PADDING_Y * 2,
+        ))
+    }
+
+    fn accepts_focus(&self) -> bool {
+        self.base.is_enabled()
+    }
+
+    fn handle_event(&mut self, event: &Event) -> bool {
+        if self.hierarchy.dispatch_event(event) {
+            return true;
+        }
+        let size = self.size;
+        self.update_base(|base| base.handle_event(event, &size))
+    }
+
     fn update(&mut self, dt: f32) {
+        self.update_base(|base| base.update(dt));
         self.hierarchy.update_children(dt);
     }
 
     fn paint(&mut self, painter: &mut dyn PainterTrait) {
-        painter.set_draw_color(&self.border_color);
-        let painter_size = painter.size();
-        painter.draw_rect(Rect {
+        self.size = painter.size();
+        let rect = Rect {
             pos: Point2::<i32>::new(0, 0),
-            size: painter_size,
-        });
+            size: self.size,
+        };
+        let (state, focused, checked) = self.base.appearance();
+        let skin_state = match state {
+            ButtonState::Normal | ButtonState::Hovered if checked => ButtonState::Pressed,
+            state => state,
+        };
+        match self.state_skin(skin_state) {
+            Some(skin) => painter.draw_nine_patch(skin, &rect),
+            None => {
+                painter.set_draw_color(&Self::background_color(state, checked));
+                painter.fill_rect(rect);
+            }
+        }
+        if focused || self.skin.is_none() {
+            painter.set_draw_color(if focused { &FOCUS_COLOR } else { &BORDER_COLOR });
+            painter.draw_rect(rect);
+        }
+
+        let content = self.content_size();
+        let shift = if state == ButtonState::Pressed { 1 } else { 0 };
+        let mut pos = Point2::<i32>::new(
+            (self.size.x as i32 - content.x as i32) / 2 + shift,
+            (self.size.y as i32 - content.y as i32) / 2 + shift,
+        );
+        if let Some(icon) = &self.icon {
+            let icon_pos =
+                Point2::<i32>::new(pos.x, pos.y + (content.y - icon.size().y) as i32 / 2);
+            painter.draw_image(icon, &icon_pos);
+            pos.x += (icon.size().x + ICON_SPACING) as i32;
+        }
+        if !self.text.is_empty() {
+            let text_y = pos.y + (content.y - self.font.line_height()) as i32 / 2;
+            let color = if state == ButtonState::Disabled {
+                Color::GRAY
+            } else {
+                Color::WHITE
+            };
+            painter.set_draw_color(&color);
+            painter.draw_text(&self.text, &Point2::<i32>::new(pos.x, text_y), &self.font);
+        }
 
         self.hierarchy.paint_children(painter);
     }
 }
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+#[cfg(test)]
+mod tests {
+    // std
+    use std::cell::RefCell;
+    // crate
+    use crate::poly_ui::app::Brush;
+    use crate::poly_ui::app::DrawCommand;
+    use crate::poly_ui::app::RecordingPainter;
+    use crate::poly_ui::controls::button_base::BACKGROUND_COLOR;
+    use crate::poly_ui::controls::button_base::DISABLED_COLOR;
+    use crate::poly_ui::controls::button_base::PRESSED_COLOR;
+    use crate::poly_ui::events::Key;
+    use crate::poly_ui::events::KeyPressEvent;
+    use crate::poly_ui::events::MouseButton;
+    use crate::poly_ui::events::MousePressEvent;
+    use crate::poly_ui::events::MouseReleaseEvent;
+    use crate::poly_ui::layouts::CanvasLayout;
+    // super
+    use super::*;
+
+    //********************************************************************************************
+    fn painted_commands(button: &mut PushButton, size: &Vector2<u32>) -> Vec<DrawCommand> {
+        let mut painter = RecordingPainter::new(size);
+        button.paint(&mut painter);
+        painter
+            .display_list()
+            .commands
+            .into_iter()
+            .map(|command| command.command)
+            .collect()
+    }
+
+    //********************************************************************************************
+    #[test]
+    fn paint_states() {
+        let mut button = PushButton::new_raw();
+        button.set_text("Ok");
+        assert_eq!(button.preferred_size(), Some(Vector2::<u32>::new(24, 17)));
+
+        let size = Vector2::<u32>::new(30, 20);
+        let rect = Rect {
+            pos: Point2::<i32>::new(0, 0),
+            size,
+        };
+        assert_eq!(
+            painted_commands(&mut button, &size),
+            vec![
+                DrawCommand::SetBrush(Brush::Solid(BACKGROUND_COLOR)),
+                DrawCommand::FillRect(rect),
+                DrawCommand::SetBrush(Brush::Solid(BORDER_COLOR)),
+                DrawCommand::DrawRect(rect),
+                DrawCommand::SetBrush(Brush::Solid(Color::WHITE)),
+                DrawCommand::DrawText("Ok".to_string(), Point2::<i32>::new(9, 5), Font::default()),
+            ]
+        );
+
+        button.handle_event(&Event::FocusIn);
+        button.handle_event(&Event::KeyPress(KeyPressEvent::new(Key::Space)));
+        let commands = painted_commands(&mut button, &size);
+        assert_eq!(
+            commands[0],
+            DrawCommand::SetBrush(Brush::Solid(PRESSED_COLOR))
+        );
+        assert_eq!(
+            commands[2],
+            DrawCommand::SetBrush(Brush::Solid(FOCUS_COLOR))
+        );
+        assert_eq!(
+            commands[5],
+            DrawCommand::DrawText("Ok".to_string(), Point2::<i32>::new(10, 6), Font::default())
+        );
+
+        button.set_enabled(false);
+        assert!(!button.accepts_focus());
+        let commands = painted_commands(&mut button, &size);
+        assert_eq!(
+            commands[0],
+            DrawCommand::SetBrush(Brush::Solid(DISABLED_COLOR))
+        );
+        assert_eq!(
+            commands[4],
+            DrawCommand::SetBrush(Brush::Solid(Color::GRAY))
+        );
+    }
+
+    //********************************************************************************************
+    #[test]
+    fn auto_repeat_repaints() {
+        let mut button = PushButton::new_raw();
+        button.set_checkable(true);
+        button.set_auto_repeat(true);
+        let mut painter = RecordingPainter::new(&Vector2::<u32>::new(40, 20));
+        button.paint(&mut painter);
+        button.handle_event(&Event::MousePressEvent(MousePressEvent::new(
+            MouseButton::Left,
+            &Point2::<i32>::new(5, 5),
+        )));
+        button.get_hierarchy().damage().borrow_mut().take();
+
+        // the repeated click toggles the button which has to be repainted
+        button.update(300.0);
+        assert!(button.is_checked());
+        assert!(button.get_hierarchy().damage().borrow().is_damaged());
+    }
+
+    //********************************************************************************************
+    #[test]
+    fn click_in_layout() {
+        let layout = CanvasLayout::new();
+        let button = PushButton::new();
+        let button_ptr = button.get().clone();
+        button_ptr.borrow_mut().set_checkable(true);
+        layout.borrow_mut().add_child_with_transform(
+            button.make_owned(),
+            &Transform::new(&Point2::<i32>::new(10, 10), &Vector2::<u32>::new(40, 20)),
+        );
+        let mut painter = RecordingPainter::new(&Vector2::<u32>::new(100, 100));
+        layout.borrow_mut().paint(&mut painter);
+
+        let clicks = Rc::new(RefCell::new(vec![]));
+        let clicks_clone = clicks.clone();
+        button_ptr
+            .borrow()
+            .clicked()
+            .connect(move |checked| clicks_clone.borrow_mut().push(*checked));
+
+        let pos = Point2::<i32>::new(20, 15);
+        layout
+            .borrow_mut()
+            .handle_event(&Event::MousePressEvent(MousePressEvent::new(
+                MouseButton::Left,
+                &pos,
+            )));
+        assert_eq!(button_ptr.borrow().state(), ButtonState::Pressed);
+        layout
+            .borrow_mut()
+            .handle_event(&Event::MouseReleaseEvent(MouseReleaseEvent::new(
+                MouseButton::Left,
+                &pos,
+            )));
+        assert_eq!(*clicks.borrow(), vec![true]);
+        assert!(button_ptr.borrow().is_checked());
+
+        // the click focused the button so Enter clicks it again
+        let id = *button_ptr.borrow().id();
+        assert_eq!(layout.borrow().get_hierarchy().focused(), Some(id));
+        layout
+            .borrow_mut()
+            .handle_event(&Event::KeyPress(KeyPressEvent::new(Key::Return)));
+        assert_eq!(*clicks.borrow(), vec![true, false]);
+
+        // unfocused sibling doesn't get the keys
+        let other = PushButton::new();
+        let other_ptr = other.get().clone();
+        layout.borrow_mut().add_child_with_transform(
+            other.make_owned(),
+            &Transform::new(&Point2::<i32>::new(60, 10), &Vector2::<u32>::new(40, 20)),
+        );
+        let other_clicks = Rc::new(RefCell::new(0));
+        let other_clicks_clone = other_clicks.clone();
+        other_ptr
+            .borrow()
+            .clicked()
+            .connect(move |_| *other_clicks_clone.borrow_mut() += 1);
+        layout
+            .borrow_mut()
+            .handle_event(&Event::KeyPress(KeyPressEvent::new(Key::KpEnter)));
+        assert_eq!(*clicks.borrow(), vec![true, false, true]);
+        assert_eq!(*other_clicks.borrow(), 0);
+        assert!(!other_ptr
+            .borrow_mut()
+            .handle_event(&Event::KeyPress(KeyPressEvent::new(Key::Return))));
+        assert_eq!(*other_clicks.borrow(), 0);
+    }
+}
@@ -0,0 +1,433 @@
+// std
+use std::fmt::Debug;
+// deps
+use nalgebra::Point2;
+use nalgebra::Vector2;
+use uuid::Uuid;
+// crate
+use crate::poly_ui::app::Color;
+use crate::poly_ui::app::Font;
+use crate::poly_ui::app::PainterTrait;
+use crate::poly_ui::app::Rect;
+use crate::poly_ui::components::Hierarchy;
+use crate::poly_ui::components::Signal;
+use crate::poly_ui::components::Transform;
+use crate::poly_ui::events::Event;
+use crate::poly_ui::widgets::NewWidget;
+use crate::poly_ui::widgets::OwnedWidget;
+use crate::poly_ui::widgets::WidgetTrait;
+// super
+use super::button_base::BORDER_COLOR;
+use super::button_base::FOCUS_COLOR;
+use super::ButtonBase;
+use super::ButtonState;
+use super::ButtonTrait;
+
+/// Space between the circle and the text.
+const TEXT_SPACING: u32 = 4;
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// Circle with a dot and a text right of it. Clicking it or pressing Space while it's focused
+/// checks it; it's unchecked when another RadioButton of its ButtonGroup is checked.
+#[derive(Debug)]
+pub struct RadioButton {
+    id: Uuid,
+    hierarchy: Hierarchy,
+
+    base: ButtonBase,
+    text: String,
+    font: Font,
+
+    size: Vector2<u32>,
+}
+
+//************************************************************************************************
+impl RadioButton {
+    pub fn new_raw() -> Self {
+        let mut base = ButtonBase::new();
+        base.set_checkable(true);
+        base.set_exclusive(true);
+        Self {
+            id: Uuid::new_v4(),
+            hierarchy: Hierarchy::default(),
+            base,
+            text: String::new(),
+            font: Font::default(),
+            size: Vector2::<u32>::new(0, 0),
+        }
+    }
+
+    pub fn new() -> NewWidget<Self> {
+        NewWidget::new(Self::new_raw())
+    }
+
+    /// # Returns
+    /// Signal emitted with the checked state whenever the user clicks the RadioButton.
+    pub fn clicked(&self) -> &Signal<bool> {
+        self.base.clicked()
+    }
+
+    /// # Returns
+    /// Signal emitted with the new checked state whenever it changes.
+    pub fn toggled(&self) -> &Signal<bool> {
+        self.base.toggled()
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn set_text(&mut self, text: &str) {
+        self.text = text.to_string();
+        self.request_repaint();
+    }
+
+    pub fn font(&self) -> &Font {
+        &self.font
+    }
+
+    pub fn set_font(&mut self, font: &Font) {
+        self.font = *font;
+        self.request_repaint();
+    }
+
+    pub fn is_checked(&self) -> bool {
+        self.base.is_checked()
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.base.is_enabled()
+    }
+
+    /// # Arguments
+    /// * `enabled` - whether the user can check the RadioButton, disabled one is grayed out
+    pub fn set_enabled(&mut self, enabled: bool) {
+        let appearance = self.base.appearance();
+        self.base.set_enabled(enabled);
+        if self.base.appearance() != appearance {
+            self.request_repaint();
+        }
+    }
+
+    /// # Returns
+    /// Diameter of the circle, it's a bit higher than the text.
+    fn circle_size(&self) -> u32 {
+        self.font.line_height() + 4
+    }
+
+    /// # Arguments
+    /// * `center` - center of the disk
+    /// * `radius` - radius of the disk in pixels
+    /// # Returns
+    /// One row high rects covering the disk.
+    fn disk(center: &Point2<f32>, radius: f32) -> Vec<Rect> {
+        let top = (center.y - radius).ceil() as i32;
+        let bottom = (center.y + radius).floor() as i32;
+        (top..bottom)
+            .filter_map(|y| {
+                let dy = y as f32 + 0.5 - center.y;
+                let half = (radius * radius - dy * dy).max(0.0).sqrt();
+                let left = (center.x - half).round() as i32;
+                let right = (center.x + half).round() as i32;
+                (right > left).then(|| Rect {
+                    pos: Point2::<i32>::new(left, y),
+                    size: Vector2::<u32>::new((right - left) as u32, 1),
+                })
+            })
+            .collect()
+    }
+}
+
+//************************************************************************************************
+impl ButtonTrait for RadioButton {
+    fn button_base(&self) -> &ButtonBase {
+        &self.base
+    }
+
+    fn button_base_mut(&mut self) -> &mut ButtonBase {
+        &mut self.base
+    }
+}
+
+//************************************************************************************************
+impl WidgetTrait for RadioButton {
+    fn id(&self) -> &Uuid {
+        &self.id
+    }
+
+    fn remove_child(&mut self, child: &Uuid) -> OwnedWidget {
+        self.hierarchy.remove(child)
+    }
+
+    fn get_hierarchy(&self) -> &Hierarchy {
+        &self.hierarchy
+    }
+
+    fn get_child_transform(&self, child: &Uuid) -> &Transform {
+        self.hierarchy.get_transform(child)
+    }
+
+    fn preferred_size(&self) -> Option<Vector2<u32>> {
+        let text = self.font.text_size(&self.text);
+        let circle_size = self.circle_size();
+        Some(match text.x {
+            0 => Vector2::<u32>::new(circle_size, circle_size),
+            _ => Vector2::<u32>::new(circle_size + TEXT_SPACING + text.x, circle_size.max(text.y)),
+        })
+    }
+
+    fn accepts_focus(&self) -> bool {
+        self.base.is_enabled()
+    }
+
+    fn handle_event(&mut self, event: &Event) -> bool {
+        if self.hierarchy.dispatch_event(event) {
+            return true;
+        }
+        let appearance = self.base.appearance();
+        let handled = self.base.handle_event(event, &self.size);
+        if self.base.appearance() != appearance {
+            self.request_repaint();
+        }
+        handled
+    }
+
+    fn update(&mut self, dt: f32) {
+        self.hierarchy.update_children(dt);
+    }
+
+    fn paint(&mut self, painter: &mut dyn PainterTrait) {
+        self.size = painter.size();
+        let (state, focused, checked) = self.base.appearance();
+        let radius = self.circle_size() as f32 / 2.0;
+        let center = Point2::<f32>::new(radius, self.size.y as f32 / 2.0);
+        painter.set_draw_color(if focused { &FOCUS_COLOR } else { &BORDER_COLOR });
+        painter.fill_rects(&Self::disk(&center, radius));
+        painter.set_draw_color(&state.background_color());
+        painter.fill_rects(&Self::disk(&center, radius - 1.0));
+
+        let mark_color = if state == ButtonState::Disabled {
+            Color::GRAY
+        } else {
+            Color::WHITE
+        };
+        painter.set_draw_color(&mark_color);
+        if checked {
+            painter.fill_rects(&Self::disk(&center, radius / 2.0 - 1.0));
+        }
+
+        if !self.text.is_empty() {
+            let text_pos = Point2::<i32>::new(
+                (self.circle_size() + TEXT_SPACING) as i32,
+                (self.size.y as i32 - self.font.line_height() as i32) / 2,
+            );
+            painter.draw_text(&self.text, &text_pos, &self.font);
+        }
+
+        self.hierarchy.paint_children(painter);
+    }
+}
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+#[cfg(test)]
+mod tests {
+    // std
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    // crate
+    use crate::poly_ui::app::RecordingPainter;
+    use crate::poly_ui::controls::ButtonGroup;
+    use crate::poly_ui::controls::PushButton;
+    use crate::poly_ui::events::Key;
+    use crate::poly_ui::events::KeyPressEvent;
+    use crate::poly_ui::events::MouseButton;
+    use crate::poly_ui::events::MousePressEvent;
+    use crate::poly_ui::events::MouseReleaseEvent;
+    use crate::poly_ui::layouts::CanvasLayout;
+    // super
+    use super::*;
+
+    //********************************************************************************************
+    fn click_at(layout: &NewWidget<CanvasLayout>, x: i32, y: i32) {
+        let pos = Point2::<i32>::new(x, y);
+        layout
+            .borrow_mut()
+            .handle_event(&Event::MousePressEvent(MousePressEvent::new(
+                MouseButton::Left,
+                &pos,
+            )));
+        layout
+            .borrow_mut()
+            .handle_event(&Event::MouseReleaseEvent(MouseReleaseEvent::new(
+                MouseButton::Left,
+                &pos,
+            )));
+    }
+
+    //********************************************************************************************
+    fn press_key(layout: &NewWidget<CanvasLayout>, key: Key) {
+        layout
+            .borrow_mut()
+            .handle_event(&Event::KeyPress(KeyPressEvent::new(key)));
+    }
+
+    //********************************************************************************************
+    #[test]
+    fn disk() {
+        let rects = RadioButton::disk(&Point2::<f32>::new(2.0, 2.0), 2.0);
+        let rows = rects
+            .iter()
+            .map(|rect| (rect.pos.x, rect.pos.y, rect.size.x))
+            .collect::<Vec<_>>();
+        assert_eq!(rows, vec![(1, 0, 2), (0, 1, 4), (0, 2, 4), (1, 3, 2)]);
+    }
+
+    //********************************************************************************************
+    #[test]
+    fn exclusive_group_across_layouts() {
+        let group = ButtonGroup::new();
+        let changes = Rc::new(RefCell::new(vec![]));
+        let changes_clone = changes.clone();
+        group
+            .checked_changed()
+            .connect(move |id| changes_clone.borrow_mut().push(*id));
+
+        // two radio buttons in one layout and one in another, the third is disabled
+        let layouts = [CanvasLayout::new(), CanvasLayout::new()];
+        let radios = (0..3).map(|_| RadioButton::new()).collect::<Vec<_>>();
+        for (index, radio) in radios.iter().enumerate() {
+            group.add_button(radio.get(), index * 10);
+        }
+        radios[2].borrow_mut().set_enabled(false);
+        let pointers = radios
+            .iter()
+            .map(|radio| radio.get().clone())
+            .collect::<Vec<_>>();
+        for (index, radio) in radios.into_iter().enumerate() {
+            layouts[index / 2].borrow_mut().add_child_with_transform(
+                radio.make_owned(),
+                &Transform::new(
+                    &Point2::<i32>::new(0, (index % 2) as i32 * 20),
+                    &Vector2::<u32>::new(40, 13),
+                ),
+            );
+        }
+        let radios = pointers;
+        for layout in &layouts {
+            let mut painter = RecordingPainter::new(&Vector2::<u32>::new(40, 40));
+            layout.borrow_mut().paint(&mut painter);
+        }
+        assert_eq!(group.checked_id(), None);
+
+        click_at(&layouts[0], 5, 25);
+        assert_eq!(group.checked_id(), Some(10));
+        // clicking the checked radio button doesn't uncheck it
+        click_at(&layouts[0], 5, 25);
+        assert!(radios[1].borrow().is_checked());
+
+        group.set_checked_id(Some(20));
+        assert!(!radios[1].borrow().is_checked());
+        assert!(radios[2].borrow().is_checked());
+
+        // arrows skip the disabled radio button and wrap around
+        click_at(&layouts[0], 5, 5);
+        press_key(&layouts[0], Key::Down);
+        assert_eq!(group.checked_id(), Some(10));
+        press_key(&layouts[0], Key::Right);
+        assert_eq!(group.checked_id(), Some(0));
+        press_key(&layouts[0], Key::Up);
+        assert_eq!(group.checked_id(), Some(10));
+        // only the focused radio button moves the selection, the focus followed the check
+        assert!(radios[1].borrow().button_base().has_focus());
+        assert!(!radios[0]
+            .borrow_mut()
+            .handle_event(&Event::KeyPress(KeyPressEvent::new(Key::Down))));
+        assert_eq!(group.checked_id(), Some(10));
+        assert_eq!(
+            radios
+                .iter()
+                .map(|radio| radio.borrow().is_checked())
+                .collect::<Vec<_>>(),
+            vec![false, true, false]
+        );
+        assert_eq!(
+            *changes.borrow(),
+            vec![Some(10), Some(20), Some(0), Some(10), Some(0), Some(10)]
+        );
+
+        group.set_checked_id(None);
+        assert_eq!(group.checked_id(), None);
+        group.remove_button(0);
+        assert_eq!(group.ids(), vec![10, 20]);
+        assert!(radios[0].borrow().button_base().group().is_none());
+    }
+
+    //********************************************************************************************
+    #[test]
+    fn arrows_move_focus() {
+        let group = ButtonGroup::new();
+        let layout = CanvasLayout::new();
+        let radios = (0..3)
+            .map(|index| {
+                let radio = RadioButton::new();
+                let radio_ptr = radio.get().clone();
+                group.add_button(&radio_ptr, index);
+                layout.borrow_mut().add_child_with_transform(
+                    radio.make_owned(),
+                    &Transform::new(
+                        &Point2::<i32>::new(0, index as i32 * 20),
+                        &Vector2::<u32>::new(40, 13),
+                    ),
+                );
+                radio_ptr
+            })
+            .collect::<Vec<_>>();
+        let mut painter = RecordingPainter::new(&Vector2::<u32>::new(40, 60));
+        layout.borrow_mut().paint(&mut painter);
+
+        click_at(&layout, 5, 5);
+        press_key(&layout, Key::Down);
+        press_key(&layout, Key::Down);
+        assert_eq!(group.checked_id(), Some(2));
+        let focused = radios
+            .iter()
+            .map(|radio| radio.borrow().button_base().has_focus())
+            .collect::<Vec<_>>();
+        assert_eq!(focused, vec![false, false, true]);
+        let id = *radios[2].borrow().id();
+        assert_eq!(layout.borrow().get_hierarchy().focused(), Some(id));
+
+        // the next arrow starts at the focused button
+        press_key(&layout, Key::Up);
+        assert_eq!(group.checked_id(), Some(1));
+        assert!(radios[1].borrow().button_base().has_focus());
+    }
+
+    //********************************************************************************************
+    #[test]
+    fn segmented_push_buttons() {
+        let group = ButtonGroup::new();
+        let buttons = (0..2).map(|_| PushButton::new()).collect::<Vec<_>>();
+        for (index, button) in buttons.iter().enumerate() {
+            group.add_button(button.get(), index);
+        }
+        assert!(buttons[0].borrow().is_checkable());
+
+        buttons[0].borrow_mut().click();
+        buttons[1].borrow_mut().click();
+        buttons[1].borrow_mut().click();
+        assert!(!buttons[0].borrow().is_checked());
+        assert!(buttons[1].borrow().is_checked());
+        assert_eq!(group.checked_id(), Some(1));
+
+        group.set_exclusive(false);
+        buttons[0].borrow_mut().click();
+        buttons[1].borrow_mut().click();
+        assert!(buttons[0].borrow().is_checked());
+        assert!(!buttons[1].borrow().is_checked());
+    }
+}
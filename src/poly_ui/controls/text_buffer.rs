@@ -0,0 +1,592 @@
+// std
+use std::fmt::Debug;
+
+//************************************************************************************************
+/// Buffer a piece of TextBuffer refers to.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+enum Source {
+    Original,
+    Added,
+}
+
+//************************************************************************************************
+/// Span of one of the buffers; the text of TextBuffer is the concatenation of its pieces.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+struct Piece {
+    source: Source,
+    start: usize,
+    len: usize,
+}
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// Options of a text search.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub struct FindFlags {
+    /// Whether the search goes towards the start of the text.
+    pub backward: bool,
+    /// Whether ASCII letters have to match in case.
+    pub case_sensitive: bool,
+}
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// Piece table storing large text edited in place. The original text is never copied or
+/// modified, inserted text is appended to a second buffer and the text is a list of pieces of
+/// both buffers, so edits cost the number of pieces instead of the length of the text. Offsets
+/// of new lines in both buffers are indexed, so lines are found without scanning the text.
+///
+/// Offsets are in bytes and have to be at character boundaries.
+#[derive(Debug, Clone, Default)]
+pub struct TextBuffer {
+    original: String,
+    added: String,
+    /// Offsets of '\n' in the original and in the added buffer.
+    original_newlines: Vec<usize>,
+    added_newlines: Vec<usize>,
+    pieces: Vec<Piece>,
+    len: usize,
+}
+
+//************************************************************************************************
+impl TextBuffer {
+    /// # Arguments
+    /// * `text` - original text, it's moved to the buffer without copying
+    pub fn new(text: String) -> Self {
+        let original_newlines = newlines(&text, 0);
+        let pieces = if text.is_empty() {
+            vec![]
+        } else {
+            vec![Piece {
+                source: Source::Original,
+                start: 0,
+                len: text.len(),
+            }]
+        };
+        Self {
+            len: text.len(),
+            original: text,
+            added: String::new(),
+            original_newlines,
+            added_newlines: vec![],
+            pieces,
+        }
+    }
+
+    /// # Returns
+    /// Length of the text in bytes.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// # Returns
+    /// Number of lines, one more than the number of new line characters.
+    pub fn line_count(&self) -> usize {
+        1 + self
+            .pieces
+            .iter()
+            .map(|piece| self.piece_newlines(piece).len())
+            .sum::<usize>()
+    }
+
+    /// # Arguments
+    /// * `line` - index of the line
+    /// # Returns
+    /// Offset of the first character of the line, the length of the text for lines after the
+    /// last one.
+    pub fn line_start(&self, line: usize) -> usize {
+        match line {
+            0 => 0,
+            _ => self
+                .newline_offset(line)
+                .map_or(self.len, |offset| offset + 1),
+        }
+    }
+
+    /// # Arguments
+    /// * `line` - index of the line
+    /// # Returns
+    /// Offset after the last character of the line, without its new line character.
+    pub fn line_end(&self, line: usize) -> usize {
+        self.newline_offset(line + 1).unwrap_or(self.len)
+    }
+
+    /// # Arguments
+    /// * `line` - index of the line
+    /// # Returns
+    /// Text of the line without its new line character.
+    pub fn line(&self, line: usize) -> String {
+        self.slice(self.line_start(line), self.line_end(line))
+    }
+
+    /// # Arguments
+    /// * `offset` - offset in the text
+    /// # Returns
+    /// Index of the line containing the offset.
+    pub fn line_of_offset(&self, offset: usize) -> usize {
+        let mut line = 0;
+        let mut pos = 0;
+        for piece in &self.pieces {
+            let newlines = self.piece_newlines(piece);
+            if offset < pos + piece.len {
+                let inner = piece.start + offset - pos;
+                return line + newlines.partition_point(|newline| *newline < inner);
+            }
+            line += newlines.len();
+            pos += piece.len;
+        }
+        line
+    }
+
+    /// # Arguments
+    /// * `start` - offset of the first character
+    /// * `end` - offset after the last character
+    /// # Returns
+    /// Part of the text.
+    pub fn slice(&self, start: usize, end: usize) -> String {
+        let mut result = String::new();
+        let mut pos = 0;
+        for piece in &self.pieces {
+            let (piece_start, piece_end) = (pos, pos + piece.len);
+            pos = piece_end;
+            if piece_end <= start {
+                continue;
+            }
+            if piece_start >= end {
+                break;
+            }
+            let text = self.piece_text(piece);
+            let from = start.saturating_sub(piece_start);
+            let to = end.min(piece_end) - piece_start;
+            result.push_str(&text[from..to]);
+        }
+        result
+    }
+
+    /// # Returns
+    /// Whole text.
+    pub fn text(&self) -> String {
+        self.chunks().collect()
+    }
+
+    /// # Returns
+    /// Parts of the text in order, without copying.
+    pub fn chunks(&self) -> impl Iterator<Item = &str> {
+        self.pieces.iter().map(move |piece| self.piece_text(piece))
+    }
+
+    /// # Arguments
+    /// * `offset` - offset of the character
+    /// # Returns
+    /// Character starting at the offset, None at the end of the text.
+    pub fn char_at(&self, offset: usize) -> Option<char> {
+        let (piece, inner) = self.locate(offset, false)?;
+        self.piece_text(piece)[inner..].chars().next()
+    }
+
+    /// # Arguments
+    /// * `offset` - offset after the character
+    /// # Returns
+    /// Character ending at the offset, None at the start of the text.
+    pub fn char_before(&self, offset: usize) -> Option<char> {
+        let (piece, inner) = self.locate(offset, true)?;
+        self.piece_text(piece)[..inner].chars().next_back()
+    }
+
+    /// # Arguments
+    /// * `offset` - any offset, also inside of a character or past the end of the text
+    /// # Returns
+    /// Offset of the start of the character containing the offset, clamped to the length.
+    pub fn floor_char_boundary(&self, offset: usize) -> usize {
+        match self.locate(offset, false) {
+            Some((piece, inner)) => {
+                let text = self.piece_text(piece);
+                let boundary = (0..=inner)
+                    .rev()
+                    .find(|index| text.is_char_boundary(*index))
+                    .unwrap_or(0);
+                offset - (inner - boundary)
+            }
+            None => offset.min(self.len),
+        }
+    }
+
+    /// # Arguments
+    /// * `offset` - where the text is inserted
+    /// * `text` - inserted text
+    pub fn insert(&mut self, offset: usize, text: &str) {
+        if text.is_empty() {
+            return;
+        }
+        let start = self.added.len();
+        self.added_newlines.extend(newlines(text, start));
+        self.added.push_str(text);
+        self.len += text.len();
+        let new = Piece {
+            source: Source::Added,
+            start,
+            len: text.len(),
+        };
+
+        let mut pos = 0;
+        for index in 0..self.pieces.len() {
+            let piece = self.pieces[index];
+            if offset <= pos + piece.len {
+                let inner = offset - pos;
+                if inner == piece.len
+                    && piece.source == Source::Added
+                    && piece.start + piece.len == start
+                {
+                    // typing continues the last insertion
+                    self.pieces[index].len += text.len();
+                } else if inner == 0 {
+                    self.pieces.insert(index, new);
+                } else if inner == piece.len {
+                    self.pieces.insert(index + 1, new);
+                } else {
+                    let left = Piece {
+                        len: inner,
+                        ..piece
+                    };
+                    let right = Piece {
+                        start: piece.start + inner,
+                        len: piece.len - inner,
+                        ..piece
+                    };
+                    self.pieces.splice(index..=index, [left, new, right]);
+                }
+                return;
+            }
+            pos += piece.len;
+        }
+        self.pieces.push(new);
+    }
+
+    /// # Arguments
+    /// * `start` - offset of the first removed character
+    /// * `end` - offset after the last removed character
+    pub fn remove(&mut self, start: usize, end: usize) {
+        let end = end.min(self.len);
+        if start >= end {
+            return;
+        }
+        let mut pieces = Vec::with_capacity(self.pieces.len() + 1);
+        let mut pos = 0;
+        for piece in &self.pieces {
+            let (piece_start, piece_end) = (pos, pos + piece.len);
+            pos = piece_end;
+            if piece_end <= start || piece_start >= end {
+                pieces.push(*piece);
+                continue;
+            }
+            if piece_start < start {
+                pieces.push(Piece {
+                    len: start - piece_start,
+                    ..*piece
+                });
+            }
+            if piece_end > end {
+                pieces.push(Piece {
+                    start: piece.start + end - piece_start,
+                    len: piece_end - end,
+                    ..*piece
+                });
+            }
+        }
+        self.pieces = pieces;
+        self.len -= end - start;
+    }
+
+    /// Searches for the pattern piece by piece without copying the text, in time linear in the
+    /// length of the searched part.
+    /// # Arguments
+    /// * `pattern` - searched text
+    /// * `from` - offset the forward search starts at or the backward search ends at
+    /// * `flags` - options of the search
+    /// # Returns
+    /// Offset of the first match after or the last match before the given offset.
+    pub fn find(&self, pattern: &str, from: usize, flags: FindFlags) -> Option<usize> {
+        let pattern = searched_pattern(pattern, flags)?;
+        let from = from.min(self.len);
+        let mut result = None;
+        if flags.backward {
+            self.search(&pattern, flags, 0, from, true, |offset| {
+                result = Some(offset);
+                true
+            });
+        } else {
+            self.search(&pattern, flags, from, self.len, false, |offset| {
+                result = Some(offset);
+                false
+            });
+        }
+        result
+    }
+
+    /// # Arguments
+    /// * `pattern` - searched text
+    /// * `flags` - options of the search, the direction is ignored
+    /// # Returns
+    /// Offsets of all matches that don't overlap, in order.
+    pub fn find_all(&self, pattern: &str, flags: FindFlags) -> Vec<usize> {
+        let mut result = vec![];
+        if let Some(pattern) = searched_pattern(pattern, flags) {
+            self.search(&pattern, flags, 0, self.len, false, |offset| {
+                result.push(offset);
+                true
+            });
+        }
+        result
+    }
+
+    /// Knuth-Morris-Pratt search streaming bytes of the pieces, so matches may span pieces.
+    /// Matches of valid UTF-8 are always at character boundaries.
+    /// # Arguments
+    /// * `pattern` - searched bytes, ASCII lowercase for searches ignoring case
+    /// * `flags` - options of the search, the direction is ignored
+    /// * `start` - offset the searched part starts at
+    /// * `end` - offset the searched part ends at, matches end before it
+    /// * `overlapping` - whether a match may start inside of the previous one
+    /// * `found` - called with the offset of every match in order, the search stops when it
+    ///   returns false
+    fn search<F: FnMut(usize) -> bool>(
+        &self,
+        pattern: &[u8],
+        flags: FindFlags,
+        start: usize,
+        end: usize,
+        overlapping: bool,
+        mut found: F,
+    ) {
+        // length of the longest proper prefix of pattern[..=index] which is also its suffix
+        let mut failure = vec![0; pattern.len()];
+        let mut matched = 0;
+        for index in 1..pattern.len() {
+            while matched > 0 && pattern[index] != pattern[matched] {
+                matched = failure[matched - 1];
+            }
+            if pattern[index] == pattern[matched] {
+                matched += 1;
+            }
+            failure[index] = matched;
+        }
+
+        let mut matched = 0;
+        let mut pos = 0;
+        for piece in &self.pieces {
+            let (piece_start, piece_end) = (pos, pos + piece.len);
+            pos = piece_end;
+            if piece_end <= start {
+                continue;
+            }
+            if piece_start >= end {
+                break;
+            }
+            let from = start.saturating_sub(piece_start);
+            let to = end.min(piece_end) - piece_start;
+            let bytes = &self.piece_text(piece).as_bytes()[from..to];
+            for (index, byte) in bytes.iter().enumerate() {
+                let byte = if flags.case_sensitive {
+                    *byte
+                } else {
+                    byte.to_ascii_lowercase()
+                };
+                while matched > 0 && pattern[matched] != byte {
+                    matched = failure[matched - 1];
+                }
+                if pattern[matched] == byte {
+                    matched += 1;
+                }
+                if matched == pattern.len() {
+                    if !found(piece_start + from + index + 1 - pattern.len()) {
+                        return;
+                    }
+                    matched = if overlapping { failure[matched - 1] } else { 0 };
+                }
+            }
+        }
+    }
+
+    fn piece_text(&self, piece: &Piece) -> &str {
+        let buffer = match piece.source {
+            Source::Original => &self.original,
+            Source::Added => &self.added,
+        };
+        &buffer[piece.start..piece.start + piece.len]
+    }
+
+    /// # Returns
+    /// Offsets of new line characters of the piece in its buffer.
+    fn piece_newlines(&self, piece: &Piece) -> &[usize] {
+        let newlines = match piece.source {
+            Source::Original => &self.original_newlines,
+            Source::Added => &self.added_newlines,
+        };
+        let first = newlines.partition_point(|newline| *newline < piece.start);
+        let last = newlines.partition_point(|newline| *newline < piece.start + piece.len);
+        &newlines[first..last]
+    }
+
+    /// # Arguments
+    /// * `number` - number of the new line character, counted from 1
+    /// # Returns
+    /// Offset of the new line character in the text.
+    fn newline_offset(&self, number: usize) -> Option<usize> {
+        let mut remaining = number;
+        let mut pos = 0;
+        for piece in &self.pieces {
+            let newlines = self.piece_newlines(piece);
+            if remaining <= newlines.len() {
+                return Some(pos + newlines[remaining.checked_sub(1)?] - piece.start);
+            }
+            remaining -= newlines.len();
+            pos += piece.len;
+        }
+        None
+    }
+
+    /// # Arguments
+    /// * `offset` - offset in the text
+    /// * `before` - whether the piece ending at the offset is preferred to the one starting there
+    /// # Returns
+    /// Piece containing the offset and the offset in the piece.
+    fn locate(&self, offset: usize, before: bool) -> Option<(&Piece, usize)> {
+        let mut pos = 0;
+        for piece in &self.pieces {
+            let inside = if before {
+                offset > pos && offset <= pos + piece.len
+            } else {
+                offset >= pos && offset < pos + piece.len
+            };
+            if inside {
+                return Some((piece, offset - pos));
+            }
+            pos += piece.len;
+        }
+        None
+    }
+}
+
+//************************************************************************************************
+impl From<&str> for TextBuffer {
+    fn from(text: &str) -> Self {
+        Self::new(text.to_string())
+    }
+}
+
+//************************************************************************************************
+/// # Returns
+/// Bytes compared by a search, None for an empty pattern.
+fn searched_pattern(pattern: &str, flags: FindFlags) -> Option<Vec<u8>> {
+    if pattern.is_empty() {
+        return None;
+    }
+    let mut pattern = pattern.as_bytes().to_vec();
+    if !flags.case_sensitive {
+        // ASCII case folding keeps offsets of matches equal to offsets in the text
+        pattern.make_ascii_lowercase();
+    }
+    Some(pattern)
+}
+
+//************************************************************************************************
+/// # Arguments
+/// * `text` - indexed text
+/// * `base` - offset of the text in its buffer
+/// # Returns
+/// Offsets of new line characters in the buffer.
+fn newlines(text: &str, base: usize) -> Vec<usize> {
+    text.bytes()
+        .enumerate()
+        .filter(|(_, byte)| *byte == b'\n')
+        .map(|(index, _)| base + index)
+        .collect()
+}
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+#[cfg(test)]
+mod tests {
+    // super
+    use super::*;
+
+    //********************************************************************************************
+    #[test]
+    fn edit_and_lines() {
+        let mut buffer = TextBuffer::from("first\nsecond\nthird");
+        assert_eq!(buffer.line_count(), 3);
+        buffer.insert(6, "new\n");
+        buffer.insert(10, "ł");
+        buffer.insert(12, "!");
+        assert_eq!(buffer.pieces.len(), 3);
+        assert_eq!(buffer.text(), "first\nnew\nł!second\nthird");
+
+        buffer.remove(3, 8);
+        assert_eq!(buffer.text(), "firw\nł!second\nthird");
+        assert_eq!(buffer.len(), 20);
+        assert_eq!(buffer.line_count(), 3);
+        assert_eq!(buffer.line(1), "ł!second");
+        assert_eq!(buffer.line_start(2), 15);
+        assert_eq!(buffer.line_end(0), 4);
+        assert_eq!(buffer.line_start(3), 20);
+        assert_eq!(buffer.line_of_offset(4), 0);
+        assert_eq!(buffer.line_of_offset(5), 1);
+        assert_eq!(buffer.line_of_offset(20), 2);
+        assert_eq!(buffer.char_at(5), Some('ł'));
+        assert_eq!(buffer.char_before(7), Some('ł'));
+        assert_eq!(buffer.char_before(0), None);
+        assert_eq!(buffer.floor_char_boundary(6), 5);
+        assert_eq!(buffer.floor_char_boundary(7), 7);
+        assert_eq!(buffer.floor_char_boundary(100), 20);
+        assert_eq!(buffer.slice(2, 9), "rw\nł!s");
+
+        buffer.remove(0, buffer.len());
+        assert!(buffer.is_empty());
+        assert_eq!(buffer.line_count(), 1);
+        buffer.insert(0, "\n");
+        assert_eq!(buffer.line_count(), 2);
+        assert_eq!(buffer.line(1), "");
+    }
+
+    //********************************************************************************************
+    #[test]
+    fn find() {
+        let mut buffer = TextBuffer::from("Key = 1\nkey = 2");
+        buffer.insert(4, " ");
+        let backward = FindFlags {
+            backward: true,
+            ..FindFlags::default()
+        };
+        assert_eq!(buffer.find("key", 0, FindFlags::default()), Some(0));
+        assert_eq!(buffer.find("key", 1, FindFlags::default()), Some(9));
+        assert_eq!(buffer.find("key", 9, backward), Some(0));
+        let case_sensitive = FindFlags {
+            case_sensitive: true,
+            ..FindFlags::default()
+        };
+        assert_eq!(buffer.find("Key  =", 1, case_sensitive), None);
+        assert_eq!(buffer.find("", 0, FindFlags::default()), None);
+        assert_eq!(buffer.find_all("KEY", FindFlags::default()), vec![0, 9]);
+
+        // matches spanning pieces, overlapping only backward
+        let mut buffer = TextBuffer::from("aXa");
+        buffer.remove(1, 2);
+        buffer.insert(1, "A");
+        buffer.insert(3, "a");
+        assert_eq!(buffer.pieces.len(), 4);
+        assert_eq!(buffer.text(), "aAaa");
+        assert_eq!(buffer.find_all("aa", FindFlags::default()), vec![0, 2]);
+        assert_eq!(buffer.find_all("aa", case_sensitive), vec![2]);
+        assert_eq!(buffer.find("aa", 4, backward), Some(2));
+        assert_eq!(buffer.find("aa", 3, backward), Some(1));
+        assert_eq!(buffer.find("Aa", 1, case_sensitive), Some(1));
+        assert_eq!(buffer.find("aaaaa", 0, FindFlags::default()), None);
+    }
+}
@@ -0,0 +1,1475 @@
+// std
+use std::fmt::Debug;
+use std::iter::once;
+use std::ops::Range;
+// deps
+use nalgebra::Point2;
+use nalgebra::Vector2;
+use uuid::Uuid;
+// crate
+use crate::poly_ui::app::clipboard;
+use crate::poly_ui::app::Color;
+use crate::poly_ui::app::ElideMode;
+use crate::poly_ui::app::Font;
+use crate::poly_ui::app::PainterTrait;
+use crate::poly_ui::app::Rect;
+use crate::poly_ui::app::TextLayout;
+use crate::poly_ui::app::TextLine;
+use crate::poly_ui::components::Hierarchy;
+use crate::poly_ui::components::Signal;
+use crate::poly_ui::components::Transform;
+use crate::poly_ui::events::Event;
+use crate::poly_ui::events::Key;
+use crate::poly_ui::events::KeyPressEvent;
+use crate::poly_ui::events::MouseButton;
+use crate::poly_ui::widgets::NewWidget;
+use crate::poly_ui::widgets::OwnedWidget;
+use crate::poly_ui::widgets::WidgetTrait;
+// super
+use super::button_base::BORDER_COLOR;
+use super::button_base::FOCUS_COLOR;
+use super::label::SELECTION_COLOR;
+use super::line_edit::BACKGROUND_COLOR;
+use super::line_edit::CURSOR_BLINK_INTERVAL;
+use super::FindFlags;
+use super::TextBuffer;
+
+/// Space between the border and the text.
+const PADDING: u32 = 3;
+/// Space on both sides of line numbers.
+const GUTTER_PADDING: u32 = 4;
+const GUTTER_COLOR: Color = Color::rgb(40, 40, 40);
+/// Number of characters and lines the preferred size has room for.
+const PREFERRED_COLUMNS: u32 = 40;
+const PREFERRED_ROWS: u32 = 10;
+/// Number of rows or columns scrolled by one step of the mouse wheel.
+const WHEEL_STEP: i32 = 3;
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// Look of a range of text in a TextEdit.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct TextStyle {
+    pub color: Color,
+    pub background: Option<Color>,
+    pub bold: bool,
+}
+
+//************************************************************************************************
+impl TextStyle {
+    /// # Arguments
+    /// * `color` - color of the text, the background is not painted and the font is not bold
+    pub fn new(color: Color) -> Self {
+        Self {
+            color,
+            background: None,
+            bold: false,
+        }
+    }
+}
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// Style of a part of a line.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct StyledRange {
+    /// Range of bytes of the line text.
+    pub range: Range<usize>,
+    pub style: TextStyle,
+}
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// Styles lines of a TextEdit when they are painted, e.g. for syntax highlighting. Only visible
+/// lines are styled, so highlighting a large text costs only what's on the screen.
+pub trait HighlighterTrait: Debug {
+    /// # Arguments
+    /// * `buffer` - whole text, e.g. for looking at previous lines
+    /// * `line` - index of the styled line
+    /// * `text` - text of the line
+    /// # Returns
+    /// Styled ranges of the line; later ranges override earlier ones and text outside of all
+    /// ranges has the color of the TextEdit.
+    fn highlight_line(&self, buffer: &TextBuffer, line: usize, text: &str) -> Vec<StyledRange>;
+}
+
+//************************************************************************************************
+/// Replacement of a part of the text recorded for undo.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Edit {
+    offset: usize,
+    removed: String,
+    inserted: String,
+}
+
+//************************************************************************************************
+impl Edit {
+    fn apply(&self, buffer: &mut TextBuffer) {
+        buffer.remove(self.offset, self.offset + self.removed.len());
+        buffer.insert(self.offset, &self.inserted);
+    }
+
+    fn revert(&self, buffer: &mut TextBuffer) {
+        buffer.remove(self.offset, self.offset + self.inserted.len());
+        buffer.insert(self.offset, &self.removed);
+    }
+}
+
+//************************************************************************************************
+/// Edits undone at once with the cursor and the anchor before and after them. Only the changed
+/// text is stored, so undo history of a large text stays small.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct UndoStep {
+    /// Edits in the order they were applied.
+    edits: Vec<Edit>,
+    before: (usize, usize),
+    after: (usize, usize),
+}
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// Widget for editing multi-line text stored in a TextBuffer, so large files stay responsive:
+/// only visible lines are laid out and painted. Lines are optionally wrapped at spaces; without
+/// wrapping the text scrolls horizontally as well as vertically to keep the cursor visible.
+/// The mouse wheel scrolls without moving the cursor. Line numbers can be shown in a gutter on the left and lines can be styled with a
+/// highlighter.
+///
+/// Keyboard editing matches LineEdit with Up, Down, Page Up and Page Down moving by rows,
+/// Ctrl+Home and Ctrl+End moving to the ends of the text and Return inserting a new line.
+/// Consecutive typing is undone at once.
+///
+/// Positions are byte offsets in the text like in TextBuffer, unlike LineEdit which uses indices
+/// of characters; offsets inside of a character are moved to its start.
+#[derive(Debug)]
+pub struct TextEdit {
+    id: Uuid,
+    hierarchy: Hierarchy,
+
+    buffer: TextBuffer,
+    cursor: usize,
+    anchor: usize,
+    /// Column the cursor returns to when moving up and down through shorter rows.
+    preferred_column: Option<usize>,
+    /// First visible line and its first visible row.
+    top: (usize, usize),
+    /// Number of columns scrolled horizontally.
+    scroll_x: usize,
+    /// Whether the next paint scrolls to the cursor.
+    follow_cursor: bool,
+    font: Font,
+    color: Color,
+    word_wrap: bool,
+    line_numbers: bool,
+    read_only: bool,
+    highlighter: Option<Box<dyn HighlighterTrait>>,
+
+    undo_stack: Vec<UndoStep>,
+    redo_stack: Vec<UndoStep>,
+    text_changed: Signal<()>,
+
+    focused: bool,
+    selecting: bool,
+    cursor_visible: bool,
+    blink_timer: f32,
+    size: Vector2<u32>,
+}
+
+//************************************************************************************************
+impl TextEdit {
+    pub fn new_raw() -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            hierarchy: Hierarchy::default(),
+            buffer: TextBuffer::default(),
+            cursor: 0,
+            anchor: 0,
+            preferred_column: None,
+            top: (0, 0),
+            scroll_x: 0,
+            follow_cursor: false,
+            font: Font::default(),
+            color: Color::WHITE,
+            word_wrap: false,
+            line_numbers: false,
+            read_only: false,
+            highlighter: None,
+            undo_stack: vec![],
+            redo_stack: vec![],
+            text_changed: Signal::new(),
+            focused: false,
+            selecting: false,
+            cursor_visible: true,
+            blink_timer: 0.0,
+            size: Vector2::<u32>::new(0, 0),
+        }
+    }
+
+    pub fn new() -> NewWidget<Self> {
+        NewWidget::new(Self::new_raw())
+    }
+
+    /// # Returns
+    /// Signal emitted whenever the text changes; the text is not passed as it may be large.
+    pub fn text_changed(&self) -> &Signal<()> {
+        &self.text_changed
+    }
+
+    pub fn buffer(&self) -> &TextBuffer {
+        &self.buffer
+    }
+
+    /// Replaces the text, moves the cursor to its start and clears the undo history.
+    /// # Arguments
+    /// * `buffer` - new text
+    pub fn set_buffer(&mut self, buffer: TextBuffer) {
+        self.buffer = buffer;
+        self.cursor = 0;
+        self.anchor = 0;
+        self.top = (0, 0);
+        self.scroll_x = 0;
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+        self.text_edited();
+    }
+
+    /// # Returns
+    /// Copy of the whole text.
+    pub fn text(&self) -> String {
+        self.buffer.text()
+    }
+
+    /// Replaces the text, see set_buffer().
+    /// # Arguments
+    /// * `text` - new text, lines are separated with '\n'
+    pub fn set_text(&mut self, text: &str) {
+        self.set_buffer(TextBuffer::from(text));
+    }
+
+    pub fn font(&self) -> &Font {
+        &self.font
+    }
+
+    pub fn set_font(&mut self, font: &Font) {
+        self.font = *font;
+        self.follow_cursor = true;
+        self.request_repaint();
+    }
+
+    pub fn color(&self) -> &Color {
+        &self.color
+    }
+
+    /// # Arguments
+    /// * `color` - color of text not styled by the highlighter
+    pub fn set_color(&mut self, color: &Color) {
+        self.color = *color;
+        self.request_repaint();
+    }
+
+    pub fn word_wrap(&self) -> bool {
+        self.word_wrap
+    }
+
+    /// # Arguments
+    /// * `word_wrap` - whether lines wider than the TextEdit are wrapped instead of scrolled
+    pub fn set_word_wrap(&mut self, word_wrap: bool) {
+        self.word_wrap = word_wrap;
+        self.top.1 = 0;
+        self.scroll_x = 0;
+        self.follow_cursor = true;
+        self.request_repaint();
+    }
+
+    pub fn line_numbers(&self) -> bool {
+        self.line_numbers
+    }
+
+    /// # Arguments
+    /// * `line_numbers` - whether line numbers are shown in a gutter
+    pub fn set_line_numbers(&mut self, line_numbers: bool) {
+        self.line_numbers = line_numbers;
+        self.follow_cursor = true;
+        self.request_repaint();
+    }
+
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
+    /// # Arguments
+    /// * `read_only` - whether the user can only select and copy the text
+    pub fn set_read_only(&mut self, read_only: bool) {
+        self.read_only = read_only;
+        self.request_repaint();
+    }
+
+    pub fn highlighter(&self) -> Option<&dyn HighlighterTrait> {
+        self.highlighter.as_deref()
+    }
+
+    /// # Arguments
+    /// * `highlighter` - styles of painted lines, None to paint all text in one color
+    pub fn set_highlighter(&mut self, highlighter: Option<Box<dyn HighlighterTrait>>) {
+        self.highlighter = highlighter;
+        self.request_repaint();
+    }
+
+    /// # Returns
+    /// Offset of the cursor in the text.
+    pub fn cursor_position(&self) -> usize {
+        self.cursor
+    }
+
+    /// # Returns
+    /// Index of the line of the cursor and index of the character (not byte) in the line.
+    pub fn cursor_line_column(&self) -> (usize, usize) {
+        let line = self.buffer.line_of_offset(self.cursor);
+        let start = self.buffer.line_start(line);
+        (line, self.buffer.slice(start, self.cursor).chars().count())
+    }
+
+    /// Moves the cursor and clears the selection.
+    /// # Arguments
+    /// * `position` - offset of the cursor
+    pub fn set_cursor_position(&mut self, position: usize) {
+        self.move_cursor(position, false);
+    }
+
+    /// # Returns
+    /// Range of selected bytes, None if nothing is selected.
+    pub fn selection(&self) -> Option<(usize, usize)> {
+        if self.anchor == self.cursor {
+            None
+        } else {
+            Some((self.anchor.min(self.cursor), self.anchor.max(self.cursor)))
+        }
+    }
+
+    /// Selects text and puts the cursor at the end of the selection.
+    /// # Arguments
+    /// * `start` - offset of the first selected character
+    /// * `end` - offset after the last selected character, equal to start to clear the selection
+    pub fn set_selection(&mut self, start: usize, end: usize) {
+        self.move_cursor(start, false);
+        self.move_cursor(end, true);
+    }
+
+    pub fn select_all(&mut self) {
+        self.set_selection(0, self.buffer.len());
+    }
+
+    pub fn selected_text(&self) -> String {
+        match self.selection() {
+            Some((start, end)) => self.buffer.slice(start, end),
+            None => String::new(),
+        }
+    }
+
+    /// Copies the selection to the clipboard.
+    pub fn copy(&self) {
+        if self.selection().is_some() {
+            clipboard().borrow_mut().set_text(&self.selected_text());
+        }
+    }
+
+    /// Moves the selection to the clipboard unless the TextEdit is read only.
+    pub fn cut(&mut self) {
+        if !self.read_only {
+            self.copy();
+            self.insert("");
+        }
+    }
+
+    /// Replaces the selection with the text in the clipboard.
+    pub fn paste(&mut self) {
+        let text = clipboard().borrow().text();
+        if let Some(text) = text {
+            self.insert(&text);
+        }
+    }
+
+    /// Replaces the selection with the text as if it was typed.
+    /// # Arguments
+    /// * `text` - inserted text, control characters other than new lines and tabs are ignored
+    /// # Returns
+    /// Whether the text changed, false if the TextEdit is read only.
+    pub fn insert(&mut self, text: &str) -> bool {
+        let text: String = text
+            .chars()
+            .filter(|ch| !ch.is_control() || *ch == '\n' || *ch == '\t')
+            .collect();
+        let (start, end) = self.selection().unwrap_or((self.cursor, self.cursor));
+        self.replace_range(start, end, &text)
+    }
+
+    pub fn is_undo_available(&self) -> bool {
+        !self.undo_stack.is_empty()
+    }
+
+    pub fn is_redo_available(&self) -> bool {
+        !self.redo_stack.is_empty()
+    }
+
+    /// Reverts the last edit.
+    pub fn undo(&mut self) {
+        if let Some(step) = self.undo_stack.pop() {
+            for edit in step.edits.iter().rev() {
+                edit.revert(&mut self.buffer);
+            }
+            (self.cursor, self.anchor) = step.before;
+            self.redo_stack.push(step);
+            self.text_edited();
+        }
+    }
+
+    /// Repeats the last undone edit.
+    pub fn redo(&mut self) {
+        if let Some(step) = self.redo_stack.pop() {
+            for edit in &step.edits {
+                edit.apply(&mut self.buffer);
+            }
+            (self.cursor, self.anchor) = step.after;
+            self.undo_stack.push(step);
+            self.text_edited();
+        }
+    }
+
+    /// Selects the next match of the pattern after the selection, or the previous one before it
+    /// when searching backward; the search wraps around the ends of the text.
+    /// # Arguments
+    /// * `pattern` - searched text
+    /// * `flags` - options of the search
+    /// # Returns
+    /// Whether a match was found.
+    pub fn find(&mut self, pattern: &str, flags: FindFlags) -> bool {
+        let (start, end) = self.selection().unwrap_or((self.cursor, self.cursor));
+        let found = if flags.backward {
+            self.buffer
+                .find(pattern, start, flags)
+                .or_else(|| self.buffer.find(pattern, self.buffer.len(), flags))
+        } else {
+            self.buffer
+                .find(pattern, end, flags)
+                .or_else(|| self.buffer.find(pattern, 0, flags))
+        };
+        match found {
+            Some(offset) => {
+                self.set_selection(offset, offset + pattern.len());
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Replaces the selection if it matches the pattern and selects the next match.
+    /// # Arguments
+    /// * `pattern` - searched text
+    /// * `replacement` - text replacing the match
+    /// * `flags` - options of the search
+    /// # Returns
+    /// Whether the next match was found.
+    pub fn replace(&mut self, pattern: &str, replacement: &str, flags: FindFlags) -> bool {
+        let selected = self.selected_text();
+        let matches = if flags.case_sensitive {
+            selected == pattern
+        } else {
+            selected.eq_ignore_ascii_case(pattern)
+        };
+        if let (true, Some((start, end))) = (matches, self.selection()) {
+            self.replace_range(start, end, replacement);
+        }
+        self.find(pattern, flags)
+    }
+
+    /// Replaces all matches of the pattern in one undo step.
+    /// # Arguments
+    /// * `pattern` - searched text
+    /// * `replacement` - text replacing the matches
+    /// * `flags` - options of the search, the direction is ignored
+    /// # Returns
+    /// Number of replaced matches.
+    pub fn replace_all(&mut self, pattern: &str, replacement: &str, flags: FindFlags) -> usize {
+        if self.read_only {
+            return 0;
+        }
+        let offsets = self.buffer.find_all(pattern, flags);
+        let first = match offsets.first() {
+            Some(first) => *first,
+            None => return 0,
+        };
+        // replacing from the end keeps offsets of the remaining matches valid
+        let edits: Vec<Edit> = offsets
+            .iter()
+            .rev()
+            .map(|offset| Edit {
+                offset: *offset,
+                removed: self.buffer.slice(*offset, offset + pattern.len()),
+                inserted: replacement.to_string(),
+            })
+            .collect();
+        for edit in &edits {
+            edit.apply(&mut self.buffer);
+        }
+        let before = (self.cursor, self.anchor);
+        self.cursor = first + replacement.len();
+        self.anchor = self.cursor;
+        self.push_undo(UndoStep {
+            edits,
+            before,
+            after: (self.cursor, self.anchor),
+        });
+        self.text_edited();
+        offsets.len()
+    }
+
+    /// # Returns
+    /// Index of the first visible line.
+    pub fn first_visible_line(&self) -> usize {
+        self.top.0
+    }
+
+    /// # Returns
+    /// Number of columns the text is scrolled horizontally.
+    pub fn horizontal_scroll(&self) -> usize {
+        self.scroll_x
+    }
+
+    /// Scrolls without moving the cursor.
+    /// # Arguments
+    /// * `rows` - number of rows to scroll down, negative to scroll up
+    /// * `columns` - number of columns to scroll right, ignored with word wrap
+    pub fn scroll_by(&mut self, rows: i32, columns: i32) {
+        self.top = self.step_row(self.top, rows);
+        if !self.word_wrap {
+            self.scroll_x = (self.scroll_x as i64 + columns as i64).max(0) as usize;
+        }
+        self.follow_cursor = false;
+        self.request_repaint();
+    }
+
+    /// Replaces a range of the text and records it for undo.
+    /// # Returns
+    /// Whether the text changed.
+    fn replace_range(&mut self, start: usize, end: usize, text: &str) -> bool {
+        if self.read_only || (start == end && text.is_empty()) {
+            return false;
+        }
+        let edit = Edit {
+            offset: start,
+            removed: self.buffer.slice(start, end),
+            inserted: text.to_string(),
+        };
+        edit.apply(&mut self.buffer);
+        let before = (self.cursor, self.anchor);
+        self.cursor = start + text.len();
+        self.anchor = self.cursor;
+        self.push_undo(UndoStep {
+            edits: vec![edit],
+            before,
+            after: (self.cursor, self.anchor),
+        });
+        self.text_edited();
+        true
+    }
+
+    /// Pushes the step on the undo stack; typing continuing the last insertion on the same line
+    /// is merged into it.
+    fn push_undo(&mut self, step: UndoStep) {
+        self.redo_stack.clear();
+        if let (Some(last), [edit]) = (self.undo_stack.last_mut(), step.edits.as_slice()) {
+            if let [last_edit] = last.edits.as_mut_slice() {
+                if last.after == step.before
+                    && edit.removed.is_empty()
+                    && last_edit.removed.is_empty()
+                    && !edit.inserted.contains('\n')
+                    && !last_edit.inserted.contains('\n')
+                    && edit.offset == last_edit.offset + last_edit.inserted.len()
+                {
+                    last_edit.inserted.push_str(&edit.inserted);
+                    last.after = step.after;
+                    return;
+                }
+            }
+        }
+        self.undo_stack.push(step);
+    }
+
+    /// Scrolls to the cursor on the next paint and emits the text_changed signal after a change
+    /// of the text.
+    fn text_edited(&mut self) {
+        self.cursor = self.cursor.min(self.buffer.len());
+        self.anchor = self.anchor.min(self.buffer.len());
+        self.preferred_column = None;
+        self.cursor_moved();
+        self.text_changed.emit(&());
+    }
+
+    /// # Arguments
+    /// * `position` - new offset of the cursor, it's moved to the start of its character
+    /// * `extend` - whether the selection is extended to the cursor instead of cleared
+    fn move_cursor(&mut self, position: usize, extend: bool) {
+        self.cursor = self.buffer.floor_char_boundary(position);
+        if !extend {
+            self.anchor = self.cursor;
+        }
+        self.preferred_column = None;
+        self.cursor_moved();
+    }
+
+    /// Shows the cursor, scrolls to it on the next paint and repaints.
+    fn cursor_moved(&mut self) {
+        self.cursor_visible = true;
+        self.blink_timer = 0.0;
+        self.follow_cursor = true;
+        self.request_repaint();
+    }
+
+    /// # Arguments
+    /// * `rows` - number of rows to move, negative to move up
+    /// * `extend` - whether the selection is extended to the cursor instead of cleared
+    fn move_vertically(&mut self, rows: i32, extend: bool) {
+        let (line, row, column) = self.visual_position(self.cursor);
+        let column = self.preferred_column.unwrap_or(column);
+        let (line, row) = self.step_row((line, row), rows);
+        let offset = self.offset_at(line, row, column);
+        self.move_cursor(offset, extend);
+        self.preferred_column = Some(column);
+    }
+
+    fn gutter_width(&self) -> u32 {
+        if self.line_numbers {
+            let digits = self.buffer.line_count().to_string().len() as u32;
+            digits * self.font.char_width() + GUTTER_PADDING * 2
+        } else {
+            0
+        }
+    }
+
+    /// # Returns
+    /// X coordinate of the first visible column.
+    fn text_x(&self) -> i32 {
+        (self.gutter_width() + PADDING) as i32
+    }
+
+    fn text_width(&self) -> u32 {
+        self.size
+            .x
+            .saturating_sub(self.gutter_width() + PADDING * 2)
+    }
+
+    /// # Returns
+    /// Number of whole rows fitting into the TextEdit.
+    fn visible_rows(&self) -> usize {
+        (self.size.y.saturating_sub(PADDING * 2) / self.font.line_height().max(1)) as usize
+    }
+
+    /// # Returns
+    /// Number of whole columns fitting into the TextEdit.
+    fn visible_columns(&self) -> usize {
+        (self.text_width() / self.font.char_width().max(1)) as usize
+    }
+
+    /// # Arguments
+    /// * `text` - text of a line
+    /// # Returns
+    /// Rows the line is displayed in, one without word wrap.
+    fn rows_of(&self, text: &str) -> Vec<TextLine> {
+        let width = self.text_width();
+        let layout = if self.word_wrap && width >= self.font.char_width() {
+            TextLayout::new(text, &self.font, Some(width), true, ElideMode::None)
+        } else {
+            TextLayout::new(text, &self.font, None, false, ElideMode::None)
+        };
+        layout.lines().clone()
+    }
+
+    fn row_count(&self, line: usize) -> usize {
+        if self.word_wrap {
+            self.rows_of(&self.buffer.line(line)).len()
+        } else {
+            1
+        }
+    }
+
+    /// # Arguments
+    /// * `offset` - offset in the text
+    /// # Returns
+    /// Line of the offset, row of the line and column of the row.
+    fn visual_position(&self, offset: usize) -> (usize, usize, usize) {
+        let line = self.buffer.line_of_offset(offset);
+        let start = self.buffer.line_start(line);
+        let text = self.buffer.line(line);
+        let index = text[..offset - start].chars().count();
+        let rows = self.rows_of(&text);
+        let row = rows.iter().rposition(|row| row.start <= index).unwrap_or(0);
+        (line, row, index - rows[row].start)
+    }
+
+    /// # Arguments
+    /// * `line` - index of the line
+    /// * `row` - index of the row of the line
+    /// * `column` - column in the row, clamped to its end
+    /// # Returns
+    /// Offset in the text.
+    fn offset_at(&self, line: usize, row: usize, column: usize) -> usize {
+        let text = self.buffer.line(line);
+        let rows = self.rows_of(&text);
+        let row = &rows[row.min(rows.len() - 1)];
+        let index = (row.start + column).min(row.end());
+        let byte = text
+            .char_indices()
+            .nth(index)
+            .map_or(text.len(), |(byte, _)| byte);
+        self.buffer.line_start(line) + byte
+    }
+
+    /// # Arguments
+    /// * `from` - line and row
+    /// * `rows` - number of rows to move, negative to move up
+    /// # Returns
+    /// Line and row the given number of rows away, clamped to the ends of the text.
+    fn step_row(&self, from: (usize, usize), rows: i32) -> (usize, usize) {
+        let line_count = self.buffer.line_count();
+        let (mut line, mut row) = from;
+        for _ in 0..rows.unsigned_abs() {
+            if rows > 0 {
+                if row + 1 < self.row_count(line) {
+                    row += 1;
+                } else if line + 1 < line_count {
+                    line += 1;
+                    row = 0;
+                } else {
+                    break;
+                }
+            } else if row > 0 {
+                row -= 1;
+            } else if line > 0 {
+                line -= 1;
+                row = self.row_count(line) - 1;
+            } else {
+                break;
+            }
+        }
+        (line, row)
+    }
+
+    /// Scrolls the least possible number of rows and columns to make the cursor visible.
+    fn scroll_to_cursor(&mut self) {
+        let visible_rows = self.visible_rows();
+        if visible_rows == 0 {
+            return;
+        }
+        let (line, row, column) = self.visual_position(self.cursor);
+        if (line, row) < self.top {
+            self.top = (line, row);
+        } else if (line, row) > self.step_row(self.top, visible_rows as i32 - 1) {
+            self.top = self.step_row((line, row), 1 - visible_rows as i32);
+        }
+
+        let visible_columns = self.visible_columns();
+        if self.word_wrap {
+            self.scroll_x = 0;
+        } else if column < self.scroll_x {
+            self.scroll_x = column;
+        } else if column > self.scroll_x + visible_columns {
+            self.scroll_x = column - visible_columns;
+        }
+    }
+
+    /// Keeps the first visible row inside of the text after it got shorter.
+    fn clamp_top(&mut self) {
+        let line = self.top.0.min(self.buffer.line_count() - 1);
+        self.top = (line, self.top.1.min(self.row_count(line) - 1));
+    }
+
+    fn previous_boundary(&self, offset: usize) -> usize {
+        offset - self.buffer.char_before(offset).map_or(0, char::len_utf8)
+    }
+
+    fn next_boundary(&self, offset: usize) -> usize {
+        offset + self.buffer.char_at(offset).map_or(0, char::len_utf8)
+    }
+
+    /// # Arguments
+    /// * `forward` - direction of the search
+    /// # Returns
+    /// Offset of the start of the previous word or the end of the next word.
+    fn word_boundary(&self, forward: bool) -> usize {
+        let mut offset = self.cursor;
+        if forward {
+            while self.buffer.char_at(offset).is_some_and(char::is_whitespace) {
+                offset = self.next_boundary(offset);
+            }
+            while self
+                .buffer
+                .char_at(offset)
+                .is_some_and(|ch| !ch.is_whitespace())
+            {
+                offset = self.next_boundary(offset);
+            }
+        } else {
+            while self
+                .buffer
+                .char_before(offset)
+                .is_some_and(char::is_whitespace)
+            {
+                offset = self.previous_boundary(offset);
+            }
+            while self
+                .buffer
+                .char_before(offset)
+                .is_some_and(|ch| !ch.is_whitespace())
+            {
+                offset = self.previous_boundary(offset);
+            }
+        }
+        offset
+    }
+
+    /// # Arguments
+    /// * `pos` - point in the TextEdit
+    /// # Returns
+    /// Offset of the character boundary nearest to the point; points outside of the text area
+    /// move one row or column beyond the visible ones, so dragging there scrolls.
+    fn hit_test(&self, pos: &Point2<i32>) -> usize {
+        let line_height = self.font.line_height().max(1) as i32;
+        let char_width = self.font.char_width().max(1) as i32;
+        let rows = (pos.y - PADDING as i32)
+            .div_euclid(line_height)
+            .clamp(-1, self.visible_rows() as i32);
+        let (line, row) = self.step_row(self.top, rows);
+        let column = (pos.x - self.text_x() + char_width / 2)
+            .div_euclid(char_width)
+            .max(-1);
+        let column = (self.scroll_x as i32 + column).max(0) as usize;
+        self.offset_at(line, row, column)
+    }
+
+    /// # Returns
+    /// Whether the key was a handled shortcut or editing key.
+    fn handle_key(&mut self, press: &KeyPressEvent) -> bool {
+        let modifiers = press.modifiers();
+        let (start, end) = self.selection().unwrap_or((self.cursor, self.cursor));
+        let page = self.visible_rows().max(1) as i32;
+        match press.key() {
+            Key::Left if modifiers.ctrl => {
+                self.move_cursor(self.word_boundary(false), modifiers.shift)
+            }
+            Key::Right if modifiers.ctrl => {
+                self.move_cursor(self.word_boundary(true), modifiers.shift)
+            }
+            Key::Left if start != end && !modifiers.shift => self.move_cursor(start, false),
+            Key::Right if start != end && !modifiers.shift => self.move_cursor(end, false),
+            Key::Left => self.move_cursor(self.previous_boundary(self.cursor), modifiers.shift),
+            Key::Right => self.move_cursor(self.next_boundary(self.cursor), modifiers.shift),
+            Key::Up => self.move_vertically(-1, modifiers.shift),
+            Key::Down => self.move_vertically(1, modifiers.shift),
+            Key::PageUp => self.move_vertically(-page, modifiers.shift),
+            Key::PageDown => self.move_vertically(page, modifiers.shift),
+            Key::Home if modifiers.ctrl => self.move_cursor(0, modifiers.shift),
+            Key::End if modifiers.ctrl => self.move_cursor(self.buffer.len(), modifiers.shift),
+            Key::Home => {
+                let line = self.buffer.line_of_offset(self.cursor);
+                self.move_cursor(self.buffer.line_start(line), modifiers.shift);
+            }
+            Key::End => {
+                let line = self.buffer.line_of_offset(self.cursor);
+                self.move_cursor(self.buffer.line_end(line), modifiers.shift);
+            }
+            Key::Backspace | Key::Delete if start != end => {
+                self.replace_range(start, end, "");
+            }
+            Key::Backspace => {
+                let from = if modifiers.ctrl {
+                    self.word_boundary(false)
+                } else {
+                    self.previous_boundary(self.cursor)
+                };
+                self.replace_range(from, self.cursor, "");
+            }
+            Key::Delete => {
+                let to = if modifiers.ctrl {
+                    self.word_boundary(true)
+                } else {
+                    self.next_boundary(self.cursor)
+                };
+                self.replace_range(self.cursor, to, "");
+            }
+            Key::Return | Key::KpEnter => {
+                self.insert("\n");
+            }
+            Key::A if modifiers.ctrl => self.select_all(),
+            Key::C if modifiers.ctrl => self.copy(),
+            Key::X if modifiers.ctrl => self.cut(),
+            Key::V if modifiers.ctrl => self.paste(),
+            Key::Z if modifiers.ctrl && modifiers.shift => self.redo(),
+            Key::Z if modifiers.ctrl => self.undo(),
+            Key::Y if modifiers.ctrl => self.redo(),
+            _ => return false,
+        }
+        true
+    }
+}
+
+//************************************************************************************************
+impl WidgetTrait for TextEdit {
+    fn id(&self) -> &Uuid {
+        &self.id
+    }
+
+    fn remove_child(&mut self, child: &Uuid) -> OwnedWidget {
+        self.hierarchy.remove(child)
+    }
+
+    fn get_hierarchy(&self) -> &Hierarchy {
+        &self.hierarchy
+    }
+
+    fn get_child_transform(&self, child: &Uuid) -> &Transform {
+        self.hierarchy.get_transform(child)
+    }
+
+    fn preferred_size(&self) -> Option<Vector2<u32>> {
+        Some(Vector2::<u32>::new(
+            self.font.char_width() * PREFERRED_COLUMNS + self.gutter_width() + PADDING * 2,
+            self.font.line_height() * PREFERRED_ROWS + PADDING * 2,
+        ))
+    }
+
+    fn accepts_focus(&self) -> bool {
+        true
+    }
+
+    fn handle_event(&mut self, event: &Event) -> bool {
+        if self.hierarchy.dispatch_event(event) {
+            return true;
+        }
+
+        match event {
+            Event::FocusIn => {
+                self.focused = true;
+                self.cursor_moved();
+            }
+            Event::FocusOut => {
+                self.focused = false;
+                self.selecting = false;
+                self.request_repaint();
+            }
+            Event::MousePressEvent(press) if press.button() == MouseButton::Left => {
+                self.selecting = true;
+                self.move_cursor(self.hit_test(&press.pos()), false);
+            }
+            Event::MouseMoveEvent(movement) if self.selecting => {
+                self.move_cursor(self.hit_test(&movement.pos()), true);
+            }
+            Event::MouseReleaseEvent(release) if release.button() == MouseButton::Left => {
+                if self.selecting {
+                    self.move_cursor(self.hit_test(&release.pos()), true);
+                }
+                self.selecting = false;
+            }
+            Event::MouseWheel(wheel) => {
+                let delta = wheel.delta();
+                self.scroll_by(delta.y * WHEEL_STEP, delta.x * WHEEL_STEP);
+            }
+            Event::KeyPress(press) if self.focused => return self.handle_key(press),
+            Event::TextInput(input) if self.focused => {
+                let mut buffer = [0; 4];
+                self.insert(input.ch().encode_utf8(&mut buffer));
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    fn update(&mut self, dt: f32) {
+        if self.focused {
+            self.blink_timer += dt;
+            if self.blink_timer >= CURSOR_BLINK_INTERVAL {
+                self.blink_timer %= CURSOR_BLINK_INTERVAL;
+                self.cursor_visible = !self.cursor_visible;
+                self.request_repaint();
+            }
+        }
+        self.hierarchy.update_children(dt);
+    }
+
+    fn paint(&mut self, painter: &mut dyn PainterTrait) {
+        self.size = painter.size();
+        self.clamp_top();
+        if self.follow_cursor {
+            self.scroll_to_cursor();
+            self.follow_cursor = false;
+        }
+        let rect = Rect {
+            pos: Point2::<i32>::new(0, 0),
+            size: self.size,
+        };
+        painter.set_draw_color(&BACKGROUND_COLOR);
+        painter.fill_rect(rect);
+        let gutter_width = self.gutter_width();
+        if gutter_width > 0 {
+            painter.set_draw_color(&GUTTER_COLOR);
+            painter.fill_rect(Rect {
+                pos: Point2::<i32>::new(0, 0),
+                size: Vector2::<u32>::new(gutter_width, self.size.y),
+            });
+        }
+
+        let char_width = self.font.char_width();
+        let line_height = self.font.line_height();
+        let text_x = self.text_x();
+        let x_of = |column: usize| text_x + (column as u32 * char_width) as i32;
+        let visible_rows = self.visible_rows();
+        let visible_columns = self.visible_columns();
+        let line_count = self.buffer.line_count();
+        let cursor = self.visual_position(self.cursor);
+        let selection = self.selection();
+        let default_style = TextStyle::new(self.color);
+        let mut cursor_pos = None;
+
+        let (mut line, mut first_row) = self.top;
+        let mut y = PADDING as i32;
+        let mut painted = 0;
+        while painted < visible_rows && line < line_count {
+            let start = self.buffer.line_start(line);
+            let text = self.buffer.line(line);
+            // offsets in the text of characters of the line and of its end
+            let offsets: Vec<usize> = text
+                .char_indices()
+                .map(|(offset, _)| start + offset)
+                .chain(once(start + text.len()))
+                .collect();
+            let styles = self
+                .highlighter
+                .as_ref()
+                .map(|highlighter| highlighter.highlight_line(&self.buffer, line, &text))
+                .unwrap_or_default();
+            let style_of = |index: usize| {
+                let byte = offsets[index] - start;
+                styles
+                    .iter()
+                    .rev()
+                    .find(|styled| styled.range.contains(&byte))
+                    .map_or(default_style, |styled| styled.style)
+            };
+
+            if gutter_width > 0 && first_row == 0 {
+                let number = (line + 1).to_string();
+                let x = (gutter_width - GUTTER_PADDING - self.font.text_width(&number)) as i32;
+                let color = if line == cursor.0 {
+                    Color::WHITE
+                } else {
+                    Color::GRAY
+                };
+                painter.set_draw_color(&color);
+                painter.draw_text(&number, &Point2::<i32>::new(x, y), &self.font);
+            }
+
+            for (row_index, row) in self.rows_of(&text).iter().enumerate().skip(first_row) {
+                if painted >= visible_rows {
+                    break;
+                }
+                let first = self.scroll_x.min(row.glyphs.len());
+                let last = row.glyphs.len().min(self.scroll_x + visible_columns);
+                let glyphs = &row.glyphs[first..last];
+                let index_of = |column: usize| glyphs[column].index.unwrap_or(row.start);
+
+                // runs of glyphs with the same style
+                let mut runs = vec![];
+                let mut column = 0;
+                while column < glyphs.len() {
+                    let style = style_of(index_of(column));
+                    let mut end = column + 1;
+                    while end < glyphs.len() && style_of(index_of(end)) == style {
+                        end += 1;
+                    }
+                    runs.push((column, end, style));
+                    column = end;
+                }
+                let run_rect = |start: usize, end: usize| Rect {
+                    pos: Point2::<i32>::new(x_of(start), y),
+                    size: Vector2::<u32>::new((end - start) as u32 * char_width, line_height),
+                };
+
+                for (start, end, style) in &runs {
+                    if let Some(background) = style.background {
+                        painter.set_draw_color(&background);
+                        painter.fill_rect(run_rect(*start, *end));
+                    }
+                }
+                if let Some((selection_start, selection_end)) = selection {
+                    let mut selected = (0..glyphs.len()).filter(|column| {
+                        let offset = offsets[index_of(*column)];
+                        offset >= selection_start && offset < selection_end
+                    });
+                    if let Some(first) = selected.next() {
+                        let last = selected.next_back().unwrap_or(first);
+                        painter.set_draw_color(&SELECTION_COLOR);
+                        painter.fill_rect(run_rect(first, last + 1));
+                    }
+                }
+                for (start, end, style) in &runs {
+                    let run: String = glyphs[*start..*end].iter().map(|glyph| glyph.ch).collect();
+                    let font = if style.bold {
+                        self.font.bold()
+                    } else {
+                        self.font
+                    };
+                    painter.set_draw_color(&style.color);
+                    painter.draw_text(&run, &Point2::<i32>::new(x_of(*start), y), &font);
+                }
+
+                let (cursor_line, cursor_row, cursor_column) = cursor;
+                if (line, row_index) == (cursor_line, cursor_row)
+                    && cursor_column >= self.scroll_x
+                    && cursor_column <= self.scroll_x + visible_columns
+                {
+                    cursor_pos = Some(Point2::<i32>::new(x_of(cursor_column - self.scroll_x), y));
+                }
+                y += line_height as i32;
+                painted += 1;
+            }
+            line += 1;
+            first_row = 0;
+        }
+
+        painter.set_draw_color(if self.focused {
+            &FOCUS_COLOR
+        } else {
+            &BORDER_COLOR
+        });
+        painter.draw_rect(rect);
+        if let (true, Some(pos)) = (
+            self.focused && self.cursor_visible && !self.read_only,
+            cursor_pos,
+        ) {
+            painter.set_draw_color(&Color::WHITE);
+            painter.fill_rect(Rect {
+                pos,
+                size: Vector2::<u32>::new(1, line_height),
+            });
+        }
+
+        self.hierarchy.paint_children(painter);
+    }
+}
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+#[cfg(test)]
+mod tests {
+    // std
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    // crate
+    use crate::poly_ui::app::Brush;
+    use crate::poly_ui::app::DrawCommand;
+    use crate::poly_ui::app::RecordingPainter;
+    use crate::poly_ui::events::KeyModifiers;
+    use crate::poly_ui::events::MouseMoveEvent;
+    use crate::poly_ui::events::MousePressEvent;
+    use crate::poly_ui::events::MouseReleaseEvent;
+    use crate::poly_ui::events::MouseWheelEvent;
+    use crate::poly_ui::events::TextInputEvent;
+    // super
+    use super::*;
+
+    //********************************************************************************************
+    fn painted_commands(edit: &mut TextEdit, size: &Vector2<u32>) -> Vec<DrawCommand> {
+        let mut painter = RecordingPainter::new(size);
+        edit.paint(&mut painter);
+        painter
+            .display_list()
+            .commands
+            .into_iter()
+            .map(|command| command.command)
+            .collect()
+    }
+
+    //********************************************************************************************
+    fn painted_texts(edit: &mut TextEdit, size: &Vector2<u32>) -> Vec<(String, i32, i32)> {
+        painted_commands(edit, size)
+            .into_iter()
+            .filter_map(|command| match command {
+                DrawCommand::DrawText(text, pos, _) => Some((text, pos.x, pos.y)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    //********************************************************************************************
+    fn type_text(edit: &mut TextEdit, text: &str) {
+        for ch in text.chars() {
+            edit.handle_event(&Event::TextInput(TextInputEvent::new(ch)));
+        }
+    }
+
+    //********************************************************************************************
+    fn press(edit: &mut TextEdit, key: Key, modifiers: KeyModifiers) -> bool {
+        edit.handle_event(&Event::KeyPress(KeyPressEvent::with_modifiers(
+            key, modifiers,
+        )))
+    }
+
+    //********************************************************************************************
+    #[derive(Debug)]
+    struct KeyHighlighter;
+
+    //********************************************************************************************
+    impl HighlighterTrait for KeyHighlighter {
+        fn highlight_line(&self, _: &TextBuffer, _: usize, text: &str) -> Vec<StyledRange> {
+            match text.find('=') {
+                Some(equals) => vec![StyledRange {
+                    range: 0..equals,
+                    style: TextStyle {
+                        color: Color::YELLOW,
+                        background: Some(Color::NAVY),
+                        bold: true,
+                    },
+                }],
+                None => vec![],
+            }
+        }
+    }
+
+    //********************************************************************************************
+    #[test]
+    fn edit_navigate_and_undo() {
+        let mut edit = TextEdit::new_raw();
+        let changes = Rc::new(RefCell::new(0));
+        let changes_clone = changes.clone();
+        edit.text_changed()
+            .connect(move |_| *changes_clone.borrow_mut() += 1);
+
+        type_text(&mut edit, "x");
+        assert_eq!(edit.text(), "");
+        edit.handle_event(&Event::FocusIn);
+        type_text(&mut edit, "first line");
+        press(&mut edit, Key::Return, KeyModifiers::NONE);
+        type_text(&mut edit, "ąb");
+        assert_eq!(edit.text(), "first line\nąb");
+        assert_eq!(*changes.borrow(), 13);
+        assert_eq!(edit.cursor_line_column(), (1, 2));
+
+        // the preferred column survives the short line
+        press(&mut edit, Key::Up, KeyModifiers::NONE);
+        press(&mut edit, Key::End, KeyModifiers::NONE);
+        assert_eq!(edit.cursor_position(), 10);
+        press(&mut edit, Key::Left, KeyModifiers::NONE);
+        press(&mut edit, Key::Down, KeyModifiers::SHIFT);
+        assert_eq!(edit.selected_text(), "e\nąb");
+        press(&mut edit, Key::Up, KeyModifiers::SHIFT);
+        assert_eq!(edit.cursor_position(), 9);
+        press(&mut edit, Key::Left, KeyModifiers::CTRL);
+        press(&mut edit, Key::Delete, KeyModifiers::CTRL);
+        assert_eq!(edit.text(), "first \nąb");
+        press(&mut edit, Key::End, KeyModifiers::CTRL);
+        press(&mut edit, Key::Backspace, KeyModifiers::NONE);
+        assert_eq!(edit.text(), "first \ną");
+        assert!(!press(&mut edit, Key::Tab, KeyModifiers::NONE));
+
+        // typing is undone at once and the line break separately
+        press(&mut edit, Key::Z, KeyModifiers::CTRL);
+        press(&mut edit, Key::Z, KeyModifiers::CTRL);
+        assert_eq!(edit.text(), "first line\nąb");
+        press(&mut edit, Key::Z, KeyModifiers::CTRL);
+        assert_eq!(edit.text(), "first line\n");
+        press(&mut edit, Key::Z, KeyModifiers::CTRL);
+        press(&mut edit, Key::Z, KeyModifiers::CTRL);
+        assert_eq!(edit.text(), "");
+        assert!(!edit.is_undo_available());
+        press(&mut edit, Key::Y, KeyModifiers::CTRL);
+        assert_eq!(edit.text(), "first line");
+        assert_eq!(edit.cursor_position(), 10);
+
+        // clipboard
+        press(&mut edit, Key::Left, KeyModifiers::CTRL);
+        press(&mut edit, Key::End, KeyModifiers::SHIFT);
+        press(&mut edit, Key::X, KeyModifiers::CTRL);
+        press(&mut edit, Key::Home, KeyModifiers::NONE);
+        press(&mut edit, Key::V, KeyModifiers::CTRL);
+        assert_eq!(edit.text(), "linefirst ");
+        edit.set_read_only(true);
+        type_text(&mut edit, "x");
+        assert_eq!(edit.text(), "linefirst ");
+    }
+
+    //********************************************************************************************
+    #[test]
+    fn wrap_and_scroll() {
+        let mut edit = TextEdit::new_raw();
+        edit.set_text("one two three\nfour\nfive\nsix");
+        edit.set_line_numbers(true);
+        assert_eq!(edit.preferred_size(), Some(Vector2::<u32>::new(260, 96)));
+
+        // 3 rows of 8 columns next to the gutter of 14 pixels
+        let size = Vector2::<u32>::new(68, 33);
+        assert_eq!(
+            painted_texts(&mut edit, &size),
+            vec![
+                ("1".to_string(), 4, 3),
+                ("one two ".to_string(), 17, 3),
+                ("2".to_string(), 4, 12),
+                ("four".to_string(), 17, 12),
+                ("3".to_string(), 4, 21),
+                ("five".to_string(), 17, 21),
+            ]
+        );
+
+        // the cursor at the end scrolls down and right
+        edit.handle_event(&Event::FocusIn);
+        press(&mut edit, Key::End, KeyModifiers::CTRL);
+        press(&mut edit, Key::Up, KeyModifiers::NONE);
+        press(&mut edit, Key::Up, KeyModifiers::NONE);
+        press(&mut edit, Key::Up, KeyModifiers::NONE);
+        press(&mut edit, Key::End, KeyModifiers::NONE);
+        painted_texts(&mut edit, &size);
+        assert_eq!(edit.horizontal_scroll(), 5);
+        press(&mut edit, Key::End, KeyModifiers::CTRL);
+        let texts = painted_texts(&mut edit, &size);
+        assert_eq!(edit.first_visible_line(), 1);
+        assert_eq!(edit.horizontal_scroll(), 3);
+        assert_eq!(texts[1], ("r".to_string(), 17, 3));
+        assert_eq!(texts.len(), 5);
+
+        edit.set_word_wrap(true);
+        edit.scroll_by(-5, 0);
+        assert_eq!(
+            painted_texts(&mut edit, &size),
+            vec![
+                ("1".to_string(), 4, 3),
+                ("one two".to_string(), 17, 3),
+                ("three".to_string(), 17, 12),
+                ("2".to_string(), 4, 21),
+                ("four".to_string(), 17, 21),
+            ]
+        );
+        // the second row of the first line is below the first one
+        edit.handle_event(&Event::MousePressEvent(MousePressEvent::new(
+            MouseButton::Left,
+            &Point2::<i32>::new(30, 14),
+        )));
+        edit.handle_event(&Event::MouseMoveEvent(MouseMoveEvent::new(
+            &Point2::<i32>::new(36, 22),
+        )));
+        edit.handle_event(&Event::MouseReleaseEvent(MouseReleaseEvent::new(
+            MouseButton::Left,
+            &Point2::<i32>::new(36, 22),
+        )));
+        assert_eq!(edit.selected_text(), "ree\nfou");
+        press(&mut edit, Key::Down, KeyModifiers::NONE);
+        press(&mut edit, Key::PageDown, KeyModifiers::NONE);
+        painted_texts(&mut edit, &size);
+        assert_eq!(edit.cursor_line_column(), (3, 3));
+        assert_eq!(edit.first_visible_line(), 1);
+    }
+
+    //********************************************************************************************
+    #[test]
+    fn find_replace_and_styles() {
+        let mut edit = TextEdit::new_raw();
+        edit.set_text("name = a\nNAME = b\nother = name");
+        assert!(edit.find("name", FindFlags::default()));
+        assert_eq!(edit.selection(), Some((0, 4)));
+        assert!(edit.find("name", FindFlags::default()));
+        assert_eq!(edit.selection(), Some((9, 13)));
+        let backward = FindFlags {
+            backward: true,
+            ..FindFlags::default()
+        };
+        assert!(edit.find("name", backward));
+        assert_eq!(edit.selection(), Some((0, 4)));
+        assert!(edit.find("name", backward));
+        assert_eq!(edit.selection(), Some((26, 30)));
+        let case_sensitive = FindFlags {
+            case_sensitive: true,
+            ..FindFlags::default()
+        };
+        assert!(!edit.find("Name", case_sensitive));
+
+        // replacing the selected match selects the next one
+        assert!(edit.replace("name", "key", case_sensitive));
+        assert_eq!(edit.text(), "name = a\nNAME = b\nother = key");
+        assert_eq!(edit.selection(), Some((0, 4)));
+        assert_eq!(edit.replace_all("name", "id", FindFlags::default()), 2);
+        assert_eq!(edit.text(), "id = a\nid = b\nother = key");
+        edit.undo();
+        assert_eq!(edit.text(), "name = a\nNAME = b\nother = key");
+        edit.redo();
+
+        edit.set_highlighter(Some(Box::new(KeyHighlighter)));
+        let commands = painted_commands(&mut edit, &Vector2::<u32>::new(100, 15));
+        assert_eq!(
+            commands[2..8],
+            [
+                DrawCommand::SetBrush(Brush::Solid(Color::NAVY)),
+                DrawCommand::FillRect(Rect {
+                    pos: Point2::<i32>::new(3, 3),
+                    size: Vector2::<u32>::new(18, 9),
+                }),
+                DrawCommand::SetBrush(Brush::Solid(Color::YELLOW)),
+                DrawCommand::DrawText(
+                    "id ".to_string(),
+                    Point2::<i32>::new(3, 3),
+                    Font::default().bold()
+                ),
+                DrawCommand::SetBrush(Brush::Solid(Color::WHITE)),
+                DrawCommand::DrawText(
+                    "= a".to_string(),
+                    Point2::<i32>::new(21, 3),
+                    Font::default()
+                ),
+            ]
+        );
+    }
+
+    //********************************************************************************************
+    #[test]
+    fn offsets_inside_characters() {
+        let mut edit = TextEdit::new_raw();
+        edit.set_text("ąb\nżó");
+        edit.set_cursor_position(1);
+        assert_eq!(edit.cursor_position(), 0);
+        edit.set_selection(3, 5);
+        assert_eq!(edit.selection(), Some((3, 4)));
+        assert_eq!(edit.selected_text(), "\n");
+        edit.set_cursor_position(7);
+        assert_eq!(edit.cursor_line_column(), (1, 1));
+        assert!(!painted_texts(&mut edit, &Vector2::<u32>::new(100, 30)).is_empty());
+    }
+
+    //********************************************************************************************
+    #[test]
+    fn mouse_wheel_scrolls() {
+        let mut edit = TextEdit::new_raw();
+        let text: Vec<String> = (0..8).map(|line| format!("line {}", line)).collect();
+        edit.set_text(&text.join("\n"));
+        let size = Vector2::<u32>::new(40, 24);
+        painted_texts(&mut edit, &size);
+        let wheel = |x: i32, y: i32| {
+            Event::MouseWheel(MouseWheelEvent::new(
+                &Vector2::<i32>::new(x, y),
+                &Point2::<i32>::new(5, 5),
+            ))
+        };
+
+        assert!(edit.handle_event(&wheel(0, 1)));
+        assert_eq!(edit.first_visible_line(), 3);
+        edit.handle_event(&wheel(0, 2));
+        assert_eq!(edit.first_visible_line(), 7);
+        edit.handle_event(&wheel(1, -1));
+        assert_eq!(edit.first_visible_line(), 4);
+        assert_eq!(edit.horizontal_scroll(), 3);
+        // the cursor stays where it was and the view doesn't jump back to it
+        let texts = painted_texts(&mut edit, &size);
+        assert_eq!(texts[0], ("e 4".to_string(), 3, 3));
+        assert_eq!(edit.cursor_position(), 0);
+        assert_eq!(edit.first_visible_line(), 4);
+    }
+}
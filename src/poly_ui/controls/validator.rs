@@ -0,0 +1,249 @@
+// std
+use std::cell::RefCell;
+use std::fmt::Debug;
+// deps
+use regex_automata::hybrid::dfa::Cache;
+use regex_automata::hybrid::dfa::DFA;
+use regex_automata::Anchored;
+use regex_automata::Input;
+use regex_automata::MatchKind;
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// Result of the validation of an edited text.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Validity {
+    /// Text can't become acceptable by typing more characters; the edit is rejected.
+    Invalid,
+    /// Text is not acceptable yet but it's a prefix of an acceptable one.
+    Intermediate,
+    Acceptable,
+}
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// Checks text typed into a LineEdit.
+pub trait ValidatorTrait: Debug {
+    /// # Arguments
+    /// * `text` - whole text of the LineEdit after an edit
+    /// # Returns
+    /// Whether the text is acceptable, can become acceptable or the edit must be rejected.
+    fn validate(&self, text: &str) -> Validity;
+}
+
+//************************************************************************************************
+/// # Arguments
+/// * `negative` - whether the number is negative
+/// * `below` - whether the number is below the minimum
+/// * `above` - whether the number is above the maximum
+/// # Returns
+/// Validity of a number; typing more digits only makes its magnitude bigger, so a positive
+/// number above the maximum or a negative one below the minimum can't become acceptable.
+fn range_validity(negative: bool, below: bool, above: bool) -> Validity {
+    match (below, above) {
+        (false, false) => Validity::Acceptable,
+        _ if (above && !negative) || (below && negative) => Validity::Invalid,
+        _ => Validity::Intermediate,
+    }
+}
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// Accepts decimal integers in a range.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct IntValidator {
+    pub min: i64,
+    pub max: i64,
+}
+
+//************************************************************************************************
+impl IntValidator {
+    /// # Arguments
+    /// * `min` - smallest acceptable number
+    /// * `max` - biggest acceptable number
+    pub fn new(min: i64, max: i64) -> Self {
+        Self { min, max }
+    }
+}
+
+//************************************************************************************************
+impl ValidatorTrait for IntValidator {
+    fn validate(&self, text: &str) -> Validity {
+        let digits = text.strip_prefix(['-', '+']).unwrap_or(text);
+        let negative = text.starts_with('-');
+        if !digits.chars().all(|ch| ch.is_ascii_digit()) || (negative && self.min >= 0) {
+            return Validity::Invalid;
+        }
+        if digits.is_empty() {
+            return Validity::Intermediate;
+        }
+        match text.parse::<i64>() {
+            Ok(value) => range_validity(negative, value < self.min, value > self.max),
+            // too many digits
+            Err(_) => Validity::Invalid,
+        }
+    }
+}
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// Accepts decimal numbers with a limited number of decimals in a range. The decimal separator
+/// is '.'; exponents are not accepted.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct FloatValidator {
+    pub min: f64,
+    pub max: f64,
+    pub decimals: usize,
+}
+
+//************************************************************************************************
+impl FloatValidator {
+    /// # Arguments
+    /// * `min` - smallest acceptable number
+    /// * `max` - biggest acceptable number
+    /// * `decimals` - maximal number of digits after the decimal separator
+    pub fn new(min: f64, max: f64, decimals: usize) -> Self {
+        Self { min, max, decimals }
+    }
+}
+
+//************************************************************************************************
+impl ValidatorTrait for FloatValidator {
+    fn validate(&self, text: &str) -> Validity {
+        let number = text.strip_prefix(['-', '+']).unwrap_or(text);
+        let negative = text.starts_with('-');
+        let (integer, fraction) = match number.split_once('.') {
+            Some((integer, fraction)) => (integer, Some(fraction)),
+            None => (number, None),
+        };
+        let digits = |part: &str| part.chars().all(|ch| ch.is_ascii_digit());
+        if !digits(integer)
+            || !fraction.map_or(true, digits)
+            || fraction.is_some_and(|fraction| fraction.len() > self.decimals)
+            || (negative && self.min >= 0.0)
+        {
+            return Validity::Invalid;
+        }
+        if integer.is_empty() && fraction.map_or(true, str::is_empty) {
+            return Validity::Intermediate;
+        }
+        let value = match number.parse::<f64>() {
+            Ok(value) if negative => -value,
+            Ok(value) => value,
+            Err(_) => return Validity::Intermediate,
+        };
+        range_validity(negative, value < self.min, value > self.max)
+    }
+}
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// Accepts text fully matching a regular expression. Text that can't be extended to a match is
+/// invalid.
+#[derive(Debug)]
+pub struct RegexValidator {
+    pattern: String,
+    dfa: DFA,
+    cache: RefCell<Cache>,
+}
+
+//************************************************************************************************
+impl RegexValidator {
+    /// # Arguments
+    /// * `pattern` - regular expression the whole text has to match
+    /// # Returns
+    /// Validator or description of the error in the pattern.
+    pub fn new(pattern: &str) -> Result<Self, String> {
+        // the DFA reports matches one byte late, the end anchor makes it dead right after bytes
+        // that can't be followed by the end of an acceptable text
+        let dfa = DFA::builder()
+            .configure(DFA::config().match_kind(MatchKind::All))
+            .build(&format!("(?:{})$", pattern))
+            .map_err(|error| error.to_string())?;
+        let cache = RefCell::new(dfa.create_cache());
+        Ok(Self {
+            pattern: pattern.to_string(),
+            dfa,
+            cache,
+        })
+    }
+
+    pub fn pattern(&self) -> &str {
+        &self.pattern
+    }
+}
+
+//************************************************************************************************
+impl ValidatorTrait for RegexValidator {
+    fn validate(&self, text: &str) -> Validity {
+        let cache = &mut *self.cache.borrow_mut();
+        let input = Input::new(text).anchored(Anchored::Yes);
+        let Ok(mut state) = self.dfa.start_state_forward(cache, &input) else {
+            return Validity::Invalid;
+        };
+        for byte in text.bytes() {
+            state = match self.dfa.next_state(cache, state, byte) {
+                Ok(state) if !state.is_dead() && !state.is_quit() => state,
+                _ => return Validity::Invalid,
+            };
+        }
+        match self.dfa.next_eoi_state(cache, state) {
+            Ok(state) if state.is_match() => Validity::Acceptable,
+            _ => Validity::Intermediate,
+        }
+    }
+}
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+#[cfg(test)]
+mod tests {
+    // super
+    use super::*;
+
+    //********************************************************************************************
+    #[test]
+    fn numbers() {
+        let validator = IntValidator::new(-20, 150);
+        assert_eq!(validator.validate(""), Validity::Intermediate);
+        assert_eq!(validator.validate("-"), Validity::Intermediate);
+        assert_eq!(validator.validate("149"), Validity::Acceptable);
+        assert_eq!(validator.validate("151"), Validity::Invalid);
+        assert_eq!(validator.validate("-3"), Validity::Acceptable);
+        assert_eq!(validator.validate("-30"), Validity::Invalid);
+        assert_eq!(validator.validate("1a"), Validity::Invalid);
+        assert_eq!(
+            IntValidator::new(10, 99).validate("5"),
+            Validity::Intermediate
+        );
+        assert_eq!(IntValidator::new(0, 9).validate("-"), Validity::Invalid);
+
+        let validator = FloatValidator::new(-1.0, 10.0, 2);
+        assert_eq!(validator.validate("."), Validity::Intermediate);
+        assert_eq!(validator.validate("-0.5"), Validity::Acceptable);
+        assert_eq!(validator.validate("9.99"), Validity::Acceptable);
+        assert_eq!(validator.validate("9.999"), Validity::Invalid);
+        assert_eq!(validator.validate("10.5"), Validity::Invalid);
+        assert_eq!(validator.validate("-1.5"), Validity::Invalid);
+        assert_eq!(validator.validate("1.2.3"), Validity::Invalid);
+        assert_eq!(validator.validate("1e3"), Validity::Invalid);
+    }
+
+    //********************************************************************************************
+    #[test]
+    fn regex() {
+        let validator = RegexValidator::new("[a-z]+@[a-z]+\\.(com|org)").unwrap();
+        assert_eq!(validator.validate(""), Validity::Intermediate);
+        assert_eq!(validator.validate("me@home.c"), Validity::Intermediate);
+        assert_eq!(validator.validate("me@home.org"), Validity::Acceptable);
+        assert_eq!(validator.validate("me@home.orgx"), Validity::Invalid);
+        assert_eq!(validator.validate("Me"), Validity::Invalid);
+        assert!(RegexValidator::new("(").is_err());
+    }
+}
@@ -1,5 +1,6 @@
 // deps
 use nalgebra::Point2;
+use nalgebra::Vector2;
 
 #[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
 pub enum Key {
@@ -258,9 +259,63 @@ pub enum MouseButton {
     X2,
 }
 
+/// Modifier keys held while a key is pressed.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+pub struct KeyModifiers {
+    pub shift: bool,
+    pub ctrl: bool,
+    pub alt: bool,
+}
+
+impl KeyModifiers {
+    pub const NONE: KeyModifiers = KeyModifiers {
+        shift: false,
+        ctrl: false,
+        alt: false,
+    };
+    pub const SHIFT: KeyModifiers = KeyModifiers {
+        shift: true,
+        ..KeyModifiers::NONE
+    };
+    pub const CTRL: KeyModifiers = KeyModifiers {
+        ctrl: true,
+        ..KeyModifiers::NONE
+    };
+    pub const ALT: KeyModifiers = KeyModifiers {
+        alt: true,
+        ..KeyModifiers::NONE
+    };
+}
+
 #[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
 pub struct KeyPressEvent {
     key: Key,
+    modifiers: KeyModifiers,
+}
+
+impl KeyPressEvent {
+    pub fn new(key: Key) -> Self {
+        Self::with_modifiers(key, KeyModifiers::NONE)
+    }
+
+    /// # Arguments
+    /// * `key` - pressed key
+    /// * `modifiers` - modifier keys held while the key was pressed
+    pub fn with_modifiers(key: Key, modifiers: KeyModifiers) -> Self {
+        Self { key, modifiers }
+    }
+
+    /// # Returns
+    /// Pressed key.
+    pub fn key(&self) -> Key {
+        self.key
+    }
+
+    /// # Returns
+    /// Modifier keys held while the key was pressed.
+    pub fn modifiers(&self) -> KeyModifiers {
+        self.modifiers
+    }
 }
 
 #[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
@@ -268,26 +323,178 @@ pub struct KeyReleaseEvent {
     key: Key,
 }
 
+impl KeyReleaseEvent {
+    pub fn new(key: Key) -> Self {
+        Self { key }
+    }
+
+    /// # Returns
+    /// Released key.
+    pub fn key(&self) -> Key {
+        self.key
+    }
+}
+
+/// Character typed by the user. Unlike KeyPress it respects the keyboard layout, modifiers and
+/// input methods, so text editing Widgets should insert these characters instead of keys.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct TextInputEvent {
+    ch: char,
+}
+
+impl TextInputEvent {
+    pub fn new(ch: char) -> Self {
+        Self { ch }
+    }
+
+    /// # Returns
+    /// Typed character.
+    pub fn ch(&self) -> char {
+        self.ch
+    }
+}
+
 #[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
 pub struct MousePressEvent {
     button: MouseButton,
+    pos: Point2<i32>,
+}
+
+impl MousePressEvent {
+    pub fn new(button: MouseButton, pos: &Point2<i32>) -> Self {
+        Self { button, pos: *pos }
+    }
+
+    /// # Returns
+    /// Pressed button.
+    pub fn button(&self) -> MouseButton {
+        self.button
+    }
+
+    /// # Returns
+    /// Position of the mouse in coordinates of the Widget receiving the event.
+    pub fn pos(&self) -> Point2<i32> {
+        self.pos
+    }
 }
 
 #[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
 pub struct MouseReleaseEvent {
     button: MouseButton,
+    pos: Point2<i32>,
+}
+
+impl MouseReleaseEvent {
+    pub fn new(button: MouseButton, pos: &Point2<i32>) -> Self {
+        Self { button, pos: *pos }
+    }
+
+    /// # Returns
+    /// Released button.
+    pub fn button(&self) -> MouseButton {
+        self.button
+    }
+
+    /// # Returns
+    /// Position of the mouse in coordinates of the Widget receiving the event.
+    pub fn pos(&self) -> Point2<i32> {
+        self.pos
+    }
 }
 
 #[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
 pub struct MouseMoveEvent {
-    pos: Point2<u32>,
+    pos: Point2<i32>,
+}
+
+impl MouseMoveEvent {
+    pub fn new(pos: &Point2<i32>) -> Self {
+        Self { pos: *pos }
+    }
+
+    /// # Returns
+    /// Position of the mouse in coordinates of the Widget receiving the event.
+    pub fn pos(&self) -> Point2<i32> {
+        self.pos
+    }
+}
+
+/// Rotation of the mouse wheel or scrolling with a touchpad.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct MouseWheelEvent {
+    delta: Vector2<i32>,
+    pos: Point2<i32>,
+}
+
+impl MouseWheelEvent {
+    /// # Arguments
+    /// * `delta` - number of steps scrolled, positive y scrolls down and positive x right
+    /// * `pos` - position of the mouse
+    pub fn new(delta: &Vector2<i32>, pos: &Point2<i32>) -> Self {
+        Self {
+            delta: *delta,
+            pos: *pos,
+        }
+    }
+
+    /// # Returns
+    /// Number of steps scrolled, positive y scrolls down and positive x right.
+    pub fn delta(&self) -> Vector2<i32> {
+        self.delta
+    }
+
+    /// # Returns
+    /// Position of the mouse in coordinates of the Widget receiving the event.
+    pub fn pos(&self) -> Point2<i32> {
+        self.pos
+    }
 }
 
 #[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
 pub enum Event {
     KeyPress(KeyPressEvent),
     KeyReleaseEvent(KeyReleaseEvent),
+    /// Typed character, it's sent to the focused Widget like key events.
+    TextInput(TextInputEvent),
     MousePressEvent(MousePressEvent),
     MouseReleaseEvent(MouseReleaseEvent),
     MouseMoveEvent(MouseMoveEvent),
+    /// Mouse wheel turned, it's sent to the Widget under the mouse.
+    MouseWheel(MouseWheelEvent),
+    /// Mouse left the Widget; sent by the parent Hierarchy when the mouse moves to another child.
+    MouseLeave,
+    /// Widget got the keyboard focus, see WidgetTrait::accepts_focus().
+    FocusIn,
+    /// Widget or its descendant lost the keyboard focus.
+    FocusOut,
+}
+
+impl Event {
+    /// # Returns
+    /// Position of the mouse for mouse events, None for other events.
+    pub fn pos(&self) -> Option<Point2<i32>> {
+        match self {
+            Event::MousePressEvent(event) => Some(event.pos),
+            Event::MouseReleaseEvent(event) => Some(event.pos),
+            Event::MouseMoveEvent(event) => Some(event.pos),
+            Event::MouseWheel(event) => Some(event.pos),
+            _ => None,
+        }
+    }
+
+    /// # Arguments
+    /// * `pos` - new position of the mouse
+    /// # Returns
+    /// Copy of this event with the given mouse position. Events without position are unchanged.
+    pub fn with_pos(&self, pos: &Point2<i32>) -> Self {
+        let mut event = *self;
+        match &mut event {
+            Event::MousePressEvent(event) => event.pos = *pos,
+            Event::MouseReleaseEvent(event) => event.pos = *pos,
+            Event::MouseMoveEvent(event) => event.pos = *pos,
+            Event::MouseWheel(event) => event.pos = *pos,
+            _ => {}
+        }
+        event
+    }
 }
@@ -3,11 +3,14 @@ mod event_handler;
 
 pub use event::Event;
 pub use event::Key;
+pub use event::KeyModifiers;
 pub use event::KeyPressEvent;
 pub use event::KeyReleaseEvent;
 pub use event::MouseButton;
 pub use event::MouseMoveEvent;
 pub use event::MousePressEvent;
 pub use event::MouseReleaseEvent;
+pub use event::MouseWheelEvent;
+pub use event::TextInputEvent;
 
 pub use event_handler::EventHandler;
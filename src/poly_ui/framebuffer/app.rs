@@ -0,0 +1,203 @@
+// std
+use std::time::Duration;
+use std::time::Instant;
+use std::{cell::RefCell, rc::Rc};
+// crate
+use crate::poly_ui::app::AppTrait;
+use crate::poly_ui::app::WindowsManagerTrait;
+use crate::poly_ui::events::Event;
+use crate::poly_ui::events::Key;
+// super
+use super::EvdevParser;
+use super::Framebuffer;
+use super::FramebufferConfig;
+use super::InputDevice;
+use super::WindowsManager;
+
+/// How long in milliseconds an idle application sleeps before it checks input again.
+const IDLE_TIMEOUT: u64 = 16;
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// A framebuffer implementation for the AppTrait for devices without X11 or Wayland. Windows
+/// are painted into a linear framebuffer and input is read from evdev devices; it's passed to
+/// the shown window (see WindowsManager). Pressing Escape ends the application.
+pub struct App {
+    devices: Vec<InputDevice>,
+    windows_manager: WindowsManager,
+}
+
+//************************************************************************************************
+impl App {
+    /// Opens the framebuffer and all input devices.
+    /// # Arguments
+    /// * `config` - configuration of the framebuffer and input devices
+    /// # Returns
+    /// App or error description if any of the files can't be opened.
+    pub fn new(config: &FramebufferConfig) -> Result<Self, String> {
+        let framebuffer = Framebuffer::open(config)
+            .map_err(|error| format!("{}: {}", config.path.display(), error))?;
+        let devices = config
+            .input_devices
+            .iter()
+            .map(|path| {
+                InputDevice::open(path, EvdevParser::new(&config.size, config.abs_range))
+                    .map_err(|error| format!("{}: {}", path.display(), error))
+            })
+            .collect::<Result<Vec<InputDevice>, String>>()?;
+
+        Ok(Self {
+            devices,
+            windows_manager: WindowsManager::new(Rc::new(RefCell::new(framebuffer))),
+        })
+    }
+
+    /// Reads input from all devices and passes it to the shown window.
+    /// # Returns
+    /// False if the application should end.
+    fn process_input(&mut self) -> Result<bool, String> {
+        for device in &mut self.devices {
+            for event in device.read().map_err(|error| error.to_string())? {
+                if let Event::KeyPress(key_event) = &event {
+                    if key_event.key() == Key::Escape {
+                        return Ok(false);
+                    }
+                }
+                if let Some(window) = self.windows_manager.top_window() {
+                    window.borrow_mut().handle_event(&event);
+                }
+            }
+        }
+        Ok(true)
+    }
+}
+
+//************************************************************************************************
+impl AppTrait for App {
+    fn exec(&mut self) -> Result<(), String> {
+        let mut last_frame = Instant::now();
+        loop {
+            let now = Instant::now();
+            let dt = now.duration_since(last_frame).as_secs_f32() * 1000.0;
+            last_frame = now;
+            self.windows_manager.update_windows(dt);
+            let idle = !self.windows_manager.paint_windows();
+
+            if !self.process_input()? {
+                break;
+            }
+            // there is no way to wait for input of regular files so idle application sleeps
+            if idle {
+                std::thread::sleep(Duration::from_millis(IDLE_TIMEOUT));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn get_windows_manager(&mut self) -> &mut dyn WindowsManagerTrait {
+        &mut self.windows_manager
+    }
+}
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+#[cfg(test)]
+mod tests {
+    // std
+    use std::path::PathBuf;
+    // deps
+    use nalgebra::Point2;
+    use nalgebra::Vector2;
+    use uuid::Uuid;
+    // crate
+    use crate::poly_ui::components::Transform;
+    use crate::poly_ui::events::KeyPressEvent;
+    use crate::poly_ui::events::MouseButton;
+    use crate::poly_ui::events::MouseMoveEvent;
+    use crate::poly_ui::events::MousePressEvent;
+    use crate::poly_ui::framebuffer::PixelFormat;
+    use crate::poly_ui::widgets::MockWidget;
+    // super
+    use super::*;
+
+    //********************************************************************************************
+    fn temp_path() -> PathBuf {
+        std::env::temp_dir().join(format!("poly_ui_fb_app_{}", Uuid::new_v4()))
+    }
+
+    //********************************************************************************************
+    /// Encodes a recorded input event with zero timestamp.
+    fn record(kind: u16, code: u16, value: i32) -> Vec<u8> {
+        let mut bytes = vec![0; std::mem::size_of::<libc::input_event>() - 8];
+        bytes.extend_from_slice(&kind.to_ne_bytes());
+        bytes.extend_from_slice(&code.to_ne_bytes());
+        bytes.extend_from_slice(&value.to_ne_bytes());
+        bytes
+    }
+
+    //********************************************************************************************
+    #[test]
+    fn run_recorded_session() {
+        let (screen, input) = (temp_path(), temp_path());
+        let mut stream = vec![];
+        stream.extend(record(2, 0, 5));
+        stream.extend(record(2, 1, 2));
+        stream.extend(record(1, 0x110, 1));
+        stream.extend(record(0, 0, 0));
+        stream.extend(record(1, 1, 1));
+        stream.extend(record(0, 0, 0));
+        std::fs::write(&input, stream).unwrap();
+
+        let mut config = FramebufferConfig::new(
+            screen.clone(),
+            &Vector2::<u32>::new(8, 4),
+            PixelFormat::Rgb565,
+        );
+        config.input_devices.push(input.clone());
+        assert!(App::new(&FramebufferConfig {
+            input_devices: vec![temp_path()],
+            ..config.clone()
+        })
+        .is_err());
+
+        let mut app = App::new(&config).unwrap();
+        let window = app
+            .get_windows_manager()
+            .create_window("test", 0, 0)
+            .unwrap();
+        assert_eq!(window.borrow().size(), Vector2::<u32>::new(8, 4));
+        let child = MockWidget::new();
+        let child_ptr = child.get().clone();
+        window
+            .borrow()
+            .widget()
+            .borrow_mut()
+            .add_child_with_transform(
+                child.make_owned(),
+                &Transform::new(&Point2::<i32>::new(4, 0), &Vector2::<u32>::new(4, 4)),
+            );
+        assert_eq!(app.exec(), Ok(()));
+
+        // the first frame is painted before the recorded input ends the application
+        assert_eq!(std::fs::read(&screen).unwrap(), vec![0; 8 * 4 * 2]);
+        assert_eq!(
+            child_ptr.borrow().events,
+            vec![
+                Event::MouseMoveEvent(MouseMoveEvent::new(&Point2::<i32>::new(1, 2))),
+                Event::MousePressEvent(MousePressEvent::new(
+                    MouseButton::Left,
+                    &Point2::<i32>::new(1, 2)
+                )),
+            ]
+        );
+        assert!(!child_ptr
+            .borrow()
+            .events
+            .contains(&Event::KeyPress(KeyPressEvent::new(Key::Escape))));
+        std::fs::remove_file(&screen).unwrap();
+        std::fs::remove_file(&input).unwrap();
+    }
+}
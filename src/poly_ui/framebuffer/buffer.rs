@@ -0,0 +1,175 @@
+// std
+use std::fs::File;
+use std::fs::OpenOptions;
+use std::io;
+use std::os::unix::fs::FileExt;
+use std::path::PathBuf;
+// deps
+use nalgebra::Vector2;
+// crate
+use crate::poly_ui::app::Color;
+use crate::poly_ui::app::Image;
+// super
+use super::PixelFormat;
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// Configuration of the framebuffer backend.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FramebufferConfig {
+    /// Framebuffer device (e.g. /dev/fb0) or any file with the same memory layout.
+    pub path: PathBuf,
+    /// Visible resolution in pixels.
+    pub size: Vector2<u32>,
+    pub format: PixelFormat,
+    /// Number of bytes between starts of two lines, None if lines are packed.
+    pub stride: Option<usize>,
+    /// evdev input devices (e.g. /dev/input/event0) or files with recorded input events.
+    pub input_devices: Vec<PathBuf>,
+    /// Maximal values of absolute axes (touchscreens) which are mapped to the right and bottom
+    /// edge of the screen, None if the absolute axes report pixels.
+    pub abs_range: Option<Vector2<i32>>,
+}
+
+//************************************************************************************************
+impl FramebufferConfig {
+    /// # Arguments
+    /// * `path` - framebuffer device or file
+    /// * `size` - visible resolution in pixels
+    /// * `format` - layout of pixels
+    /// # Returns
+    /// Configuration with packed lines and no input devices.
+    pub fn new(path: PathBuf, size: &Vector2<u32>, format: PixelFormat) -> Self {
+        Self {
+            path,
+            size: *size,
+            format,
+            stride: None,
+            input_devices: vec![],
+            abs_range: None,
+        }
+    }
+
+    /// # Returns
+    /// Number of bytes between starts of two lines.
+    pub fn line_length(&self) -> usize {
+        self.stride
+            .unwrap_or(self.size.x as usize * self.format.bytes_per_pixel())
+    }
+}
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// Linear framebuffer. Images are converted into its pixel format and written at the offsets of
+/// their lines; only lines that changed since the previous image are written.
+#[derive(Debug)]
+pub struct Framebuffer {
+    file: File,
+    config: FramebufferConfig,
+    shown: Option<Image>,
+}
+
+//************************************************************************************************
+impl Framebuffer {
+    /// Opens the framebuffer for writing. Regular files are created if they don't exist, their
+    /// content is kept.
+    /// # Arguments
+    /// * `config` - configuration of the framebuffer
+    pub fn open(config: &FramebufferConfig) -> io::Result<Self> {
+        let file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&config.path)?;
+        Ok(Self {
+            file,
+            config: config.clone(),
+            shown: None,
+        })
+    }
+
+    /// # Returns
+    /// Visible resolution in pixels.
+    pub fn size(&self) -> Vector2<u32> {
+        self.config.size
+    }
+
+    /// Writes the image into the framebuffer. Pixels are composited over black as the screen is
+    /// opaque; parts of the image outside of the screen are ignored.
+    /// # Arguments
+    /// * `image` - presented image
+    pub fn present(&mut self, image: &Image) -> io::Result<()> {
+        let size = self.config.size;
+        let width = size.x.min(image.size().x);
+        let format = self.config.format;
+        let bytes_per_pixel = format.bytes_per_pixel();
+        let mut line = vec![0; width as usize * bytes_per_pixel];
+
+        for y in 0..size.y.min(image.size().y) {
+            if let Some(shown) = &self.shown {
+                if shown.size() == image.size() && row(shown, y) == row(image, y) {
+                    continue;
+                }
+            }
+            for (x, pixel) in line.chunks_exact_mut(bytes_per_pixel).enumerate() {
+                format.encode(&image.pixel(x as u32, y).over(&Color::BLACK), pixel);
+            }
+            let offset = y as usize * self.config.line_length();
+            self.file.write_all_at(&line, offset as u64)?;
+        }
+
+        self.shown = Some(image.clone());
+        Ok(())
+    }
+}
+
+//************************************************************************************************
+/// # Returns
+/// Bytes of the given row of the image.
+fn row(image: &Image, y: u32) -> &[u8] {
+    let length = image.size().x as usize * 4;
+    &image.pixels()[y as usize * length..(y as usize + 1) * length]
+}
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+#[cfg(test)]
+mod tests {
+    // deps
+    use uuid::Uuid;
+    // super
+    use super::*;
+
+    //********************************************************************************************
+    #[test]
+    fn present_changed_lines() {
+        let path = std::env::temp_dir().join(format!("poly_ui_fb_{}", Uuid::new_v4()));
+        let mut config = FramebufferConfig::new(
+            path.clone(),
+            &Vector2::<u32>::new(2, 2),
+            PixelFormat::Bgrx8888,
+        );
+        config.stride = Some(12);
+        let mut framebuffer = Framebuffer::open(&config).unwrap();
+
+        let mut image = Image::new(&Vector2::<u32>::new(2, 2));
+        image.set_pixel(1, 1, &Color::RED);
+        framebuffer.present(&image).unwrap();
+        let written = std::fs::read(&path).unwrap();
+        assert_eq!(written.len(), 20);
+        assert_eq!(&written[0..8], &[0, 0, 0, 255, 0, 0, 0, 255]);
+        assert_eq!(&written[12..20], &[0, 0, 0, 255, 0, 0, 255, 255]);
+
+        // unchanged first line is not written again
+        std::fs::write(&path, vec![7; 20]).unwrap();
+        image.set_pixel(0, 1, &Color::BLUE);
+        framebuffer.present(&image).unwrap();
+        let written = std::fs::read(&path).unwrap();
+        assert_eq!(&written[0..8], &[7; 8]);
+        assert_eq!(&written[12..16], &[255, 0, 0, 255]);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
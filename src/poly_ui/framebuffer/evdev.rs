@@ -0,0 +1,502 @@
+// std
+use std::fs::File;
+use std::fs::OpenOptions;
+use std::io;
+use std::io::Read;
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::Path;
+// deps
+use nalgebra::Point2;
+use nalgebra::Vector2;
+// crate
+use crate::poly_ui::events::Event;
+use crate::poly_ui::events::Key;
+use crate::poly_ui::events::KeyModifiers;
+use crate::poly_ui::events::KeyPressEvent;
+use crate::poly_ui::events::KeyReleaseEvent;
+use crate::poly_ui::events::MouseButton;
+use crate::poly_ui::events::MouseMoveEvent;
+use crate::poly_ui::events::MousePressEvent;
+use crate::poly_ui::events::MouseReleaseEvent;
+use crate::poly_ui::events::TextInputEvent;
+
+/// Size of struct input_event; a timestamp followed by type, code and value.
+const EVENT_SIZE: usize = std::mem::size_of::<libc::input_event>();
+
+const EV_SYN: u16 = 0;
+const EV_KEY: u16 = 1;
+const EV_REL: u16 = 2;
+const EV_ABS: u16 = 3;
+const SYN_REPORT: u16 = 0;
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// Parser of the Linux evdev input event stream. Pointer motion of mice (relative axes) and
+/// touchscreens (absolute axes) moves a single pointer kept inside of the screen; touches are
+/// reported as the left mouse button. Events of one report are emitted together when the report
+/// ends (SYN_REPORT), the pointer move first so buttons are pressed at the final position. Key
+/// presses without Ctrl or Alt are followed by TextInput of the character on a US layout.
+#[derive(Debug)]
+pub struct EvdevParser {
+    pending: Vec<u8>,
+    size: Vector2<u32>,
+    abs_range: Option<Vector2<i32>>,
+    pos: Point2<i32>,
+    moved: bool,
+    modifiers: KeyModifiers,
+    report: Vec<Event>,
+}
+
+//************************************************************************************************
+impl EvdevParser {
+    /// # Arguments
+    /// * `size` - size of the screen in pixels
+    /// * `abs_range` - maximal values of absolute axes mapped to the right and bottom screen
+    ///   edge, None if they report pixels
+    pub fn new(size: &Vector2<u32>, abs_range: Option<Vector2<i32>>) -> Self {
+        Self {
+            pending: vec![],
+            size: *size,
+            abs_range,
+            pos: Point2::<i32>::new(0, 0),
+            moved: false,
+            modifiers: KeyModifiers::NONE,
+            report: vec![],
+        }
+    }
+
+    /// # Returns
+    /// Current pointer position in pixels.
+    pub fn pos(&self) -> Point2<i32> {
+        self.pos
+    }
+
+    /// Parses bytes read from an input device. Events split between reads are kept until the
+    /// rest arrives.
+    /// # Arguments
+    /// * `bytes` - bytes read from the device
+    /// # Returns
+    /// Events of all finished reports.
+    pub fn parse(&mut self, bytes: &[u8]) -> Vec<Event> {
+        self.pending.extend_from_slice(bytes);
+        let complete = self.pending.len() / EVENT_SIZE * EVENT_SIZE;
+        let records: Vec<u8> = self.pending.drain(..complete).collect();
+
+        let mut events = vec![];
+        for record in records.chunks_exact(EVENT_SIZE) {
+            // the timestamp at the start is not needed
+            let data = &record[EVENT_SIZE - 8..];
+            let kind = u16::from_ne_bytes([data[0], data[1]]);
+            let code = u16::from_ne_bytes([data[2], data[3]]);
+            let value = i32::from_ne_bytes([data[4], data[5], data[6], data[7]]);
+            match (kind, code) {
+                (EV_SYN, SYN_REPORT) => {
+                    if self.moved {
+                        events.push(Event::MouseMoveEvent(MouseMoveEvent::new(&self.pos)));
+                        self.moved = false;
+                    }
+                    events.append(&mut self.report);
+                }
+                (EV_KEY, _) => self.key(code, value),
+                (EV_REL, 0) => self.move_to(self.pos.x + value, self.pos.y),
+                (EV_REL, 1) => self.move_to(self.pos.x, self.pos.y + value),
+                (EV_ABS, 0) => self.move_to(self.scale_abs(value, 0), self.pos.y),
+                (EV_ABS, 1) => self.move_to(self.pos.x, self.scale_abs(value, 1)),
+                _ => {}
+            }
+        }
+        events
+    }
+
+    /// Adds events of the key or button to the current report. Auto repeat (value 2) is
+    /// reported as another press.
+    fn key(&mut self, code: u16, value: i32) {
+        let button = match code {
+            0x110 | 0x14a => Some(MouseButton::Left),
+            0x111 => Some(MouseButton::Right),
+            0x112 => Some(MouseButton::Middle),
+            0x113 => Some(MouseButton::X1),
+            0x114 => Some(MouseButton::X2),
+            _ => None,
+        };
+        let event = match (button, value) {
+            (Some(button), 0) => {
+                Event::MouseReleaseEvent(MouseReleaseEvent::new(button, &self.pos))
+            }
+            (Some(button), 1) => Event::MousePressEvent(MousePressEvent::new(button, &self.pos)),
+            (Some(_), _) => return,
+            (None, 0) => match linux_key(code) {
+                Some(key) => Event::KeyReleaseEvent(KeyReleaseEvent::new(key)),
+                None => return,
+            },
+            (None, _) => match linux_key(code) {
+                Some(key) => Event::KeyPress(KeyPressEvent::with_modifiers(key, self.modifiers)),
+                None => return,
+            },
+        };
+        // positions of buttons are set when the report ends
+        self.report.push(event);
+
+        if let Event::KeyPress(press) = event {
+            if !self.modifiers.ctrl && !self.modifiers.alt {
+                if let Some(ch) = us_char(press.key(), self.modifiers.shift) {
+                    self.report.push(Event::TextInput(TextInputEvent::new(ch)));
+                }
+            }
+        }
+        let pressed = value != 0;
+        match linux_key(code) {
+            Some(Key::LShift) | Some(Key::RShift) => self.modifiers.shift = pressed,
+            Some(Key::LCtrl) | Some(Key::RCtrl) => self.modifiers.ctrl = pressed,
+            Some(Key::LAlt) | Some(Key::RAlt) => self.modifiers.alt = pressed,
+            _ => {}
+        }
+    }
+
+    fn move_to(&mut self, x: i32, y: i32) {
+        // a zero size screen has a single position at the origin
+        let x = x.clamp(0, (self.size.x as i32 - 1).max(0));
+        let y = y.clamp(0, (self.size.y as i32 - 1).max(0));
+        if Point2::<i32>::new(x, y) != self.pos {
+            self.pos = Point2::<i32>::new(x, y);
+            self.moved = true;
+        }
+        for event in &mut self.report {
+            *event = event.with_pos(&self.pos);
+        }
+    }
+
+    /// # Returns
+    /// Value of the absolute axis converted into pixels.
+    fn scale_abs(&self, value: i32, axis: usize) -> i32 {
+        match self.abs_range {
+            Some(range) if range[axis] > 0 => {
+                (value as i64 * (self.size[axis] as i64 - 1) / range[axis] as i64) as i32
+            }
+            _ => value,
+        }
+    }
+}
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// Non-blocking reader of an evdev device or of a file with recorded events.
+#[derive(Debug)]
+pub struct InputDevice {
+    file: File,
+    parser: EvdevParser,
+}
+
+//************************************************************************************************
+impl InputDevice {
+    /// # Arguments
+    /// * `path` - device (e.g. /dev/input/event0) or file
+    /// * `parser` - parser of the read events
+    pub fn open<P: AsRef<Path>>(path: P, parser: EvdevParser) -> io::Result<Self> {
+        let file = OpenOptions::new()
+            .read(true)
+            .custom_flags(libc::O_NONBLOCK)
+            .open(path)?;
+        Ok(Self { file, parser })
+    }
+
+    /// Reads everything available without waiting.
+    /// # Returns
+    /// Events of all finished reports.
+    pub fn read(&mut self) -> io::Result<Vec<Event>> {
+        let mut events = vec![];
+        let mut buffer = [0u8; EVENT_SIZE * 64];
+        loop {
+            match self.file.read(&mut buffer) {
+                Ok(0) => break,
+                Ok(read) => events.extend(self.parser.parse(&buffer[..read])),
+                Err(error) if error.kind() == io::ErrorKind::WouldBlock => break,
+                Err(error) => return Err(error),
+            }
+        }
+        Ok(events)
+    }
+}
+
+//************************************************************************************************
+/// # Returns
+/// Key of the Linux key code (see linux/input-event-codes.h).
+fn linux_key(code: u16) -> Option<Key> {
+    const ROW_Q: [Key; 10] = [
+        Key::Q,
+        Key::W,
+        Key::E,
+        Key::R,
+        Key::T,
+        Key::Y,
+        Key::U,
+        Key::I,
+        Key::O,
+        Key::P,
+    ];
+    const ROW_A: [Key; 9] = [
+        Key::A,
+        Key::S,
+        Key::D,
+        Key::F,
+        Key::G,
+        Key::H,
+        Key::J,
+        Key::K,
+        Key::L,
+    ];
+    const ROW_Z: [Key; 7] = [Key::Z, Key::X, Key::C, Key::V, Key::B, Key::N, Key::M];
+    const DIGITS: [Key; 10] = [
+        Key::Num1,
+        Key::Num2,
+        Key::Num3,
+        Key::Num4,
+        Key::Num5,
+        Key::Num6,
+        Key::Num7,
+        Key::Num8,
+        Key::Num9,
+        Key::Num0,
+    ];
+    const FUNCTIONS: [Key; 10] = [
+        Key::F1,
+        Key::F2,
+        Key::F3,
+        Key::F4,
+        Key::F5,
+        Key::F6,
+        Key::F7,
+        Key::F8,
+        Key::F9,
+        Key::F10,
+    ];
+
+    let code = code as usize;
+    match code {
+        1 => Some(Key::Escape),
+        2..=11 => Some(DIGITS[code - 2]),
+        12 => Some(Key::Minus),
+        13 => Some(Key::Equals),
+        14 => Some(Key::Backspace),
+        15 => Some(Key::Tab),
+        16..=25 => Some(ROW_Q[code - 16]),
+        26 => Some(Key::LeftBracket),
+        27 => Some(Key::RightBracket),
+        28 => Some(Key::Return),
+        29 => Some(Key::LCtrl),
+        30..=38 => Some(ROW_A[code - 30]),
+        39 => Some(Key::Semicolon),
+        40 => Some(Key::Quote),
+        41 => Some(Key::Backquote),
+        42 => Some(Key::LShift),
+        43 => Some(Key::Backslash),
+        44..=50 => Some(ROW_Z[code - 44]),
+        51 => Some(Key::Comma),
+        52 => Some(Key::Period),
+        53 => Some(Key::Slash),
+        54 => Some(Key::RShift),
+        55 => Some(Key::KpMultiply),
+        56 => Some(Key::LAlt),
+        57 => Some(Key::Space),
+        58 => Some(Key::CapsLock),
+        59..=68 => Some(FUNCTIONS[code - 59]),
+        87 => Some(Key::F11),
+        88 => Some(Key::F12),
+        96 => Some(Key::KpEnter),
+        97 => Some(Key::RCtrl),
+        100 => Some(Key::RAlt),
+        102 => Some(Key::Home),
+        103 => Some(Key::Up),
+        104 => Some(Key::PageUp),
+        105 => Some(Key::Left),
+        106 => Some(Key::Right),
+        107 => Some(Key::End),
+        108 => Some(Key::Down),
+        109 => Some(Key::PageDown),
+        110 => Some(Key::Insert),
+        111 => Some(Key::Delete),
+        _ => None,
+    }
+}
+
+//************************************************************************************************
+/// # Arguments
+/// * `key` - pressed key
+/// * `shift` - whether Shift is held
+/// # Returns
+/// Character the key types on a US keyboard layout.
+fn us_char(key: Key, shift: bool) -> Option<char> {
+    const KEYS: [Key; 47] = [
+        Key::A,
+        Key::B,
+        Key::C,
+        Key::D,
+        Key::E,
+        Key::F,
+        Key::G,
+        Key::H,
+        Key::I,
+        Key::J,
+        Key::K,
+        Key::L,
+        Key::M,
+        Key::N,
+        Key::O,
+        Key::P,
+        Key::Q,
+        Key::R,
+        Key::S,
+        Key::T,
+        Key::U,
+        Key::V,
+        Key::W,
+        Key::X,
+        Key::Y,
+        Key::Z,
+        Key::Num0,
+        Key::Num1,
+        Key::Num2,
+        Key::Num3,
+        Key::Num4,
+        Key::Num5,
+        Key::Num6,
+        Key::Num7,
+        Key::Num8,
+        Key::Num9,
+        Key::Minus,
+        Key::Equals,
+        Key::LeftBracket,
+        Key::RightBracket,
+        Key::Semicolon,
+        Key::Quote,
+        Key::Backquote,
+        Key::Backslash,
+        Key::Comma,
+        Key::Period,
+        Key::Slash,
+    ];
+    const PLAIN: &str = "abcdefghijklmnopqrstuvwxyz0123456789-=[];'`\\,./";
+    const SHIFTED: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ)!@#$%^&*(_+{}:\"~|<>?";
+
+    if key == Key::Space {
+        return Some(' ');
+    }
+    let index = KEYS.iter().position(|known| *known == key)?;
+    let chars = if shift { SHIFTED } else { PLAIN };
+    chars.chars().nth(index)
+}
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+#[cfg(test)]
+mod tests {
+    // std
+    use std::io::Write;
+    // deps
+    use uuid::Uuid;
+    // super
+    use super::*;
+
+    //********************************************************************************************
+    /// Encodes a recorded input event with zero timestamp.
+    fn record(kind: u16, code: u16, value: i32) -> Vec<u8> {
+        let mut bytes = vec![0; EVENT_SIZE - 8];
+        bytes.extend_from_slice(&kind.to_ne_bytes());
+        bytes.extend_from_slice(&code.to_ne_bytes());
+        bytes.extend_from_slice(&value.to_ne_bytes());
+        bytes
+    }
+
+    //********************************************************************************************
+    #[test]
+    fn parse_reports() {
+        let mut parser = EvdevParser::new(&Vector2::<u32>::new(100, 50), None);
+        let mut stream = vec![];
+        stream.extend(record(EV_KEY, 30, 1));
+        stream.extend(record(EV_SYN, SYN_REPORT, 0));
+        stream.extend(record(EV_KEY, 30, 0));
+        stream.extend(record(EV_REL, 0, 500));
+        stream.extend(record(EV_REL, 1, 20));
+        // button comes before the motion of the same report
+        stream.extend(record(EV_KEY, 0x110, 1));
+        stream.extend(record(EV_REL, 1, 5));
+
+        // incomplete record is kept for the next read
+        assert_eq!(
+            parser.parse(&stream[..EVENT_SIZE * 2 + 3]),
+            vec![
+                Event::KeyPress(KeyPressEvent::new(Key::A)),
+                Event::TextInput(TextInputEvent::new('a')),
+            ]
+        );
+        assert!(parser.parse(&stream[EVENT_SIZE * 2 + 3..]).is_empty());
+        let pos = Point2::<i32>::new(99, 25);
+        assert_eq!(
+            parser.parse(&record(EV_SYN, SYN_REPORT, 0)),
+            vec![
+                Event::MouseMoveEvent(MouseMoveEvent::new(&pos)),
+                Event::KeyReleaseEvent(KeyReleaseEvent::new(Key::A)),
+                Event::MousePressEvent(MousePressEvent::new(MouseButton::Left, &pos)),
+            ]
+        );
+
+        // Shift+2 types '@'
+        let mut stream = record(EV_KEY, 42, 1);
+        stream.extend(record(EV_KEY, 3, 1));
+        stream.extend(record(EV_SYN, SYN_REPORT, 0));
+        assert_eq!(
+            parser.parse(&stream)[1..],
+            [
+                Event::KeyPress(KeyPressEvent::with_modifiers(
+                    Key::Num2,
+                    KeyModifiers::SHIFT
+                )),
+                Event::TextInput(TextInputEvent::new('@')),
+            ]
+        );
+    }
+
+    //********************************************************************************************
+    #[test]
+    fn zero_size_screen() {
+        let mut parser = EvdevParser::new(&Vector2::<u32>::new(0, 0), None);
+        let mut stream = record(EV_REL, 0, 10);
+        stream.extend(record(EV_KEY, 0x110, 1));
+        stream.extend(record(EV_SYN, SYN_REPORT, 0));
+        assert_eq!(
+            parser.parse(&stream),
+            vec![Event::MousePressEvent(MousePressEvent::new(
+                MouseButton::Left,
+                &Point2::<i32>::new(0, 0)
+            ))]
+        );
+    }
+
+    //********************************************************************************************
+    #[test]
+    fn read_recorded_touches() {
+        let path = std::env::temp_dir().join(format!("poly_ui_evdev_{}", Uuid::new_v4()));
+        let mut file = File::create(&path).unwrap();
+        file.write_all(&record(EV_ABS, 0, 1000)).unwrap();
+        file.write_all(&record(EV_ABS, 1, 4000)).unwrap();
+        file.write_all(&record(EV_KEY, 0x14a, 1)).unwrap();
+        file.write_all(&record(EV_SYN, SYN_REPORT, 0)).unwrap();
+
+        let range = Vector2::<i32>::new(4000, 4000);
+        let parser = EvdevParser::new(&Vector2::<u32>::new(101, 51), Some(range));
+        let mut device = InputDevice::open(&path, parser).unwrap();
+        let pos = Point2::<i32>::new(25, 50);
+        assert_eq!(
+            device.read().unwrap(),
+            vec![
+                Event::MouseMoveEvent(MouseMoveEvent::new(&pos)),
+                Event::MousePressEvent(MousePressEvent::new(MouseButton::Left, &pos)),
+            ]
+        );
+        assert!(device.read().unwrap().is_empty());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
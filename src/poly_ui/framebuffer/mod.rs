@@ -0,0 +1,15 @@
+mod app;
+mod buffer;
+mod evdev;
+mod pixel_format;
+mod window_provider;
+mod windows_manager;
+
+pub use app::App;
+pub use buffer::Framebuffer;
+pub use buffer::FramebufferConfig;
+pub use evdev::EvdevParser;
+pub use evdev::InputDevice;
+pub use pixel_format::PixelFormat;
+pub use window_provider::WindowProvider;
+pub use windows_manager::WindowsManager;
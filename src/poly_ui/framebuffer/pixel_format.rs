@@ -0,0 +1,84 @@
+// deps
+use serde::Deserialize;
+use serde::Serialize;
+// crate
+use crate::poly_ui::app::Color;
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// Layout of one pixel in the framebuffer memory. Names list the channels in the order of bytes
+/// in memory (X is an unused byte), multi-byte packed formats are little endian.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PixelFormat {
+    /// 16 bit, 5 bits red, 6 bits green and 5 bits blue from the most significant bit.
+    Rgb565,
+    Rgb888,
+    Bgr888,
+    Rgbx8888,
+    /// The most common format of 32 bit Linux framebuffers (XRGB8888 in DRM terms).
+    Bgrx8888,
+    Rgba8888,
+    Bgra8888,
+}
+
+//************************************************************************************************
+impl PixelFormat {
+    /// # Returns
+    /// Size of one pixel in bytes.
+    pub fn bytes_per_pixel(&self) -> usize {
+        match self {
+            PixelFormat::Rgb565 => 2,
+            PixelFormat::Rgb888 | PixelFormat::Bgr888 => 3,
+            PixelFormat::Rgbx8888
+            | PixelFormat::Bgrx8888
+            | PixelFormat::Rgba8888
+            | PixelFormat::Bgra8888 => 4,
+        }
+    }
+
+    /// Writes the color in this format.
+    /// # Arguments
+    /// * `color` - written color, it should be opaque in formats without alpha
+    /// * `pixel` - memory of the pixel, bytes_per_pixel() long
+    pub fn encode(&self, color: &Color, pixel: &mut [u8]) {
+        match self {
+            PixelFormat::Rgb565 => {
+                let value = ((color.r as u16 >> 3) << 11)
+                    | ((color.g as u16 >> 2) << 5)
+                    | (color.b as u16 >> 3);
+                pixel.copy_from_slice(&value.to_le_bytes());
+            }
+            PixelFormat::Rgb888 => pixel.copy_from_slice(&[color.r, color.g, color.b]),
+            PixelFormat::Bgr888 => pixel.copy_from_slice(&[color.b, color.g, color.r]),
+            PixelFormat::Rgbx8888 => pixel.copy_from_slice(&[color.r, color.g, color.b, 255]),
+            PixelFormat::Bgrx8888 => pixel.copy_from_slice(&[color.b, color.g, color.r, 255]),
+            PixelFormat::Rgba8888 => pixel.copy_from_slice(&[color.r, color.g, color.b, color.a]),
+            PixelFormat::Bgra8888 => pixel.copy_from_slice(&[color.b, color.g, color.r, color.a]),
+        }
+    }
+}
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+#[cfg(test)]
+mod tests {
+    // super
+    use super::*;
+
+    //********************************************************************************************
+    #[test]
+    fn encode() {
+        let color = Color::rgb(255, 128, 8);
+        let encoded = |format: PixelFormat| {
+            let mut pixel = vec![0; format.bytes_per_pixel()];
+            format.encode(&color, &mut pixel);
+            pixel
+        };
+        assert_eq!(encoded(PixelFormat::Rgb565), vec![0x01, 0xfc]);
+        assert_eq!(encoded(PixelFormat::Bgr888), vec![8, 128, 255]);
+        assert_eq!(encoded(PixelFormat::Bgrx8888), vec![8, 128, 255, 255]);
+        assert_eq!(encoded(PixelFormat::Rgba8888), vec![255, 128, 8, 255]);
+    }
+}
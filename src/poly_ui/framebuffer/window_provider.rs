@@ -0,0 +1,69 @@
+// std
+use std::{cell::RefCell, rc::Rc};
+// deps
+use nalgebra::Point2;
+use nalgebra::Vector2;
+// crate
+use crate::poly_ui::app::Color;
+use crate::poly_ui::app::Rect;
+use crate::poly_ui::software::Frame;
+use crate::poly_ui::widgets::WidgetTrait;
+use crate::poly_ui::widgets::WindowProviderTrait;
+// super
+use super::Framebuffer;
+
+/// Color of the window below all widgets.
+const BACKGROUND: Color = Color::BLACK;
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// A framebuffer implementation for the WindowProvider trait. The window covers the whole
+/// screen. Widgets are painted into a software Frame whose image is then written into the
+/// framebuffer.
+pub struct WindowProvider {
+    framebuffer: Rc<RefCell<Framebuffer>>,
+    frame: Frame,
+}
+
+//************************************************************************************************
+impl WindowProvider {
+    pub fn new(framebuffer: Rc<RefCell<Framebuffer>>) -> Self {
+        Self {
+            framebuffer,
+            frame: Frame::default(),
+        }
+    }
+}
+
+//************************************************************************************************
+impl WindowProviderTrait for WindowProvider {
+    fn paint_widget(&mut self, widget: &mut dyn WidgetTrait, regions: &[Rect], scale: f32) {
+        let size = self.framebuffer.borrow().size();
+        self.frame.paint(&size, widget, regions, scale, &BACKGROUND);
+        self.framebuffer
+            .borrow_mut()
+            .present(&self.frame.image().unwrap())
+            .unwrap();
+    }
+
+    fn pos(&self) -> Point2<i32> {
+        Point2::<i32>::new(0, 0)
+    }
+
+    fn set_pos(&mut self, _new: Point2<i32>) {}
+
+    fn size(&self) -> Vector2<u32> {
+        self.framebuffer.borrow().size()
+    }
+
+    /// The resolution of the framebuffer is fixed.
+    fn set_size(&mut self, _new: Vector2<u32>) {}
+}
+
+//************************************************************************************************
+impl std::fmt::Debug for WindowProvider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WindowProvider").finish()
+    }
+}
@@ -0,0 +1,64 @@
+// std
+use std::{cell::RefCell, rc::Rc, vec::Vec};
+// crate
+use crate::poly_ui::app::WindowsManagerTrait;
+use crate::poly_ui::widgets::Window;
+use crate::poly_ui::widgets::WindowTrait;
+// super
+use super::Framebuffer;
+use super::WindowProvider;
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// Framebuffer implementation for the WindowsManager trait. The screen shows only the last
+/// created window; all windows are updated but only that one is painted and gets input.
+pub struct WindowsManager {
+    framebuffer: Rc<RefCell<Framebuffer>>,
+    windows: Vec<Rc<RefCell<dyn WindowTrait>>>,
+}
+
+//************************************************************************************************
+impl WindowsManager {
+    pub fn new(framebuffer: Rc<RefCell<Framebuffer>>) -> Self {
+        Self {
+            framebuffer,
+            windows: Vec::new(),
+        }
+    }
+
+    /// # Returns
+    /// Window shown on the screen, None if no window was created yet.
+    pub fn top_window(&self) -> Option<&Rc<RefCell<dyn WindowTrait>>> {
+        self.windows.last()
+    }
+}
+
+//************************************************************************************************
+impl WindowsManagerTrait for WindowsManager {
+    /// Creates a window covering the whole screen, the title and the requested size are ignored.
+    fn create_window(
+        &mut self,
+        _title: &str,
+        _width: u32,
+        _height: u32,
+    ) -> Result<Rc<RefCell<dyn WindowTrait>>, String> {
+        let window_provider = Box::new(WindowProvider::new(self.framebuffer.clone()));
+        let window = Rc::new(RefCell::new(Window::new(window_provider)));
+        self.windows.push(window.clone());
+        Ok(window)
+    }
+
+    fn update_windows(&mut self, dt: f32) {
+        for window in &mut self.windows {
+            window.borrow_mut().update(dt);
+        }
+    }
+
+    fn paint_windows(&mut self) -> bool {
+        match self.windows.last() {
+            Some(window) => window.borrow_mut().paint(),
+            None => false,
+        }
+    }
+}
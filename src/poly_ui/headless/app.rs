@@ -0,0 +1,168 @@
+// crate
+use crate::poly_ui::app::AppTrait;
+use crate::poly_ui::app::WindowsManagerTrait;
+// super
+use super::WindowsManager;
+
+/// Delta time of the frames run by App::exec() in milliseconds.
+pub const FRAME_TIME: f32 = 16.0;
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// A headless implementation for the AppTrait. It needs no display; windows are painted with the
+/// software Painter into in-memory images (see Screen). Time is virtual, tests step frames
+/// manually with step() and the dt of their choice, so animations are deterministic.
+#[derive(Default)]
+pub struct App {
+    windows_manager: WindowsManager,
+    time: f64,
+    frame_count: u64,
+}
+
+//************************************************************************************************
+impl App {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// # Returns
+    /// WindowsManager of this App with access to the Screens of windows and event injection.
+    pub fn windows_manager(&mut self) -> &mut WindowsManager {
+        &mut self.windows_manager
+    }
+
+    /// # Returns
+    /// Virtual time in milliseconds; sum of dt of all steps.
+    pub fn time(&self) -> f64 {
+        self.time
+    }
+
+    /// # Returns
+    /// Number of steps run so far.
+    pub fn frame_count(&self) -> u64 {
+        self.frame_count
+    }
+
+    /// Runs one frame; advances the virtual time, updates all windows and paints their damage.
+    /// # Arguments
+    /// * `dt` - delta time from the previous frame in milliseconds
+    /// # Returns
+    /// True if any window was painted.
+    pub fn step(&mut self, dt: f32) -> bool {
+        self.time += dt as f64;
+        self.frame_count += 1;
+        self.windows_manager.update_windows(dt);
+        self.windows_manager.paint_windows()
+    }
+}
+
+//************************************************************************************************
+impl AppTrait for App {
+    /// Runs frames of FRAME_TIME until the application is idle (a frame paints nothing). There
+    /// is no input so nothing would change afterwards.
+    fn exec(&mut self) -> Result<(), String> {
+        while self.step(FRAME_TIME) {}
+        Ok(())
+    }
+
+    fn get_windows_manager(&mut self) -> &mut dyn WindowsManagerTrait {
+        &mut self.windows_manager
+    }
+}
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+#[cfg(test)]
+mod tests {
+    // deps
+    use nalgebra::Point2;
+    use nalgebra::Vector2;
+    // crate
+    use crate::poly_ui::app::Color;
+    use crate::poly_ui::components::Transform;
+    use crate::poly_ui::events::Event;
+    use crate::poly_ui::events::MouseButton;
+    use crate::poly_ui::events::MousePressEvent;
+    use crate::poly_ui::layouts::CanvasLayout;
+    use crate::poly_ui::widgets::MockWidget;
+    // super
+    use super::*;
+
+    //********************************************************************************************
+    #[test]
+    fn step_frames() {
+        let mut app = App::new();
+        let window = app
+            .get_windows_manager()
+            .create_window("test", 20, 10)
+            .unwrap();
+        let id = *window.borrow().id();
+        let child = MockWidget::new();
+        let child_ptr = child.get().clone();
+        window
+            .borrow()
+            .widget()
+            .borrow_mut()
+            .add_child_with_transform(
+                child.make_owned(),
+                &Transform::new(&Point2::<i32>::new(10, 0), &Vector2::<u32>::new(10, 10)),
+            );
+
+        assert!(app.step(5.0));
+        assert!(!app.step(10.0));
+        assert_eq!(app.time(), 15.0);
+        assert_eq!(app.frame_count(), 2);
+        assert_eq!(child_ptr.borrow().update_call_count, 2);
+
+        let screen = app.windows_manager().screen(&id).unwrap();
+        assert_eq!(screen.borrow().title(), "test");
+        assert_eq!(screen.borrow().frame_count(), 1);
+        assert_eq!(screen.borrow().image().unwrap().pixel(0, 0), Color::BLACK);
+
+        let press = Event::MousePressEvent(MousePressEvent::new(
+            MouseButton::Left,
+            &Point2::<i32>::new(12, 3),
+        ));
+        assert!(app.windows_manager().send_event(&id, &press));
+        assert_eq!(
+            child_ptr.borrow().events,
+            vec![press.with_pos(&Point2::<i32>::new(2, 3))]
+        );
+
+        // resizing and HiDPI screens repaint the window
+        screen.borrow_mut().set_density(2.0);
+        assert!(app.step(1.0));
+        assert_eq!(window.borrow().scale_factor(), 2.0);
+        assert_eq!(
+            screen.borrow().image().unwrap().size(),
+            Vector2::<u32>::new(40, 20)
+        );
+        screen.borrow_mut().set_size(&Vector2::<u32>::new(30, 10));
+        assert!(app.step(1.0));
+        assert_eq!(window.borrow().size(), Vector2::<u32>::new(30, 10));
+    }
+
+    //********************************************************************************************
+    #[test]
+    fn exec_until_idle() {
+        let mut app = App::new();
+        let window = app
+            .get_windows_manager()
+            .create_window("test", 4, 4)
+            .unwrap();
+        window
+            .borrow()
+            .widget()
+            .borrow_mut()
+            .add_child_with_transform(
+                CanvasLayout::new().make_owned(),
+                &Transform::new(&Point2::<i32>::new(0, 0), &Vector2::<u32>::new(4, 4)),
+            );
+        assert_eq!(app.exec(), Ok(()));
+        assert!(app.frame_count() >= 2);
+        assert_eq!(app.time(), app.frame_count() as f64 * FRAME_TIME as f64);
+        assert_eq!(app.windows_manager().windows().len(), 1);
+    }
+}
@@ -0,0 +1,10 @@
+mod app;
+mod screen;
+mod window_provider;
+mod windows_manager;
+
+pub use app::App;
+pub use app::FRAME_TIME;
+pub use screen::Screen;
+pub use window_provider::WindowProvider;
+pub use windows_manager::WindowsManager;
@@ -0,0 +1,121 @@
+// std
+use std::cell::Ref;
+// deps
+use nalgebra::Point2;
+use nalgebra::Vector2;
+// crate
+use crate::poly_ui::app::Color;
+use crate::poly_ui::app::Image;
+use crate::poly_ui::app::Rect;
+use crate::poly_ui::software::Frame;
+use crate::poly_ui::widgets::WidgetTrait;
+
+/// Color of the window below all widgets.
+const BACKGROUND: Color = Color::BLACK;
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// In-memory state of a headless window; what a real windowing system would keep. Tests can
+/// change it (e.g. resize the window like a user would) and inspect the painted image.
+#[derive(Debug)]
+pub struct Screen {
+    title: String,
+    pos: Point2<i32>,
+    size: Vector2<u32>,
+    density: f32,
+    frame: Frame,
+    frame_count: u32,
+}
+
+//************************************************************************************************
+impl Screen {
+    /// # Arguments
+    /// * `title` - title of the window
+    /// * `size` - size of the window in units of the windowing system
+    pub fn new(title: &str, size: &Vector2<u32>) -> Self {
+        Self {
+            title: title.to_string(),
+            pos: Point2::<i32>::new(0, 0),
+            size: *size,
+            density: 1.0,
+            frame: Frame::default(),
+            frame_count: 0,
+        }
+    }
+
+    /// # Returns
+    /// Title of the window.
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    /// # Returns
+    /// Position of the window on the virtual desktop.
+    pub fn pos(&self) -> Point2<i32> {
+        self.pos
+    }
+
+    /// Moves the window on the virtual desktop.
+    pub fn set_pos(&mut self, pos: &Point2<i32>) {
+        self.pos = *pos;
+    }
+
+    /// # Returns
+    /// Size of the window in units of the windowing system.
+    pub fn size(&self) -> Vector2<u32> {
+        self.size
+    }
+
+    /// Resizes the window; the Window notices it when it's painted next time.
+    /// # Arguments
+    /// * `size` - new size in units of the windowing system
+    pub fn set_size(&mut self, size: &Vector2<u32>) {
+        self.size = *size;
+    }
+
+    /// # Returns
+    /// Number of physical pixels per unit of the windowing system, 1 by default.
+    pub fn density(&self) -> f32 {
+        self.density
+    }
+
+    /// Simulates a HiDPI screen (or moving the window to a screen with other density).
+    /// # Arguments
+    /// * `density` - number of physical pixels per unit of the windowing system
+    pub fn set_density(&mut self, density: f32) {
+        self.density = density;
+    }
+
+    /// # Returns
+    /// Size of the painted image in physical pixels.
+    pub fn drawable_size(&self) -> Vector2<u32> {
+        Vector2::<u32>::new(
+            (self.size.x as f32 * self.density).round() as u32,
+            (self.size.y as f32 * self.density).round() as u32,
+        )
+    }
+
+    /// # Returns
+    /// Image painted in the last frame, None if the window wasn't painted yet.
+    pub fn image(&self) -> Option<Ref<'_, Image>> {
+        self.frame.image()
+    }
+
+    /// # Returns
+    /// Number of frames painted into this screen.
+    pub fn frame_count(&self) -> u32 {
+        self.frame_count
+    }
+
+    /// Paints damaged regions of the widget into the image; used by the WindowProvider.
+    /// # Arguments
+    /// * `widget` - Layout Widget of the window
+    /// * `regions` - damaged rects in logical coordinates
+    /// * `scale` - number of physical pixels per logical pixel
+    pub fn paint(&mut self, widget: &mut dyn WidgetTrait, regions: &[Rect], scale: f32) {
+        let size = self.drawable_size();
+        self.frame.paint(&size, widget, regions, scale, &BACKGROUND);
+        self.frame_count += 1;
+    }
+}
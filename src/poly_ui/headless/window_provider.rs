@@ -0,0 +1,55 @@
+// std
+use std::{cell::RefCell, rc::Rc};
+// deps
+use nalgebra::Point2;
+use nalgebra::Vector2;
+// crate
+use crate::poly_ui::app::Rect;
+use crate::poly_ui::widgets::WidgetTrait;
+use crate::poly_ui::widgets::WindowProviderTrait;
+// super
+use super::Screen;
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// A headless implementation for the WindowProvider trait. All state of the window is kept in a
+/// Screen shared with the WindowsManager.
+#[derive(Debug)]
+pub struct WindowProvider {
+    screen: Rc<RefCell<Screen>>,
+}
+
+//************************************************************************************************
+impl WindowProvider {
+    pub fn new(screen: Rc<RefCell<Screen>>) -> Self {
+        Self { screen }
+    }
+}
+
+//************************************************************************************************
+impl WindowProviderTrait for WindowProvider {
+    fn paint_widget(&mut self, widget: &mut dyn WidgetTrait, regions: &[Rect], scale: f32) {
+        self.screen.borrow_mut().paint(widget, regions, scale);
+    }
+
+    fn pos(&self) -> Point2<i32> {
+        self.screen.borrow().pos()
+    }
+
+    fn set_pos(&mut self, new: Point2<i32>) {
+        self.screen.borrow_mut().set_pos(&new);
+    }
+
+    fn size(&self) -> Vector2<u32> {
+        self.screen.borrow().size()
+    }
+
+    fn drawable_size(&self) -> Vector2<u32> {
+        self.screen.borrow().drawable_size()
+    }
+
+    fn set_size(&mut self, new: Vector2<u32>) {
+        self.screen.borrow_mut().set_size(&new);
+    }
+}
@@ -0,0 +1,112 @@
+// std
+use std::{cell::RefCell, rc::Rc, vec::Vec};
+// deps
+use nalgebra::Vector2;
+use uuid::Uuid;
+// crate
+use crate::poly_ui::app::WindowsManagerTrait;
+use crate::poly_ui::events::Event;
+use crate::poly_ui::widgets::Window;
+use crate::poly_ui::widgets::WindowTrait;
+// super
+use super::Screen;
+use super::WindowProvider;
+
+/// Window together with its Screen.
+type ScreenWindow = (Rc<RefCell<dyn WindowTrait>>, Rc<RefCell<Screen>>);
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// Headless implementation for the WindowsManager trait. Windows are kept in memory together
+/// with their Screens so tests can inspect them and send them events.
+#[derive(Default)]
+pub struct WindowsManager {
+    windows: Vec<ScreenWindow>,
+}
+
+//************************************************************************************************
+impl WindowsManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// # Returns
+    /// All created windows in the order of creation.
+    pub fn windows(&self) -> Vec<Rc<RefCell<dyn WindowTrait>>> {
+        self.windows
+            .iter()
+            .map(|(window, _)| window.clone())
+            .collect()
+    }
+
+    /// # Arguments
+    /// * `id` - id of the window
+    /// # Returns
+    /// Window with the given id, None if there is no such window.
+    pub fn window(&self, id: &Uuid) -> Option<Rc<RefCell<dyn WindowTrait>>> {
+        self.find(id).map(|(window, _)| window.clone())
+    }
+
+    /// # Arguments
+    /// * `id` - id of the window
+    /// # Returns
+    /// Screen of the window with the given id, None if there is no such window.
+    pub fn screen(&self, id: &Uuid) -> Option<Rc<RefCell<Screen>>> {
+        self.find(id).map(|(_, screen)| screen.clone())
+    }
+
+    /// Passes an input event to the window with the given id, as if it came from the windowing
+    /// system.
+    /// # Arguments
+    /// * `id` - id of the window
+    /// * `event` - event with mouse position in units of the windowing system
+    /// # Returns
+    /// True if any Widget handled the event, false if none did or there is no such window.
+    pub fn send_event(&mut self, id: &Uuid, event: &Event) -> bool {
+        match self.find(id) {
+            Some((window, _)) => window.borrow_mut().handle_event(event),
+            None => false,
+        }
+    }
+
+    fn find(&self, id: &Uuid) -> Option<&ScreenWindow> {
+        self.windows
+            .iter()
+            .find(|(window, _)| window.borrow().id() == id)
+    }
+}
+
+//************************************************************************************************
+impl WindowsManagerTrait for WindowsManager {
+    fn create_window(
+        &mut self,
+        title: &str,
+        width: u32,
+        height: u32,
+    ) -> Result<Rc<RefCell<dyn WindowTrait>>, String> {
+        let screen = Rc::new(RefCell::new(Screen::new(
+            title,
+            &Vector2::<u32>::new(width, height),
+        )));
+        let window_provider = Box::new(WindowProvider::new(screen.clone()));
+        let window: Rc<RefCell<dyn WindowTrait>> =
+            Rc::new(RefCell::new(Window::new(window_provider)));
+        self.windows.push((window.clone(), screen));
+        Ok(window)
+    }
+
+    fn update_windows(&mut self, dt: f32) {
+        for (window, _) in &self.windows {
+            window.borrow_mut().update(dt);
+        }
+    }
+
+    fn paint_windows(&mut self) -> bool {
+        let mut painted = false;
+        for (window, _) in &self.windows {
+            painted |= window.borrow_mut().paint();
+        }
+        painted
+    }
+}
@@ -19,7 +19,8 @@ use super::Layout;
 //************************************************************************************************
 //************************************************************************************************
 /// Layout with rows and columns. They can have min/max sizes and stretch factors. Each cell can
-/// get its own widget or can be empty
+/// get its own widget or can be empty. Columns and rows are at least as large as preferred sizes
+/// of their widgets unless their max size is lower.
 #[derive(Debug)]
 pub struct GridLayout {
     id: Uuid,
@@ -29,6 +30,7 @@ pub struct GridLayout {
     is_column_layout_dirty: bool,
     row_layout: Layout,
     is_row_layout_dirty: bool,
+    preferred_sizes: (Vec<u32>, Vec<u32>),
 
     children_columns_rows: Vec<Vec<Option<Uuid>>>,
 }
@@ -43,6 +45,7 @@ impl GridLayout {
             is_column_layout_dirty: true,
             row_layout: Layout::new(0, vec![]),
             is_row_layout_dirty: true,
+            preferred_sizes: (vec![], vec![]),
             children_columns_rows: vec![],
         }
     }
@@ -195,11 +198,13 @@ impl GridLayout {
     fn refresh_children_transforms(&mut self, size: &Vector2<u32>) {
         if self.column_layout.size != size.x || self.is_column_layout_dirty {
             self.column_layout.size = size.x;
-            self.column_layout.refresh();
+            refresh_with_preferred_sizes(&mut self.column_layout, &self.preferred_sizes.0);
+            self.is_column_layout_dirty = false;
         }
         if self.row_layout.size != size.y || self.is_row_layout_dirty {
             self.row_layout.size = size.y;
-            self.row_layout.refresh();
+            refresh_with_preferred_sizes(&mut self.row_layout, &self.preferred_sizes.1);
+            self.is_row_layout_dirty = false;
         }
 
         let mut col_offset = 0;
@@ -226,6 +231,28 @@ impl GridLayout {
             col_offset += col_size as i32;
         }
     }
+
+    /// # Returns
+    /// Largest preferred widths of widgets in every column and largest preferred heights of
+    /// widgets in every row.
+    fn children_preferred_sizes(&self) -> (Vec<u32>, Vec<u32>) {
+        let mut columns = vec![0; self.column_layout.items.len()];
+        let mut rows = vec![0; self.row_layout.items.len()];
+        for (col, column) in self.children_columns_rows.iter().enumerate() {
+            for (row, id) in column.iter().enumerate() {
+                let index = match id.and_then(|id| self.hierarchy.index(&id)) {
+                    Some(index) => index,
+                    None => continue,
+                };
+                let widget = self.hierarchy.children()[index].widget.borrow();
+                if let Some(size) = widget.preferred_size() {
+                    columns[col] = columns[col].max(size.x);
+                    rows[row] = rows[row].max(size.y);
+                }
+            }
+        }
+        (columns, rows)
+    }
 }
 
 //************************************************************************************************
@@ -247,6 +274,13 @@ impl WidgetTrait for GridLayout {
     }
 
     fn paint(&mut self, painter: &mut dyn PainterTrait) {
+        let preferred_sizes = self.children_preferred_sizes();
+        if preferred_sizes != self.preferred_sizes {
+            self.preferred_sizes = preferred_sizes;
+            self.is_column_layout_dirty = true;
+            self.is_row_layout_dirty = true;
+        }
+
         let painter_size = painter.size();
         if self.is_row_layout_dirty
             || self.is_column_layout_dirty
@@ -260,6 +294,24 @@ impl WidgetTrait for GridLayout {
     }
 }
 
+//************************************************************************************************
+/// Refreshes sizes of items with min sizes raised to the preferred sizes. Configured min sizes
+/// are kept.
+fn refresh_with_preferred_sizes(layout: &mut Layout, preferred_sizes: &[u32]) {
+    let min_sizes = layout
+        .items
+        .iter()
+        .map(|item| item.min_size)
+        .collect::<Vec<u32>>();
+    for (item, preferred) in layout.items.iter_mut().zip(preferred_sizes) {
+        item.min_size = item.min_size.max(item.max_size.min(*preferred));
+    }
+    layout.refresh();
+    for (item, min_size) in layout.items.iter_mut().zip(min_sizes) {
+        item.min_size = min_size;
+    }
+}
+
 #[cfg(test)]
 mod tests {
     // crate
@@ -1,14 +1,21 @@
 extern crate sdl2;
 
-use std::{cell::RefCell, rc::Rc};
+use std::{cell::RefCell, rc::Rc, time::Instant};
 
 use sdl2::event::Event;
 use sdl2::keyboard::Keycode;
 
+use super::convert_event;
+use super::AppConfig;
+use super::Clipboard;
 use super::WindowsManager;
+use crate::poly_ui::app::set_clipboard;
 use crate::poly_ui::app::AppTrait;
 use crate::poly_ui::app::WindowsManagerTrait;
 
+/// How long in milliseconds an idle application waits for events before the next update.
+const IDLE_TIMEOUT: u32 = 16;
+
 //************************************************************************************************
 //************************************************************************************************
 //************************************************************************************************
@@ -20,36 +27,71 @@ pub struct App {
 }
 
 //************************************************************************************************
-impl Default for App {
-    fn default() -> Self {
-        let context = sdl2::init().unwrap();
-        let video = Rc::new(RefCell::new(context.video().unwrap()));
+impl App {
+    /// Initializes SDL with the video driver of the configuration and makes the SDL clipboard
+    /// the clipboard of this thread.
+    /// # Arguments
+    /// * `config` - configuration of the video driver and renderers
+    /// # Returns
+    /// App or error description if SDL or its video subsystem can't be initialized.
+    pub fn new(config: AppConfig) -> Result<Self, String> {
+        if let Some(driver) = &config.video_driver {
+            sdl2::hint::set("SDL_VIDEODRIVER", driver);
+        }
+        let context = sdl2::init()?;
+        let video = Rc::new(RefCell::new(context.video()?));
+        set_clipboard(Rc::new(RefCell::new(Clipboard::new(
+            video.borrow().clipboard(),
+        ))));
 
-        App {
+        Ok(App {
             sdl_context: context,
             _sdl_video: video.clone(),
-            windows_manager: WindowsManager::new(video),
-        }
+            windows_manager: WindowsManager::new(video, config),
+        })
+    }
+}
+
+//************************************************************************************************
+impl Default for App {
+    fn default() -> Self {
+        Self::new(AppConfig::default()).unwrap()
     }
 }
 
 //************************************************************************************************
 impl AppTrait for App {
     fn exec(&mut self) -> Result<(), String> {
+        let mut event_pump = self.sdl_context.event_pump()?;
+        let mut idle = false;
+        let mut last_frame = Instant::now();
         'mainloop: loop {
-            for event in self.sdl_context.event_pump()?.poll_iter() {
+            // when nothing was painted in the last frame wait for events instead of spinning
+            let first = if idle {
+                event_pump.wait_event_timeout(IDLE_TIMEOUT)
+            } else {
+                None
+            };
+            for event in first.into_iter().chain(event_pump.poll_iter()) {
                 match event {
                     Event::KeyDown {
                         keycode: Some(Keycode::Escape),
                         ..
                     }
                     | Event::Quit { .. } => break 'mainloop,
-                    _ => {}
+                    event => {
+                        for (window_id, event) in convert_event(&event) {
+                            self.windows_manager.send_event(window_id, &event);
+                        }
+                    }
                 }
             }
 
-            self.windows_manager.update_windows(0.0);
-            self.windows_manager.paint_windows();
+            let now = Instant::now();
+            let dt = now.duration_since(last_frame).as_secs_f32() * 1000.0;
+            last_frame = now;
+            self.windows_manager.update_windows(dt);
+            idle = !self.windows_manager.paint_windows();
         }
 
         Ok(())
@@ -0,0 +1,43 @@
+extern crate sdl2;
+
+use sdl2::clipboard::ClipboardUtil;
+
+use crate::poly_ui::app::ClipboardTrait;
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// System clipboard accessed through SDL.
+pub struct Clipboard {
+    util: ClipboardUtil,
+}
+
+//************************************************************************************************
+impl Clipboard {
+    pub fn new(util: ClipboardUtil) -> Self {
+        Self { util }
+    }
+}
+
+//************************************************************************************************
+impl std::fmt::Debug for Clipboard {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        formatter.write_str("Clipboard")
+    }
+}
+
+//************************************************************************************************
+impl ClipboardTrait for Clipboard {
+    fn text(&self) -> Option<String> {
+        if self.util.has_clipboard_text() {
+            self.util.clipboard_text().ok()
+        } else {
+            None
+        }
+    }
+
+    fn set_text(&mut self, text: &str) {
+        // the clipboard is best effort, failures are not reported to Widgets
+        let _ = self.util.set_clipboard_text(text);
+    }
+}
@@ -0,0 +1,100 @@
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// Kind of the SDL renderer windows are painted with.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum RendererKind {
+    /// Hardware accelerated renderer (OpenGL, Direct3D, Metal, ...).
+    Accelerated,
+    /// SDL software renderer drawing into the window surface; it works without a GPU.
+    Software,
+}
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// Configuration of the SDL backend.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AppConfig {
+    /// Preferred renderer of windows.
+    pub renderer: RendererKind,
+    /// Whether presenting waits for the vertical sync of the display.
+    pub vsync: bool,
+    /// Whether windows are painted into a persistent frame texture so only damaged regions have
+    /// to be repainted. Without it (or when the renderer doesn't support render targets) the
+    /// whole window is painted directly every frame and layers are not composited.
+    pub target_texture: bool,
+    /// SDL video driver, e.g. "dummy" or "offscreen" for machines without a display. None lets
+    /// SDL choose (the SDL_VIDEODRIVER environment variable still applies).
+    pub video_driver: Option<String>,
+    /// Whether the software renderer is used when the accelerated one can't be created.
+    pub fallback: bool,
+}
+
+//************************************************************************************************
+impl AppConfig {
+    /// # Returns
+    /// Configuration with the software renderer on the dummy video driver and no vsync, for
+    /// machines without a GPU or a display (CI).
+    pub fn headless() -> Self {
+        Self {
+            renderer: RendererKind::Software,
+            vsync: false,
+            video_driver: Some("dummy".to_string()),
+            ..Self::default()
+        }
+    }
+
+    /// # Returns
+    /// Renderer kinds to try in order when a window is created.
+    pub fn renderers(&self) -> Vec<RendererKind> {
+        match self.renderer {
+            RendererKind::Accelerated if self.fallback => {
+                vec![RendererKind::Accelerated, RendererKind::Software]
+            }
+            kind => vec![kind],
+        }
+    }
+}
+
+//************************************************************************************************
+impl Default for AppConfig {
+    /// Accelerated renderer with vsync and frame texture falling back to the software one.
+    fn default() -> Self {
+        Self {
+            renderer: RendererKind::Accelerated,
+            vsync: true,
+            target_texture: true,
+            video_driver: None,
+            fallback: true,
+        }
+    }
+}
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+#[cfg(test)]
+mod tests {
+    // super
+    use super::*;
+
+    //********************************************************************************************
+    #[test]
+    fn renderers() {
+        assert_eq!(
+            AppConfig::default().renderers(),
+            vec![RendererKind::Accelerated, RendererKind::Software]
+        );
+        let config = AppConfig {
+            fallback: false,
+            ..AppConfig::default()
+        };
+        assert_eq!(config.renderers(), vec![RendererKind::Accelerated]);
+
+        let config = AppConfig::headless();
+        assert_eq!(config.renderers(), vec![RendererKind::Software]);
+        assert_eq!(config.video_driver.as_deref(), Some("dummy"));
+        assert!(!config.vsync);
+    }
+}
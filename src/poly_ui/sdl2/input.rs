@@ -0,0 +1,411 @@
+extern crate sdl2;
+
+use nalgebra::Point2;
+use nalgebra::Vector2;
+use sdl2::event::WindowEvent;
+use sdl2::keyboard::Keycode;
+use sdl2::keyboard::Mod;
+
+use crate::poly_ui::events::Event;
+use crate::poly_ui::events::Key;
+use crate::poly_ui::events::KeyModifiers;
+use crate::poly_ui::events::KeyPressEvent;
+use crate::poly_ui::events::KeyReleaseEvent;
+use crate::poly_ui::events::MouseButton;
+use crate::poly_ui::events::MouseMoveEvent;
+use crate::poly_ui::events::MousePressEvent;
+use crate::poly_ui::events::MouseReleaseEvent;
+use crate::poly_ui::events::MouseWheelEvent;
+use crate::poly_ui::events::TextInputEvent;
+
+/// Maps SDL keycodes to Keys of the same name.
+macro_rules! same_keys {
+    ($keycode:expr, $($name:ident),* $(,)?) => {
+        match $keycode {
+            $(Keycode::$name => Some(Key::$name),)*
+            Keycode::KpEqualsAS400 => Some(Key::KpEqualsAs400),
+        }
+    };
+}
+
+//************************************************************************************************
+/// # Arguments
+/// * `keycode` - SDL keycode
+/// # Returns
+/// Key with the same meaning.
+pub fn convert_key(keycode: Keycode) -> Option<Key> {
+    same_keys!(
+        keycode,
+        Backspace,
+        Tab,
+        Return,
+        Escape,
+        Space,
+        Exclaim,
+        Quotedbl,
+        Hash,
+        Dollar,
+        Percent,
+        Ampersand,
+        Quote,
+        LeftParen,
+        RightParen,
+        Asterisk,
+        Plus,
+        Comma,
+        Minus,
+        Period,
+        Slash,
+        Num0,
+        Num1,
+        Num2,
+        Num3,
+        Num4,
+        Num5,
+        Num6,
+        Num7,
+        Num8,
+        Num9,
+        Colon,
+        Semicolon,
+        Less,
+        Equals,
+        Greater,
+        Question,
+        At,
+        LeftBracket,
+        Backslash,
+        RightBracket,
+        Caret,
+        Underscore,
+        Backquote,
+        A,
+        B,
+        C,
+        D,
+        E,
+        F,
+        G,
+        H,
+        I,
+        J,
+        K,
+        L,
+        M,
+        N,
+        O,
+        P,
+        Q,
+        R,
+        S,
+        T,
+        U,
+        V,
+        W,
+        X,
+        Y,
+        Z,
+        Delete,
+        CapsLock,
+        F1,
+        F2,
+        F3,
+        F4,
+        F5,
+        F6,
+        F7,
+        F8,
+        F9,
+        F10,
+        F11,
+        F12,
+        PrintScreen,
+        ScrollLock,
+        Pause,
+        Insert,
+        Home,
+        PageUp,
+        End,
+        PageDown,
+        Right,
+        Left,
+        Down,
+        Up,
+        NumLockClear,
+        KpDivide,
+        KpMultiply,
+        KpMinus,
+        KpPlus,
+        KpEnter,
+        Kp1,
+        Kp2,
+        Kp3,
+        Kp4,
+        Kp5,
+        Kp6,
+        Kp7,
+        Kp8,
+        Kp9,
+        Kp0,
+        KpPeriod,
+        Application,
+        Power,
+        KpEquals,
+        F13,
+        F14,
+        F15,
+        F16,
+        F17,
+        F18,
+        F19,
+        F20,
+        F21,
+        F22,
+        F23,
+        F24,
+        Execute,
+        Help,
+        Menu,
+        Select,
+        Stop,
+        Again,
+        Undo,
+        Cut,
+        Copy,
+        Paste,
+        Find,
+        Mute,
+        VolumeUp,
+        VolumeDown,
+        KpComma,
+        AltErase,
+        Sysreq,
+        Cancel,
+        Clear,
+        Prior,
+        Return2,
+        Separator,
+        Out,
+        Oper,
+        ClearAgain,
+        CrSel,
+        ExSel,
+        Kp00,
+        Kp000,
+        ThousandsSeparator,
+        DecimalSeparator,
+        CurrencyUnit,
+        CurrencySubUnit,
+        KpLeftParen,
+        KpRightParen,
+        KpLeftBrace,
+        KpRightBrace,
+        KpTab,
+        KpBackspace,
+        KpA,
+        KpB,
+        KpC,
+        KpD,
+        KpE,
+        KpF,
+        KpXor,
+        KpPower,
+        KpPercent,
+        KpLess,
+        KpGreater,
+        KpAmpersand,
+        KpDblAmpersand,
+        KpVerticalBar,
+        KpDblVerticalBar,
+        KpColon,
+        KpHash,
+        KpSpace,
+        KpAt,
+        KpExclam,
+        KpMemStore,
+        KpMemRecall,
+        KpMemClear,
+        KpMemAdd,
+        KpMemSubtract,
+        KpMemMultiply,
+        KpMemDivide,
+        KpPlusMinus,
+        KpClear,
+        KpClearEntry,
+        KpBinary,
+        KpOctal,
+        KpDecimal,
+        KpHexadecimal,
+        LCtrl,
+        LShift,
+        LAlt,
+        LGui,
+        RCtrl,
+        RShift,
+        RAlt,
+        RGui,
+        Mode,
+        AudioNext,
+        AudioPrev,
+        AudioStop,
+        AudioPlay,
+        AudioMute,
+        MediaSelect,
+        Www,
+        Mail,
+        Calculator,
+        Computer,
+        AcSearch,
+        AcHome,
+        AcBack,
+        AcForward,
+        AcStop,
+        AcRefresh,
+        AcBookmarks,
+        BrightnessDown,
+        BrightnessUp,
+        DisplaySwitch,
+        KbdIllumToggle,
+        KbdIllumDown,
+        KbdIllumUp,
+        Eject,
+        Sleep,
+    )
+}
+
+//************************************************************************************************
+/// # Arguments
+/// * `button` - SDL mouse button
+/// # Returns
+/// MouseButton with the same meaning, None for unknown buttons.
+pub fn convert_mouse_button(button: sdl2::mouse::MouseButton) -> Option<MouseButton> {
+    match button {
+        sdl2::mouse::MouseButton::Left => Some(MouseButton::Left),
+        sdl2::mouse::MouseButton::Middle => Some(MouseButton::Middle),
+        sdl2::mouse::MouseButton::Right => Some(MouseButton::Right),
+        sdl2::mouse::MouseButton::X1 => Some(MouseButton::X1),
+        sdl2::mouse::MouseButton::X2 => Some(MouseButton::X2),
+        sdl2::mouse::MouseButton::Unknown => None,
+    }
+}
+
+//************************************************************************************************
+/// # Arguments
+/// * `keymod` - SDL modifier state
+/// # Returns
+/// Held modifiers, left and right keys are not distinguished.
+pub fn convert_modifiers(keymod: Mod) -> KeyModifiers {
+    KeyModifiers {
+        shift: keymod.intersects(Mod::LSHIFTMOD | Mod::RSHIFTMOD),
+        ctrl: keymod.intersects(Mod::LCTRLMOD | Mod::RCTRLMOD),
+        alt: keymod.intersects(Mod::LALTMOD | Mod::RALTMOD),
+    }
+}
+
+//************************************************************************************************
+/// Converts SDL input event to the Events passed to Windows.
+/// # Arguments
+/// * `event` - SDL event
+/// # Returns
+/// SDL id of the window the events belong to and the converted events; text input produces an
+/// event per character. Empty for events which are not input of a window.
+pub fn convert_event(event: &sdl2::event::Event) -> Vec<(u32, Event)> {
+    match event {
+        sdl2::event::Event::TextInput {
+            window_id, text, ..
+        } => text
+            .chars()
+            .map(|ch| (*window_id, Event::TextInput(TextInputEvent::new(ch))))
+            .collect(),
+        event => convert_input(event).into_iter().collect(),
+    }
+}
+
+//************************************************************************************************
+/// Converts SDL input event that maps to a single Event.
+fn convert_input(event: &sdl2::event::Event) -> Option<(u32, Event)> {
+    match event {
+        sdl2::event::Event::KeyDown {
+            window_id,
+            keycode: Some(keycode),
+            keymod,
+            ..
+        } => Some((
+            *window_id,
+            Event::KeyPress(KeyPressEvent::with_modifiers(
+                convert_key(*keycode)?,
+                convert_modifiers(*keymod),
+            )),
+        )),
+        sdl2::event::Event::KeyUp {
+            window_id,
+            keycode: Some(keycode),
+            ..
+        } => Some((
+            *window_id,
+            Event::KeyReleaseEvent(KeyReleaseEvent::new(convert_key(*keycode)?)),
+        )),
+        sdl2::event::Event::MouseButtonDown {
+            window_id,
+            mouse_btn,
+            x,
+            y,
+            ..
+        } => Some((
+            *window_id,
+            Event::MousePressEvent(MousePressEvent::new(
+                convert_mouse_button(*mouse_btn)?,
+                &Point2::<i32>::new(*x, *y),
+            )),
+        )),
+        sdl2::event::Event::MouseButtonUp {
+            window_id,
+            mouse_btn,
+            x,
+            y,
+            ..
+        } => Some((
+            *window_id,
+            Event::MouseReleaseEvent(MouseReleaseEvent::new(
+                convert_mouse_button(*mouse_btn)?,
+                &Point2::<i32>::new(*x, *y),
+            )),
+        )),
+        sdl2::event::Event::MouseMotion {
+            window_id, x, y, ..
+        } => Some((
+            *window_id,
+            Event::MouseMoveEvent(MouseMoveEvent::new(&Point2::<i32>::new(*x, *y))),
+        )),
+        sdl2::event::Event::MouseWheel {
+            window_id,
+            x,
+            y,
+            direction,
+            ..
+        } => {
+            // SDL scrolls up for positive y and doesn't report the position of the mouse
+            let sign = match direction {
+                sdl2::mouse::MouseWheelDirection::Flipped => -1,
+                _ => 1,
+            };
+            let (mut mouse_x, mut mouse_y) = (0, 0);
+            unsafe {
+                sdl2::sys::SDL_GetMouseState(&mut mouse_x, &mut mouse_y);
+            }
+            Some((
+                *window_id,
+                Event::MouseWheel(MouseWheelEvent::new(
+                    &Vector2::<i32>::new(sign * *x, -sign * *y),
+                    &Point2::<i32>::new(mouse_x, mouse_y),
+                )),
+            ))
+        }
+        sdl2::event::Event::Window {
+            window_id,
+            win_event: WindowEvent::Leave,
+            ..
+        } => Some((*window_id, Event::MouseLeave)),
+        _ => None,
+    }
+}
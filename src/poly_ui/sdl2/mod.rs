@@ -1,9 +1,17 @@
 mod app;
+mod clipboard;
+mod config;
+mod input;
 mod painter;
+mod renderer;
 mod window_provider;
 mod windows_manager;
 
 pub use app::App;
+pub use clipboard::Clipboard;
+pub use config::{AppConfig, RendererKind};
+pub use input::{convert_event, convert_key, convert_modifiers, convert_mouse_button};
 pub use painter::Painter;
+pub use renderer::{Batch, BatchKind, Layer, Renderer};
 pub use window_provider::WindowProvider;
 pub use windows_manager::WindowsManager;
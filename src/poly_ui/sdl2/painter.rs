@@ -1,229 +1,665 @@
+use nalgebra::Matrix3;
 use nalgebra::Point2;
 use nalgebra::Vector2;
-use std::{cell::RefCell, rc::Rc, vec::Vec};
+use std::{cell::RefCell, rc::Rc};
 
+use super::BatchKind;
+use super::Layer;
+use super::Renderer;
+use crate::poly_ui::app::device_bounds;
+use crate::poly_ui::app::gaussian_blur;
+use crate::poly_ui::app::integer_translation;
+use crate::poly_ui::app::is_axis_aligned;
+use crate::poly_ui::app::local_visible_rect;
+use crate::poly_ui::app::BlendMode;
+use crate::poly_ui::app::Brush;
 use crate::poly_ui::app::Color;
+use crate::poly_ui::app::Image;
+use crate::poly_ui::app::ImageFill;
+use crate::poly_ui::app::ImagePattern;
 use crate::poly_ui::app::Line;
+use crate::poly_ui::app::PainterStateStack;
 use crate::poly_ui::app::PainterTrait;
 use crate::poly_ui::app::Rect;
+use crate::poly_ui::app::SpreadMode;
 use crate::poly_ui::components::Transform;
 
 //************************************************************************************************
 //************************************************************************************************
 //************************************************************************************************
+/// SDL implementation of the PainterTrait. All painters created for one window share the same
+/// Renderer. Every painter stores its origin as a matrix relative to the window and its clip rect
+/// in window coordinates, so drawing calls work with absolute canvas coordinates and the SDL clip
+/// rect instead of viewports. Solid primitives are batched by the Renderer.
 pub struct Painter {
-    canvas: Rc<RefCell<Option<sdl2::render::Canvas<sdl2::video::Window>>>>,
-    transform: Transform,
+    renderer: Rc<RefCell<Renderer>>,
+    origin: Matrix3<f32>,
+    size: Vector2<u32>,
+    clip: Option<sdl2::rect::Rect>,
+    states: PainterStateStack,
 }
 
 //************************************************************************************************
 impl Painter {
-    pub fn new(canvas: Rc<RefCell<Option<sdl2::render::Canvas<sdl2::video::Window>>>>) -> Self {
-        let output_size = Vector2::<u32>::new(
-            canvas.borrow().as_ref().unwrap().output_size().unwrap().0,
-            canvas.borrow().as_ref().unwrap().output_size().unwrap().1,
-        );
+    /// # Arguments
+    /// * `renderer` - renderer of the window
+    /// # Returns
+    /// Root Painter painting on the whole output of the renderer.
+    pub fn new(renderer: Rc<RefCell<Renderer>>) -> Self {
+        let output_size = renderer.borrow().output_size();
 
         Painter {
-            canvas,
-            transform: Transform::new(&Point2::<i32>::new(0, 0), &output_size),
+            renderer,
+            origin: Matrix3::<f32>::identity(),
+            size: output_size,
+            clip: Some(to_sdl_rect(&Point2::<i32>::new(0, 0), &output_size)),
+            states: PainterStateStack::default(),
         }
     }
 
-    fn ensure_correct_viewport(&mut self) {
-        let current_rect = self.canvas.borrow().as_ref().unwrap().viewport();
-        if current_rect.x() != self.transform.pos.x
-            || current_rect.y() != self.transform.pos.y
-            || current_rect.width() != self.transform.size.x
-            || current_rect.height() != self.transform.size.y
-        {
-            let new_rect = sdl2::rect::Rect::new(
-                self.transform.pos.x,
-                self.transform.pos.y,
-                self.transform.size.x,
-                self.transform.size.y,
-            );
-            self.canvas
-                .borrow_mut()
-                .as_mut()
-                .unwrap()
-                .set_viewport(new_rect);
+    /// # Returns
+    /// Matrix transforming local coordinates of this painter into canvas coordinates.
+    fn full_matrix(&self) -> Matrix3<f32> {
+        self.origin * self.states.current().matrix
+    }
+
+    /// # Returns
+    /// Color of the current brush if it's solid.
+    fn solid_color(&self) -> Option<Color> {
+        match &self.states.current().brush {
+            Brush::Solid(color) => Some(*color),
+            _ => None,
+        }
+    }
+
+    /// Evaluates the current brush at the center of the given canvas pixel.
+    /// # Arguments
+    /// * `inverse` - matrix from canvas coordinates to the local coordinates of this painter
+    fn color_at_device(&self, inverse: &Matrix3<f32>, x: i32, y: i32) -> Color {
+        let local = inverse.transform_point(&Point2::<f32>::new(x as f32 + 0.5, y as f32 + 0.5));
+        self.states.current().brush.color_at(&local)
+    }
+
+    /// Draws given canvas points, each with its own color.
+    fn draw_colored_points(&self, clip: sdl2::rect::Rect, points: &[(Point2<i32>, Color)]) {
+        let mut renderer = self.renderer.borrow_mut();
+        let canvas = renderer.direct(clip);
+
+        let mut result = Ok(());
+        for (point, color) in points {
+            canvas.set_draw_color(to_sdl_color(color));
+            result = result.and(canvas.draw_point(sdl2::rect::Point::new(point.x, point.y)));
+        }
+        renderer.report(result);
+    }
+
+    /// Strokes given local points with the current brush and matrix.
+    fn stroke_points(&self, clip: sdl2::rect::Rect, points: &[Point2<i32>]) {
+        let matrix = self.full_matrix();
+        let to_device = |point: &Point2<i32>| {
+            let center = Point2::<f32>::new(point.x as f32 + 0.5, point.y as f32 + 0.5);
+            let device = matrix.transform_point(&center);
+            Point2::<i32>::new(device.x.floor() as i32, device.y.floor() as i32)
+        };
+
+        match self.solid_color() {
+            Some(color) => self.renderer.borrow_mut().batch_points(
+                BatchKind::Points,
+                clip,
+                &color,
+                points.iter().map(|point| {
+                    let device = to_device(point);
+                    sdl2::rect::Point::new(device.x, device.y)
+                }),
+            ),
+            None => {
+                let brush = &self.states.current().brush;
+                let colored: Vec<(Point2<i32>, Color)> = points
+                    .iter()
+                    .map(|point| {
+                        let center = Point2::<f32>::new(point.x as f32 + 0.5, point.y as f32 + 0.5);
+                        (to_device(point), brush.color_at(&center))
+                    })
+                    .collect();
+                self.draw_colored_points(clip, &colored);
+            }
+        }
+    }
+
+    /// Strokes given local lines with the current brush and matrix.
+    fn stroke_lines(&self, clip: sdl2::rect::Rect, lines: &[Line]) {
+        let matrix = self.full_matrix();
+        let map = |point: &Point2<i32>| {
+            let device = matrix.transform_point(&Point2::<f32>::new(
+                point.x as f32 + 0.5,
+                point.y as f32 + 0.5,
+            ));
+            Point2::<i32>::new(device.x.floor() as i32, device.y.floor() as i32)
+        };
+        let device_lines = lines.iter().map(|line| Line {
+            start: map(&line.start),
+            end: map(&line.end),
+        });
+
+        match self.solid_color() {
+            Some(color) => self.renderer.borrow_mut().batch_points(
+                BatchKind::Lines,
+                clip,
+                &color,
+                device_lines.flat_map(|line| {
+                    std::iter::once(sdl2::rect::Point::new(line.start.x, line.start.y)).chain(
+                        std::iter::once(sdl2::rect::Point::new(line.end.x, line.end.y)),
+                    )
+                }),
+            ),
+            None => {
+                let inverse = match matrix.try_inverse() {
+                    Some(inverse) => inverse,
+                    None => return,
+                };
+                let colored: Vec<(Point2<i32>, Color)> = device_lines
+                    .flat_map(|line| line.rasterize())
+                    .map(|point| (point, self.color_at_device(&inverse, point.x, point.y)))
+                    .collect();
+                self.draw_colored_points(clip, &colored);
+            }
         }
     }
+
+    /// Fills given local rect using the given matrix and the current brush. Solid fills that stay
+    /// axis aligned are batched; everything else is evaluated per pixel into the scratch texture
+    /// of the renderer which is then copied onto the canvas.
+    /// # Arguments
+    /// * `matrix` - matrix from the rect coordinates to canvas coordinates
+    /// * `rect` - rect to fill
+    /// * `blend` - if false pixels are replaced instead of being blended with the canvas
+    fn fill(&self, matrix: &Matrix3<f32>, rect: &Rect, blend: bool) {
+        let clip = match self.clip {
+            Some(clip) => clip,
+            None => return,
+        };
+        let bounds = match intersect(&self.clip, &device_bounds(matrix, rect)) {
+            Some(bounds) => bounds,
+            None => return,
+        };
+        let blend_mode = if blend {
+            sdl2::render::BlendMode::Blend
+        } else {
+            sdl2::render::BlendMode::None
+        };
+
+        if let (Some(color), true) = (self.solid_color(), is_axis_aligned(matrix)) {
+            let mut renderer = self.renderer.borrow_mut();
+            if blend {
+                renderer.batch_rects(BatchKind::FillRects, clip, &color, std::iter::once(bounds));
+            } else {
+                let canvas = renderer.direct(clip);
+                canvas.set_blend_mode(blend_mode);
+                canvas.set_draw_color(to_sdl_color(&color));
+                let result = canvas.fill_rect(bounds);
+                canvas.set_blend_mode(sdl2::render::BlendMode::Blend);
+                renderer.report(result);
+            }
+            return;
+        }
+
+        let inverse = match matrix.try_inverse() {
+            Some(inverse) => inverse,
+            None => return,
+        };
+
+        let mut renderer = self.renderer.borrow_mut();
+        renderer.direct(clip);
+        let (canvas, texture) = match renderer.scratch(bounds.width(), bounds.height()) {
+            Some(scratch) => scratch,
+            None => return,
+        };
+        let area = sdl2::rect::Rect::new(0, 0, bounds.width(), bounds.height());
+        let brush = &self.states.current().brush;
+        let locked = texture.with_lock(area, |buffer: &mut [u8], pitch: usize| {
+            for y in 0..bounds.height() {
+                for x in 0..bounds.width() {
+                    let local = inverse.transform_point(&Point2::<f32>::new(
+                        (bounds.x() + x as i32) as f32 + 0.5,
+                        (bounds.y() + y as i32) as f32 + 0.5,
+                    ));
+                    let inside = local.x >= rect.pos.x as f32
+                        && local.y >= rect.pos.y as f32
+                        && local.x < (rect.pos.x + rect.size.x as i32) as f32
+                        && local.y < (rect.pos.y + rect.size.y as i32) as f32;
+                    let color = if inside {
+                        brush.color_at(&local)
+                    } else {
+                        Color {
+                            r: 0,
+                            g: 0,
+                            b: 0,
+                            a: 0,
+                        }
+                    };
+                    let offset = y as usize * pitch + x as usize * 4;
+                    buffer[offset] = color.r;
+                    buffer[offset + 1] = color.g;
+                    buffer[offset + 2] = color.b;
+                    buffer[offset + 3] = color.a;
+                }
+            }
+        });
+        texture.set_blend_mode(blend_mode);
+
+        let result = locked.and(canvas.copy(texture, area, bounds));
+        renderer.report(result);
+    }
+
+    /// Draws the image with an ImagePattern brush. Used for transforms SDL can't copy textures
+    /// with.
+    fn fill_image_rect(&mut self, image: &Rc<Image>, rect: &Rect, fill: ImageFill) {
+        let size = image.size();
+        self.save();
+        let pattern = match fill {
+            ImageFill::Stretch => {
+                self.translate(rect.pos.x as f32, rect.pos.y as f32);
+                self.scale(
+                    rect.size.x as f32 / size.x as f32,
+                    rect.size.y as f32 / size.y as f32,
+                );
+                ImagePattern::new(image.clone(), &Point2::<i32>::new(0, 0), SpreadMode::Pad)
+            }
+            ImageFill::Tile => ImagePattern::new(image.clone(), &rect.pos, SpreadMode::Repeat),
+        };
+        self.set_brush(&Brush::Pattern(pattern));
+        match fill {
+            ImageFill::Stretch => self.fill_rect(Rect {
+                pos: Point2::<i32>::new(0, 0),
+                size,
+            }),
+            ImageFill::Tile => self.fill_rect(*rect),
+        }
+        self.restore();
+    }
 }
 
 //************************************************************************************************
 impl PainterTrait for Painter {
     fn sub_painter(&self, transform: &Transform) -> Box<dyn PainterTrait> {
+        let origin = self.full_matrix()
+            * Matrix3::<f32>::new_translation(&Vector2::<f32>::new(
+                transform.pos.x as f32,
+                transform.pos.y as f32,
+            ));
+        let bounds = device_bounds(
+            &origin,
+            &Rect {
+                pos: Point2::<i32>::new(0, 0),
+                size: transform.size,
+            },
+        );
+
         Box::new(Painter {
-            canvas: self.canvas.clone(),
-            transform: Transform::new(
-                &Point2::<i32>::new(
-                    self.transform.pos.x + transform.pos.x,
-                    self.transform.pos.y + transform.pos.y,
-                ),
-                &transform.size,
-            ),
+            renderer: self.renderer.clone(),
+            origin,
+            size: transform.size,
+            clip: intersect(&self.clip, &bounds),
+            states: PainterStateStack::new(&self.states.current().brush),
         })
     }
 
     fn size(&self) -> Vector2<u32> {
-        self.transform.size
+        self.size
+    }
+
+    fn visible_rect(&self) -> Option<Rect> {
+        let clip = self.clip.map(|clip| Rect {
+            pos: Point2::<i32>::new(clip.x(), clip.y()),
+            size: Vector2::<u32>::new(clip.width(), clip.height()),
+        });
+        Some(local_visible_rect(&self.full_matrix(), &clip))
     }
 
     fn clear(&mut self) {
-        self.ensure_correct_viewport();
-        self.canvas.borrow_mut().as_mut().unwrap().clear();
+        self.fill(
+            &self.origin,
+            &Rect {
+                pos: Point2::<i32>::new(0, 0),
+                size: self.size,
+            },
+            false,
+        );
     }
 
-    fn draw_color(&self) -> Color {
-        let color = self.canvas.borrow().as_ref().unwrap().draw_color();
-        Color {
-            r: color.r,
-            g: color.g,
-            b: color.b,
-            a: color.a,
-        }
+    fn brush(&self) -> Brush {
+        self.states.current().brush.clone()
     }
 
-    fn set_draw_color(&mut self, new: &Color) {
-        self.canvas
-            .borrow_mut()
-            .as_mut()
-            .unwrap()
-            .set_draw_color(sdl2::pixels::Color {
-                r: new.r,
-                g: new.g,
-                b: new.b,
-                a: new.a,
-            });
+    fn set_brush(&mut self, new: &Brush) {
+        self.states.current_mut().brush = new.clone();
     }
 
-    fn draw_point(&mut self, point: &Point2<i32>) {
-        self.ensure_correct_viewport();
-        self.canvas
-            .borrow_mut()
-            .as_mut()
-            .unwrap()
-            .draw_point(sdl2::rect::Point::new(point.x, point.y))
-            .unwrap();
+    fn save(&mut self) {
+        self.states.save();
     }
 
-    fn draw_points(&mut self, points: &[Point2<i32>]) {
-        self.ensure_correct_viewport();
-        let mut converted = Vec::<sdl2::rect::Point>::new();
+    fn restore(&mut self) {
+        self.states.restore();
+    }
+
+    fn matrix(&self) -> Matrix3<f32> {
+        self.states.current().matrix
+    }
+
+    fn device_matrix(&self) -> Matrix3<f32> {
+        self.full_matrix()
+    }
+
+    fn set_matrix(&mut self, new: &Matrix3<f32>) {
+        self.states.current_mut().matrix = *new;
+    }
+
+    fn push_layer(&mut self, opacity: f32, blend_mode: BlendMode) {
+        let mut renderer = self.renderer.borrow_mut();
+        // without render targets content of the layer is painted directly below it
+        let target = match self.clip.filter(|_| renderer.target_texture()) {
+            Some(clip) => renderer
+                .layer_texture(clip.width(), clip.height())
+                .map(|texture| (texture, clip)),
+            None => None,
+        };
+        let (texture, clip) = match target {
+            Some(target) => target,
+            None => {
+                renderer.push_layer(Layer {
+                    texture: None,
+                    clip: sdl2::rect::Rect::new(0, 0, 1, 1),
+                    opacity,
+                    blend_mode,
+                });
+                return;
+            }
+        };
+
+        renderer.push_layer(Layer {
+            texture: Some(texture),
+            clip,
+            opacity,
+            blend_mode,
+        });
+
+        let canvas = renderer.canvas_mut();
+        canvas.set_clip_rect(None);
+        canvas.set_blend_mode(sdl2::render::BlendMode::None);
+        canvas.set_draw_color(sdl2::pixels::Color::RGBA(0, 0, 0, 0));
+        canvas.clear();
+        canvas.set_blend_mode(sdl2::render::BlendMode::Blend);
+    }
+
+    fn pop_layer(&mut self) {
+        let mut renderer = self.renderer.borrow_mut();
+        let (texture, clip) = match renderer.layers().last() {
+            Some(Layer {
+                texture: Some(_),
+                clip,
+                ..
+            }) => (true, *clip),
+            Some(_) => (false, sdl2::rect::Rect::new(0, 0, 1, 1)),
+            None => return,
+        };
+        if !texture {
+            renderer.pop_layer();
+            return;
+        }
+
+        // Layer is composited on the CPU because SDL has no screen blend mode and layer pixels
+        // are premultiplied (they were blended onto transparent black).
+        let src = renderer.read_pixels(clip);
+        let layer = renderer.pop_layer().unwrap();
+        let dst = renderer.read_pixels(clip);
+        let (src, mut dst) = match (src, dst) {
+            (Ok(src), Ok(dst)) => (src, dst),
+            (Err(error), _) | (_, Err(error)) => {
+                renderer.report(Err(error));
+                renderer.release_layer(layer);
+                return;
+            }
+        };
 
-        for point in points {
-            converted.push(sdl2::rect::Point::new(point.x, point.y));
+        for (src_pixel, dst_pixel) in src.chunks_exact(4).zip(dst.chunks_exact_mut(4)) {
+            let straight = |channel: u8| {
+                if src_pixel[3] == 0 {
+                    0
+                } else {
+                    (channel as u32 * 255 / src_pixel[3] as u32).min(255) as u8
+                }
+            };
+            let result = layer.blend_mode.blend(
+                &Color {
+                    r: straight(src_pixel[0]),
+                    g: straight(src_pixel[1]),
+                    b: straight(src_pixel[2]),
+                    a: src_pixel[3],
+                },
+                &Color {
+                    r: dst_pixel[0],
+                    g: dst_pixel[1],
+                    b: dst_pixel[2],
+                    a: dst_pixel[3],
+                },
+                layer.opacity,
+            );
+            dst_pixel.copy_from_slice(&[result.r, result.g, result.b, result.a]);
         }
 
-        self.canvas
-            .borrow_mut()
-            .as_mut()
-            .unwrap()
-            .draw_points(&*converted.into_boxed_slice())
-            .unwrap();
+        renderer.direct(clip);
+        let (canvas, composited) = match renderer.scratch(clip.width(), clip.height()) {
+            Some(scratch) => scratch,
+            None => {
+                renderer.release_layer(layer);
+                return;
+            }
+        };
+        let area = sdl2::rect::Rect::new(0, 0, clip.width(), clip.height());
+        let updated = composited
+            .update(area, &dst, clip.width() as usize * 4)
+            .map_err(|error| error.to_string());
+        composited.set_blend_mode(sdl2::render::BlendMode::None);
+        let result = updated.and_then(|_| canvas.copy(composited, area, clip));
+        renderer.report(result);
+        renderer.release_layer(layer);
+    }
+
+    fn draw_point(&mut self, point: &Point2<i32>) {
+        self.draw_points(&[*point]);
+    }
+
+    fn draw_points(&mut self, points: &[Point2<i32>]) {
+        if let Some(clip) = self.clip {
+            self.stroke_points(clip, points);
+        }
     }
 
     fn draw_line(&mut self, line: &Line) {
-        self.ensure_correct_viewport();
-        self.canvas
-            .borrow_mut()
-            .as_mut()
-            .unwrap()
-            .draw_line(
-                sdl2::rect::Point::new(line.start.x, line.start.y),
-                sdl2::rect::Point::new(line.end.x, line.end.y),
-            )
-            .unwrap();
+        self.draw_lines(&[*line]);
     }
 
     fn draw_lines(&mut self, lines: &[Line]) {
-        self.ensure_correct_viewport();
-        let mut converted = Vec::<sdl2::rect::Point>::new();
-
-        for line in lines {
-            converted.push(sdl2::rect::Point::new(line.start.x, line.start.y));
-            converted.push(sdl2::rect::Point::new(line.end.x, line.end.y));
+        if let Some(clip) = self.clip {
+            self.stroke_lines(clip, lines);
         }
-
-        self.canvas
-            .borrow_mut()
-            .as_mut()
-            .unwrap()
-            .draw_points(&*converted.into_boxed_slice())
-            .unwrap();
     }
 
     fn draw_rect(&mut self, rect: Rect) {
-        self.ensure_correct_viewport();
-        self.canvas
-            .borrow_mut()
-            .as_mut()
-            .unwrap()
-            .draw_rect(sdl2::rect::Rect::new(
-                rect.pos.x,
-                rect.pos.x,
-                rect.size.x,
-                rect.size.x,
-            ))
-            .unwrap();
+        self.draw_rects(&[rect]);
     }
 
     fn draw_rects(&mut self, rects: &[Rect]) {
-        self.ensure_correct_viewport();
-        let mut converted = Vec::<sdl2::rect::Rect>::new();
+        let clip = match self.clip {
+            Some(clip) => clip,
+            None => return,
+        };
 
-        for rect in rects {
-            converted.push(sdl2::rect::Rect::new(
-                rect.pos.x,
-                rect.pos.x,
-                rect.size.x,
-                rect.size.x,
-            ));
+        let translation = integer_translation(&self.full_matrix());
+        match (translation, self.solid_color()) {
+            (Some(offset), Some(color)) => self.renderer.borrow_mut().batch_rects(
+                BatchKind::Rects,
+                clip,
+                &color,
+                rects
+                    .iter()
+                    .filter(|rect| rect.size.x > 0 && rect.size.y > 0)
+                    .map(|rect| to_sdl_rect(&(rect.pos + offset.coords), &rect.size)),
+            ),
+            _ => {
+                let lines: Vec<Line> = rects.iter().flat_map(|rect| rect.outline()).collect();
+                self.stroke_lines(clip, &lines);
+            }
         }
-
-        self.canvas
-            .borrow_mut()
-            .as_mut()
-            .unwrap()
-            .draw_rects(&*converted.into_boxed_slice())
-            .unwrap();
     }
 
     fn fill_rect(&mut self, rect: Rect) {
-        self.ensure_correct_viewport();
-        self.canvas
-            .borrow_mut()
-            .as_mut()
-            .unwrap()
-            .fill_rect(sdl2::rect::Rect::new(
-                rect.pos.x,
-                rect.pos.x,
-                rect.size.x,
-                rect.size.x,
-            ))
-            .unwrap();
+        self.fill_rects(&[rect]);
     }
 
     fn fill_rects(&mut self, rects: &[Rect]) {
-        self.ensure_correct_viewport();
-        let mut converted = Vec::<sdl2::rect::Rect>::new();
-
+        let matrix = self.full_matrix();
         for rect in rects {
-            converted.push(sdl2::rect::Rect::new(
-                rect.pos.x,
-                rect.pos.x,
-                rect.size.x,
-                rect.size.x,
-            ));
+            self.fill(&matrix, rect, true);
+        }
+    }
+
+    fn draw_image(&mut self, image: &Rc<Image>, pos: &Point2<i32>) {
+        self.draw_image_rect(
+            image,
+            &Rect {
+                pos: *pos,
+                size: image.size(),
+            },
+            ImageFill::Stretch,
+        );
+    }
+
+    fn draw_image_rect(&mut self, image: &Rc<Image>, rect: &Rect, fill: ImageFill) {
+        let clip = match self.clip {
+            Some(clip) => clip,
+            None => return,
+        };
+        let size = image.size();
+        if size.x == 0 || size.y == 0 {
+            return;
         }
 
-        self.canvas
-            .borrow_mut()
-            .as_mut()
-            .unwrap()
-            .fill_rects(&*converted.into_boxed_slice())
-            .unwrap();
+        let matrix = self.full_matrix();
+        let target = match intersect(&self.clip, &device_bounds(&matrix, rect)) {
+            Some(target) => target,
+            None => return,
+        };
+        let unmirrored = is_axis_aligned(&matrix) && matrix[(0, 0)] > 0.0 && matrix[(1, 1)] > 0.0;
+        match (fill, unmirrored, integer_translation(&matrix)) {
+            (ImageFill::Stretch, true, _) => {
+                let bounds = device_bounds(&matrix, rect).unwrap();
+                let mut renderer = self.renderer.borrow_mut();
+                renderer.direct(clip);
+                if let Some((canvas, texture)) = renderer.image_texture(image) {
+                    let result = canvas.copy(texture, None, to_sdl_rect(&bounds.pos, &bounds.size));
+                    renderer.report(result);
+                }
+            }
+            (ImageFill::Tile, _, Some(offset)) => {
+                let origin = rect.pos + offset.coords;
+                let first = |start: i32, origin: i32, step: u32| {
+                    origin + (start - origin).div_euclid(step as i32) * step as i32
+                };
+                let mut renderer = self.renderer.borrow_mut();
+                renderer.direct(target);
+                let (canvas, texture) = match renderer.image_texture(image) {
+                    Some(image_texture) => image_texture,
+                    None => return,
+                };
+                let mut result = Ok(());
+                let mut y = first(target.y(), origin.y, size.y);
+                while y < target.bottom() {
+                    let mut x = first(target.x(), origin.x, size.x);
+                    while x < target.right() {
+                        let tile = sdl2::rect::Rect::new(x, y, size.x, size.y);
+                        result = result.and(canvas.copy(texture, None, tile));
+                        x += size.x as i32;
+                    }
+                    y += size.y as i32;
+                }
+                renderer.report(result);
+            }
+            _ => self.fill_image_rect(image, rect, fill),
+        }
+    }
+
+    fn blur_rect(&mut self, rect: &Rect, radius: u32) {
+        let clip = match self.clip {
+            Some(clip) => clip,
+            None => return,
+        };
+        let matrix = self.full_matrix();
+        let target = match intersect(&self.clip, &device_bounds(&matrix, rect)) {
+            Some(target) => target,
+            None => return,
+        };
+        // radius is scaled by the average scale of the matrix
+        let determinant = matrix[(0, 0)] * matrix[(1, 1)] - matrix[(0, 1)] * matrix[(1, 0)];
+        let radius = (radius as f32 * determinant.abs().sqrt()).round() as u32;
+
+        // SDL has no blur so painted pixels are read back, blurred on the CPU and copied back
+        let mut renderer = self.renderer.borrow_mut();
+        let pixels = match renderer.read_pixels(target) {
+            Ok(pixels) => pixels,
+            Err(error) => {
+                renderer.report(Err(error));
+                return;
+            }
+        };
+        let size = Vector2::<u32>::new(target.width(), target.height());
+        let mut image = Image::from_pixels(&size, pixels);
+        let area = Rect {
+            pos: Point2::<i32>::new(0, 0),
+            size,
+        };
+        gaussian_blur(&mut image, &area, radius);
+
+        renderer.direct(clip);
+        let (canvas, blurred) = match renderer.scratch(target.width(), target.height()) {
+            Some(scratch) => scratch,
+            None => return,
+        };
+        let area = sdl2::rect::Rect::new(0, 0, target.width(), target.height());
+        let updated = blurred
+            .update(area, image.pixels(), target.width() as usize * 4)
+            .map_err(|error| error.to_string());
+        blurred.set_blend_mode(sdl2::render::BlendMode::None);
+        let result = updated.and_then(|_| canvas.copy(blurred, area, target));
+        renderer.report(result);
     }
 }
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+fn to_sdl_color(color: &Color) -> sdl2::pixels::Color {
+    sdl2::pixels::Color {
+        r: color.r,
+        g: color.g,
+        b: color.b,
+        a: color.a,
+    }
+}
+
+//************************************************************************************************
+fn to_sdl_rect(pos: &Point2<i32>, size: &Vector2<u32>) -> sdl2::rect::Rect {
+    sdl2::rect::Rect::new(pos.x, pos.y, size.x, size.y)
+}
+
+//************************************************************************************************
+/// # Arguments
+/// * `clip` - current clip, None means that everything is clipped
+/// * `bounds` - bounds to intersect with, None means empty bounds
+/// # Returns
+/// Intersection of the clip and the bounds or None if it's empty.
+fn intersect(clip: &Option<sdl2::rect::Rect>, bounds: &Option<Rect>) -> Option<sdl2::rect::Rect> {
+    let bounds = (*bounds)?;
+    (*clip)?.intersection(to_sdl_rect(&bounds.pos, &bounds.size))
+}
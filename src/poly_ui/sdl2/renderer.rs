@@ -0,0 +1,708 @@
+extern crate sdl2;
+
+use nalgebra::Vector2;
+use sdl2::pixels::PixelFormatEnum;
+use sdl2::rect::Point;
+use sdl2::render::Canvas;
+use sdl2::render::Texture;
+use sdl2::video::Window;
+use std::rc::Rc;
+use std::rc::Weak;
+use std::time::Duration;
+use std::time::Instant;
+
+/// Number of unused layer textures kept for reuse.
+const LAYER_POOL_LIMIT: usize = 4;
+
+use crate::poly_ui::app::BlendMode;
+use crate::poly_ui::app::Color;
+use crate::poly_ui::app::Image;
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// Offscreen layer started with PainterTrait::push_layer(). Its texture covers only the clip rect
+/// of the painter which started it; while it's the render target the viewport is moved by the
+/// position of the clip rect so painters keep using canvas coordinates. Textures come from a pool
+/// of the Renderer and may be larger than the clip rect. Layers started by fully clipped painters
+/// or without a texture available have no texture.
+pub struct Layer {
+    pub texture: Option<Texture>,
+    pub clip: sdl2::rect::Rect,
+    pub opacity: f32,
+    pub blend_mode: BlendMode,
+}
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// Kind of primitives collected in a Batch.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum BatchKind {
+    Points,
+    Lines,
+    Rects,
+    FillRects,
+}
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// Consecutive primitives of the same kind, clip rect and color which are sent to SDL with a
+/// single call (one per run of connected lines). Buffers are kept between frames so batching doesn't allocate once they are
+/// large enough.
+#[derive(Debug)]
+pub struct Batch {
+    kind: BatchKind,
+    clip: Option<sdl2::rect::Rect>,
+    color: Color,
+    points: Vec<Point>,
+    rects: Vec<sdl2::rect::Rect>,
+    polyline: Vec<Point>,
+}
+
+//************************************************************************************************
+impl Batch {
+    pub fn new() -> Self {
+        Self {
+            kind: BatchKind::Points,
+            clip: None,
+            color: Color {
+                r: 0,
+                g: 0,
+                b: 0,
+                a: 0,
+            },
+            points: vec![],
+            rects: vec![],
+            polyline: vec![],
+        }
+    }
+
+    /// # Returns
+    /// True if there are no primitives in this batch.
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty() && self.rects.is_empty()
+    }
+
+    /// # Returns
+    /// True if primitives with the given parameters can be appended to this batch.
+    pub fn accepts(&self, kind: BatchKind, clip: sdl2::rect::Rect, color: &Color) -> bool {
+        self.is_empty() || (self.kind == kind && self.clip == Some(clip) && self.color == *color)
+    }
+
+    /// Joins batched lines into polylines, each run of lines starting where the previous one ends
+    /// forms one polyline.
+    /// # Arguments
+    /// * `draw` - called with the points of every polyline
+    /// # Returns
+    /// First error returned by `draw`.
+    pub fn polylines<F>(&mut self, mut draw: F) -> Result<(), String>
+    where
+        F: FnMut(&[Point]) -> Result<(), String>,
+    {
+        let mut result = Ok(());
+        let polyline = &mut self.polyline;
+        for line in self.points.chunks_exact(2) {
+            if polyline.last() != Some(&line[0]) {
+                if polyline.len() > 1 {
+                    result = result.and(draw(&polyline[..]));
+                }
+                polyline.clear();
+                polyline.push(line[0]);
+            }
+            polyline.push(line[1]);
+        }
+        if polyline.len() > 1 {
+            result = result.and(draw(&polyline[..]));
+        }
+        polyline.clear();
+        result
+    }
+
+    /// Starts collecting primitives with new parameters. The batch must be empty.
+    fn reset(&mut self, kind: BatchKind, clip: sdl2::rect::Rect, color: &Color) {
+        debug_assert!(self.is_empty());
+        self.kind = kind;
+        self.clip = Some(clip);
+        self.color = *color;
+    }
+}
+
+//************************************************************************************************
+impl Default for Batch {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// State of the SDL rendering shared by all painters of one window. It owns the canvas for the
+/// whole life of the window together with a persistent frame texture all painters render into,
+/// so only damaged regions have to be repainted and the frame is copied to the window when it's
+/// finished. Renderers without render targets paint directly into the window and the whole
+/// window is repainted every frame; the same happens when the frame texture can't be created.
+/// Solid primitives are batched and sent to SDL when a primitive of other kind, clip rect or
+/// color comes or when the frame ends. SDL errors don't stop the frame, the first one is reported
+/// by end_frame().
+pub struct Renderer {
+    canvas: Canvas<Window>,
+    target_texture: bool,
+    frame: Option<Texture>,
+    scratch: Option<Texture>,
+    images: Vec<(Weak<Image>, Texture)>,
+    layers: Vec<Layer>,
+    layer_pool: Vec<Texture>,
+    batch: Batch,
+    frame_start: Option<Instant>,
+    frame_time: Duration,
+    error: Option<String>,
+}
+
+//************************************************************************************************
+impl Renderer {
+    /// # Arguments
+    /// * `canvas` - canvas of the window, it will be used for the whole life of the window
+    /// * `target_texture` - whether to paint into a persistent frame texture, it's ignored when
+    ///   the canvas doesn't support render targets
+    pub fn new(mut canvas: Canvas<Window>, target_texture: bool) -> Self {
+        canvas.set_blend_mode(sdl2::render::BlendMode::Blend);
+        let target_texture = target_texture && canvas.render_target_supported();
+        Self {
+            canvas,
+            target_texture,
+            frame: None,
+            scratch: None,
+            images: vec![],
+            layers: vec![],
+            layer_pool: vec![],
+            batch: Batch::new(),
+            frame_start: None,
+            frame_time: Duration::default(),
+            error: None,
+        }
+    }
+
+    /// # Returns
+    /// Canvas of the window. Rendering with it directly bypasses batching.
+    pub fn canvas(&self) -> &Canvas<Window> {
+        &self.canvas
+    }
+
+    /// # Returns
+    /// Mutable window canvas. Pending batch is flushed first so it can be used for rendering.
+    pub fn canvas_mut(&mut self) -> &mut Canvas<Window> {
+        self.flush();
+        &mut self.canvas
+    }
+
+    /// # Returns
+    /// True if painting goes to render target textures (the frame texture and layers).
+    pub fn target_texture(&self) -> bool {
+        self.target_texture
+    }
+
+    /// # Returns
+    /// Size of the rendered area in pixels.
+    pub fn output_size(&self) -> Vector2<u32> {
+        let (width, height) = self
+            .canvas
+            .output_size()
+            .unwrap_or_else(|_| self.canvas.window().drawable_size());
+        Vector2::<u32>::new(width, height)
+    }
+
+    /// Sets persistent frame texture as the render target. The texture is (re)created when
+    /// there is none yet or when the output size changed.
+    /// # Returns
+    /// False if the frame texture was recreated or there are no render targets and so the whole
+    /// window has to be repainted.
+    pub fn begin_frame(&mut self) -> bool {
+        self.frame_start = Some(Instant::now());
+        if !self.target_texture {
+            // content of the window is undefined after it was presented
+            return false;
+        }
+        let size = self.output_size();
+        let valid = match &self.frame {
+            Some(frame) => {
+                let query = frame.query();
+                query.width == size.x && query.height == size.y
+            }
+            None => false,
+        };
+
+        if !valid {
+            if let Some(frame) = self.frame.take() {
+                unsafe {
+                    frame.destroy();
+                }
+            }
+            match self
+                .canvas
+                .create_texture_target(PixelFormatEnum::RGBA8888, size.x, size.y)
+            {
+                Ok(mut frame) => {
+                    frame.set_blend_mode(sdl2::render::BlendMode::None);
+                    self.frame = Some(frame);
+                }
+                Err(_) => {
+                    // window is painted directly from now on
+                    self.target_texture = false;
+                    return false;
+                }
+            }
+        }
+
+        self.activate_target();
+        valid
+    }
+
+    /// Flushes pending primitives, copies the frame texture to the window and presents it.
+    /// # Returns
+    /// First SDL error which occurred while painting the frame, the frame is presented anyway.
+    pub fn end_frame(&mut self) -> Result<(), String> {
+        self.flush();
+        unsafe {
+            sdl2::sys::SDL_SetRenderTarget(self.canvas.raw(), std::ptr::null_mut());
+        }
+        if let Some(frame) = &self.frame {
+            self.canvas.set_clip_rect(None);
+            let result = self.canvas.copy(frame, None, None);
+            self.report(result);
+        }
+        if let Some(start) = self.frame_start.take() {
+            self.frame_time = start.elapsed();
+        }
+        self.canvas.present();
+        match self.error.take() {
+            Some(error) => Err(error),
+            None => Ok(()),
+        }
+    }
+
+    /// Remembers the error of an SDL call if it's the first one in the current frame.
+    /// # Arguments
+    /// * `result` - result of the SDL call
+    pub fn report(&mut self, result: Result<(), String>) {
+        if let Err(error) = result {
+            self.error.get_or_insert(error);
+        }
+    }
+
+    /// # Returns
+    /// Time spent painting the last frame, from begin_frame() to end_frame() excluding the wait
+    /// for presentation.
+    pub fn frame_time(&self) -> Duration {
+        self.frame_time
+    }
+
+    /// Adds points to the batch.
+    /// # Arguments
+    /// * `kind` - BatchKind::Points or BatchKind::Lines where every two points form a line
+    /// * `clip` - clip rect of the points
+    /// * `color` - color of the points
+    /// * `points` - points in canvas coordinates
+    pub fn batch_points<I: Iterator<Item = Point>>(
+        &mut self,
+        kind: BatchKind,
+        clip: sdl2::rect::Rect,
+        color: &Color,
+        points: I,
+    ) {
+        self.prepare_batch(kind, clip, color);
+        self.batch.points.extend(points);
+    }
+
+    /// Adds rects to the batch.
+    /// # Arguments
+    /// * `kind` - either BatchKind::Rects for outlines or BatchKind::FillRects
+    /// * `clip` - clip rect of the rects
+    /// * `color` - color of the rects
+    /// * `rects` - rects in canvas coordinates
+    pub fn batch_rects<I: Iterator<Item = sdl2::rect::Rect>>(
+        &mut self,
+        kind: BatchKind,
+        clip: sdl2::rect::Rect,
+        color: &Color,
+        rects: I,
+    ) {
+        self.prepare_batch(kind, clip, color);
+        self.batch.rects.extend(rects);
+    }
+
+    /// Flushes pending primitives and sets the given clip rect so the canvas can be used
+    /// directly.
+    /// # Returns
+    /// Window canvas.
+    pub fn direct(&mut self, clip: sdl2::rect::Rect) -> &mut Canvas<Window> {
+        self.flush();
+        if self.canvas.clip_rect() != Some(clip) {
+            self.canvas.set_clip_rect(clip);
+        }
+        &mut self.canvas
+    }
+
+    /// Returns streaming RGBA32 texture at least as large as requested. The texture is reused
+    /// between calls and grows when needed.
+    /// # Arguments
+    /// * `width` - required width
+    /// * `height` - required height
+    /// # Returns
+    /// Window canvas and the texture or None if the texture can't be created.
+    pub fn scratch(
+        &mut self,
+        width: u32,
+        height: u32,
+    ) -> Option<(&mut Canvas<Window>, &mut Texture)> {
+        self.flush();
+        let (old_width, old_height) = match &self.scratch {
+            Some(scratch) => (scratch.query().width, scratch.query().height),
+            None => (0, 0),
+        };
+        if old_width < width || old_height < height {
+            if let Some(scratch) = self.scratch.take() {
+                unsafe {
+                    scratch.destroy();
+                }
+            }
+            self.scratch = self
+                .canvas
+                .create_texture_streaming(
+                    PixelFormatEnum::RGBA32,
+                    width.max(old_width),
+                    height.max(old_height),
+                )
+                .ok();
+        }
+
+        let scratch = self.scratch.as_mut()?;
+        Some((&mut self.canvas, scratch))
+    }
+
+    /// Returns static texture with the content of the given image. Textures are kept while the
+    /// image is alive, so drawing the same image again (e.g. a cached Widget) is a single copy.
+    /// # Arguments
+    /// * `image` - image to upload
+    /// # Returns
+    /// Window canvas and the texture or None if the texture can't be created.
+    pub fn image_texture(&mut self, image: &Rc<Image>) -> Option<(&mut Canvas<Window>, &Texture)> {
+        self.flush();
+        let (alive, dead): (Vec<_>, Vec<_>) = self
+            .images
+            .drain(..)
+            .partition(|(weak, _)| weak.strong_count() > 0);
+        self.images = alive;
+        for (_, texture) in dead {
+            unsafe {
+                texture.destroy();
+            }
+        }
+
+        let index = match self
+            .images
+            .iter()
+            .position(|(weak, _)| weak.as_ptr() == Rc::as_ptr(image))
+        {
+            Some(index) => index,
+            None => {
+                let size = image.size();
+                let mut texture = self
+                    .canvas
+                    .create_texture_static(PixelFormatEnum::RGBA32, size.x, size.y)
+                    .ok()?;
+                if let Err(error) = texture.update(None, image.pixels(), size.x as usize * 4) {
+                    self.report(Err(error.to_string()));
+                    unsafe {
+                        texture.destroy();
+                    }
+                    return None;
+                }
+                texture.set_blend_mode(sdl2::render::BlendMode::Blend);
+                self.images.push((Rc::downgrade(image), texture));
+                self.images.len() - 1
+            }
+        };
+
+        Some((&mut self.canvas, &self.images[index].1))
+    }
+
+    /// # Returns
+    /// Stack of currently painted layers.
+    pub fn layers(&self) -> &Vec<Layer> {
+        &self.layers
+    }
+
+    /// Returns render target texture for a layer, preferably the smallest large enough one from
+    /// the pool of released layer textures.
+    /// # Arguments
+    /// * `width` - required width
+    /// * `height` - required height
+    /// # Returns
+    /// The texture or None if it can't be created.
+    pub fn layer_texture(&mut self, width: u32, height: u32) -> Option<Texture> {
+        let sizes = self.layer_pool.iter().map(|texture| {
+            let query = texture.query();
+            (query.width, query.height)
+        });
+        let pooled = smallest_fitting(sizes, width, height);
+        if let Some(index) = pooled {
+            return Some(self.layer_pool.remove(index));
+        }
+
+        let format = self.canvas.default_pixel_format();
+        self.canvas
+            .create_texture_target(format, width, height)
+            .ok()
+    }
+
+    /// Starts new layer and makes its texture the render target.
+    pub fn push_layer(&mut self, layer: Layer) {
+        self.flush();
+        self.layers.push(layer);
+        self.activate_target();
+    }
+
+    /// Removes the top layer and makes the texture below it the render target.
+    /// # Returns
+    /// Removed layer or None if there are no layers. It should be given back with
+    /// release_layer() so its texture is reused.
+    pub fn pop_layer(&mut self) -> Option<Layer> {
+        self.flush();
+        let layer = self.layers.pop();
+        self.activate_target();
+        layer
+    }
+
+    /// Puts the texture of a popped layer to the pool, the oldest pooled texture is destroyed
+    /// when the pool is full.
+    /// # Arguments
+    /// * `layer` - layer returned by pop_layer()
+    pub fn release_layer(&mut self, layer: Layer) {
+        if let Some(texture) = layer.texture {
+            self.layer_pool.push(texture);
+        }
+        if self.layer_pool.len() > LAYER_POOL_LIMIT {
+            let texture = self.layer_pool.remove(0);
+            unsafe {
+                texture.destroy();
+            }
+        }
+    }
+
+    /// Reads pixels of the current render target.
+    /// # Arguments
+    /// * `rect` - area to read in canvas coordinates
+    /// # Returns
+    /// RGBA32 pixels of the area.
+    pub fn read_pixels(&mut self, rect: sdl2::rect::Rect) -> Result<Vec<u8>, String> {
+        self.flush();
+        let origin = self.target_origin();
+        let viewport = self.canvas.viewport();
+        // the area is read in coordinates of the target itself
+        self.canvas.set_viewport(None);
+        let pixels = self.canvas.read_pixels(
+            sdl2::rect::Rect::new(
+                rect.x() - origin.x(),
+                rect.y() - origin.y(),
+                rect.width(),
+                rect.height(),
+            ),
+            PixelFormatEnum::RGBA32,
+        );
+        self.canvas.set_viewport(viewport);
+        pixels
+    }
+
+    /// Sends pending primitives to SDL.
+    pub fn flush(&mut self) {
+        if self.batch.is_empty() {
+            return;
+        }
+
+        if self.canvas.clip_rect() != self.batch.clip {
+            self.canvas.set_clip_rect(self.batch.clip);
+        }
+        let color = &self.batch.color;
+        self.canvas.set_draw_color(sdl2::pixels::Color::RGBA(
+            color.r, color.g, color.b, color.a,
+        ));
+        let result = match self.batch.kind {
+            BatchKind::Points => self.canvas.draw_points(&self.batch.points[..]),
+            BatchKind::Lines => {
+                let canvas = &mut self.canvas;
+                self.batch.polylines(|points| canvas.draw_lines(points))
+            }
+            BatchKind::Rects => self.canvas.draw_rects(&self.batch.rects[..]),
+            BatchKind::FillRects => self.canvas.fill_rects(&self.batch.rects[..]),
+        };
+        self.report(result);
+        self.batch.points.clear();
+        self.batch.rects.clear();
+    }
+
+    fn prepare_batch(&mut self, kind: BatchKind, clip: sdl2::rect::Rect, color: &Color) {
+        if !self.batch.accepts(kind, clip, color) {
+            self.flush();
+        }
+        if self.batch.is_empty() {
+            self.batch.reset(kind, clip, color);
+        }
+    }
+
+    /// # Returns
+    /// Top layer with a texture.
+    fn top_layer(&self) -> Option<&Layer> {
+        self.layers
+            .iter()
+            .rev()
+            .find(|layer| layer.texture.is_some())
+    }
+
+    /// # Returns
+    /// Canvas position of the top left corner of the render target.
+    fn target_origin(&self) -> Point {
+        match self.top_layer() {
+            Some(layer) => layer.clip.top_left(),
+            None => Point::new(0, 0),
+        }
+    }
+
+    /// Makes the texture of the top layer or the frame texture if there are no layers the render
+    /// target. Viewport of a layer is moved so canvas coordinates map to its clip rect.
+    fn activate_target(&mut self) {
+        let (texture, clip) = match self.top_layer() {
+            Some(layer) => (layer.texture.as_ref(), Some(layer.clip)),
+            None => (self.frame.as_ref(), None),
+        };
+        let target = texture.map_or(std::ptr::null_mut(), |texture| texture.raw());
+        unsafe {
+            sdl2::sys::SDL_SetRenderTarget(self.canvas.raw(), target);
+        }
+        if let Some(clip) = clip {
+            self.canvas.set_viewport(sdl2::rect::Rect::new(
+                -clip.x(),
+                -clip.y(),
+                clip.right() as u32,
+                clip.bottom() as u32,
+            ));
+        } else {
+            self.canvas.set_viewport(None);
+        }
+    }
+}
+
+//************************************************************************************************
+/// # Arguments
+/// * `sizes` - widths and heights of textures
+/// * `width` - required width
+/// * `height` - required height
+/// # Returns
+/// Index of the smallest texture at least as large as required.
+fn smallest_fitting<I: Iterator<Item = (u32, u32)>>(
+    sizes: I,
+    width: u32,
+    height: u32,
+) -> Option<usize> {
+    sizes
+        .enumerate()
+        .filter(|(_, size)| size.0 >= width && size.1 >= height)
+        .min_by_key(|(_, size)| size.0 as u64 * size.1 as u64)
+        .map(|(index, _)| index)
+}
+
+//************************************************************************************************
+impl Drop for Renderer {
+    fn drop(&mut self) {
+        let textures = self
+            .layers
+            .drain(..)
+            .filter_map(|layer| layer.texture)
+            .chain(self.layer_pool.drain(..))
+            .chain(self.images.drain(..).map(|(_, texture)| texture))
+            .chain(self.frame.take())
+            .chain(self.scratch.take());
+        for texture in textures {
+            unsafe {
+                texture.destroy();
+            }
+        }
+    }
+}
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+#[cfg(test)]
+mod tests {
+    // super
+    use super::*;
+
+    //********************************************************************************************
+    #[test]
+    fn batch_accepts() {
+        let clip = sdl2::rect::Rect::new(0, 0, 10, 10);
+        let red = Color {
+            r: 255,
+            g: 0,
+            b: 0,
+            a: 255,
+        };
+        let mut batch = Batch::new();
+        assert!(batch.accepts(BatchKind::Rects, clip, &red));
+
+        batch.reset(BatchKind::Points, clip, &red);
+        batch.points.push(Point::new(1, 1));
+        assert!(batch.accepts(BatchKind::Points, clip, &red));
+        assert!(!batch.accepts(BatchKind::FillRects, clip, &red));
+        assert!(!batch.accepts(BatchKind::Points, sdl2::rect::Rect::new(0, 0, 5, 5), &red));
+        assert!(!batch.accepts(
+            BatchKind::Points,
+            clip,
+            &Color {
+                r: 0,
+                g: 0,
+                b: 0,
+                a: 255
+            }
+        ));
+    }
+
+    //********************************************************************************************
+    #[test]
+    fn batch_polylines() {
+        let clip = sdl2::rect::Rect::new(0, 0, 10, 10);
+        let mut batch = Batch::new();
+        batch.reset(BatchKind::Lines, clip, &Color::default());
+        let lines = [(0, 0), (5, 0), (5, 0), (5, 5), (1, 1), (2, 2)];
+        batch
+            .points
+            .extend(lines.iter().map(|(x, y)| Point::new(*x, *y)));
+
+        let mut polylines = vec![];
+        let result = batch.polylines(|points| {
+            polylines.push(points.to_vec());
+            Err(format!("{}", polylines.len()))
+        });
+        assert_eq!(result, Err("1".to_string()));
+        assert_eq!(
+            polylines,
+            vec![
+                vec![Point::new(0, 0), Point::new(5, 0), Point::new(5, 5)],
+                vec![Point::new(1, 1), Point::new(2, 2)],
+            ]
+        );
+    }
+
+    //********************************************************************************************
+    #[test]
+    fn layer_pool_fitting() {
+        let sizes = [(10, 10), (40, 30), (20, 20), (20, 5)];
+        assert_eq!(smallest_fitting(sizes.iter().copied(), 15, 8), Some(2));
+        assert_eq!(smallest_fitting(sizes.iter().copied(), 10, 10), Some(0));
+        assert_eq!(smallest_fitting(sizes.iter().copied(), 30, 25), Some(1));
+        assert_eq!(smallest_fitting(sizes.iter().copied(), 50, 1), None);
+    }
+}
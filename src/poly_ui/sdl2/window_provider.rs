@@ -5,79 +5,131 @@ use nalgebra::Vector2;
 use sdl2::video::WindowPos;
 use std::{cell::RefCell, rc::Rc};
 
+use super::AppConfig;
 use super::Painter;
+use super::Renderer;
+use super::RendererKind;
+use crate::poly_ui::app::Color;
+use crate::poly_ui::app::Rect;
+use crate::poly_ui::components::logical_painter;
+use crate::poly_ui::components::paint_damage;
 use crate::poly_ui::widgets::WidgetTrait;
 use crate::poly_ui::widgets::WindowProviderTrait;
 
+/// Color of the window below all widgets.
+const BACKGROUND: Color = Color {
+    r: 0,
+    g: 0,
+    b: 0,
+    a: 255,
+};
+
 //************************************************************************************************
 //************************************************************************************************
 //************************************************************************************************
-/// An SDL implementation for the WindowProvider trait.
+/// An SDL implementation for the WindowProvider trait. The canvas of the window is created once
+/// and kept in a Renderer for the whole life of the window.
 pub struct WindowProvider {
-    window: Option<sdl2::video::Window>,
+    renderer: Rc<RefCell<Renderer>>,
 }
 
 //************************************************************************************************
 impl WindowProvider {
-    pub fn new(wnd: sdl2::video::Window) -> Self {
-        WindowProvider { window: Some(wnd) }
+    /// Creates the canvas of the window with the first renderer of the configuration that works.
+    /// # Arguments
+    /// * `wnd` - SDL window
+    /// * `config` - configuration of the renderer
+    /// # Returns
+    /// WindowProvider or error of the last renderer that failed.
+    pub fn new(wnd: sdl2::video::Window, config: &AppConfig) -> Result<Self, String> {
+        // building a canvas consumes the window even when it fails, the context keeps it alive
+        let context = wnd.context();
+        let mut wnd = Some(wnd);
+        let mut error = String::new();
+        for kind in config.renderers() {
+            let wnd = match wnd.take() {
+                Some(wnd) => wnd,
+                None => unsafe { sdl2::video::Window::from_ref(context.clone()) },
+            };
+            let mut builder = match kind {
+                RendererKind::Accelerated => wnd.into_canvas().accelerated(),
+                RendererKind::Software => wnd.into_canvas().software(),
+            };
+            if config.vsync {
+                builder = builder.present_vsync();
+            }
+            if config.target_texture {
+                builder = builder.target_texture();
+            }
+            match builder.build() {
+                Ok(canvas) => {
+                    return Ok(WindowProvider {
+                        renderer: Rc::new(RefCell::new(Renderer::new(
+                            canvas,
+                            config.target_texture,
+                        ))),
+                    })
+                }
+                Err(e) => error = e.to_string(),
+            }
+        }
+        Err(error)
     }
 }
 
 impl WindowProviderTrait for WindowProvider {
-    fn paint_widget(&mut self, widget: &mut dyn WidgetTrait) {
-        let sdl_canvas = Rc::new(RefCell::new(Some(
-            self.window
-                .take()
-                .unwrap()
-                .into_canvas()
-                .present_vsync()
-                .build()
-                .unwrap(),
-        )));
+    fn paint_widget(&mut self, widget: &mut dyn WidgetTrait, regions: &[Rect], scale: f32) {
+        // a new frame texture has undefined content so everything is repainted
+        let full = !self.renderer.borrow_mut().begin_frame();
 
         {
-            let mut painter = Painter::new(sdl_canvas.clone());
-            widget.paint(&mut painter);
+            let mut painter = Painter::new(self.renderer.clone());
+            let mut logical = logical_painter(&mut painter, scale);
+            let window_rect = [Rect {
+                pos: Point2::<i32>::new(0, 0),
+                size: logical.size(),
+            }];
+            let regions = if full { &window_rect[..] } else { regions };
+            paint_damage(widget, &mut *logical, regions, &BACKGROUND);
         }
 
-        if Rc::strong_count(&sdl_canvas) != 1 {
-            panic!();
+        if let Err(error) = self.renderer.borrow_mut().end_frame() {
+            // painting goes on with the next frame, the window may miss some primitives
+            eprintln!("SDL rendering failed: {}", error);
         }
-
-        sdl_canvas.borrow_mut().as_mut().unwrap().present();
-
-        self.window = Some(sdl_canvas.borrow_mut().take().unwrap().into_window());
     }
 
     fn pos(&self) -> Point2<i32> {
-        match &self.window {
-            Some(wnd) => Point2::<i32>::new(wnd.position().0, wnd.position().1),
-            None => panic!(),
-        }
+        let renderer = self.renderer.borrow();
+        let (x, y) = renderer.canvas().window().position();
+        Point2::<i32>::new(x, y)
     }
 
     fn set_pos(&mut self, new: Point2<i32>) {
-        match &mut self.window {
-            Some(wnd) => {
-                wnd.set_position(WindowPos::Positioned(new.x), WindowPos::Positioned(new.y))
-            }
-            None => panic!(),
-        }
+        self.renderer
+            .borrow_mut()
+            .canvas_mut()
+            .window_mut()
+            .set_position(WindowPos::Positioned(new.x), WindowPos::Positioned(new.y));
     }
 
     fn size(&self) -> Vector2<u32> {
-        match &self.window {
-            Some(wnd) => Vector2::<u32>::new(wnd.size().0, wnd.size().1),
-            None => panic!(),
-        }
+        let renderer = self.renderer.borrow();
+        let (width, height) = renderer.canvas().window().size();
+        Vector2::<u32>::new(width, height)
+    }
+
+    fn drawable_size(&self) -> Vector2<u32> {
+        self.renderer.borrow().output_size()
     }
 
     fn set_size(&mut self, new: Vector2<u32>) {
-        match &mut self.window {
-            Some(wnd) => wnd.set_size(new.x, new.y).unwrap(),
-            None => panic!(),
-        }
+        self.renderer
+            .borrow_mut()
+            .canvas_mut()
+            .window_mut()
+            .set_size(new.x, new.y)
+            .unwrap();
     }
 }
 
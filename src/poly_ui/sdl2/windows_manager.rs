@@ -2,8 +2,11 @@ extern crate sdl2;
 
 use std::{cell::RefCell, rc::Rc, vec::Vec};
 
+use super::AppConfig;
+use super::RendererKind;
 use super::WindowProvider;
 use crate::poly_ui::app::WindowsManagerTrait;
+use crate::poly_ui::events::Event;
 use crate::poly_ui::widgets::Window;
 use crate::poly_ui::widgets::WindowTrait;
 
@@ -13,15 +16,35 @@ use crate::poly_ui::widgets::WindowTrait;
 /// SDL implementation for the WindowsManager trait
 pub struct WindowsManager {
     sdl_video: Rc<RefCell<sdl2::VideoSubsystem>>,
+    config: AppConfig,
     windows: Vec<Rc<RefCell<dyn WindowTrait>>>,
+    sdl_ids: Vec<u32>,
 }
 
 //************************************************************************************************
 impl WindowsManager {
-    pub fn new(video: Rc<RefCell<sdl2::VideoSubsystem>>) -> Self {
+    /// # Arguments
+    /// * `video` - SDL video subsystem
+    /// * `config` - configuration of renderers of created windows
+    pub fn new(video: Rc<RefCell<sdl2::VideoSubsystem>>, config: AppConfig) -> Self {
         WindowsManager {
             sdl_video: video,
+            config,
             windows: Vec::new(),
+            sdl_ids: Vec::new(),
+        }
+    }
+
+    /// Passes the input event to the window.
+    /// # Arguments
+    /// * `sdl_id` - SDL id of the window
+    /// * `event` - event in window coordinates
+    /// # Returns
+    /// True if the window handled the event.
+    pub fn send_event(&mut self, sdl_id: u32, event: &Event) -> bool {
+        match self.sdl_ids.iter().position(|id| *id == sdl_id) {
+            Some(index) => self.windows[index].borrow_mut().handle_event(event),
+            None => false,
         }
     }
 }
@@ -33,21 +56,28 @@ impl WindowsManagerTrait for WindowsManager {
         title: &str,
         width: u32,
         height: u32,
-    ) -> Rc<RefCell<dyn WindowTrait>> {
-        let window = self
-            .sdl_video
-            .borrow_mut()
-            .window(title, width, height)
-            .position_centered()
-            .opengl()
-            .build()
-            .map_err(|e| e.to_string())
-            .unwrap();
+    ) -> Result<Rc<RefCell<dyn WindowTrait>>, String> {
+        // OpenGL windows are requested only for accelerated renderers
+        let mut opengl = self.config.renderer == RendererKind::Accelerated;
+        let window = loop {
+            let mut builder = self.sdl_video.borrow_mut().window(title, width, height);
+            builder.position_centered().allow_highdpi();
+            if opengl {
+                builder.opengl();
+            }
+            match builder.build() {
+                Ok(window) => break window,
+                Err(_) if opengl && self.config.fallback => opengl = false,
+                Err(e) => return Err(e.to_string()),
+            }
+        };
 
-        let window_provider = Box::new(WindowProvider::new(window));
+        let sdl_id = window.id();
+        let window_provider = Box::new(WindowProvider::new(window, &self.config)?);
         let window = Rc::new(RefCell::new(Window::new(window_provider)));
+        self.sdl_ids.push(sdl_id);
         self.windows.push(window.clone());
-        window
+        Ok(window)
     }
 
     fn update_windows(&mut self, dt: f32) {
@@ -56,9 +86,11 @@ impl WindowsManagerTrait for WindowsManager {
         }
     }
 
-    fn paint_windows(&mut self) {
+    fn paint_windows(&mut self) -> bool {
+        let mut painted = false;
         for window in &mut self.windows {
-            window.borrow_mut().paint();
+            painted |= window.borrow_mut().paint();
         }
+        painted
     }
 }
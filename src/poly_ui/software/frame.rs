@@ -0,0 +1,132 @@
+// std
+use std::cell::Ref;
+// deps
+use nalgebra::Point2;
+use nalgebra::Vector2;
+// crate
+use crate::poly_ui::app::Color;
+use crate::poly_ui::app::Image;
+use crate::poly_ui::app::PainterTrait;
+use crate::poly_ui::app::Rect;
+use crate::poly_ui::components::logical_painter;
+use crate::poly_ui::components::paint_damage;
+use crate::poly_ui::widgets::WidgetTrait;
+// super
+use super::Painter;
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// Window content painted with the software Painter. The image is kept between frames so only
+/// damaged regions have to be repainted; WindowProviders of backends without their own
+/// rendering (terminal, headless, framebuffer) paint with it and present the image.
+#[derive(Debug, Default)]
+pub struct Frame {
+    painter: Option<Painter>,
+}
+
+//************************************************************************************************
+impl Frame {
+    /// Paints damaged regions of the widget. When the size changed a new image is created and
+    /// the whole widget is painted.
+    /// # Arguments
+    /// * `size` - size of the image in physical pixels
+    /// * `widget` - Layout Widget of the window
+    /// * `regions` - damaged rects in logical coordinates
+    /// * `scale` - number of physical pixels per logical pixel
+    /// * `background` - color below all widgets
+    /// # Returns
+    /// True if the whole image was painted again.
+    pub fn paint(
+        &mut self,
+        size: &Vector2<u32>,
+        widget: &mut dyn WidgetTrait,
+        regions: &[Rect],
+        scale: f32,
+        background: &Color,
+    ) -> bool {
+        let resized = match &self.painter {
+            Some(painter) => painter.size() != *size,
+            None => true,
+        };
+        if resized {
+            self.painter = Some(Painter::new(size));
+        }
+        let painter = self.painter.as_mut().unwrap();
+
+        let mut logical = logical_painter(painter, scale);
+        let window_rect = [Rect {
+            pos: Point2::<i32>::new(0, 0),
+            size: logical.size(),
+        }];
+        let regions = if resized { &window_rect[..] } else { regions };
+        paint_damage(widget, &mut *logical, regions, background);
+        resized
+    }
+
+    /// # Returns
+    /// Image painted in the last frame, None if nothing was painted yet.
+    pub fn image(&self) -> Option<Ref<'_, Image>> {
+        self.painter.as_ref().map(|painter| painter.image())
+    }
+}
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+#[cfg(test)]
+mod tests {
+    // crate
+    use crate::poly_ui::components::Transform;
+    use crate::poly_ui::layouts::CanvasLayout;
+    use crate::poly_ui::widgets::MockWidget;
+    // super
+    use super::*;
+
+    //********************************************************************************************
+    #[test]
+    fn repaint_damage_only() {
+        let layout = CanvasLayout::new();
+        layout.borrow_mut().add_child_with_transform(
+            MockWidget::new().make_owned(),
+            &Transform::new(&Point2::<i32>::new(0, 0), &Vector2::<u32>::new(2, 2)),
+        );
+        let mut frame = Frame::default();
+        assert!(frame.image().is_none());
+
+        let size = Vector2::<u32>::new(4, 4);
+        let region = Rect {
+            pos: Point2::<i32>::new(2, 2),
+            size: Vector2::<u32>::new(2, 2),
+        };
+        assert!(frame.paint(
+            &size,
+            &mut *layout.borrow_mut(),
+            &[region],
+            1.0,
+            &Color::RED
+        ));
+        assert_eq!(frame.image().unwrap().pixel(0, 0), Color::RED);
+
+        // only the damaged region is cleared with the new background
+        assert!(!frame.paint(
+            &size,
+            &mut *layout.borrow_mut(),
+            &[region],
+            1.0,
+            &Color::BLUE
+        ));
+        assert_eq!(frame.image().unwrap().pixel(0, 0), Color::RED);
+        assert_eq!(frame.image().unwrap().pixel(3, 3), Color::BLUE);
+
+        let size = Vector2::<u32>::new(2, 2);
+        assert!(frame.paint(
+            &size,
+            &mut *layout.borrow_mut(),
+            &[region],
+            1.0,
+            &Color::BLUE
+        ));
+        assert_eq!(frame.image().unwrap().size(), size);
+    }
+}
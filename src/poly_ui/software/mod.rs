@@ -0,0 +1,9 @@
+mod frame;
+mod painter;
+mod render;
+
+pub use frame::Frame;
+pub use painter::Painter;
+pub use render::assert_golden;
+pub use render::render_widget;
+pub use render::UPDATE_GOLDEN_VAR;
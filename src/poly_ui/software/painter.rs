@@ -0,0 +1,548 @@
+// std
+use std::{cell::Ref, cell::RefCell, rc::Rc};
+// deps
+use nalgebra::Matrix3;
+use nalgebra::Point2;
+use nalgebra::Vector2;
+// crate
+use crate::poly_ui::app::device_bounds;
+use crate::poly_ui::app::gaussian_blur;
+use crate::poly_ui::app::local_visible_rect;
+use crate::poly_ui::app::BlendMode;
+use crate::poly_ui::app::Brush;
+use crate::poly_ui::app::Color;
+use crate::poly_ui::app::Image;
+use crate::poly_ui::app::Line;
+use crate::poly_ui::app::PainterStateStack;
+use crate::poly_ui::app::PainterTrait;
+use crate::poly_ui::app::Rect;
+use crate::poly_ui::components::Transform;
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// Offscreen layer started with PainterTrait::push_layer(). Its image has the size of the whole
+/// surface so painters can keep using surface coordinates.
+#[derive(Debug)]
+struct Layer {
+    image: Image,
+    clip: Option<Rect>,
+    opacity: f32,
+    blend_mode: BlendMode,
+}
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// Image shared by the root Painter and all its SubPainters together with the stack of layers
+/// that are currently painted into.
+#[derive(Debug)]
+struct Surface {
+    image: Image,
+    layers: Vec<Layer>,
+}
+
+//************************************************************************************************
+impl Surface {
+    /// # Returns
+    /// Image that is currently painted into; the last layer or the surface image.
+    fn target(&mut self) -> &mut Image {
+        match self.layers.last_mut() {
+            Some(layer) => &mut layer.image,
+            None => &mut self.image,
+        }
+    }
+}
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// CPU implementation of the PainterTrait drawing into an in-memory RGBA Image. It follows the
+/// semantics of the SDL Painter: SubPainters are clipped to their rects, strokes are one pixel
+/// wide, colors are alpha blended with what was painted before and clear() replaces pixels.
+/// It doesn't need any display so it can be used for headless rendering and in unit tests.
+#[derive(Debug)]
+pub struct Painter {
+    surface: Rc<RefCell<Surface>>,
+    origin: Matrix3<f32>,
+    size: Vector2<u32>,
+    clip: Option<Rect>,
+    states: PainterStateStack,
+}
+
+//************************************************************************************************
+impl Painter {
+    /// # Arguments
+    /// * `size` - size of the image this painter will paint on
+    /// # Returns
+    /// Root Painter painting on a new transparent image.
+    pub fn new(size: &Vector2<u32>) -> Self {
+        Self::from_image(Image::new(size))
+    }
+
+    /// # Arguments
+    /// * `image` - image this painter will paint on
+    /// # Returns
+    /// Root Painter painting on the given image.
+    pub fn from_image(image: Image) -> Self {
+        let size = image.size();
+        Self {
+            surface: Rc::new(RefCell::new(Surface {
+                image,
+                layers: vec![],
+            })),
+            origin: Matrix3::<f32>::identity(),
+            size,
+            clip: Some(Rect {
+                pos: Point2::<i32>::new(0, 0),
+                size,
+            }),
+            states: PainterStateStack::default(),
+        }
+    }
+
+    /// # Returns
+    /// Image painted so far. Layers that were not popped yet are not included. The image is
+    /// shared with all SubPainters of the root Painter.
+    pub fn image(&self) -> Ref<'_, Image> {
+        Ref::map(self.surface.borrow(), |surface| &surface.image)
+    }
+
+    /// # Returns
+    /// Matrix transforming local coordinates of this painter into surface coordinates.
+    fn full_matrix(&self) -> Matrix3<f32> {
+        self.origin * self.states.current().matrix
+    }
+
+    /// Blends given color into the current target if the pixel isn't clipped.
+    fn plot(&self, target: &mut Image, point: &Point2<i32>, color: &Color) {
+        if let Some(clip) = &self.clip {
+            if clip.contains(point) {
+                let (x, y) = (point.x as u32, point.y as u32);
+                let blended = BlendMode::Normal.blend(color, &target.pixel(x, y), 1.0);
+                target.set_pixel(x, y, &blended);
+            }
+        }
+    }
+
+    /// Strokes given local points with the current brush and matrix.
+    fn stroke_points(&mut self, points: &[Point2<i32>]) {
+        let matrix = self.full_matrix();
+        let brush = &self.states.current().brush;
+        let mut surface = self.surface.borrow_mut();
+        let target = surface.target();
+
+        for point in points {
+            let center = Point2::<f32>::new(point.x as f32 + 0.5, point.y as f32 + 0.5);
+            let device = matrix.transform_point(&center);
+            self.plot(
+                target,
+                &Point2::<i32>::new(device.x.floor() as i32, device.y.floor() as i32),
+                &brush.color_at(&center),
+            );
+        }
+    }
+
+    /// Strokes given local line with the current brush and matrix.
+    fn stroke_line(&mut self, line: &Line) {
+        let matrix = self.full_matrix();
+        let inverse = match matrix.try_inverse() {
+            Some(inverse) => inverse,
+            None => return,
+        };
+        let map = |point: &Point2<i32>| {
+            let device = matrix.transform_point(&Point2::<f32>::new(
+                point.x as f32 + 0.5,
+                point.y as f32 + 0.5,
+            ));
+            Point2::<i32>::new(device.x.floor() as i32, device.y.floor() as i32)
+        };
+        let device_line = Line {
+            start: map(&line.start),
+            end: map(&line.end),
+        };
+
+        let brush = &self.states.current().brush;
+        let mut surface = self.surface.borrow_mut();
+        let target = surface.target();
+        for point in device_line.rasterize() {
+            let local = inverse.transform_point(&Point2::<f32>::new(
+                point.x as f32 + 0.5,
+                point.y as f32 + 0.5,
+            ));
+            self.plot(target, &point, &brush.color_at(&local));
+        }
+    }
+
+    /// Fills given local rect using the given matrix and the current brush. Every pixel whose
+    /// center lies inside of the transformed rect is painted.
+    /// # Arguments
+    /// * `matrix` - matrix from the rect coordinates to surface coordinates
+    /// * `rect` - rect to fill
+    /// * `blend` - if false pixels are replaced instead of being blended with the surface
+    fn fill(&mut self, matrix: &Matrix3<f32>, rect: &Rect, blend: bool) {
+        let bounds = match (&self.clip, device_bounds(matrix, rect)) {
+            (Some(clip), Some(bounds)) => match clip.intersection(&bounds) {
+                Some(bounds) => bounds,
+                None => return,
+            },
+            _ => return,
+        };
+        let inverse = match matrix.try_inverse() {
+            Some(inverse) => inverse,
+            None => return,
+        };
+
+        let brush = &self.states.current().brush;
+        let mut surface = self.surface.borrow_mut();
+        let target = surface.target();
+        for y in bounds.pos.y..bounds.pos.y + bounds.size.y as i32 {
+            for x in bounds.pos.x..bounds.pos.x + bounds.size.x as i32 {
+                let local =
+                    inverse.transform_point(&Point2::<f32>::new(x as f32 + 0.5, y as f32 + 0.5));
+                if local.x < rect.pos.x as f32
+                    || local.y < rect.pos.y as f32
+                    || local.x >= (rect.pos.x + rect.size.x as i32) as f32
+                    || local.y >= (rect.pos.y + rect.size.y as i32) as f32
+                {
+                    continue;
+                }
+
+                let color = brush.color_at(&local);
+                if blend {
+                    self.plot(target, &Point2::<i32>::new(x, y), &color);
+                } else {
+                    target.set_pixel(x as u32, y as u32, &color);
+                }
+            }
+        }
+    }
+}
+
+//************************************************************************************************
+impl PainterTrait for Painter {
+    fn sub_painter(&self, transform: &Transform) -> Box<dyn PainterTrait> {
+        let origin = self.full_matrix()
+            * Matrix3::<f32>::new_translation(&Vector2::<f32>::new(
+                transform.pos.x as f32,
+                transform.pos.y as f32,
+            ));
+        let bounds = device_bounds(
+            &origin,
+            &Rect {
+                pos: Point2::<i32>::new(0, 0),
+                size: transform.size,
+            },
+        );
+
+        Box::new(Painter {
+            surface: self.surface.clone(),
+            origin,
+            size: transform.size,
+            clip: match (&self.clip, bounds) {
+                (Some(clip), Some(bounds)) => clip.intersection(&bounds),
+                _ => None,
+            },
+            states: PainterStateStack::new(&self.states.current().brush),
+        })
+    }
+
+    fn size(&self) -> Vector2<u32> {
+        self.size
+    }
+
+    fn visible_rect(&self) -> Option<Rect> {
+        Some(local_visible_rect(&self.full_matrix(), &self.clip))
+    }
+
+    fn clear(&mut self) {
+        let origin = self.origin;
+        self.fill(
+            &origin,
+            &Rect {
+                pos: Point2::<i32>::new(0, 0),
+                size: self.size,
+            },
+            false,
+        );
+    }
+
+    fn brush(&self) -> Brush {
+        self.states.current().brush.clone()
+    }
+
+    fn set_brush(&mut self, new: &Brush) {
+        self.states.current_mut().brush = new.clone();
+    }
+
+    fn save(&mut self) {
+        self.states.save();
+    }
+
+    fn restore(&mut self) {
+        self.states.restore();
+    }
+
+    fn matrix(&self) -> Matrix3<f32> {
+        self.states.current().matrix
+    }
+
+    fn device_matrix(&self) -> Matrix3<f32> {
+        self.full_matrix()
+    }
+
+    fn set_matrix(&mut self, new: &Matrix3<f32>) {
+        self.states.current_mut().matrix = *new;
+    }
+
+    fn push_layer(&mut self, opacity: f32, blend_mode: BlendMode) {
+        let size = self.surface.borrow().image.size();
+        self.surface.borrow_mut().layers.push(Layer {
+            image: Image::new(&size),
+            clip: self.clip,
+            opacity,
+            blend_mode,
+        });
+    }
+
+    fn pop_layer(&mut self) {
+        let mut surface = self.surface.borrow_mut();
+        let layer = match surface.layers.pop() {
+            Some(layer) => layer,
+            None => return,
+        };
+        let clip = match layer.clip {
+            Some(clip) => clip,
+            None => return,
+        };
+
+        let target = surface.target();
+        for y in clip.pos.y as u32..clip.pos.y as u32 + clip.size.y {
+            for x in clip.pos.x as u32..clip.pos.x as u32 + clip.size.x {
+                let blended = layer.blend_mode.blend(
+                    &layer.image.pixel(x, y),
+                    &target.pixel(x, y),
+                    layer.opacity,
+                );
+                target.set_pixel(x, y, &blended);
+            }
+        }
+    }
+
+    fn draw_point(&mut self, point: &Point2<i32>) {
+        self.stroke_points(&[*point]);
+    }
+
+    fn draw_points(&mut self, points: &[Point2<i32>]) {
+        self.stroke_points(points);
+    }
+
+    fn draw_line(&mut self, line: &Line) {
+        self.stroke_line(line);
+    }
+
+    fn draw_lines(&mut self, lines: &[Line]) {
+        for line in lines {
+            self.stroke_line(line);
+        }
+    }
+
+    fn draw_rect(&mut self, rect: Rect) {
+        self.draw_rects(&[rect]);
+    }
+
+    fn draw_rects(&mut self, rects: &[Rect]) {
+        for rect in rects {
+            for line in rect.outline() {
+                self.stroke_line(&line);
+            }
+        }
+    }
+
+    fn fill_rect(&mut self, rect: Rect) {
+        self.fill_rects(&[rect]);
+    }
+
+    fn fill_rects(&mut self, rects: &[Rect]) {
+        let matrix = self.full_matrix();
+        for rect in rects {
+            self.fill(&matrix, rect, true);
+        }
+    }
+
+    fn blur_rect(&mut self, rect: &Rect, radius: u32) {
+        let matrix = self.full_matrix();
+        let bounds = match (&self.clip, device_bounds(&matrix, rect)) {
+            (Some(clip), Some(bounds)) => match clip.intersection(&bounds) {
+                Some(bounds) => bounds,
+                None => return,
+            },
+            _ => return,
+        };
+        // radius is scaled by the average scale of the matrix
+        let determinant = matrix[(0, 0)] * matrix[(1, 1)] - matrix[(0, 1)] * matrix[(1, 0)];
+        let radius = (radius as f32 * determinant.abs().sqrt()).round() as u32;
+        gaussian_blur(self.surface.borrow_mut().target(), &bounds, radius);
+    }
+}
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+#[cfg(test)]
+mod tests {
+    // super
+    use super::*;
+
+    //********************************************************************************************
+    const RED: Color = Color {
+        r: 255,
+        g: 0,
+        b: 0,
+        a: 255,
+    };
+
+    //********************************************************************************************
+    const TRANSPARENT: Color = Color {
+        r: 0,
+        g: 0,
+        b: 0,
+        a: 0,
+    };
+
+    //********************************************************************************************
+    #[test]
+    fn fill_rect() {
+        let mut painter = Painter::new(&Vector2::<u32>::new(4, 4));
+        painter.set_draw_color(&RED);
+        painter.fill_rect(Rect {
+            pos: Point2::<i32>::new(1, 1),
+            size: Vector2::<u32>::new(2, 2),
+        });
+
+        let image = painter.image();
+        assert_eq!(image.pixel(0, 0), TRANSPARENT);
+        assert_eq!(image.pixel(1, 1), RED);
+        assert_eq!(image.pixel(2, 2), RED);
+        assert_eq!(image.pixel(3, 3), TRANSPARENT);
+    }
+
+    //********************************************************************************************
+    #[test]
+    fn sub_painter_is_translated_and_clipped() {
+        let mut painter = Painter::new(&Vector2::<u32>::new(4, 4));
+        let mut sub_painter = painter.sub_painter(&Transform::new(
+            &Point2::<i32>::new(2, 2),
+            &Vector2::<u32>::new(1, 1),
+        ));
+        sub_painter.set_draw_color(&RED);
+        sub_painter.fill_rect(Rect {
+            pos: Point2::<i32>::new(-5, -5),
+            size: Vector2::<u32>::new(20, 20),
+        });
+        painter.draw_point(&Point2::<i32>::new(0, 0));
+
+        let image = painter.image();
+        assert_eq!(image.pixel(0, 0), painter.draw_color());
+        assert_eq!(image.pixel(1, 1), TRANSPARENT);
+        assert_eq!(image.pixel(2, 2), RED);
+        assert_eq!(image.pixel(3, 3), TRANSPARENT);
+    }
+
+    //********************************************************************************************
+    #[test]
+    fn draw_rect_and_line() {
+        let mut painter = Painter::new(&Vector2::<u32>::new(3, 3));
+        painter.set_draw_color(&RED);
+        painter.draw_rect(Rect {
+            pos: Point2::<i32>::new(0, 0),
+            size: Vector2::<u32>::new(3, 3),
+        });
+        painter.draw_line(&Line {
+            start: Point2::<i32>::new(0, 0),
+            end: Point2::<i32>::new(2, 2),
+        });
+
+        let image = painter.image();
+        for y in 0..3 {
+            for x in 0..3 {
+                assert_eq!(image.pixel(x, y), RED);
+            }
+        }
+    }
+
+    //********************************************************************************************
+    #[test]
+    fn transform_stack() {
+        let mut painter = Painter::new(&Vector2::<u32>::new(4, 4));
+        painter.set_draw_color(&RED);
+        painter.save();
+        painter.translate(2.0, 0.0);
+        painter.scale(2.0, 2.0);
+        painter.fill_rect(Rect {
+            pos: Point2::<i32>::new(0, 0),
+            size: Vector2::<u32>::new(1, 1),
+        });
+        painter.restore();
+        painter.draw_point(&Point2::<i32>::new(0, 3));
+
+        let image = painter.image();
+        assert_eq!(image.pixel(1, 1), TRANSPARENT);
+        assert_eq!(image.pixel(2, 0), RED);
+        assert_eq!(image.pixel(3, 1), RED);
+        assert_eq!(image.pixel(0, 3), RED);
+    }
+
+    //********************************************************************************************
+    #[test]
+    fn layer_opacity() {
+        let mut painter = Painter::new(&Vector2::<u32>::new(1, 1));
+        painter.set_draw_color(&Color {
+            r: 0,
+            g: 0,
+            b: 0,
+            a: 255,
+        });
+        painter.clear();
+        painter.push_layer(0.5, BlendMode::Normal);
+        painter.set_draw_color(&RED);
+        painter.draw_point(&Point2::<i32>::new(0, 0));
+        painter.draw_point(&Point2::<i32>::new(0, 0));
+        painter.pop_layer();
+
+        assert_eq!(
+            painter.image().pixel(0, 0),
+            Color {
+                r: 128,
+                g: 0,
+                b: 0,
+                a: 255
+            }
+        );
+    }
+    //********************************************************************************************
+    #[test]
+    fn blur_rect_is_clipped() {
+        let mut painter = Painter::new(&Vector2::<u32>::new(8, 1));
+        painter.set_draw_color(&RED);
+        painter.draw_point(&Point2::<i32>::new(2, 0));
+        painter.draw_point(&Point2::<i32>::new(5, 0));
+        let mut sub_painter = painter.sub_painter(&Transform::new(
+            &Point2::<i32>::new(0, 0),
+            &Vector2::<u32>::new(4, 1),
+        ));
+        sub_painter.blur_rect(
+            &Rect {
+                pos: Point2::<i32>::new(0, 0),
+                size: Vector2::<u32>::new(8, 1),
+            },
+            2,
+        );
+
+        let image = painter.image();
+        assert!(image.pixel(2, 0).a < 255);
+        assert!(image.pixel(1, 0).a > 0);
+        assert_eq!(image.pixel(4, 0), TRANSPARENT);
+        assert_eq!(image.pixel(5, 0), RED);
+    }
+}
@@ -0,0 +1,111 @@
+// std
+use std::env;
+use std::path::Path;
+// deps
+use nalgebra::Vector2;
+// crate
+use crate::poly_ui::app::Image;
+use crate::poly_ui::widgets::WidgetTrait;
+// super
+use super::Painter;
+
+/// Name of the environment variable that makes assert_golden() overwrite golden images with
+/// the rendered ones instead of comparing them.
+pub const UPDATE_GOLDEN_VAR: &str = "POLY_UI_UPDATE_GOLDEN";
+
+/// Paints given widget with all its children offscreen.
+/// # Arguments
+/// * `widget` - root of the painted subtree, it is painted at position (0, 0)
+/// * `size` - size of the widget and of the resulting image
+/// # Returns
+/// Image with everything the widget painted over a transparent background.
+pub fn render_widget(widget: &mut dyn WidgetTrait, size: &Vector2<u32>) -> Image {
+    let mut painter = Painter::new(size);
+    widget.paint(&mut painter);
+    let image = painter.image().clone();
+    image
+}
+
+/// Test helper comparing rendered image with the golden one stored in a PNG file. If the images
+/// differ the rendered image is written next to the golden one with `.actual.png` extension and
+/// the visualization of the differences with `.diff.png` extension and the function panics.
+/// If the golden image doesn't exist yet or the POLY_UI_UPDATE_GOLDEN environment variable is
+/// set the rendered image is saved as the new golden image; in the first case the function
+/// panics as well so new golden images are always reviewed.
+/// # Arguments
+/// * `image` - rendered image
+/// * `golden` - path of the golden PNG file
+/// * `tolerance` - maximal allowed difference of a single channel
+pub fn assert_golden<P: AsRef<Path>>(image: &Image, golden: P, tolerance: u8) {
+    let golden = golden.as_ref();
+    let exists = golden.exists();
+    if !exists || env::var_os(UPDATE_GOLDEN_VAR).is_some() {
+        if let Some(dir) = golden.parent() {
+            std::fs::create_dir_all(dir).unwrap();
+        }
+        image.save_png(golden).unwrap();
+        assert!(
+            exists,
+            "golden image {} didn't exist and was created, review it and run the test again",
+            golden.display()
+        );
+        return;
+    }
+
+    let expected = Image::load_png(golden).unwrap();
+    let diff = image.compare(&expected, tolerance);
+    if !diff.is_match() {
+        let actual_path = golden.with_extension("actual.png");
+        let diff_path = golden.with_extension("diff.png");
+        image.save_png(&actual_path).unwrap();
+        diff.image.save_png(&diff_path).unwrap();
+        panic!(
+            "image doesn't match golden image {}: size matches: {}, {} pixels differ, max \
+             difference {}; rendered image saved to {}, differences saved to {}",
+            golden.display(),
+            diff.size_matches,
+            diff.mismatched_pixels,
+            diff.max_difference,
+            actual_path.display(),
+            diff_path.display()
+        );
+    }
+}
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+#[cfg(test)]
+mod tests {
+    // deps
+    use nalgebra::Point2;
+    // crate
+    use crate::poly_ui::app::Color;
+    use crate::poly_ui::components::Transform;
+    use crate::poly_ui::controls::PushButton;
+    use crate::poly_ui::layouts::CanvasLayout;
+    // super
+    use super::*;
+
+    //********************************************************************************************
+    #[test]
+    fn render_push_button() {
+        let layout = CanvasLayout::new();
+        layout.borrow_mut().add_child_with_transform(
+            PushButton::new().make_owned(),
+            &Transform::new(&Point2::<i32>::new(2, 3), &Vector2::<u32>::new(10, 6)),
+        );
+
+        let image = render_widget(&mut *layout.borrow_mut(), &Vector2::<u32>::new(16, 12));
+        assert_eq!(image.size(), Vector2::<u32>::new(16, 12));
+        assert_eq!(image.pixel(4, 3), Color::rgb(110, 110, 110));
+        assert_eq!(image.pixel(5, 5), Color::rgb(60, 60, 60));
+        assert_eq!(image.pixel(1, 1).a, 0);
+
+        assert_golden(
+            &image,
+            concat!(env!("CARGO_MANIFEST_DIR"), "/tests/golden/push_button.png"),
+            0,
+        );
+    }
+}
@@ -0,0 +1,59 @@
+// deps
+use nalgebra::Vector2;
+// crate
+use crate::poly_ui::widgets::WidgetTrait;
+use crate::poly_ui::widgets::WindowTrait;
+// super
+use super::Painter;
+
+/// Paints given widget with all its children into a new SVG document.
+/// # Arguments
+/// * `widget` - root of the painted subtree, it is painted at position (0, 0)
+/// * `size` - size of the widget and of the document
+/// # Returns
+/// Text of the SVG document.
+pub fn export_widget(widget: &mut dyn WidgetTrait, size: &Vector2<u32>) -> String {
+    let mut painter = Painter::new(size);
+    widget.paint(&mut painter);
+    painter.to_svg()
+}
+
+/// Paints Layout Widget of the given window into a new SVG document of the window size.
+/// # Arguments
+/// * `window` - exported window
+/// # Returns
+/// Text of the SVG document.
+pub fn export_window(window: &dyn WindowTrait) -> String {
+    export_widget(&mut *window.widget().borrow_mut(), &window.size())
+}
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+#[cfg(test)]
+mod tests {
+    // deps
+    use nalgebra::Point2;
+    // crate
+    use crate::poly_ui::components::Transform;
+    use crate::poly_ui::controls::PushButton;
+    use crate::poly_ui::layouts::CanvasLayout;
+    // super
+    use super::*;
+
+    //********************************************************************************************
+    #[test]
+    fn export_push_button() {
+        let layout = CanvasLayout::new();
+        layout.borrow_mut().add_child_with_transform(
+            PushButton::new().make_owned(),
+            &Transform::new(&Point2::<i32>::new(2, 3), &Vector2::<u32>::new(10, 6)),
+        );
+
+        let svg = export_widget(&mut *layout.borrow_mut(), &Vector2::<u32>::new(16, 12));
+        assert!(svg.starts_with("<svg "));
+        assert!(svg.contains("<g transform=\"matrix(1 0 0 1 2 3)\" clip-path=\"url(#clip0)\">"));
+        assert!(svg.contains("stroke=\"rgb(110,110,110)\""));
+        assert!(svg.ends_with("</svg>\n"));
+    }
+}
@@ -0,0 +1,6 @@
+mod export;
+mod painter;
+
+pub use export::export_widget;
+pub use export::export_window;
+pub use painter::Painter;
@@ -0,0 +1,604 @@
+// std
+use std::fmt::Write;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::{cell::RefCell, rc::Rc};
+// deps
+use nalgebra::Matrix3;
+use nalgebra::Point2;
+use nalgebra::Vector2;
+// crate
+use crate::poly_ui::app::BlendMode;
+use crate::poly_ui::app::Brush;
+use crate::poly_ui::app::Color;
+use crate::poly_ui::app::GradientStop;
+use crate::poly_ui::app::Line;
+use crate::poly_ui::app::PainterStateStack;
+use crate::poly_ui::app::PainterTrait;
+use crate::poly_ui::app::Rect;
+use crate::poly_ui::app::SpreadMode;
+use crate::poly_ui::components::Transform;
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// Content of an SVG group; either a complete element or a nested group.
+#[derive(Debug)]
+enum Node {
+    Element(String),
+    Group(usize),
+}
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// SVG `<g>` element. Groups are stored in the Document and referenced by their indices so
+/// painters can keep adding content to them.
+#[derive(Debug, Default)]
+struct Group {
+    attributes: String,
+    children: Vec<Node>,
+}
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// SVG document shared by the root Painter and all its SubPainters. Group 0 is the root group.
+#[derive(Debug)]
+struct Document {
+    size: Vector2<u32>,
+    defs: Vec<String>,
+    groups: Vec<Group>,
+}
+
+//************************************************************************************************
+impl Document {
+    /// Adds new group as the last child of the given one.
+    /// # Returns
+    /// Index of the new group.
+    fn add_group(&mut self, parent: usize, attributes: String) -> usize {
+        self.groups.push(Group {
+            attributes,
+            children: vec![],
+        });
+        let idx = self.groups.len() - 1;
+        self.groups[parent].children.push(Node::Group(idx));
+        idx
+    }
+
+    /// Adds new definition with a unique id.
+    /// # Arguments
+    /// * `prefix` - prefix of the id
+    /// * `definition` - function creating the definition element from the id
+    /// # Returns
+    /// Id of the new definition.
+    fn add_def<F: FnOnce(&str) -> String>(&mut self, prefix: &str, definition: F) -> String {
+        let id = format!("{}{}", prefix, self.defs.len());
+        self.defs.push(definition(&id));
+        id
+    }
+
+    fn write_group(&self, out: &mut String, idx: usize, indent: usize) {
+        let group = &self.groups[idx];
+        writeln!(
+            out,
+            "{:indent$}<g{}>",
+            "",
+            group.attributes,
+            indent = indent
+        )
+        .unwrap();
+        for child in &group.children {
+            match child {
+                Node::Element(element) => {
+                    writeln!(out, "{:indent$}{}", "", element, indent = indent + 2).unwrap()
+                }
+                Node::Group(child) => self.write_group(out, *child, indent + 2),
+            }
+        }
+        writeln!(out, "{:indent$}</g>", "", indent = indent).unwrap();
+    }
+
+    fn to_svg(&self) -> String {
+        let mut out = String::new();
+        writeln!(
+            out,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" \
+             xmlns:xlink=\"http://www.w3.org/1999/xlink\" \
+             width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">",
+            w = self.size.x,
+            h = self.size.y
+        )
+        .unwrap();
+        if !self.defs.is_empty() {
+            out.push_str("  <defs>\n");
+            for def in &self.defs {
+                writeln!(out, "    {}", def).unwrap();
+            }
+            out.push_str("  </defs>\n");
+        }
+        self.write_group(&mut out, 0, 2);
+        out.push_str("</svg>\n");
+        out
+    }
+}
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// Implementation of the PainterTrait writing an SVG document. Every SubPainter becomes a group
+/// translated to its position and clipped to its rect, layers become groups with opacity and
+/// mix-blend-mode, strokes are one unit wide and aligned to the pixel grid like in the other
+/// painters. Image patterns are embedded as PNG images and are always repeated.
+#[derive(Debug)]
+pub struct Painter {
+    document: Rc<RefCell<Document>>,
+    groups: Vec<usize>,
+    size: Vector2<u32>,
+    states: PainterStateStack,
+}
+
+//************************************************************************************************
+impl Painter {
+    /// # Arguments
+    /// * `size` - size of the SVG document
+    /// # Returns
+    /// Root Painter painting on a new, empty document.
+    pub fn new(size: &Vector2<u32>) -> Self {
+        Self {
+            document: Rc::new(RefCell::new(Document {
+                size: *size,
+                defs: vec![],
+                groups: vec![Group::default()],
+            })),
+            groups: vec![0],
+            size: *size,
+            states: PainterStateStack::default(),
+        }
+    }
+
+    /// # Returns
+    /// Whole SVG document painted so far by this Painter and all Painters sharing it.
+    pub fn to_svg(&self) -> String {
+        self.document.borrow().to_svg()
+    }
+
+    /// Writes the SVG document to a file.
+    /// # Arguments
+    /// * `path` - path of the file, existing file will be overwritten
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        fs::write(path, self.to_svg())
+    }
+
+    /// Adds element to the group this Painter currently paints into.
+    fn push_element(&self, element: String) {
+        let group = *self.groups.last().unwrap();
+        self.document.borrow_mut().groups[group]
+            .children
+            .push(Node::Element(element));
+    }
+
+    /// # Returns
+    /// Transform attribute for the current matrix or empty string for the identity matrix.
+    fn transform_attribute(&self) -> String {
+        let matrix = &self.states.current().matrix;
+        if *matrix == Matrix3::<f32>::identity() {
+            String::new()
+        } else {
+            format!(" transform=\"{}\"", matrix_to_svg(matrix))
+        }
+    }
+
+    /// # Arguments
+    /// * `kind` - either "fill" or "stroke"
+    /// # Returns
+    /// Attributes painting given kind with the current brush.
+    fn paint_attributes(&self, kind: &str) -> String {
+        let paint = match &self.states.current().brush {
+            Brush::Solid(color) => return color_attributes(kind, color),
+            Brush::LinearGradient(gradient) => {
+                self.document.borrow_mut().add_def("gradient", |id| {
+                    format!(
+                        "<linearGradient id=\"{}\" gradientUnits=\"userSpaceOnUse\" x1=\"{}\" \
+                         y1=\"{}\" x2=\"{}\" y2=\"{}\" spreadMethod=\"{}\">{}</linearGradient>",
+                        id,
+                        gradient.start.x,
+                        gradient.start.y,
+                        gradient.end.x,
+                        gradient.end.y,
+                        spread_to_svg(gradient.spread),
+                        stops_to_svg(&gradient.stops)
+                    )
+                })
+            }
+            Brush::RadialGradient(gradient) => {
+                self.document.borrow_mut().add_def("gradient", |id| {
+                    format!(
+                        "<radialGradient id=\"{}\" gradientUnits=\"userSpaceOnUse\" cx=\"{}\" \
+                         cy=\"{}\" r=\"{}\" spreadMethod=\"{}\">{}</radialGradient>",
+                        id,
+                        gradient.center.x,
+                        gradient.center.y,
+                        gradient.radius,
+                        spread_to_svg(gradient.spread),
+                        stops_to_svg(&gradient.stops)
+                    )
+                })
+            }
+            Brush::Pattern(pattern) => {
+                let size = pattern.image.size();
+                let mut png = vec![];
+                pattern.image.write_png(&mut png).unwrap();
+                self.document.borrow_mut().add_def("pattern", |id| {
+                    format!(
+                        "<pattern id=\"{}\" patternUnits=\"userSpaceOnUse\" x=\"{}\" y=\"{}\" \
+                         width=\"{w}\" height=\"{h}\"><image width=\"{w}\" height=\"{h}\" \
+                         style=\"image-rendering:pixelated\" \
+                         xlink:href=\"data:image/png;base64,{}\"/></pattern>",
+                        id,
+                        pattern.offset.x,
+                        pattern.offset.y,
+                        base64(&png),
+                        w = size.x,
+                        h = size.y
+                    )
+                })
+            }
+        };
+        format!(" {}=\"url(#{})\"", kind, paint)
+    }
+
+    /// Adds element filling given rects with the current brush and matrix.
+    fn fill(&self, rects: &[Rect]) {
+        let rects: Vec<&Rect> = rects
+            .iter()
+            .filter(|rect| rect.size.x > 0 && rect.size.y > 0)
+            .collect();
+        if rects.is_empty() {
+            return;
+        }
+
+        let element = if rects.len() == 1 {
+            format!(
+                "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\"{}{}/>",
+                rects[0].pos.x,
+                rects[0].pos.y,
+                rects[0].size.x,
+                rects[0].size.y,
+                self.paint_attributes("fill"),
+                self.transform_attribute()
+            )
+        } else {
+            let mut path = String::new();
+            for rect in rects {
+                write!(
+                    path,
+                    "M{} {}h{}v{}h-{}z",
+                    rect.pos.x, rect.pos.y, rect.size.x, rect.size.y, rect.size.x
+                )
+                .unwrap();
+            }
+            format!(
+                "<path d=\"{}\"{}{}/>",
+                path,
+                self.paint_attributes("fill"),
+                self.transform_attribute()
+            )
+        };
+        self.push_element(element);
+    }
+
+    /// Adds element stroking given lines with the current brush and matrix.
+    fn stroke(&self, lines: &[Line]) {
+        if lines.is_empty() {
+            return;
+        }
+
+        let mut path = String::new();
+        for line in lines {
+            write!(
+                path,
+                "M{} {}L{} {}",
+                line.start.x as f32 + 0.5,
+                line.start.y as f32 + 0.5,
+                line.end.x as f32 + 0.5,
+                line.end.y as f32 + 0.5
+            )
+            .unwrap();
+        }
+        self.push_element(format!(
+            "<path d=\"{}\" fill=\"none\"{} stroke-width=\"1\" stroke-linecap=\"square\"{}/>",
+            path,
+            self.paint_attributes("stroke"),
+            self.transform_attribute()
+        ));
+    }
+}
+
+//************************************************************************************************
+impl PainterTrait for Painter {
+    fn sub_painter(&self, transform: &Transform) -> Box<dyn PainterTrait> {
+        let matrix = self.states.current().matrix
+            * Matrix3::<f32>::new_translation(&Vector2::<f32>::new(
+                transform.pos.x as f32,
+                transform.pos.y as f32,
+            ));
+
+        let mut document = self.document.borrow_mut();
+        let clip = document.add_def("clip", |id| {
+            format!(
+                "<clipPath id=\"{}\"><rect x=\"0\" y=\"0\" width=\"{}\" height=\"{}\"/></clipPath>",
+                id, transform.size.x, transform.size.y
+            )
+        });
+        let group = document.add_group(
+            *self.groups.last().unwrap(),
+            format!(
+                " transform=\"{}\" clip-path=\"url(#{})\"",
+                matrix_to_svg(&matrix),
+                clip
+            ),
+        );
+
+        Box::new(Painter {
+            document: self.document.clone(),
+            groups: vec![group],
+            size: transform.size,
+            states: PainterStateStack::new(&self.states.current().brush),
+        })
+    }
+
+    fn size(&self) -> Vector2<u32> {
+        self.size
+    }
+
+    fn clear(&mut self) {
+        // everything painted so far into this group would be covered anyway
+        let group = *self.groups.last().unwrap();
+        self.document.borrow_mut().groups[group].children.clear();
+        self.push_element(format!(
+            "<rect x=\"0\" y=\"0\" width=\"{}\" height=\"{}\"{}/>",
+            self.size.x,
+            self.size.y,
+            self.paint_attributes("fill")
+        ));
+    }
+
+    fn brush(&self) -> Brush {
+        self.states.current().brush.clone()
+    }
+
+    fn set_brush(&mut self, new: &Brush) {
+        self.states.current_mut().brush = new.clone();
+    }
+
+    fn save(&mut self) {
+        self.states.save();
+    }
+
+    fn restore(&mut self) {
+        self.states.restore();
+    }
+
+    fn matrix(&self) -> Matrix3<f32> {
+        self.states.current().matrix
+    }
+
+    fn set_matrix(&mut self, new: &Matrix3<f32>) {
+        self.states.current_mut().matrix = *new;
+    }
+
+    fn push_layer(&mut self, opacity: f32, blend_mode: BlendMode) {
+        let blend = match blend_mode {
+            BlendMode::Normal => String::new(),
+            BlendMode::Add => " style=\"mix-blend-mode:plus-lighter\"".to_string(),
+            BlendMode::Multiply => " style=\"mix-blend-mode:multiply\"".to_string(),
+            BlendMode::Screen => " style=\"mix-blend-mode:screen\"".to_string(),
+        };
+        let group = self.document.borrow_mut().add_group(
+            *self.groups.last().unwrap(),
+            format!(" opacity=\"{}\"{}", opacity.clamp(0.0, 1.0), blend),
+        );
+        self.groups.push(group);
+    }
+
+    fn pop_layer(&mut self) {
+        if self.groups.len() > 1 {
+            self.groups.pop();
+        }
+    }
+
+    fn draw_point(&mut self, point: &Point2<i32>) {
+        self.draw_points(&[*point]);
+    }
+
+    fn draw_points(&mut self, points: &[Point2<i32>]) {
+        let rects: Vec<Rect> = points
+            .iter()
+            .map(|point| Rect {
+                pos: *point,
+                size: Vector2::<u32>::new(1, 1),
+            })
+            .collect();
+        self.fill(&rects);
+    }
+
+    fn draw_line(&mut self, line: &Line) {
+        self.stroke(&[*line]);
+    }
+
+    fn draw_lines(&mut self, lines: &[Line]) {
+        self.stroke(lines);
+    }
+
+    fn draw_rect(&mut self, rect: Rect) {
+        self.draw_rects(&[rect]);
+    }
+
+    fn draw_rects(&mut self, rects: &[Rect]) {
+        let lines: Vec<Line> = rects.iter().flat_map(|rect| rect.outline()).collect();
+        self.stroke(&lines);
+    }
+
+    fn fill_rect(&mut self, rect: Rect) {
+        self.fill(&[rect]);
+    }
+
+    fn fill_rects(&mut self, rects: &[Rect]) {
+        self.fill(rects);
+    }
+}
+
+//************************************************************************************************
+fn matrix_to_svg(matrix: &Matrix3<f32>) -> String {
+    format!(
+        "matrix({} {} {} {} {} {})",
+        matrix[(0, 0)],
+        matrix[(1, 0)],
+        matrix[(0, 1)],
+        matrix[(1, 1)],
+        matrix[(0, 2)],
+        matrix[(1, 2)]
+    )
+}
+
+//************************************************************************************************
+fn color_attributes(kind: &str, color: &Color) -> String {
+    let mut result = format!(" {}=\"rgb({},{},{})\"", kind, color.r, color.g, color.b);
+    if color.a != 255 {
+        write!(result, " {}-opacity=\"{}\"", kind, color.a as f32 / 255.0).unwrap();
+    }
+    result
+}
+
+//************************************************************************************************
+fn stops_to_svg(stops: &[GradientStop]) -> String {
+    let mut result = String::new();
+    for stop in stops {
+        write!(
+            result,
+            "<stop offset=\"{}\" stop-color=\"rgb({},{},{})\" stop-opacity=\"{}\"/>",
+            stop.offset,
+            stop.color.r,
+            stop.color.g,
+            stop.color.b,
+            stop.color.a as f32 / 255.0
+        )
+        .unwrap();
+    }
+    result
+}
+
+//************************************************************************************************
+fn spread_to_svg(spread: SpreadMode) -> &'static str {
+    match spread {
+        SpreadMode::Pad => "pad",
+        SpreadMode::Repeat => "repeat",
+        SpreadMode::Reflect => "reflect",
+    }
+}
+
+//************************************************************************************************
+fn base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut result = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let bytes = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let triple = (bytes[0] as u32) << 16 | (bytes[1] as u32) << 8 | bytes[2] as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                result.push(ALPHABET[(triple >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                result.push('=');
+            }
+        }
+    }
+    result
+}
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+#[cfg(test)]
+mod tests {
+    // crate
+    use crate::poly_ui::app::LinearGradient;
+    // super
+    use super::*;
+
+    //********************************************************************************************
+    #[test]
+    fn base64_encoding() {
+        assert_eq!(base64(b""), "");
+        assert_eq!(base64(b"f"), "Zg==");
+        assert_eq!(base64(b"fo"), "Zm8=");
+        assert_eq!(base64(b"foo"), "Zm9v");
+        assert_eq!(base64(b"foobar"), "Zm9vYmFy");
+    }
+
+    //********************************************************************************************
+    #[test]
+    fn sub_painter_group() {
+        let painter = Painter::new(&Vector2::<u32>::new(100, 50));
+        let mut sub_painter = painter.sub_painter(&Transform::new(
+            &Point2::<i32>::new(10, 20),
+            &Vector2::<u32>::new(30, 40),
+        ));
+        sub_painter.set_draw_color(&Color {
+            r: 255,
+            g: 0,
+            b: 128,
+            a: 51,
+        });
+        sub_painter.fill_rect(Rect {
+            pos: Point2::<i32>::new(1, 2),
+            size: Vector2::<u32>::new(3, 4),
+        });
+
+        let svg = painter.to_svg();
+        assert!(svg.contains("width=\"100\" height=\"50\" viewBox=\"0 0 100 50\""));
+        assert!(svg.contains(
+            "<clipPath id=\"clip0\"><rect x=\"0\" y=\"0\" width=\"30\" height=\"40\"/></clipPath>"
+        ));
+        assert!(svg.contains("<g transform=\"matrix(1 0 0 1 10 20)\" clip-path=\"url(#clip0)\">"));
+        assert!(svg.contains(
+            "<rect x=\"1\" y=\"2\" width=\"3\" height=\"4\" fill=\"rgb(255,0,128)\" \
+             fill-opacity=\"0.2\"/>"
+        ));
+    }
+
+    //********************************************************************************************
+    #[test]
+    fn strokes_gradients_and_layers() {
+        let mut painter = Painter::new(&Vector2::<u32>::new(10, 10));
+        painter.push_layer(0.5, BlendMode::Multiply);
+        painter.set_brush(&Brush::LinearGradient(LinearGradient::new(
+            &Point2::<f32>::new(0.0, 0.0),
+            &Point2::<f32>::new(10.0, 0.0),
+            vec![],
+            SpreadMode::Reflect,
+        )));
+        painter.translate(2.0, 3.0);
+        painter.draw_line(&Line {
+            start: Point2::<i32>::new(0, 0),
+            end: Point2::<i32>::new(4, 0),
+        });
+        painter.pop_layer();
+
+        let svg = painter.to_svg();
+        assert!(svg.contains("<g opacity=\"0.5\" style=\"mix-blend-mode:multiply\">"));
+        assert!(svg.contains(
+            "<linearGradient id=\"gradient0\" gradientUnits=\"userSpaceOnUse\" x1=\"0\" y1=\"0\" \
+             x2=\"10\" y2=\"0\" spreadMethod=\"reflect\"></linearGradient>"
+        ));
+        assert!(svg.contains(
+            "<path d=\"M0.5 0.5L4.5 0.5\" fill=\"none\" stroke=\"url(#gradient0)\" \
+             stroke-width=\"1\" stroke-linecap=\"square\" transform=\"matrix(1 0 0 1 2 3)\"/>"
+        ));
+    }
+}
@@ -0,0 +1,86 @@
+// std
+use std::{cell::RefCell, rc::Rc, time::Instant};
+// crate
+use crate::poly_ui::app::AppTrait;
+use crate::poly_ui::app::WindowsManagerTrait;
+use crate::poly_ui::events::Event;
+use crate::poly_ui::events::Key;
+// super
+use super::Input;
+use super::InputParser;
+use super::Terminal;
+use super::WindowsManager;
+
+/// How long in milliseconds an idle application waits for input before the next update.
+const IDLE_TIMEOUT: u32 = 16;
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// A terminal implementation for the AppTrait. It runs in the terminal of the process (e.g. over
+/// SSH); the terminal is switched to the raw mode for the lifetime of the App. Keyboard and
+/// mouse input is passed to the shown window (see WindowsManager), Escape or Ctrl+C ends the
+/// application.
+pub struct App {
+    terminal: Rc<RefCell<Terminal>>,
+    parser: InputParser,
+    windows_manager: WindowsManager,
+}
+
+//************************************************************************************************
+impl App {
+    /// # Returns
+    /// App or error description if the process doesn't run in a terminal.
+    pub fn new() -> Result<Self, String> {
+        let terminal = Rc::new(RefCell::new(Terminal::new()?));
+        Ok(Self {
+            terminal: terminal.clone(),
+            parser: InputParser::default(),
+            windows_manager: WindowsManager::new(terminal),
+        })
+    }
+}
+
+//************************************************************************************************
+impl AppTrait for App {
+    fn exec(&mut self) -> Result<(), String> {
+        let mut idle = false;
+        let mut last_frame = Instant::now();
+        'mainloop: loop {
+            // when nothing was painted in the last frame wait for input instead of spinning
+            let timeout = if idle { IDLE_TIMEOUT } else { 0 };
+            let bytes = self.terminal.borrow_mut().read(timeout);
+            let inputs = if bytes.is_empty() {
+                self.parser.flush()
+            } else {
+                self.parser.parse(&bytes)
+            };
+
+            for input in inputs {
+                match input {
+                    Input::Event(Event::KeyPress(event)) if event.key() == Key::Escape => {
+                        break 'mainloop
+                    }
+                    Input::Interrupt => break 'mainloop,
+                    Input::Event(event) => {
+                        if let Some(window) = self.windows_manager.top_window() {
+                            window.borrow_mut().handle_event(&event);
+                        }
+                    }
+                }
+            }
+
+            let now = Instant::now();
+            let dt = now.duration_since(last_frame).as_secs_f32() * 1000.0;
+            last_frame = now;
+            self.windows_manager.update_windows(dt);
+            idle = !self.windows_manager.paint_windows();
+        }
+
+        Ok(())
+    }
+
+    fn get_windows_manager(&mut self) -> &mut dyn WindowsManagerTrait {
+        &mut self.windows_manager
+    }
+}
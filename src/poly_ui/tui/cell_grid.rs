@@ -0,0 +1,176 @@
+// std
+use std::fmt::Write;
+// deps
+use nalgebra::Vector2;
+// crate
+use crate::poly_ui::app::Color;
+use crate::poly_ui::app::Image;
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// One character cell of the terminal.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Cell {
+    pub ch: char,
+    pub fg: Color,
+    pub bg: Color,
+}
+
+//************************************************************************************************
+impl Default for Cell {
+    fn default() -> Self {
+        Self {
+            ch: ' ',
+            fg: Color::WHITE,
+            bg: Color::BLACK,
+        }
+    }
+}
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// Content of the terminal screen; a row major grid of cells. Widgets are painted into an image
+/// with one pixel per cell which is converted into the grid, then only cells that differ from
+/// the previous frame are written to the terminal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CellGrid {
+    size: Vector2<u32>,
+    cells: Vec<Cell>,
+}
+
+//************************************************************************************************
+impl CellGrid {
+    /// # Arguments
+    /// * `size` - number of columns and rows
+    /// # Returns
+    /// Grid filled with default (blank) cells.
+    pub fn new(size: &Vector2<u32>) -> Self {
+        Self {
+            size: *size,
+            cells: vec![Cell::default(); (size.x * size.y) as usize],
+        }
+    }
+
+    /// # Arguments
+    /// * `image` - painted image, every pixel becomes background of one blank cell
+    /// # Returns
+    /// Grid of the image size. Pixels are composited over black since terminal colors are opaque.
+    pub fn from_image(image: &Image) -> Self {
+        let size = image.size();
+        let mut grid = Self::new(&size);
+        for y in 0..size.y {
+            for x in 0..size.x {
+                let cell = Cell {
+                    bg: image.pixel(x, y).over(&Color::BLACK),
+                    ..Cell::default()
+                };
+                grid.set_cell(x, y, &cell);
+            }
+        }
+        grid
+    }
+
+    /// # Returns
+    /// Number of columns and rows.
+    pub fn size(&self) -> Vector2<u32> {
+        self.size
+    }
+
+    /// # Returns
+    /// Cell in the given column and row.
+    pub fn cell(&self, x: u32, y: u32) -> &Cell {
+        &self.cells[(y * self.size.x + x) as usize]
+    }
+
+    /// Replaces the cell in the given column and row.
+    pub fn set_cell(&mut self, x: u32, y: u32, cell: &Cell) {
+        self.cells[(y * self.size.x + x) as usize] = *cell;
+    }
+
+    /// Generates ANSI escape sequences which turn the previous screen content into this grid.
+    /// Colors are written as 24-bit SGR sequences.
+    /// # Arguments
+    /// * `previous` - grid currently shown by the terminal, None (or a grid of other size) means
+    ///   that the screen content is unknown and every cell is written
+    /// # Returns
+    /// Output for the terminal, empty if nothing changed.
+    pub fn render(&self, previous: Option<&CellGrid>) -> String {
+        let previous = previous.filter(|previous| previous.size == self.size);
+        let mut output = String::new();
+        let mut cursor = None;
+        let mut colors = None;
+        for y in 0..self.size.y {
+            for x in 0..self.size.x {
+                let cell = self.cell(x, y);
+                if previous.map(|previous| previous.cell(x, y)) == Some(cell) {
+                    continue;
+                }
+                if cursor != Some((x, y)) {
+                    write!(output, "\x1b[{};{}H", y + 1, x + 1).unwrap();
+                }
+                if colors != Some((cell.fg, cell.bg)) {
+                    write!(
+                        output,
+                        "\x1b[38;2;{};{};{};48;2;{};{};{}m",
+                        cell.fg.r, cell.fg.g, cell.fg.b, cell.bg.r, cell.bg.g, cell.bg.b
+                    )
+                    .unwrap();
+                    colors = Some((cell.fg, cell.bg));
+                }
+                output.push(cell.ch);
+                // the cursor doesn't move past the last column
+                cursor = if x + 1 < self.size.x {
+                    Some((x + 1, y))
+                } else {
+                    None
+                };
+            }
+        }
+        if !output.is_empty() {
+            output.push_str("\x1b[0m");
+        }
+        output
+    }
+}
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+#[cfg(test)]
+mod tests {
+    // super
+    use super::*;
+
+    //********************************************************************************************
+    #[test]
+    fn render_changed_cells() {
+        let mut image = Image::new(&Vector2::<u32>::new(3, 2));
+        image.set_pixel(1, 0, &Color::RED);
+        image.set_pixel(2, 0, &Color::RED);
+        let grid = CellGrid::from_image(&image);
+        assert_eq!(grid.cell(0, 0).bg, Color::BLACK);
+        assert_eq!(grid.cell(1, 0).bg, Color::RED);
+
+        let full = grid.render(None);
+        assert!(full.starts_with("\x1b[1;1H\x1b[38;2;255;255;255;48;2;0;0;0m "));
+        assert_eq!(full.matches('H').count(), 2);
+        assert!(grid.render(Some(&grid)).is_empty());
+
+        let mut changed = grid.clone();
+        changed.set_cell(
+            1,
+            1,
+            &Cell {
+                ch: 'x',
+                ..Cell::default()
+            },
+        );
+        changed.set_cell(2, 1, &Cell::default());
+        assert_eq!(
+            changed.render(Some(&grid)),
+            "\x1b[2;2H\x1b[38;2;255;255;255;48;2;0;0;0mx\x1b[0m"
+        );
+    }
+}
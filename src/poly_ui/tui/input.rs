@@ -0,0 +1,546 @@
+// deps
+use nalgebra::Point2;
+use nalgebra::Vector2;
+// crate
+use crate::poly_ui::events::Event;
+use crate::poly_ui::events::Key;
+use crate::poly_ui::events::KeyModifiers;
+use crate::poly_ui::events::KeyPressEvent;
+use crate::poly_ui::events::KeyReleaseEvent;
+use crate::poly_ui::events::MouseButton;
+use crate::poly_ui::events::MouseMoveEvent;
+use crate::poly_ui::events::MousePressEvent;
+use crate::poly_ui::events::MouseReleaseEvent;
+use crate::poly_ui::events::MouseWheelEvent;
+use crate::poly_ui::events::TextInputEvent;
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// Input read from the terminal.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Input {
+    /// Key or mouse event. Mouse positions are in cells relative to the top left corner.
+    Event(Event),
+    /// Ctrl+C was pressed; signals are disabled in the raw mode so it's reported as input.
+    Interrupt,
+}
+
+//************************************************************************************************
+/// Result of parsing one sequence from the start of the input.
+enum Parsed {
+    /// Sequence was parsed, it's followed by the given number of bytes.
+    Done(Vec<Input>, usize),
+    /// Sequence is not complete yet.
+    Incomplete,
+}
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// Parser of the bytes read from a terminal in the raw mode. It understands keys including the
+/// common VT and xterm escape sequences and SGR mouse reporting (mode 1006). Terminals don't
+/// report key releases so every key produces a KeyPress followed by a KeyReleaseEvent; typed
+/// characters also produce a TextInput between them.
+#[derive(Debug, Default)]
+pub struct InputParser {
+    pending: Vec<u8>,
+}
+
+//************************************************************************************************
+impl InputParser {
+    /// Parses the bytes read from the terminal. Escape sequences split between reads are kept
+    /// until the rest arrives.
+    /// # Arguments
+    /// * `bytes` - bytes read from the terminal
+    /// # Returns
+    /// Parsed input in the order it was read.
+    pub fn parse(&mut self, bytes: &[u8]) -> Vec<Input> {
+        self.pending.extend_from_slice(bytes);
+        let mut inputs = vec![];
+        let mut start = 0;
+        while start < self.pending.len() {
+            match parse_sequence(&self.pending[start..]) {
+                Parsed::Done(parsed, length) => {
+                    inputs.extend(parsed);
+                    start += length;
+                }
+                Parsed::Incomplete => break,
+            }
+        }
+        self.pending.drain(..start);
+        inputs
+    }
+
+    /// Should be called when no more input came for a while. An incomplete sequence is then
+    /// a lone Escape key press (possibly followed by other keys).
+    /// # Returns
+    /// Input parsed from the pending bytes.
+    pub fn flush(&mut self) -> Vec<Input> {
+        if self.pending.first() != Some(&0x1b) {
+            return vec![];
+        }
+        let rest = self.pending.split_off(1);
+        self.pending.clear();
+        let mut inputs = key(Key::Escape);
+        inputs.extend(self.parse(&rest));
+        inputs
+    }
+}
+
+//************************************************************************************************
+/// Parses one key, escape sequence or UTF-8 character from the start of the bytes.
+fn parse_sequence(bytes: &[u8]) -> Parsed {
+    match bytes[0] {
+        0x03 => Parsed::Done(vec![Input::Interrupt], 1),
+        0x1b => match bytes.get(1) {
+            None => Parsed::Incomplete,
+            Some(b'[') => parse_csi(bytes),
+            Some(b'O') => match bytes.get(2) {
+                None => Parsed::Incomplete,
+                Some(code) => Parsed::Done(final_key(*code, 1).map_or(vec![], key), 3),
+            },
+            Some(0x1b) if !matches!(bytes.get(2), Some(b'[') | Some(b'O')) => {
+                Parsed::Done(key(Key::Escape), 1)
+            }
+            // Alt modified key or escape sequence
+            Some(_) => match parse_sequence(&bytes[1..]) {
+                Parsed::Done(inputs, length) => Parsed::Done(alt_modified(inputs), length + 1),
+                Parsed::Incomplete => Parsed::Incomplete,
+            },
+        },
+        0x08 | 0x7f => Parsed::Done(key(Key::Backspace), 1),
+        b'\t' => Parsed::Done(key(Key::Tab), 1),
+        b'\r' | b'\n' => Parsed::Done(key(Key::Return), 1),
+        // Ctrl modified letter
+        code @ 0x01..=0x1a => {
+            let letter = (b'a' + code - 1) as char;
+            let inputs =
+                char_key(letter).map_or(vec![], |letter| modified_key(letter, KeyModifiers::CTRL));
+            Parsed::Done(inputs, 1)
+        }
+        code @ 0x20..=0x7e => {
+            let ch = code as char;
+            let mut inputs = char_key(ch).map_or(vec![], key);
+            // between the press and the release if the character has a key
+            inputs.insert(inputs.len().min(1), text_input(ch));
+            Parsed::Done(inputs, 1)
+        }
+        // other characters have no keys, they are only typed
+        code => {
+            let length = match code {
+                0xc0..=0xdf => 2,
+                0xe0..=0xef => 3,
+                0xf0..=0xf7 => 4,
+                _ => 1,
+            };
+            if bytes.len() < length {
+                Parsed::Incomplete
+            } else {
+                let inputs = std::str::from_utf8(&bytes[..length])
+                    .map_or(vec![], |text| text.chars().map(text_input).collect());
+                Parsed::Done(inputs, length)
+            }
+        }
+    }
+}
+
+//************************************************************************************************
+/// Parses a CSI sequence (ESC [ parameters final) starting at the beginning of the bytes.
+fn parse_csi(bytes: &[u8]) -> Parsed {
+    let end = match bytes[2..]
+        .iter()
+        .position(|byte| (0x40..=0x7e).contains(byte))
+    {
+        Some(index) => index + 2,
+        None => return Parsed::Incomplete,
+    };
+    let parameters = String::from_utf8_lossy(&bytes[2..end]);
+    let code = bytes[end];
+    let length = end + 1;
+
+    if let Some(mouse) = parameters.strip_prefix('<') {
+        return Parsed::Done(parse_mouse(mouse, code).into_iter().collect(), length);
+    }
+
+    let numbers: Vec<u32> = parameters
+        .split(';')
+        .map(|number| number.parse().unwrap_or(1))
+        .collect();
+    let parsed = if code == b'~' {
+        tilde_key(numbers[0])
+    } else {
+        final_key(code, numbers[0])
+    };
+    let mut modifiers = numbers
+        .get(1)
+        .map_or(KeyModifiers::NONE, |number| xterm_modifiers(*number));
+    // Shift+Tab
+    modifiers.shift |= code == b'Z';
+    Parsed::Done(
+        parsed.map_or(vec![], |parsed| modified_key(parsed, modifiers)),
+        length,
+    )
+}
+
+//************************************************************************************************
+/// # Arguments
+/// * `number` - modifier parameter of an xterm sequence, e.g. 5 in ESC [ 1 ; 5 C
+/// # Returns
+/// Modifiers encoded in the parameter, it's 1 plus a bit mask of Shift (1), Alt (2) and Ctrl (4).
+fn xterm_modifiers(number: u32) -> KeyModifiers {
+    let mask = number.saturating_sub(1);
+    KeyModifiers {
+        shift: mask & 1 != 0,
+        alt: mask & 2 != 0,
+        ctrl: mask & 4 != 0,
+    }
+}
+
+//************************************************************************************************
+/// Adds Alt to the key presses of a sequence prefixed with ESC. Alt modified keys are shortcuts
+/// so they don't type characters.
+fn alt_modified(inputs: Vec<Input>) -> Vec<Input> {
+    inputs
+        .into_iter()
+        .filter_map(|input| match input {
+            Input::Event(Event::KeyPress(event)) => {
+                let mut modifiers = event.modifiers();
+                modifiers.alt = true;
+                Some(Input::Event(Event::KeyPress(
+                    KeyPressEvent::with_modifiers(event.key(), modifiers),
+                )))
+            }
+            Input::Event(Event::TextInput(_)) => None,
+            input => Some(input),
+        })
+        .collect()
+}
+
+//************************************************************************************************
+/// Parses parameters of an SGR mouse report, e.g. "0;10;5" for a left button at column 10,
+/// row 5 (both 1 based).
+fn parse_mouse(parameters: &str, code: u8) -> Option<Input> {
+    let numbers: Vec<i32> = parameters
+        .split(';')
+        .map(|number| number.parse().ok())
+        .collect::<Option<Vec<i32>>>()?;
+    if numbers.len() != 3 {
+        return None;
+    }
+    let pos = Point2::<i32>::new(numbers[1] - 1, numbers[2] - 1);
+    let buttons = numbers[0];
+    // scroll wheel, 64 to 67 for up, down, left and right, reported only on press
+    if buttons & 64 != 0 {
+        let delta = match buttons & 3 {
+            0 => Vector2::<i32>::new(0, -1),
+            1 => Vector2::<i32>::new(0, 1),
+            2 => Vector2::<i32>::new(-1, 0),
+            _ => Vector2::<i32>::new(1, 0),
+        };
+        return Some(Input::Event(Event::MouseWheel(MouseWheelEvent::new(
+            &delta, &pos,
+        ))));
+    }
+    let button = match buttons & 3 {
+        0 => Some(MouseButton::Left),
+        1 => Some(MouseButton::Middle),
+        2 => Some(MouseButton::Right),
+        _ => None,
+    };
+    let event = match (buttons & 32 != 0, button, code) {
+        (true, _, _) | (false, None, _) => Event::MouseMoveEvent(MouseMoveEvent::new(&pos)),
+        (false, Some(button), b'M') => Event::MousePressEvent(MousePressEvent::new(button, &pos)),
+        (false, Some(button), _) => Event::MouseReleaseEvent(MouseReleaseEvent::new(button, &pos)),
+    };
+    Some(Input::Event(event))
+}
+
+//************************************************************************************************
+/// # Returns
+/// Key of the sequences ending with a letter, e.g. ESC [ A or ESC O P.
+fn final_key(code: u8, number: u32) -> Option<Key> {
+    match (code, number) {
+        (b'A', _) => Some(Key::Up),
+        (b'B', _) => Some(Key::Down),
+        (b'C', _) => Some(Key::Right),
+        (b'D', _) => Some(Key::Left),
+        (b'H', _) => Some(Key::Home),
+        (b'F', _) => Some(Key::End),
+        (b'P', _) => Some(Key::F1),
+        (b'Q', _) => Some(Key::F2),
+        (b'R', _) => Some(Key::F3),
+        (b'S', _) => Some(Key::F4),
+        // Shift+Tab
+        (b'Z', _) => Some(Key::Tab),
+        _ => None,
+    }
+}
+
+//************************************************************************************************
+/// # Returns
+/// Key of the sequences ending with tilde, e.g. ESC [ 3 ~.
+fn tilde_key(number: u32) -> Option<Key> {
+    match number {
+        1 | 7 => Some(Key::Home),
+        2 => Some(Key::Insert),
+        3 => Some(Key::Delete),
+        4 | 8 => Some(Key::End),
+        5 => Some(Key::PageUp),
+        6 => Some(Key::PageDown),
+        11 => Some(Key::F1),
+        12 => Some(Key::F2),
+        13 => Some(Key::F3),
+        14 => Some(Key::F4),
+        15 => Some(Key::F5),
+        17 => Some(Key::F6),
+        18 => Some(Key::F7),
+        19 => Some(Key::F8),
+        20 => Some(Key::F9),
+        21 => Some(Key::F10),
+        23 => Some(Key::F11),
+        24 => Some(Key::F12),
+        _ => None,
+    }
+}
+
+//************************************************************************************************
+/// # Returns
+/// Key producing the given ASCII character, letters are case insensitive.
+fn char_key(ch: char) -> Option<Key> {
+    const LETTERS: [Key; 26] = [
+        Key::A,
+        Key::B,
+        Key::C,
+        Key::D,
+        Key::E,
+        Key::F,
+        Key::G,
+        Key::H,
+        Key::I,
+        Key::J,
+        Key::K,
+        Key::L,
+        Key::M,
+        Key::N,
+        Key::O,
+        Key::P,
+        Key::Q,
+        Key::R,
+        Key::S,
+        Key::T,
+        Key::U,
+        Key::V,
+        Key::W,
+        Key::X,
+        Key::Y,
+        Key::Z,
+    ];
+    const DIGITS: [Key; 10] = [
+        Key::Num0,
+        Key::Num1,
+        Key::Num2,
+        Key::Num3,
+        Key::Num4,
+        Key::Num5,
+        Key::Num6,
+        Key::Num7,
+        Key::Num8,
+        Key::Num9,
+    ];
+
+    match ch {
+        'a'..='z' => Some(LETTERS[ch as usize - 'a' as usize]),
+        'A'..='Z' => Some(LETTERS[ch as usize - 'A' as usize]),
+        '0'..='9' => Some(DIGITS[ch as usize - '0' as usize]),
+        ' ' => Some(Key::Space),
+        '!' => Some(Key::Exclaim),
+        '"' => Some(Key::Quotedbl),
+        '#' => Some(Key::Hash),
+        '$' => Some(Key::Dollar),
+        '%' => Some(Key::Percent),
+        '&' => Some(Key::Ampersand),
+        '\'' => Some(Key::Quote),
+        '(' => Some(Key::LeftParen),
+        ')' => Some(Key::RightParen),
+        '*' => Some(Key::Asterisk),
+        '+' => Some(Key::Plus),
+        ',' => Some(Key::Comma),
+        '-' => Some(Key::Minus),
+        '.' => Some(Key::Period),
+        '/' => Some(Key::Slash),
+        ':' => Some(Key::Colon),
+        ';' => Some(Key::Semicolon),
+        '<' => Some(Key::Less),
+        '=' => Some(Key::Equals),
+        '>' => Some(Key::Greater),
+        '?' => Some(Key::Question),
+        '@' => Some(Key::At),
+        '[' => Some(Key::LeftBracket),
+        '\\' => Some(Key::Backslash),
+        ']' => Some(Key::RightBracket),
+        '^' => Some(Key::Caret),
+        '_' => Some(Key::Underscore),
+        '`' => Some(Key::Backquote),
+        _ => None,
+    }
+}
+
+//************************************************************************************************
+/// # Returns
+/// Press and release of the key.
+fn key(key: Key) -> Vec<Input> {
+    modified_key(key, KeyModifiers::NONE)
+}
+
+//************************************************************************************************
+/// # Returns
+/// Press with the modifiers and release of the key.
+fn modified_key(key: Key, modifiers: KeyModifiers) -> Vec<Input> {
+    vec![
+        Input::Event(Event::KeyPress(KeyPressEvent::with_modifiers(
+            key, modifiers,
+        ))),
+        Input::Event(Event::KeyReleaseEvent(KeyReleaseEvent::new(key))),
+    ]
+}
+
+//************************************************************************************************
+fn text_input(ch: char) -> Input {
+    Input::Event(Event::TextInput(TextInputEvent::new(ch)))
+}
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+#[cfg(test)]
+mod tests {
+    // super
+    use super::*;
+
+    //********************************************************************************************
+    fn pressed(inputs: &[Input]) -> Vec<Key> {
+        inputs
+            .iter()
+            .filter_map(|input| match input {
+                Input::Event(Event::KeyPress(event)) => Some(event.key()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    //********************************************************************************************
+    #[test]
+    fn keys() {
+        let mut parser = InputParser::default();
+        let inputs = parser.parse(b"aZ1 \r\x7f\x1b[A\x1bOP\x1b[3~\x1b[15~");
+        assert_eq!(
+            pressed(&inputs),
+            vec![
+                Key::A,
+                Key::Z,
+                Key::Num1,
+                Key::Space,
+                Key::Return,
+                Key::Backspace,
+                Key::Up,
+                Key::F1,
+                Key::Delete,
+                Key::F5
+            ]
+        );
+        assert_eq!(inputs.len(), 24);
+        assert_eq!(inputs[1], text_input('a'));
+        assert_eq!(parser.parse(b"\x03"), vec![Input::Interrupt]);
+        assert_eq!(
+            parser.parse(b"\x18\xc5\x82"),
+            vec![
+                Input::Event(Event::KeyPress(KeyPressEvent::with_modifiers(
+                    Key::X,
+                    KeyModifiers::CTRL
+                ))),
+                Input::Event(Event::KeyReleaseEvent(KeyReleaseEvent::new(Key::X))),
+                text_input('ł'),
+            ]
+        );
+
+        // sequence split between reads and a lone escape
+        assert!(parser.parse(b"\x1b[").is_empty());
+        assert_eq!(pressed(&parser.parse(b"B\x1b")), vec![Key::Down]);
+        assert_eq!(pressed(&parser.flush()), vec![Key::Escape]);
+        assert!(parser.flush().is_empty());
+    }
+
+    //********************************************************************************************
+    fn press(key: Key, modifiers: KeyModifiers) -> Input {
+        Input::Event(Event::KeyPress(KeyPressEvent::with_modifiers(
+            key, modifiers,
+        )))
+    }
+
+    //********************************************************************************************
+    #[test]
+    fn modifiers() {
+        let mut parser = InputParser::default();
+        let presses: Vec<Input> = parser
+            .parse(b"\x1b[1;2D\x1b[1;5C\x1b[3;5~\x1b[1;8A\x1b[Z\x1b[5~")
+            .into_iter()
+            .filter(|input| matches!(input, Input::Event(Event::KeyPress(_))))
+            .collect();
+        assert_eq!(
+            presses,
+            vec![
+                press(Key::Left, KeyModifiers::SHIFT),
+                press(Key::Right, KeyModifiers::CTRL),
+                press(Key::Delete, KeyModifiers::CTRL),
+                press(
+                    Key::Up,
+                    KeyModifiers {
+                        shift: true,
+                        ctrl: true,
+                        alt: true,
+                    }
+                ),
+                press(Key::Tab, KeyModifiers::SHIFT),
+                press(Key::PageUp, KeyModifiers::NONE),
+            ]
+        );
+
+        // ESC prefix is Alt and Alt modified characters are not typed
+        assert_eq!(
+            parser.parse(b"\x1bx\x1b\x1b[A"),
+            vec![
+                press(Key::X, KeyModifiers::ALT),
+                Input::Event(Event::KeyReleaseEvent(KeyReleaseEvent::new(Key::X))),
+                press(Key::Up, KeyModifiers::ALT),
+                Input::Event(Event::KeyReleaseEvent(KeyReleaseEvent::new(Key::Up))),
+            ]
+        );
+    }
+
+    //********************************************************************************************
+    #[test]
+    fn mouse() {
+        let mut parser = InputParser::default();
+        let inputs = parser.parse(b"\x1b[<0;10;5M\x1b[<32;11;5M\x1b[<0;11;5m\x1b[<65;1;2M");
+        let pos = Point2::<i32>::new(10, 4);
+        assert_eq!(
+            inputs,
+            vec![
+                Input::Event(Event::MousePressEvent(MousePressEvent::new(
+                    MouseButton::Left,
+                    &Point2::<i32>::new(9, 4)
+                ))),
+                Input::Event(Event::MouseMoveEvent(MouseMoveEvent::new(&pos))),
+                Input::Event(Event::MouseReleaseEvent(MouseReleaseEvent::new(
+                    MouseButton::Left,
+                    &pos
+                ))),
+                Input::Event(Event::MouseWheel(MouseWheelEvent::new(
+                    &Vector2::<i32>::new(0, 1),
+                    &Point2::<i32>::new(0, 1)
+                ))),
+            ]
+        );
+    }
+}
@@ -0,0 +1,15 @@
+mod app;
+mod cell_grid;
+mod input;
+mod terminal;
+mod window_provider;
+mod windows_manager;
+
+pub use app::App;
+pub use cell_grid::Cell;
+pub use cell_grid::CellGrid;
+pub use input::Input;
+pub use input::InputParser;
+pub use terminal::Terminal;
+pub use window_provider::WindowProvider;
+pub use windows_manager::WindowsManager;
@@ -0,0 +1,130 @@
+// std
+use std::io::Write;
+// deps
+use nalgebra::Vector2;
+
+/// Switches to the alternate screen, hides the cursor and enables reporting of all mouse events
+/// in the SGR format.
+const ENTER: &str = "\x1b[?1049h\x1b[?25l\x1b[?1003h\x1b[?1006h\x1b[2J";
+
+/// Reverts everything ENTER did.
+const LEAVE: &str = "\x1b[?1006l\x1b[?1003l\x1b[?25h\x1b[?1049l";
+
+/// Size used when the terminal doesn't report its size.
+const DEFAULT_SIZE: (u32, u32) = (80, 24);
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// Terminal of the process (its standard input and output) switched to the raw mode and to the
+/// alternate screen. The original state is restored when it's dropped.
+pub struct Terminal {
+    original: libc::termios,
+}
+
+//************************************************************************************************
+impl Terminal {
+    /// Switches the terminal into the raw mode.
+    /// # Returns
+    /// Terminal or error description if the standard input is not a terminal.
+    pub fn new() -> Result<Self, String> {
+        let original = unsafe {
+            if libc::isatty(libc::STDIN_FILENO) == 0 {
+                return Err("standard input is not a terminal".to_string());
+            }
+            let mut original: libc::termios = std::mem::zeroed();
+            if libc::tcgetattr(libc::STDIN_FILENO, &mut original) != 0 {
+                return Err(std::io::Error::last_os_error().to_string());
+            }
+            let mut raw = original;
+            libc::cfmakeraw(&mut raw);
+            if libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &raw) != 0 {
+                return Err(std::io::Error::last_os_error().to_string());
+            }
+            original
+        };
+
+        let mut terminal = Self { original };
+        terminal.write(ENTER);
+        Ok(terminal)
+    }
+
+    /// # Returns
+    /// Number of columns and rows of the terminal.
+    pub fn size(&self) -> Vector2<u32> {
+        let mut size: libc::winsize = unsafe { std::mem::zeroed() };
+        let result = unsafe { libc::ioctl(libc::STDOUT_FILENO, libc::TIOCGWINSZ, &mut size) };
+        if result != 0 || size.ws_col == 0 || size.ws_row == 0 {
+            return Vector2::<u32>::new(DEFAULT_SIZE.0, DEFAULT_SIZE.1);
+        }
+        Vector2::<u32>::new(size.ws_col as u32, size.ws_row as u32)
+    }
+
+    /// Asks the terminal emulator to resize its window. Many emulators ignore it.
+    /// # Arguments
+    /// * `size` - number of columns and rows
+    pub fn set_size(&mut self, size: &Vector2<u32>) {
+        self.write(&format!("\x1b[8;{};{}t", size.y, size.x));
+    }
+
+    /// Sets title of the terminal emulator window.
+    pub fn set_title(&mut self, title: &str) {
+        self.write(&format!("\x1b]0;{}\x07", title));
+    }
+
+    /// Writes the output to the terminal and flushes it.
+    pub fn write(&mut self, output: &str) {
+        let stdout = std::io::stdout();
+        let mut stdout = stdout.lock();
+        // there is nowhere to report errors of the terminal output to
+        let _ = stdout.write_all(output.as_bytes());
+        let _ = stdout.flush();
+    }
+
+    /// Reads available input, waiting for it up to the given time.
+    /// # Arguments
+    /// * `timeout` - maximal waiting time in milliseconds, 0 doesn't wait at all
+    /// # Returns
+    /// Bytes read from the terminal, empty if nothing came in time.
+    pub fn read(&mut self, timeout: u32) -> Vec<u8> {
+        let mut descriptor = libc::pollfd {
+            fd: libc::STDIN_FILENO,
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        let ready = unsafe { libc::poll(&mut descriptor, 1, timeout as libc::c_int) };
+        if ready <= 0 {
+            return vec![];
+        }
+
+        let mut buffer = [0u8; 1024];
+        let read = unsafe {
+            libc::read(
+                libc::STDIN_FILENO,
+                buffer.as_mut_ptr() as *mut libc::c_void,
+                buffer.len(),
+            )
+        };
+        if read <= 0 {
+            return vec![];
+        }
+        buffer[..read as usize].to_vec()
+    }
+}
+
+//************************************************************************************************
+impl Drop for Terminal {
+    fn drop(&mut self) {
+        self.write(LEAVE);
+        unsafe {
+            libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &self.original);
+        }
+    }
+}
+
+//************************************************************************************************
+impl std::fmt::Debug for Terminal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Terminal").finish()
+    }
+}
@@ -0,0 +1,77 @@
+// std
+use std::{cell::RefCell, rc::Rc};
+// deps
+use nalgebra::Point2;
+use nalgebra::Vector2;
+// crate
+use crate::poly_ui::app::Color;
+use crate::poly_ui::app::Rect;
+use crate::poly_ui::software::Frame;
+use crate::poly_ui::widgets::WidgetTrait;
+use crate::poly_ui::widgets::WindowProviderTrait;
+// super
+use super::CellGrid;
+use super::Terminal;
+
+/// Color of the window below all widgets.
+const BACKGROUND: Color = Color::BLACK;
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// A terminal implementation for the WindowProvider trait. The window covers the whole terminal
+/// and one cell is one pixel. Widgets are painted into a software Frame; after painting its image
+/// is converted into a CellGrid and only the changed cells are written to the terminal.
+pub struct WindowProvider {
+    terminal: Rc<RefCell<Terminal>>,
+    frame: Frame,
+    shown: Option<CellGrid>,
+}
+
+//************************************************************************************************
+impl WindowProvider {
+    pub fn new(terminal: Rc<RefCell<Terminal>>) -> Self {
+        Self {
+            terminal,
+            frame: Frame::default(),
+            shown: None,
+        }
+    }
+}
+
+//************************************************************************************************
+impl WindowProviderTrait for WindowProvider {
+    fn paint_widget(&mut self, widget: &mut dyn WidgetTrait, regions: &[Rect], scale: f32) {
+        let size = self.terminal.borrow().size();
+        if self.frame.paint(&size, widget, regions, scale, &BACKGROUND) {
+            // the image was painted again after a resize so the screen is written whole
+            self.shown = None;
+        }
+
+        let grid = CellGrid::from_image(&self.frame.image().unwrap());
+        let output = grid.render(self.shown.as_ref());
+        self.terminal.borrow_mut().write(&output);
+        self.shown = Some(grid);
+    }
+
+    fn pos(&self) -> Point2<i32> {
+        Point2::<i32>::new(0, 0)
+    }
+
+    fn set_pos(&mut self, _new: Point2<i32>) {}
+
+    fn size(&self) -> Vector2<u32> {
+        self.terminal.borrow().size()
+    }
+
+    fn set_size(&mut self, new: Vector2<u32>) {
+        self.terminal.borrow_mut().set_size(&new);
+    }
+}
+
+//************************************************************************************************
+impl std::fmt::Debug for WindowProvider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WindowProvider").finish()
+    }
+}
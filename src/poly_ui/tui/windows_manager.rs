@@ -0,0 +1,67 @@
+// std
+use std::{cell::RefCell, rc::Rc, vec::Vec};
+// crate
+use crate::poly_ui::app::WindowsManagerTrait;
+use crate::poly_ui::widgets::Window;
+use crate::poly_ui::widgets::WindowTrait;
+// super
+use super::Terminal;
+use super::WindowProvider;
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+/// Terminal implementation for the WindowsManager trait. The terminal can show only one window;
+/// it's the last created one. All windows are updated but only that one is painted and gets
+/// input.
+pub struct WindowsManager {
+    terminal: Rc<RefCell<Terminal>>,
+    windows: Vec<Rc<RefCell<dyn WindowTrait>>>,
+}
+
+//************************************************************************************************
+impl WindowsManager {
+    pub fn new(terminal: Rc<RefCell<Terminal>>) -> Self {
+        Self {
+            terminal,
+            windows: Vec::new(),
+        }
+    }
+
+    /// # Returns
+    /// Window shown in the terminal, None if no window was created yet.
+    pub fn top_window(&self) -> Option<&Rc<RefCell<dyn WindowTrait>>> {
+        self.windows.last()
+    }
+}
+
+//************************************************************************************************
+impl WindowsManagerTrait for WindowsManager {
+    /// Creates a window covering the whole terminal, the requested size is ignored. The title is
+    /// set as the title of the terminal emulator.
+    fn create_window(
+        &mut self,
+        title: &str,
+        _width: u32,
+        _height: u32,
+    ) -> Result<Rc<RefCell<dyn WindowTrait>>, String> {
+        self.terminal.borrow_mut().set_title(title);
+        let window_provider = Box::new(WindowProvider::new(self.terminal.clone()));
+        let window = Rc::new(RefCell::new(Window::new(window_provider)));
+        self.windows.push(window.clone());
+        Ok(window)
+    }
+
+    fn update_windows(&mut self, dt: f32) {
+        for window in &mut self.windows {
+            window.borrow_mut().update(dt);
+        }
+    }
+
+    fn paint_windows(&mut self) -> bool {
+        match self.windows.last() {
+            Some(window) => window.borrow_mut().paint(),
+            None => false,
+        }
+    }
+}
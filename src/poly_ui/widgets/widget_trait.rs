@@ -1,10 +1,13 @@
 // std
+use nalgebra::Vector2;
 use std::fmt::Debug;
 use uuid::Uuid;
 // crate
 use crate::poly_ui::app::PainterTrait;
+use crate::poly_ui::app::Rect;
 use crate::poly_ui::components::Hierarchy;
 use crate::poly_ui::components::Transform;
+use crate::poly_ui::events::Event;
 // super
 use super::NewWidget;
 use super::OwnedWidget;
@@ -38,6 +41,59 @@ pub trait WidgetTrait: Debug {
         self.get_hierarchy().get_transform(child)
     }
 
+    /// Requests repaint of this whole Widget. The request is passed through parent Hierarchies
+    /// to the Window which will repaint the damaged area in the next frame.
+    fn request_repaint(&self) {
+        self.get_hierarchy().request_repaint();
+    }
+
+    /// Requests keyboard focus for this Widget. It's moved to it after the key event being
+    /// dispatched is handled.
+    fn request_focus(&self) {
+        self.get_hierarchy().request_focus();
+    }
+
+    /// Requests repaint of the given rect of this Widget.
+    /// # Arguments
+    /// * `rect` - rect in this Widget coordinates
+    fn request_repaint_rect(&self, rect: &Rect) {
+        self.get_hierarchy().request_repaint_rect(rect);
+    }
+
+    /// Marks this Widget as cacheable. Its painted output including all children is then kept
+    /// in an image which is reused by the parent until this Widget or any of its descendants
+    /// requests repaint or its size changes.
+    /// # Arguments
+    /// * `cacheable` - true if this Widget should be cached
+    fn set_cacheable(&self, cacheable: bool) {
+        self.get_hierarchy().set_cacheable(cacheable);
+    }
+
+    /// # Returns
+    /// Size this Widget would like to get from layouts in logical pixels, None if it has no
+    /// preference. Layouts don't make their cells smaller than preferred sizes of their
+    /// children unless limited by max sizes. The default implementation has no preference.
+    fn preferred_size(&self) -> Option<Vector2<u32>> {
+        None
+    }
+
+    /// # Returns
+    /// True if this Widget takes the keyboard focus when it handles a mouse press. The default
+    /// implementation returns false.
+    fn accepts_focus(&self) -> bool {
+        false
+    }
+
+    /// Handles an input event. The default implementation passes it to the children (see
+    /// Hierarchy::dispatch_event()).
+    /// # Arguments
+    /// * `event` - event with mouse position in this Widget coordinates
+    /// # Returns
+    /// True if the event was handled and shouldn't be passed to other Widgets.
+    fn handle_event(&mut self, event: &Event) -> bool {
+        self.get_hierarchy().dispatch_event(event)
+    }
+
     /// This function updateds state of this widget if necessary. This Widget will also call
     /// update on all its children Widgets.
     /// # Arguments
@@ -76,6 +132,7 @@ impl std::cmp::Eq for dyn WidgetTrait {}
 pub struct MockWidget {
     pub update_call_count: u32,
     pub paint_call_count: u32,
+    pub events: Vec<Event>,
 
     id: Uuid,
     hierarchy: Hierarchy,
@@ -87,6 +144,7 @@ impl MockWidget {
         Self {
             update_call_count: 0,
             paint_call_count: 0,
+            events: vec![],
             id: Uuid::new_v4(),
             hierarchy: Hierarchy::default(),
         }
@@ -111,6 +169,14 @@ impl WidgetTrait for MockWidget {
         &self.hierarchy
     }
 
+    fn handle_event(&mut self, event: &Event) -> bool {
+        if self.hierarchy.dispatch_event(event) {
+            return true;
+        }
+        self.events.push(*event);
+        true
+    }
+
     fn update(&mut self, dt: f32) {
         self.hierarchy.update_children(dt);
         self.update_call_count += 1;
@@ -5,6 +5,9 @@ use nalgebra::Point2;
 use nalgebra::Vector2;
 use uuid::Uuid;
 // crate
+use crate::poly_ui::app::Rect;
+use crate::poly_ui::components::logical_size;
+use crate::poly_ui::events::Event;
 use crate::poly_ui::layouts::CanvasLayout;
 // super
 use super::OwnedWidget;
@@ -23,6 +26,8 @@ pub struct Window {
     owned_widget: OwnedWidget,
     id: Uuid,
     window_provider: Box<dyn WindowProviderTrait>,
+    scale_override: Option<f32>,
+    painted: Option<(Vector2<u32>, f32)>,
 }
 
 //************************************************************************************************
@@ -34,13 +39,26 @@ impl Window {
     /// position and size and painting child widgets on it
     pub fn new(provider: Box<dyn WindowProviderTrait>) -> Self {
         let widget = CanvasLayout::new();
+        widget.borrow().request_repaint();
         Self {
             widget_ptr: widget.get().clone(),
             owned_widget: widget.make_owned(),
             id: Uuid::new_v4(),
             window_provider: provider,
+            scale_override: None,
+            painted: None,
         }
     }
+
+    /// # Returns
+    /// Number of physical pixels per unit of the windowing system.
+    fn native_scale(&self) -> f32 {
+        let size = self.window_provider.size();
+        if size.x == 0 {
+            return 1.0;
+        }
+        self.window_provider.drawable_size().x as f32 / size.x as f32
+    }
 }
 
 //************************************************************************************************
@@ -62,19 +80,265 @@ impl WindowTrait for Window {
     }
 
     fn size(&self) -> Vector2<u32> {
-        self.window_provider.size()
+        logical_size(&self.window_provider.drawable_size(), self.scale_factor())
     }
 
     fn set_size(&mut self, new: Vector2<u32>) {
-        self.window_provider.set_size(new);
+        let factor = self.scale_factor() / self.native_scale();
+        self.window_provider.set_size(Vector2::<u32>::new(
+            (new.x as f32 * factor).round() as u32,
+            (new.y as f32 * factor).round() as u32,
+        ));
+        self.widget_ptr.borrow().request_repaint();
+    }
+
+    fn scale_factor(&self) -> f32 {
+        match self.scale_override {
+            Some(scale) => scale,
+            None => self.native_scale(),
+        }
+    }
+
+    fn set_scale_factor_override(&mut self, scale: Option<f32>) {
+        self.scale_override = scale;
+        self.widget_ptr.borrow().request_repaint();
+    }
+
+    fn map_from_window(&self, pos: &Point2<i32>) -> Point2<i32> {
+        let factor = self.native_scale() / self.scale_factor();
+        Point2::<i32>::new(
+            (pos.x as f32 * factor).floor() as i32,
+            (pos.y as f32 * factor).floor() as i32,
+        )
+    }
+
+    fn handle_event(&mut self, event: &Event) -> bool {
+        let event = match event.pos() {
+            Some(pos) => event.with_pos(&self.map_from_window(&pos)),
+            None => *event,
+        };
+        self.widget_ptr.borrow_mut().handle_event(&event)
     }
 
     fn update(&mut self, dt: f32) {
         self.widget_ptr.borrow_mut().update(dt);
     }
 
-    fn paint(&mut self) {
+    fn paint(&mut self) -> bool {
+        // resizing or moving the window to a monitor with other density invalidates everything
+        let scale = self.scale_factor();
+        let drawable_size = self.window_provider.drawable_size();
+        if self.painted != Some((drawable_size, scale)) {
+            self.painted = Some((drawable_size, scale));
+            self.widget_ptr.borrow().request_repaint();
+        }
+
+        let window_rect = Rect {
+            pos: Point2::<i32>::new(0, 0),
+            size: self.size(),
+        };
+        let regions: Vec<Rect> = self
+            .widget_ptr
+            .borrow()
+            .get_hierarchy()
+            .damage()
+            .borrow_mut()
+            .take()
+            .iter()
+            .filter_map(|region| region.intersection(&window_rect))
+            .collect();
+        if regions.is_empty() {
+            return false;
+        }
+
         self.window_provider
-            .paint_widget(&mut *self.widget_ptr.borrow_mut());
+            .paint_widget(&mut *self.widget_ptr.borrow_mut(), &regions, scale);
+        true
+    }
+}
+
+//************************************************************************************************
+//************************************************************************************************
+//************************************************************************************************
+#[cfg(test)]
+mod tests {
+    // crate
+    use crate::poly_ui::components::Transform;
+    use crate::poly_ui::events::Key;
+    use crate::poly_ui::events::KeyPressEvent;
+    use crate::poly_ui::events::MouseButton;
+    use crate::poly_ui::events::MousePressEvent;
+    use crate::poly_ui::events::MouseReleaseEvent;
+    use crate::poly_ui::widgets::MockWidget;
+    // super
+    use super::*;
+
+    //********************************************************************************************
+    #[derive(Debug)]
+    struct MockWindowProvider {
+        painted: Rc<RefCell<Vec<Vec<Rect>>>>,
+        size: Vector2<u32>,
+        drawable_size: Vector2<u32>,
+    }
+
+    //********************************************************************************************
+    impl MockWindowProvider {
+        fn new(painted: &Rc<RefCell<Vec<Vec<Rect>>>>) -> Self {
+            Self {
+                painted: painted.clone(),
+                size: Vector2::<u32>::new(100, 50),
+                drawable_size: Vector2::<u32>::new(100, 50),
+            }
+        }
+    }
+
+    //********************************************************************************************
+    impl WindowProviderTrait for MockWindowProvider {
+        fn paint_widget(&mut self, _widget: &mut dyn WidgetTrait, regions: &[Rect], _scale: f32) {
+            self.painted.borrow_mut().push(regions.to_vec());
+        }
+
+        fn pos(&self) -> Point2<i32> {
+            Point2::<i32>::new(0, 0)
+        }
+
+        fn set_pos(&mut self, _new: Point2<i32>) {}
+
+        fn size(&self) -> Vector2<u32> {
+            self.size
+        }
+
+        fn drawable_size(&self) -> Vector2<u32> {
+            self.drawable_size
+        }
+
+        fn set_size(&mut self, new: Vector2<u32>) {
+            self.drawable_size = self
+                .drawable_size
+                .component_mul(&new)
+                .component_div(&self.size);
+            self.size = new;
+        }
+    }
+
+    //********************************************************************************************
+    #[test]
+    fn paint_only_when_damaged() {
+        let painted = Rc::new(RefCell::new(vec![]));
+        let mut window = Window::new(Box::new(MockWindowProvider::new(&painted)));
+        let child = MockWidget::new();
+        let child_ptr = child.get().clone();
+        window.widget().borrow_mut().add_child_with_transform(
+            child.make_owned(),
+            &Transform::new(&Point2::<i32>::new(10, 10), &Vector2::<u32>::new(20, 20)),
+        );
+
+        assert!(window.paint());
+        assert!(!window.paint());
+        child_ptr.borrow().request_repaint();
+        assert!(window.paint());
+
+        let window_rect = Rect {
+            pos: Point2::<i32>::new(0, 0),
+            size: Vector2::<u32>::new(100, 50),
+        };
+        let child_rect = Rect {
+            pos: Point2::<i32>::new(10, 10),
+            size: Vector2::<u32>::new(20, 20),
+        };
+        assert_eq!(*painted.borrow(), vec![vec![window_rect], vec![child_rect]]);
+    }
+
+    //********************************************************************************************
+    #[test]
+    fn scale_factor() {
+        let painted = Rc::new(RefCell::new(vec![]));
+        let mut provider = MockWindowProvider::new(&painted);
+        provider.drawable_size = Vector2::<u32>::new(200, 100);
+        let mut window = Window::new(Box::new(provider));
+        assert_eq!(window.scale_factor(), 2.0);
+        assert_eq!(window.size(), Vector2::<u32>::new(100, 50));
+        assert_eq!(
+            window.map_from_window(&Point2::<i32>::new(10, 20)),
+            Point2::<i32>::new(10, 20)
+        );
+        assert!(window.paint());
+
+        window.set_scale_factor_override(Some(1.0));
+        assert_eq!(window.size(), Vector2::<u32>::new(200, 100));
+        assert_eq!(
+            window.map_from_window(&Point2::<i32>::new(10, 20)),
+            Point2::<i32>::new(20, 40)
+        );
+        assert!(window.paint());
+        assert!(!window.paint());
+
+        window.set_size(Vector2::<u32>::new(100, 100));
+        assert_eq!(window.size(), Vector2::<u32>::new(100, 100));
+        assert_eq!(
+            *painted.borrow().last().unwrap(),
+            vec![Rect {
+                pos: Point2::<i32>::new(0, 0),
+                size: Vector2::<u32>::new(200, 100),
+            }]
+        );
+    }
+
+    //********************************************************************************************
+    #[test]
+    fn dispatch_events() {
+        let painted = Rc::new(RefCell::new(vec![]));
+        let mut provider = MockWindowProvider::new(&painted);
+        provider.drawable_size = Vector2::<u32>::new(200, 100);
+        let mut window = Window::new(Box::new(provider));
+        let bottom = MockWidget::new();
+        let top = MockWidget::new();
+        let (bottom_ptr, top_ptr) = (bottom.get().clone(), top.get().clone());
+        window.widget().borrow_mut().add_child_with_transform(
+            bottom.make_owned(),
+            &Transform::new(&Point2::<i32>::new(0, 0), &Vector2::<u32>::new(40, 40)),
+        );
+        window.widget().borrow_mut().add_child_with_transform(
+            top.make_owned(),
+            &Transform::new(&Point2::<i32>::new(30, 30), &Vector2::<u32>::new(20, 20)),
+        );
+
+        // positions are mapped to logical pixels and to the topmost child under the mouse
+        let press = |x: i32, y: i32| {
+            Event::MousePressEvent(MousePressEvent::new(
+                MouseButton::Left,
+                &Point2::<i32>::new(x, y),
+            ))
+        };
+        let release = |x: i32, y: i32| {
+            Event::MouseReleaseEvent(MouseReleaseEvent::new(
+                MouseButton::Left,
+                &Point2::<i32>::new(x, y),
+            ))
+        };
+        assert!(window.handle_event(&press(35, 35)));
+        // the child that handled the press gets mouse events until the release
+        assert!(window.handle_event(&release(90, 10)));
+        assert!(window.handle_event(&press(10, 10)));
+        assert!(window.handle_event(&release(10, 10)));
+        assert!(!window.handle_event(&press(90, 10)));
+        assert_eq!(top_ptr.borrow().events, vec![press(5, 5), release(60, -20)]);
+        assert_eq!(
+            bottom_ptr.borrow().events,
+            vec![press(10, 10), release(10, 10)]
+        );
+
+        // key events go only to the focused child
+        let key = Event::KeyPress(KeyPressEvent::new(Key::A));
+        assert!(!window.handle_event(&key));
+        let top_id = *top_ptr.borrow().id();
+        window
+            .widget()
+            .borrow()
+            .get_hierarchy()
+            .set_focus(Some(top_id));
+        assert!(window.handle_event(&key));
+        assert_eq!(top_ptr.borrow().events.last(), Some(&key));
+        assert_ne!(bottom_ptr.borrow().events.last(), Some(&key));
     }
 }
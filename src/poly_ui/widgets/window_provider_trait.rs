@@ -3,6 +3,8 @@ use std::fmt::Debug;
 // deps
 use nalgebra::Point2;
 use nalgebra::Vector2;
+// crate
+use crate::poly_ui::app::Rect;
 // super
 use super::WidgetTrait;
 
@@ -15,8 +17,15 @@ pub trait WindowProviderTrait: Debug {
     /// When this function is called implementation of the widget trait will call paint method on
     /// provided widget passing its own implementation of the PainterTrait. The widget on which
     /// paint is called will create subpainters and paint its children passing subpainters as
-    /// arguments in paint methods called on them.
-    fn paint_widget(&mut self, widget: &mut dyn WidgetTrait);
+    /// arguments in paint methods called on them. Only the damaged regions have to be repainted
+    /// (see components::paint_damage()); implementations that can't keep the previous frame may
+    /// repaint the whole window. Widgets are painted in logical pixels (see
+    /// components::logical_painter()).
+    /// # Arguments
+    /// * `widget` - Layout Widget of the window
+    /// * `regions` - damaged rects in logical window coordinates, never empty
+    /// * `scale` - number of physical pixels per logical pixel
+    fn paint_widget(&mut self, widget: &mut dyn WidgetTrait, regions: &[Rect], scale: f32);
 
     /// # Returns
     /// Position of this Window
@@ -28,11 +37,20 @@ pub trait WindowProviderTrait: Debug {
     fn set_pos(&mut self, new: Point2<i32>);
 
     /// # Returns
-    /// Size of the window.
+    /// Size of the window in units of the windowing system. Positions of mouse events are in
+    /// these units as well.
     fn size(&self) -> Vector2<u32>;
 
+    /// # Returns
+    /// Size of the area painted in the window in physical pixels. It differs from size() on
+    /// systems which scale windows of HiDPI aware applications (e.g. macOS). The default
+    /// implementation returns size().
+    fn drawable_size(&self) -> Vector2<u32> {
+        self.size()
+    }
+
     /// Sets new size for the window.
     /// # Arguments
-    /// * `new` - new size
+    /// * `new` - new size in units of the windowing system
     fn set_size(&mut self, new: Vector2<u32>);
 }
@@ -5,6 +5,7 @@ use nalgebra::Point2;
 use nalgebra::Vector2;
 use uuid::Uuid;
 // crate
+use crate::poly_ui::events::Event;
 use crate::poly_ui::layouts::CanvasLayout;
 
 //************************************************************************************************
@@ -31,19 +32,48 @@ pub trait WindowTrait {
     fn set_pos(&mut self, new: Point2<i32>);
 
     /// # Returns
-    /// Size of this window
+    /// Size of this window in logical pixels
     fn size(&self) -> Vector2<u32>;
 
     /// Sets size for this Window
     /// # Arguments
-    /// * `new` - new size for this Window
+    /// * `new` - new size for this Window in logical pixels
     fn set_size(&mut self, new: Vector2<u32>);
 
+    /// # Returns
+    /// Number of physical pixels per logical pixel. Transforms, layout sizes and event
+    /// coordinates of all widgets in this Window are in logical pixels and painting is scaled by
+    /// this factor. It's derived from the drawable and window size unless it's overridden.
+    fn scale_factor(&self) -> f32;
+
+    /// Overrides the scale factor derived from the window system; the whole Window is repainted.
+    /// # Arguments
+    /// * `scale` - new scale factor or None to use the derived one again
+    fn set_scale_factor_override(&mut self, scale: Option<f32>);
+
+    /// Maps position reported by the windowing system (e.g. of a mouse event) to logical pixels.
+    /// # Arguments
+    /// * `pos` - position in units of the windowing system relative to the window
+    /// # Returns
+    /// Position in logical pixels.
+    fn map_from_window(&self, pos: &Point2<i32>) -> Point2<i32>;
+
+    /// Passes an input event to the Layout Widget of this Window.
+    /// # Arguments
+    /// * `event` - event with mouse position in units of the windowing system (see
+    ///   map_from_window())
+    /// # Returns
+    /// True if any Widget handled the event.
+    fn handle_event(&mut self, event: &Event) -> bool;
+
     /// Updates this Window and its Layout Widget with provided delta time
     /// # Arguments
     /// * `dt` - delta time from the last update in milliseconds
     fn update(&mut self, dt: f32);
 
-    /// Paints this Window and its Layout Widget.
-    fn paint(&mut self);
+    /// Paints regions of this Window damaged since the last paint. Widgets request repaint with
+    /// WidgetTrait::request_repaint().
+    /// # Returns
+    /// True if anything was painted, false if there was no damage.
+    fn paint(&mut self) -> bool;
 }